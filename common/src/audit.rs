@@ -0,0 +1,27 @@
+use sqlx::{Postgres, Transaction};
+
+use crate::{auth::BearerAuth, database::Database, request_id::RequestId};
+
+/// Begin a transaction whose changes are recorded in the `audit_log` on behalf of the user of
+/// `auth`.
+///
+/// The audited tables have triggers writing every inserted, updated and deleted row to the
+/// `audit_log` within the same transaction, they read the actor and the request id from the
+/// transaction-local `audit.actor` and `audit.request_id` settings set here. Changes made
+/// outside of such a transaction are still recorded, just without an actor.
+pub async fn begin(
+    db: &Database,
+    auth: &BearerAuth,
+) -> Result<Transaction<'static, Postgres>, sqlx::Error> {
+    let mut tx = db.db.begin().await?;
+
+    sqlx::query(
+        "SELECT set_config('audit.actor', $1, TRUE), set_config('audit.request_id', $2, TRUE)",
+    )
+    .bind(&auth.0.id)
+    .bind(RequestId::current().unwrap_or_default())
+    .execute(&mut *tx)
+    .await?;
+
+    Ok(tx)
+}
@@ -8,13 +8,16 @@ use serde::{de, Deserialize};
     bearer_format = "Bearer <access_token>",
     checker = "bearer_checker"
 )]
-pub struct BearerAuth(User);
+pub struct BearerAuth(pub User);
 
 #[derive(Debug)]
 pub struct User {
     pub id: String,
     pub email: String,
     pub access_token: String,
+
+    /// Permissions granted by the `permissions` and `scope` claims of the access token.
+    pub permissions: Vec<String>,
 }
 
 #[allow(unused)]
@@ -29,6 +32,8 @@ struct Claims {
     exp: u64,
     iat: u64,
     scope: String,
+    #[serde(default)]
+    permissions: Vec<String>, // This is added by Auth0 when RBAC is enabled for the API
 }
 
 /// Deserialize a `String` into `Vec<String>` or just `Vec<String>`.
@@ -76,7 +81,9 @@ pub async fn bearer_checker(
     .ok()?;
 
     // If the issuer is not the same as the configured Auth0 domain, then the token is not valid.
-    if config.auth0_domain.contains(token.claims.iss.as_str()) {
+    // Auth0 issues tokens with a trailing slash (e.g. `https://fgacyc.auth0.com/`) which may or may
+    // not be present in the configured domain.
+    if token.claims.iss.trim_end_matches('/') != config.auth0_domain.trim_end_matches('/') {
         tracing::debug!(
             "Token issuer ({}) does not match configured Auth0 domain ({})",
             token.claims.iss,
@@ -109,10 +116,20 @@ pub async fn bearer_checker(
         return None;
     }
 
+    let permissions = token
+        .claims
+        .permissions
+        .iter()
+        .map(String::as_str)
+        .chain(token.claims.scope.split_whitespace())
+        .map(str::to_string)
+        .collect();
+
     let user = User {
         id: token.claims.sub,
         email: token.claims.email,
         access_token: bearer.token.clone(),
+        permissions,
     };
 
     tracing::debug!("Exiting `bearer_checker`");
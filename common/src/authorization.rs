@@ -0,0 +1,133 @@
+use crate::{auth::BearerAuth, database::Database};
+
+/// A permission that a handler may require from its caller, each service declares its own.
+///
+/// Permissions are named `<resource>:<action>` where the action is one of `read`, `write` or
+/// `admin`. Holding an action on a resource implies every lesser action on the same resource, so
+/// `users:admin` satisfies `users:write` which in turn satisfies `users:read`.
+pub trait Permission: std::fmt::Debug + Copy + 'static {
+    /// Permissions granted to holders of a pastoral role with a weight at or below the given
+    /// weight. The lower the weight the more senior the role, a CGL is seeded with 4 and a coach
+    /// with 3.
+    const PASTORAL_ROLE_GRANTS: &'static [(i32, &'static [Self])];
+
+    /// Permissions granted to holders of a ministry role with a weight at or below the given
+    /// weight.
+    const MINISTRY_ROLE_GRANTS: &'static [(i32, &'static [Self])];
+
+    fn as_str(&self) -> &'static str;
+
+    /// Whether the `granted` permission string satisfies this permission.
+    fn is_satisfied_by(&self, granted: &str) -> bool {
+        fn rank(action: &str) -> Option<u8> {
+            match action {
+                "read" => Some(0),
+                "write" => Some(1),
+                "admin" => Some(2),
+                _ => None,
+            }
+        }
+
+        let (resource, action) = self
+            .as_str()
+            .split_once(':')
+            .expect("permissions are always <resource>:<action>");
+
+        match granted.split_once(':') {
+            Some((granted_resource, granted_action)) if granted_resource == resource => {
+                rank(granted_action) >= rank(action)
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error<P: Permission> {
+    #[error("Missing permission '{}'", .0.as_str())]
+    Forbidden(P),
+
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+impl BearerAuth {
+    /// Whether the `permissions` and `scope` claims of the access token grant `permission`.
+    pub fn is_granted_by_token<P: Permission>(&self, permission: P) -> bool {
+        self.0
+            .permissions
+            .iter()
+            .any(|granted| permission.is_satisfied_by(granted))
+    }
+
+    /// Ensure the authenticated user holds `permission`.
+    ///
+    /// The `permissions` and `scope` claims of the access token are checked first, the user's
+    /// pastoral and ministry roles are only looked up when the token does not grant it.
+    pub async fn require<P: Permission>(
+        &self,
+        db: &Database,
+        permission: P,
+    ) -> Result<(), Error<P>> {
+        if self.is_granted_by_token(permission) {
+            return Ok(());
+        }
+
+        let (pastoral_weight, ministry_weight) = sqlx::query_as::<_, (Option<i32>, Option<i32>)>(
+            r#"
+            SELECT
+                (
+                    SELECT MIN(pr.weight)
+                    FROM
+                        user_connect_group ucg
+                            INNER JOIN pastoral_role pr ON ucg.user_role = pr.id
+                    WHERE ucg.user_id = $1
+                ) AS pastoral_weight,
+                (
+                    SELECT MIN(mr.weight)
+                    FROM
+                        user_ministry um
+                            INNER JOIN ministry_role mr ON um.user_role = mr.id
+                    WHERE um.user_id = $1
+                ) AS ministry_weight
+            "#,
+        )
+        .bind(&self.0.id)
+        .fetch_one(&db.db)
+        .await?;
+
+        let granted = |weight: Option<i32>, grants: &[(i32, &[P])]| {
+            weight.is_some_and(|weight| {
+                grants.iter().any(|(threshold, permissions)| {
+                    weight <= *threshold
+                        && permissions
+                            .iter()
+                            .any(|granted| permission.is_satisfied_by(granted.as_str()))
+                })
+            })
+        };
+
+        if granted(pastoral_weight, P::PASTORAL_ROLE_GRANTS)
+            || granted(ministry_weight, P::MINISTRY_ROLE_GRANTS)
+        {
+            return Ok(());
+        }
+
+        Err(Error::Forbidden(permission))
+    }
+
+    /// Ensure the authenticated user either is the user identified by `user_id` or holds
+    /// `permission`, used by endpoints that users are allowed to call on themselves.
+    pub async fn require_self_or<P: Permission>(
+        &self,
+        db: &Database,
+        user_id: &str,
+        permission: P,
+    ) -> Result<(), Error<P>> {
+        if self.0.id == user_id {
+            return Ok(());
+        }
+
+        self.require(db, permission).await
+    }
+}
@@ -41,4 +41,21 @@ pub struct Config {
     /// The signing public key for Auth0 app in PEM format.
     #[clap(long, env)]
     pub auth0_public_key: String,
+
+    /// The keys encrypting sensitive personal data at rest, as comma separated `<id>:<key>` pairs
+    /// of 256-bit keys encoded in base64. The first key encrypts new data, the others are kept to
+    /// decrypt the data they encrypted until it is rotated to the first key.
+    #[clap(long, env, default_value = "")]
+    pub pii_keys: String,
+
+    /// The 256-bit key, encoded in base64, of the blind indexes sensitive personal data is looked
+    /// up by.
+    #[clap(long, env, default_value = "")]
+    pub pii_index_key: String,
+}
+
+impl AsRef<Config> for Config {
+    fn as_ref(&self) -> &Config {
+        self
+    }
 }
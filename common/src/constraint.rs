@@ -0,0 +1,582 @@
+use poem_openapi::payload;
+
+use crate::error::{Error, ErrorResponse};
+
+/// The HTTP status a constraint violation is reported with.
+#[derive(Debug, Clone, Copy)]
+pub enum Status {
+    BadRequest,
+    NotFound,
+    Conflict,
+}
+
+/// Describes how a violation of a database constraint is reported to clients.
+#[derive(Debug, Clone, Copy)]
+pub struct Constraint {
+    /// The name of the constraint in Postgres (e.g. `user_email_key`).
+    pub name: &'static str,
+
+    pub status: Status,
+
+    /// The stable code returned to clients (e.g. `USER_EMAIL_TAKEN`).
+    pub code: &'static str,
+
+    /// The request field the violation is reported against.
+    pub field: &'static str,
+
+    pub message: &'static str,
+}
+
+/// Every constraint of the database that can be violated through the API. All services share the
+/// same database, so new constraints should be registered here along with their migration.
+const CONSTRAINTS: &[Constraint] = &[
+    Constraint {
+        name: "satellite_pkey",
+        status: Status::Conflict,
+        code: "SATELLITE_ID_TAKEN",
+        field: "id",
+        message: "A satellite with this id already exists",
+    },
+    Constraint {
+        name: "connect_group_pkey",
+        status: Status::Conflict,
+        code: "CONNECT_GROUP_ID_TAKEN",
+        field: "id",
+        message: "A connect group with this id already exists",
+    },
+    Constraint {
+        name: "connect_group_satellite_id_no_variant_key",
+        status: Status::Conflict,
+        code: "CONNECT_GROUP_NO_TAKEN",
+        field: "no",
+        message: "A connect group with this number and variant already exists in the satellite",
+    },
+    Constraint {
+        name: "connect_group_satellite_id_fkey",
+        status: Status::NotFound,
+        code: "SATELLITE_NOT_FOUND",
+        field: "satellite_id",
+        message: "The satellite does not exist",
+    },
+    Constraint {
+        name: "connect_group_category_id_fkey",
+        status: Status::NotFound,
+        code: "CONNECT_GROUP_CATEGORY_NOT_FOUND",
+        field: "category_id",
+        message: "The connect group category does not exist",
+    },
+    Constraint {
+        name: "connect_group_category_pkey",
+        status: Status::Conflict,
+        code: "CONNECT_GROUP_CATEGORY_ID_TAKEN",
+        field: "id",
+        message: "A connect group category with this id already exists",
+    },
+    Constraint {
+        name: "connect_group_category_name_key",
+        status: Status::Conflict,
+        code: "CONNECT_GROUP_CATEGORY_NAME_TAKEN",
+        field: "name",
+        message: "A connect group category with this name already exists",
+    },
+    Constraint {
+        name: "ministry_team_pkey",
+        status: Status::Conflict,
+        code: "MINISTRY_TEAM_ID_TAKEN",
+        field: "id",
+        message: "A ministry team with this id already exists",
+    },
+    Constraint {
+        name: "ministry_department_pkey",
+        status: Status::Conflict,
+        code: "MINISTRY_DEPARTMENT_ID_TAKEN",
+        field: "id",
+        message: "A ministry department with this id already exists",
+    },
+    Constraint {
+        name: "ministry_pkey",
+        status: Status::Conflict,
+        code: "MINISTRY_ID_TAKEN",
+        field: "id",
+        message: "A ministry with this id already exists",
+    },
+    Constraint {
+        name: "ministry_department_id_fkey",
+        status: Status::NotFound,
+        code: "MINISTRY_DEPARTMENT_NOT_FOUND",
+        field: "department_id",
+        message: "The ministry department does not exist",
+    },
+    Constraint {
+        name: "ministry_team_id_fkey",
+        status: Status::NotFound,
+        code: "MINISTRY_TEAM_NOT_FOUND",
+        field: "team_id",
+        message: "The ministry team does not exist",
+    },
+    Constraint {
+        name: "ministry_satellite_id_fkey",
+        status: Status::NotFound,
+        code: "SATELLITE_NOT_FOUND",
+        field: "satellite_id",
+        message: "The satellite does not exist",
+    },
+    Constraint {
+        name: "user_pkey",
+        status: Status::Conflict,
+        code: "USER_ID_TAKEN",
+        field: "id",
+        message: "A user with this id already exists",
+    },
+    Constraint {
+        name: "user_no_key",
+        status: Status::Conflict,
+        code: "USER_NO_TAKEN",
+        field: "no",
+        message: "A user with this number already exists",
+    },
+    Constraint {
+        name: "user_email_key",
+        status: Status::Conflict,
+        code: "USER_EMAIL_TAKEN",
+        field: "email",
+        message: "A user with this email already exists",
+    },
+    Constraint {
+        name: "user_username_key",
+        status: Status::Conflict,
+        code: "USER_USERNAME_TAKEN",
+        field: "username",
+        message: "A user with this username already exists",
+    },
+    Constraint {
+        name: "pastoral_role_pkey",
+        status: Status::Conflict,
+        code: "PASTORAL_ROLE_ID_TAKEN",
+        field: "id",
+        message: "A pastoral role with this id already exists",
+    },
+    Constraint {
+        name: "pastoral_role_name_key",
+        status: Status::Conflict,
+        code: "PASTORAL_ROLE_NAME_TAKEN",
+        field: "name",
+        message: "A pastoral role with this name already exists",
+    },
+    Constraint {
+        name: "ministry_role_pkey",
+        status: Status::Conflict,
+        code: "MINISTRY_ROLE_ID_TAKEN",
+        field: "id",
+        message: "A ministry role with this id already exists",
+    },
+    Constraint {
+        name: "ministry_role_name_key",
+        status: Status::Conflict,
+        code: "MINISTRY_ROLE_NAME_TAKEN",
+        field: "name",
+        message: "A ministry role with this name already exists",
+    },
+    Constraint {
+        name: "user_connect_group_pkey",
+        status: Status::Conflict,
+        code: "USER_ALREADY_IN_CONNECT_GROUP",
+        field: "user_id",
+        message: "The user is already associated with the connect group",
+    },
+    Constraint {
+        name: "user_connect_group_user_id_fkey",
+        status: Status::NotFound,
+        code: "USER_NOT_FOUND",
+        field: "user_id",
+        message: "The user does not exist",
+    },
+    Constraint {
+        name: "user_connect_group_connect_group_id_fkey",
+        status: Status::NotFound,
+        code: "CONNECT_GROUP_NOT_FOUND",
+        field: "connect_group_id",
+        message: "The connect group does not exist",
+    },
+    Constraint {
+        name: "user_connect_group_user_role_fkey",
+        status: Status::NotFound,
+        code: "PASTORAL_ROLE_NOT_FOUND",
+        field: "user_role",
+        message: "The pastoral role does not exist",
+    },
+    Constraint {
+        name: "user_ministry_pkey",
+        status: Status::Conflict,
+        code: "USER_ALREADY_IN_MINISTRY",
+        field: "user_id",
+        message: "The user is already associated with the ministry",
+    },
+    Constraint {
+        name: "user_ministry_user_id_fkey",
+        status: Status::NotFound,
+        code: "USER_NOT_FOUND",
+        field: "user_id",
+        message: "The user does not exist",
+    },
+    Constraint {
+        name: "user_ministry_ministry_id_fkey",
+        status: Status::NotFound,
+        code: "MINISTRY_NOT_FOUND",
+        field: "ministry_id",
+        message: "The ministry does not exist",
+    },
+    Constraint {
+        name: "user_ministry_user_role_fkey",
+        status: Status::NotFound,
+        code: "MINISTRY_ROLE_NOT_FOUND",
+        field: "user_role",
+        message: "The ministry role does not exist",
+    },
+    Constraint {
+        name: "user_relationship_pkey",
+        status: Status::Conflict,
+        code: "USER_RELATIONSHIP_EXISTS",
+        field: "destination_user_id",
+        message: "The users already have a relationship",
+    },
+    Constraint {
+        name: "user_relationship_source_user_id_fkey",
+        status: Status::NotFound,
+        code: "USER_NOT_FOUND",
+        field: "source_user_id",
+        message: "The user does not exist",
+    },
+    Constraint {
+        name: "user_relationship_destination_user_id_fkey",
+        status: Status::NotFound,
+        code: "USER_NOT_FOUND",
+        field: "destination_user_id",
+        message: "The user does not exist",
+    },
+    Constraint {
+        name: "source_destination_check",
+        status: Status::BadRequest,
+        code: "USER_RELATIONSHIP_WITH_SELF",
+        field: "destination_user_id",
+        message: "A user cannot have a relationship with themselves",
+    },
+    Constraint {
+        name: "user_relationship_relationship_fkey",
+        status: Status::BadRequest,
+        code: "RELATIONSHIP_TYPE_NOT_FOUND",
+        field: "relationship",
+        message: "The relationship is not one of the relationship types",
+    },
+    Constraint {
+        name: "user_household_pkey",
+        status: Status::Conflict,
+        code: "USER_ALREADY_IN_HOUSEHOLD",
+        field: "user_ids",
+        message: "The user already belongs to the household",
+    },
+    Constraint {
+        name: "user_household_household_id_fkey",
+        status: Status::NotFound,
+        code: "HOUSEHOLD_NOT_FOUND",
+        field: "household_id",
+        message: "The household does not exist",
+    },
+    Constraint {
+        name: "user_household_user_id_fkey",
+        status: Status::NotFound,
+        code: "USER_NOT_FOUND",
+        field: "user_ids",
+        message: "The user does not exist",
+    },
+    Constraint {
+        name: "user_oversight_user_id_fkey",
+        status: Status::NotFound,
+        code: "USER_NOT_FOUND",
+        field: "user_id",
+        message: "The user does not exist",
+    },
+    Constraint {
+        name: "user_oversight_overseer_id_fkey",
+        status: Status::NotFound,
+        code: "USER_NOT_FOUND",
+        field: "overseer_id",
+        message: "The overseer does not exist",
+    },
+    Constraint {
+        name: "user_oversight_self_check",
+        status: Status::BadRequest,
+        code: "USER_OVERSIGHT_OF_SELF",
+        field: "overseer_id",
+        message: "A user cannot oversee themselves",
+    },
+    Constraint {
+        name: "user_erasure_pkey",
+        status: Status::Conflict,
+        code: "USER_ERASURE_EXISTS",
+        field: "user_id",
+        message: "The erasure of the user has already been requested",
+    },
+    Constraint {
+        name: "user_erasure_user_id_fkey",
+        status: Status::NotFound,
+        code: "USER_NOT_FOUND",
+        field: "user_id",
+        message: "The user does not exist",
+    },
+    Constraint {
+        name: "shadow_user_pkey",
+        status: Status::Conflict,
+        code: "SHADOW_USER_EXISTS",
+        field: "phone_number",
+        message: "A shadow user with this name and phone number already exists",
+    },
+    Constraint {
+        name: "shadow_user_no_key",
+        status: Status::Conflict,
+        code: "SHADOW_USER_NO_TAKEN",
+        field: "no",
+        message: "A shadow user with this number already exists",
+    },
+    Constraint {
+        name: "shadow_user_claimed_by_fkey",
+        status: Status::NotFound,
+        code: "USER_NOT_FOUND",
+        field: "user_id",
+        message: "The user does not exist",
+    },
+    Constraint {
+        name: "user_claim_target_check",
+        status: Status::BadRequest,
+        code: "USER_CLAIM_TARGET_INVALID",
+        field: "shadow_user_no",
+        message: "Exactly one of shadow_user_no and user_id must be given",
+    },
+    Constraint {
+        name: "user_merge_self_check",
+        status: Status::BadRequest,
+        code: "USER_MERGE_WITH_SELF",
+        field: "merged_user_id",
+        message: "A user cannot be merged into itself",
+    },
+    Constraint {
+        name: "user_duplicate_pair_check",
+        status: Status::BadRequest,
+        code: "USER_DUPLICATE_OF_SELF",
+        field: "duplicate_user_id",
+        message: "A user cannot be a duplicate of itself",
+    },
+    Constraint {
+        name: "connect_group_lineage_parent_check",
+        status: Status::BadRequest,
+        code: "CONNECT_GROUP_PARENT_OF_SELF",
+        field: "parent_id",
+        message: "A connect group cannot be multiplied out of itself",
+    },
+    Constraint {
+        name: "user_connect_group_history_period_check",
+        status: Status::BadRequest,
+        code: "MEMBERSHIP_PERIOD_INVALID",
+        field: "left_at",
+        message: "A member cannot leave a connect group before joining it",
+    },
+    Constraint {
+        name: "user_ministry_history_period_check",
+        status: Status::BadRequest,
+        code: "MEMBERSHIP_PERIOD_INVALID",
+        field: "left_at",
+        message: "A member cannot leave a ministry before joining it",
+    },
+    Constraint {
+        name: "currency_pkey",
+        status: Status::Conflict,
+        code: "CURRENCY_CODE_TAKEN",
+        field: "code",
+        message: "A currency with this code already exists",
+    },
+    Constraint {
+        name: "event_type_pkey",
+        status: Status::Conflict,
+        code: "EVENT_TYPE_NAME_TAKEN",
+        field: "name",
+        message: "An event type with this name already exists",
+    },
+    Constraint {
+        name: "event_pkey",
+        status: Status::Conflict,
+        code: "EVENT_ID_TAKEN",
+        field: "id",
+        message: "An event with this id already exists",
+    },
+    Constraint {
+        name: "event_type_fkey",
+        status: Status::NotFound,
+        code: "EVENT_TYPE_NOT_FOUND",
+        field: "type",
+        message: "The event type does not exist",
+    },
+    Constraint {
+        name: "form_field_type_pkey",
+        status: Status::Conflict,
+        code: "FORM_FIELD_TYPE_TAKEN",
+        field: "type",
+        message: "A form field type with this type already exists",
+    },
+    Constraint {
+        name: "registration_pkey",
+        status: Status::Conflict,
+        code: "REGISTRATION_ID_TAKEN",
+        field: "id",
+        message: "A registration with this id already exists",
+    },
+    Constraint {
+        name: "registration_event_id_name_key",
+        status: Status::Conflict,
+        code: "REGISTRATION_NAME_TAKEN",
+        field: "name",
+        message: "A registration with this name already exists for the event",
+    },
+    Constraint {
+        name: "registration_event_id_fkey",
+        status: Status::NotFound,
+        code: "EVENT_NOT_FOUND",
+        field: "event_id",
+        message: "The event does not exist",
+    },
+    Constraint {
+        name: "registration_form_field_pkey",
+        status: Status::Conflict,
+        code: "REGISTRATION_FORM_FIELD_NAME_TAKEN",
+        field: "name",
+        message: "A form field with this name already exists for the registration",
+    },
+    Constraint {
+        name: "registration_form_field_registration_id_weight_key",
+        status: Status::Conflict,
+        code: "REGISTRATION_FORM_FIELD_WEIGHT_TAKEN",
+        field: "weight",
+        message: "A form field with this weight already exists for the registration",
+    },
+    Constraint {
+        name: "registration_form_field_registration_id_fkey",
+        status: Status::NotFound,
+        code: "REGISTRATION_NOT_FOUND",
+        field: "registration_id",
+        message: "The registration does not exist",
+    },
+    Constraint {
+        name: "registration_form_field_type_fkey",
+        status: Status::NotFound,
+        code: "FORM_FIELD_TYPE_NOT_FOUND",
+        field: "type",
+        message: "The form field type does not exist",
+    },
+    Constraint {
+        name: "registration_form_field_data_pkey",
+        status: Status::Conflict,
+        code: "REGISTRATION_FORM_FIELD_DATA_EXISTS",
+        field: "name",
+        message: "The form field has already been filled in by the user",
+    },
+    Constraint {
+        name: "registration_form_field_data_registration_id_name_fkey",
+        status: Status::NotFound,
+        code: "REGISTRATION_FORM_FIELD_NOT_FOUND",
+        field: "name",
+        message: "The form field does not exist",
+    },
+    Constraint {
+        name: "price_pkey",
+        status: Status::Conflict,
+        code: "PRICE_ID_TAKEN",
+        field: "id",
+        message: "A price with this id already exists",
+    },
+    Constraint {
+        name: "price_event_id_name_key",
+        status: Status::Conflict,
+        code: "PRICE_NAME_TAKEN",
+        field: "name",
+        message: "A price with this name already exists for the event",
+    },
+    Constraint {
+        name: "price_event_id_fkey",
+        status: Status::NotFound,
+        code: "EVENT_NOT_FOUND",
+        field: "event_id",
+        message: "The event does not exist",
+    },
+    Constraint {
+        name: "price_currency_code_fkey",
+        status: Status::NotFound,
+        code: "CURRENCY_NOT_FOUND",
+        field: "currency_code",
+        message: "The currency does not exist",
+    },
+    Constraint {
+        name: "session_pkey",
+        status: Status::Conflict,
+        code: "SESSION_ID_TAKEN",
+        field: "id",
+        message: "A session with this id already exists",
+    },
+    Constraint {
+        name: "session_event_id_name_key",
+        status: Status::Conflict,
+        code: "SESSION_NAME_TAKEN",
+        field: "name",
+        message: "A session with this name already exists for the event",
+    },
+    Constraint {
+        name: "session_event_id_fkey",
+        status: Status::NotFound,
+        code: "EVENT_NOT_FOUND",
+        field: "event_id",
+        message: "The event does not exist",
+    },
+    Constraint {
+        name: "attendance_pkey",
+        status: Status::Conflict,
+        code: "ATTENDANCE_ALREADY_RECORDED",
+        field: "user_id",
+        message: "The attendance of the user has already been recorded for the session",
+    },
+    Constraint {
+        name: "attendance_session_id_fkey",
+        status: Status::NotFound,
+        code: "SESSION_NOT_FOUND",
+        field: "session_id",
+        message: "The session does not exist",
+    },
+    Constraint {
+        name: "address_check",
+        status: Status::BadRequest,
+        code: "INVALID_ADDRESS",
+        field: "address",
+        message: "The address is missing one of line_one, city, state, country or postal_code",
+    },
+];
+
+/// Find the registered constraint with the given name.
+pub fn find(name: &str) -> Option<&'static Constraint> {
+    CONSTRAINTS
+        .iter()
+        .find(|constraint| constraint.name == name)
+}
+
+impl Constraint {
+    /// The error reported when this constraint is violated.
+    pub fn error(&self) -> Error {
+        let response = ErrorResponse::new(self.code, self.message).with_detail(
+            self.field,
+            self.code,
+            self.message,
+        );
+
+        match self.status {
+            Status::BadRequest => Error::BadRequest(payload::Json(response)),
+            Status::NotFound => Error::NotFound(payload::Json(response)),
+            Status::Conflict => Error::Conflict(payload::Json(response)),
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    Aes256Gcm, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::config::Config;
+
+/// The version of the layout of encrypted values, see [`Envelope`].
+const VERSION: u8 = 1;
+
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("No encryption key is configured, see `PII_KEYS` and `PII_INDEX_KEY`")]
+    NoKey,
+
+    #[error("Invalid key '{0}', keys must be 256 bits encoded in base64 with a unique id")]
+    InvalidKey(String),
+
+    #[error("The value was encrypted with key '{0}', which is not in the keyring")]
+    UnknownKey(String),
+
+    #[error("The value is not in the layout of an encrypted value")]
+    Malformed,
+
+    #[error("The value could not be decrypted, it was altered or encrypted for something else")]
+    Decryption,
+}
+
+/// The keys encrypting sensitive data at rest.
+///
+/// Values are encrypted with envelope encryption: every value is encrypted with a data key of
+/// its own, which is stored along with the value wrapped by a key of the keyring. The first key
+/// wraps new data keys, the others are kept to unwrap the data keys they wrapped until these are
+/// re-wrapped with [`Keyring::rewrap`], so rotating a key never decrypts the values themselves.
+///
+/// Values are looked up by their blind index instead, a keyed hash computed with a separate key.
+#[derive(Clone, Default)]
+pub struct Keyring {
+    keys: Vec<(String, Aes256Gcm)>,
+    index_key: Option<Hmac<Sha256>>,
+}
+
+/// An encrypted value: the id of the key wrapping the data key, the wrapped data key and the
+/// value encrypted by the data key, each encrypted with a nonce of its own. Laid out as
+/// `version | key id length | key id | nonce | wrapped data key | nonce | encrypted value`.
+struct Envelope<'a> {
+    key_id: &'a str,
+    key_nonce: &'a [u8],
+    wrapped_key: &'a [u8],
+    nonce: &'a [u8],
+    ciphertext: &'a [u8],
+}
+
+impl<'a> Envelope<'a> {
+    fn parse(value: &'a [u8]) -> Result<Self, Error> {
+        let Some((&VERSION, rest)) = value.split_first() else {
+            return Err(Error::Malformed);
+        };
+        let (&key_id_len, rest) = rest.split_first().ok_or(Error::Malformed)?;
+        let key_id_len = usize::from(key_id_len);
+        if rest.len() < key_id_len + 2 * NONCE_LEN + KEY_LEN + 2 * TAG_LEN {
+            return Err(Error::Malformed);
+        }
+
+        let (key_id, rest) = rest.split_at(key_id_len);
+        let (key_nonce, rest) = rest.split_at(NONCE_LEN);
+        let (wrapped_key, rest) = rest.split_at(KEY_LEN + TAG_LEN);
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+        Ok(Self {
+            key_id: std::str::from_utf8(key_id).map_err(|_| Error::Malformed)?,
+            key_nonce,
+            wrapped_key,
+            nonce,
+            ciphertext,
+        })
+    }
+
+    fn to_vec(&self) -> Vec<u8> {
+        let mut value = vec![VERSION, self.key_id.len() as u8];
+        value.extend_from_slice(self.key_id.as_bytes());
+        value.extend_from_slice(self.key_nonce);
+        value.extend_from_slice(self.wrapped_key);
+        value.extend_from_slice(self.nonce);
+        value.extend_from_slice(self.ciphertext);
+        value
+    }
+}
+
+impl Keyring {
+    /// Parse the keys of a keyring, `keys` being comma separated `<id>:<key>` pairs, the first
+    /// one wrapping new data keys, and `index_key` the key of the blind indexes. Keys are 256
+    /// bits encoded in base64.
+    ///
+    /// Empty values leave the keyring without keys, for services holding no sensitive data.
+    pub fn new(keys: &str, index_key: &str) -> Result<Self, Error> {
+        let mut keyring = Self::default();
+
+        for key in keys.split(',').map(str::trim).filter(|key| !key.is_empty()) {
+            let (id, key) = key
+                .split_once(':')
+                .ok_or_else(|| Error::InvalidKey(key.to_string()))?;
+            let cipher = STANDARD
+                .decode(key.trim())
+                .ok()
+                .filter(|key| key.len() == KEY_LEN)
+                .and_then(|key| Aes256Gcm::new_from_slice(&key).ok());
+            match cipher {
+                Some(cipher)
+                    if !id.is_empty()
+                        && id.len() <= usize::from(u8::MAX)
+                        && keyring.key(id).is_none() =>
+                {
+                    keyring.keys.push((id.to_string(), cipher))
+                }
+                _ => return Err(Error::InvalidKey(id.to_string())),
+            }
+        }
+
+        let index_key = index_key.trim();
+        if !index_key.is_empty() {
+            keyring.index_key = Some(
+                STANDARD
+                    .decode(index_key)
+                    .ok()
+                    .filter(|key| key.len() == KEY_LEN)
+                    .and_then(|key| <Hmac<Sha256> as Mac>::new_from_slice(&key).ok())
+                    .ok_or_else(|| Error::InvalidKey("index".to_string()))?,
+            );
+        }
+
+        Ok(keyring)
+    }
+
+    /// The keyring configured by `PII_KEYS` and `PII_INDEX_KEY`.
+    pub fn from_config(config: &Config) -> Result<Self, Error> {
+        Self::new(&config.pii_keys, &config.pii_index_key)
+    }
+
+    /// Whether the keyring holds keys to encrypt values with.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Fail unless the keyring can both encrypt values and compute their blind indexes, for
+    /// services holding sensitive data to refuse to start without keys.
+    pub fn ensure_keys(&self) -> Result<(), Error> {
+        if self.keys.is_empty() || self.index_key.is_none() {
+            return Err(Error::NoKey);
+        }
+        Ok(())
+    }
+
+    /// The id of the key wrapping new data keys.
+    pub fn primary_key_id(&self) -> Option<&str> {
+        self.keys.first().map(|(id, _)| id.as_str())
+    }
+
+    fn key(&self, id: &str) -> Option<&Aes256Gcm> {
+        self.keys
+            .iter()
+            .find(|(key_id, _)| key_id == id)
+            .map(|(_, key)| key)
+    }
+
+    /// Encrypt `plaintext` with a new data key. `context` (e.g. the name of the column) must be
+    /// given again to decrypt the value, so that values cannot be swapped for one another.
+    pub fn encrypt(&self, context: &str, plaintext: &[u8]) -> Result<Vec<u8>, Error> {
+        let (key_id, key) = self.keys.first().ok_or(Error::NoKey)?;
+
+        let data_key = Aes256Gcm::generate_key(OsRng);
+        let key_nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let wrapped_key = key
+            .encrypt(&key_nonce, data_key.as_slice())
+            .expect("a data key is short enough to be encrypted");
+
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = Aes256Gcm::new(&data_key)
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext,
+                    aad: context.as_bytes(),
+                },
+            )
+            .expect("values are short enough to be encrypted");
+
+        Ok(Envelope {
+            key_id,
+            key_nonce: &key_nonce,
+            wrapped_key: &wrapped_key,
+            nonce: &nonce,
+            ciphertext: &ciphertext,
+        }
+        .to_vec())
+    }
+
+    /// Decrypt a value encrypted by [`Keyring::encrypt`] for the same `context`.
+    pub fn decrypt(&self, context: &str, value: &[u8]) -> Result<Vec<u8>, Error> {
+        let envelope = Envelope::parse(value)?;
+        let data_key = self.unwrap_key(&envelope)?;
+
+        Aes256Gcm::new_from_slice(&data_key)
+            .map_err(|_| Error::Decryption)?
+            .decrypt(
+                Nonce::from_slice(envelope.nonce),
+                Payload {
+                    msg: envelope.ciphertext,
+                    aad: context.as_bytes(),
+                },
+            )
+            .map_err(|_| Error::Decryption)
+    }
+
+    /// Re-wrap the data key of `value` with the first key of the keyring, leaving the value
+    /// itself as it is. Returns `None` when it is already wrapped with it.
+    pub fn rewrap(&self, value: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        let (key_id, key) = self.keys.first().ok_or(Error::NoKey)?;
+
+        let envelope = Envelope::parse(value)?;
+        if envelope.key_id == key_id {
+            return Ok(None);
+        }
+
+        let data_key = self.unwrap_key(&envelope)?;
+        let key_nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let wrapped_key = key
+            .encrypt(&key_nonce, data_key.as_slice())
+            .expect("a data key is short enough to be encrypted");
+
+        Ok(Some(
+            Envelope {
+                key_id,
+                key_nonce: &key_nonce,
+                wrapped_key: &wrapped_key,
+                ..envelope
+            }
+            .to_vec(),
+        ))
+    }
+
+    fn unwrap_key(&self, envelope: &Envelope) -> Result<Vec<u8>, Error> {
+        self.key(envelope.key_id)
+            .ok_or_else(|| Error::UnknownKey(envelope.key_id.to_string()))?
+            .decrypt(Nonce::from_slice(envelope.key_nonce), envelope.wrapped_key)
+            .map_err(|_| Error::Decryption)
+    }
+
+    /// A keyed hash of `value` to look it up by without decrypting anything, meant to be given
+    /// normalised values. `context` keeps the indexes of different columns apart.
+    pub fn blind_index(&self, context: &str, value: &str) -> Result<Vec<u8>, Error> {
+        let mut mac = self.index_key.clone().ok_or(Error::NoKey)?;
+        mac.update(context.as_bytes());
+        mac.update(&[0]);
+        mac.update(value.as_bytes());
+        Ok(mac.finalize().into_bytes().to_vec())
+    }
+}
@@ -2,7 +2,7 @@ use sqlx::{postgres::PgPoolOptions, PgPool};
 
 #[derive(Debug, Clone)]
 pub struct Database {
-    pub(crate) db: PgPool,
+    pub db: PgPool,
 }
 
 impl Database {
@@ -13,7 +13,7 @@ impl Database {
             // CockroachDB recommends 4 connections per CPU core as suggested in the docs.
             // see: https://www.cockroachlabs.com/docs/stable/connection-pooling.html
             .max_connections(num_cpus::get() as u32 * 4)
-            .connect(&database_url)
+            .connect(database_url)
             .await?;
 
         Ok(Self { db })
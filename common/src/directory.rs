@@ -0,0 +1,114 @@
+use auth0::management::{roles::Roles, Api};
+use reqwest::{Method, RequestBuilder};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Auth0 request failed: {0}")]
+    Request(#[from] reqwest::Error),
+}
+
+/// A user profile on Auth0, the subset of its fields kept in sync with the users of a service.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Profile {
+    pub user_id: String,
+    pub email: Option<String>,
+    #[serde(default)]
+    pub email_verified: bool,
+    pub name: Option<String>,
+    pub given_name: Option<String>,
+    pub family_name: Option<String>,
+    pub nickname: Option<String>,
+    pub picture: Option<String>,
+    #[serde(default)]
+    pub blocked: bool,
+    /// When the user signed up, as an RFC 3339 timestamp.
+    pub created_at: Option<String>,
+}
+
+/// Changes to a profile on Auth0, the fields left out are left unchanged.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProfileUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_verified: Option<bool>,
+    /// Have Auth0 send a verification email to the new `email`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verify_email: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub given_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub family_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nickname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub picture: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blocked: Option<bool>,
+}
+
+/// The users endpoints of the Auth0 management API, requested the same way as [`Roles`]: the
+/// requests are built with the client, its tenant and its access token, and left to send.
+pub trait Users {
+    /// Up to `per_page` profiles of the users who signed up at or after `created_from`, an RFC
+    /// 3339 timestamp, or of every user without it, in the order they signed up.
+    ///
+    /// Auth0 only pages through the first 1000 results of a search, so every user is listed by
+    /// searching again from the last timestamp listed instead of paging.
+    fn list_users(&self, created_from: Option<&str>, per_page: u32) -> RequestBuilder;
+
+    /// The [`Profile`] of the user `user_id`, 404 when there is no such user on Auth0.
+    fn get_user(&self, user_id: &str) -> RequestBuilder;
+
+    /// Apply `update` to the profile of the user `user_id`, 404 when there is no such user.
+    fn update_user(&self, user_id: &str, update: &ProfileUpdate) -> RequestBuilder;
+
+    /// Delete the user `user_id` from Auth0, 404 when there is no such user.
+    fn delete_user(&self, user_id: &str) -> RequestBuilder;
+}
+
+impl Users for Api {
+    fn list_users(&self, created_from: Option<&str>, per_page: u32) -> RequestBuilder {
+        let mut query = vec![
+            ("per_page", per_page.to_string()),
+            ("sort", "created_at:1".to_string()),
+            ("search_engine", "v3".to_string()),
+        ];
+        if let Some(created_from) = created_from {
+            query.push(("q", format!("created_at:[\"{}\" TO *]", created_from)));
+        }
+        request(self, Method::GET, &[]).query(&query)
+    }
+
+    fn get_user(&self, user_id: &str) -> RequestBuilder {
+        request(self, Method::GET, &[user_id])
+    }
+
+    fn update_user(&self, user_id: &str, update: &ProfileUpdate) -> RequestBuilder {
+        request(self, Method::PATCH, &[user_id]).json(update)
+    }
+
+    fn delete_user(&self, user_id: &str) -> RequestBuilder {
+        request(self, Method::DELETE, &[user_id])
+    }
+}
+
+/// A request to the users endpoints through the client of `management`. The client does not
+/// expose its tenant nor its access token, they are taken from a roles request it builds.
+fn request(management: &Api, method: Method, segments: &[&str]) -> RequestBuilder {
+    let (client, roles) = management.delete_role(String::new()).build_split();
+    let roles = roles.expect("the Auth0 management client builds valid requests");
+
+    let mut url = roles.url().clone();
+    url.set_query(None);
+    url.path_segments_mut()
+        .expect("the Auth0 domain is a base URL")
+        .clear()
+        .extend(["api", "v2", "users"])
+        .extend(segments);
+
+    client.request(method, url).headers(roles.headers().clone())
+}
@@ -0,0 +1,261 @@
+use std::collections::BTreeMap;
+
+use poem_openapi::{payload, ApiResponse, Object};
+use serde::{Deserialize, Serialize};
+use sqlx::{error::ErrorKind, postgres::PgDatabaseError};
+
+use crate::{authorization, constraint, crypto, directory, pagination, projection};
+
+/// The body of every error returned by the API.
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+pub struct ErrorResponse {
+    /// A stable machine-readable code identifying the error (e.g. `USER_EMAIL_TAKEN`).
+    pub code: String,
+
+    /// A human readable description of the error, meant for developers rather than end users.
+    pub message: String,
+
+    /// The problems with individual fields of the request, if any.
+    #[oai(default, skip_serializing_if_is_empty)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<ErrorDetail>,
+
+    /// The values the message was built from, to localise it (e.g. the missing `permission`).
+    #[oai(default, skip_serializing_if_is_empty)]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, String>,
+
+    /// Identifies the request in the logs, also returned in the `X-Request-Id` header.
+    #[oai(skip_serializing_if_is_none)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+/// A problem with a single field of the request.
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+pub struct ErrorDetail {
+    /// The field of the request body or parameter the problem is about (e.g. `email`).
+    pub field: String,
+
+    /// A stable machine-readable code identifying the problem (e.g. `USER_EMAIL_TAKEN`).
+    pub code: String,
+
+    /// A human readable description of the problem.
+    pub message: String,
+}
+
+impl ErrorResponse {
+    pub fn new(code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            code: code.into(),
+            message: message.into(),
+            details: Vec::new(),
+            params: BTreeMap::new(),
+            request_id: None,
+        }
+    }
+
+    pub fn with_detail(
+        mut self,
+        field: impl Into<String>,
+        code: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        self.details.push(ErrorDetail {
+            field: field.into(),
+            code: code.into(),
+            message: message.into(),
+        });
+        self
+    }
+
+    pub fn with_param(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.params.insert(name.into(), value.into());
+        self
+    }
+}
+
+/// The error returned by every handler.
+///
+/// Database errors are converted through [`From<sqlx::Error>`], which maps constraint violations
+/// to the stable codes registered in [`crate::constraint`] and never exposes the underlying SQL
+/// error to clients.
+#[derive(ApiResponse)]
+pub enum Error {
+    #[oai(status = 400)]
+    BadRequest(payload::Json<ErrorResponse>),
+
+    #[oai(status = 403)]
+    Forbidden(payload::Json<ErrorResponse>),
+
+    #[oai(status = 404)]
+    NotFound(payload::Json<ErrorResponse>),
+
+    #[oai(status = 409)]
+    Conflict(payload::Json<ErrorResponse>),
+
+    #[oai(status = 500)]
+    InternalServer(payload::Json<ErrorResponse>),
+}
+
+impl Error {
+    pub fn bad_request(code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::BadRequest(payload::Json(ErrorResponse::new(code, message)))
+    }
+
+    pub fn not_found(code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::NotFound(payload::Json(ErrorResponse::new(code, message)))
+    }
+
+    pub fn conflict(code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::Conflict(payload::Json(ErrorResponse::new(code, message)))
+    }
+
+    /// Report a problem with a single field of the request along with the error.
+    pub fn with_detail(
+        mut self,
+        field: impl Into<String>,
+        code: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        let (Self::BadRequest(body)
+        | Self::Forbidden(body)
+        | Self::NotFound(body)
+        | Self::Conflict(body)
+        | Self::InternalServer(body)) = &mut self;
+        body.0.details.push(ErrorDetail {
+            field: field.into(),
+            code: code.into(),
+            message: message.into(),
+        });
+        self
+    }
+
+    /// An unexpected failure, `error` is logged but not returned to the client.
+    pub fn internal(error: impl std::fmt::Display) -> Self {
+        tracing::error!("{}", error);
+
+        Self::InternalServer(payload::Json(ErrorResponse::new(
+            "INTERNAL_SERVER_ERROR",
+            "Something went wrong while processing the request",
+        )))
+    }
+}
+
+impl From<sqlx::Error> for Error {
+    fn from(e: sqlx::Error) -> Self {
+        let db = match e {
+            sqlx::Error::RowNotFound => {
+                return Error::not_found("NOT_FOUND", "The requested resource was not found")
+            }
+            sqlx::Error::Database(db) => db,
+            e => return Error::internal(e),
+        };
+
+        // Deleting or updating a row that is still referenced reports the constraint of the
+        // referencing table, which must not be mistaken for a missing reference.
+        if matches!(db.kind(), ErrorKind::ForeignKeyViolation)
+            && db.message().starts_with("update or delete")
+        {
+            return Error::conflict(
+                "RESOURCE_IN_USE",
+                "The resource is still referenced by other resources",
+            );
+        }
+
+        if let Some(constraint) = db.constraint().and_then(constraint::find) {
+            return constraint.error();
+        }
+
+        let column = db
+            .try_downcast_ref::<PgDatabaseError>()
+            .and_then(|db| db.column())
+            .map(str::to_string);
+        let with_column = |response: ErrorResponse, code: &str, message: &str| match &column {
+            Some(column) => response.with_detail(column, code, message),
+            None => response,
+        };
+
+        match db.kind() {
+            ErrorKind::UniqueViolation => Error::Conflict(payload::Json(with_column(
+                ErrorResponse::new("ALREADY_EXISTS", "The resource already exists"),
+                "TAKEN",
+                "Value is already taken",
+            ))),
+            ErrorKind::ForeignKeyViolation => Error::NotFound(payload::Json(with_column(
+                ErrorResponse::new(
+                    "REFERENCE_NOT_FOUND",
+                    "A referenced resource does not exist",
+                ),
+                "NOT_FOUND",
+                "Referenced resource does not exist",
+            ))),
+            ErrorKind::NotNullViolation => Error::BadRequest(payload::Json(with_column(
+                ErrorResponse::new("FIELD_REQUIRED", "A required field is missing"),
+                "REQUIRED",
+                "Field is required",
+            ))),
+            ErrorKind::CheckViolation => Error::BadRequest(payload::Json(with_column(
+                ErrorResponse::new("INVALID_VALUE", "A field has an invalid value"),
+                "INVALID",
+                "Field has an invalid value",
+            ))),
+            _ => Error::internal(db),
+        }
+    }
+}
+
+impl<P: authorization::Permission> From<authorization::Error<P>> for Error {
+    fn from(e: authorization::Error<P>) -> Self {
+        match e {
+            authorization::Error::Forbidden(permission) => Error::Forbidden(payload::Json(
+                ErrorResponse::new(
+                    "MISSING_PERMISSION",
+                    authorization::Error::Forbidden(permission).to_string(),
+                )
+                .with_param("permission", permission.as_str()),
+            )),
+            authorization::Error::Database(e) => Error::from(e),
+        }
+    }
+}
+
+impl From<pagination::Error> for Error {
+    fn from(e: pagination::Error) -> Self {
+        let (field, code) = match e {
+            pagination::Error::Database(e) => return Error::from(e),
+            pagination::Error::Crypto(e) => return Error::from(e),
+            pagination::Error::InvalidLimit => ("limit", "INVALID_LIMIT"),
+            pagination::Error::InvalidSort(..) => ("sort", "INVALID_SORT"),
+            pagination::Error::InvalidCursor => ("cursor", "INVALID_CURSOR"),
+        };
+
+        Error::BadRequest(payload::Json(
+            ErrorResponse::new(code, e.to_string()).with_detail(field, code, e.to_string()),
+        ))
+    }
+}
+
+impl From<projection::Error> for Error {
+    fn from(e: projection::Error) -> Self {
+        let code = match e {
+            projection::Error::UnknownField(..) => "UNKNOWN_FIELD",
+        };
+
+        Error::BadRequest(payload::Json(
+            ErrorResponse::new(code, e.to_string()).with_detail("fields", code, e.to_string()),
+        ))
+    }
+}
+
+impl From<crypto::Error> for Error {
+    fn from(e: crypto::Error) -> Self {
+        Error::internal(e)
+    }
+}
+
+impl From<directory::Error> for Error {
+    fn from(e: directory::Error) -> Self {
+        Error::internal(e)
+    }
+}
@@ -0,0 +1,22 @@
+//! The building blocks shared by every FGACYC API service, such as authentication, auditing,
+//! configuration, database access, domain events, encryption, error responses, masking,
+//! pagination, response projection, syncing Auth0 profiles and the server itself.
+
+pub mod audit;
+pub mod auth;
+pub mod authorization;
+pub mod config;
+pub mod constraint;
+pub mod crypto;
+pub mod database;
+pub mod directory;
+pub mod error;
+pub mod masking;
+pub mod outbox;
+pub mod pagination;
+pub mod projection;
+pub mod request_id;
+pub mod server;
+
+#[cfg(feature = "testing")]
+pub mod testing;
@@ -0,0 +1,122 @@
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{auth::BearerAuth, authorization::Permission};
+
+/// How a field is masked from the callers not allowed to read it.
+#[derive(Debug, Clone, Copy)]
+pub enum Mask {
+    /// Replace the field with `null`, only for optional fields.
+    Null,
+
+    /// Keep the last `n` letters or digits of a string (e.g. `********5678` for 4).
+    Last(usize),
+
+    /// Keep the first character of every word of a string (e.g. `A**** T**`).
+    Initials,
+
+    /// Mask the fields of a nested object.
+    Fields(&'static [(&'static str, Mask)]),
+}
+
+/// A field of an entity masked with `mask` unless the caller holds `permission`.
+#[derive(Debug, Clone, Copy)]
+pub struct Rule<P: Permission> {
+    pub field: &'static str,
+    pub permission: P,
+    pub mask: Mask,
+}
+
+/// How the fields of an entity are masked, declared once per entity. A field may have several
+/// rules guarded by different permissions, they are applied in order, so that a field masked by
+/// one permission may be left out entirely by another.
+#[derive(Debug, Clone, Copy)]
+pub struct Policy<P: Permission>(pub &'static [Rule<P>]);
+
+impl<P: Permission> Policy<P> {
+    /// Mask the fields of `value` that the access token of `auth` does not grant reading.
+    pub fn apply_for<T: Serialize + DeserializeOwned>(&self, auth: &BearerAuth, value: T) -> T {
+        self.apply(value, |permission| auth.is_granted_by_token(permission))
+    }
+
+    /// Mask every field of `value` covered by a rule, for when the reader is not known (e.g.
+    /// the payload of a domain event).
+    pub fn apply_all<T: Serialize + DeserializeOwned>(&self, value: T) -> T {
+        self.apply(value, |_| false)
+    }
+
+    /// Mask the fields of `value` guarded by a permission `is_granted` does not grant.
+    pub fn apply<T: Serialize + DeserializeOwned>(
+        &self,
+        value: T,
+        is_granted: impl Fn(P) -> bool,
+    ) -> T {
+        if self.0.iter().all(|rule| is_granted(rule.permission)) {
+            return value;
+        }
+
+        let mut json = serde_json::to_value(value).expect("entities serialize to JSON");
+        if let Value::Object(object) = &mut json {
+            self.mask(object, is_granted);
+        }
+        serde_json::from_value(json).expect("masked entities deserialize back")
+    }
+
+    /// Mask the fields of the JSON `object` guarded by a permission `is_granted` does not grant.
+    pub fn mask(&self, object: &mut Map<String, Value>, is_granted: impl Fn(P) -> bool) {
+        for rule in self.0 {
+            if is_granted(rule.permission) {
+                continue;
+            }
+            if let Some(value) = object.get_mut(rule.field) {
+                mask_value(value, rule.mask);
+            }
+        }
+    }
+}
+
+fn mask_value(value: &mut Value, mask: Mask) {
+    match (mask, value) {
+        (_, Value::Null) => {}
+        (Mask::Null, value) => *value = Value::Null,
+        (Mask::Last(n), Value::String(s)) => *s = last(s, n),
+        (Mask::Initials, Value::String(s)) => *s = initials(s),
+        (Mask::Fields(fields), Value::Object(object)) => {
+            for (field, mask) in fields {
+                if let Some(value) = object.get_mut(*field) {
+                    mask_value(value, *mask);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Keep the last `n` letters or digits of `value`, dropping any other character (e.g.
+/// `********5678` for 4).
+pub fn last(value: &str, n: usize) -> String {
+    let characters = value
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect::<Vec<_>>();
+    let visible = characters.len().saturating_sub(n);
+    characters
+        .iter()
+        .enumerate()
+        .map(|(i, c)| if i < visible { '*' } else { *c })
+        .collect()
+}
+
+/// Keep the first character of every word of `value` (e.g. `A**** T**`).
+pub fn initials(value: &str) -> String {
+    value
+        .split_whitespace()
+        .map(|word| {
+            word.chars()
+                .enumerate()
+                .map(|(i, c)| if i == 0 { c } else { '*' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,228 @@
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use sqlx::{types::Json, PgConnection};
+
+use crate::database::Database;
+
+/// Deliveries failing this many times in a row are dead, they are only retried on request.
+pub const MAX_ATTEMPTS: i32 = 8;
+
+/// How many deliveries a dispatcher attempts at once.
+const BATCH_SIZE: i64 = 50;
+
+/// How long a delivery is held by the dispatcher attempting it, it is attempted again after
+/// this should the dispatcher stop midway.
+const LEASE: Duration = Duration::from_secs(5 * 60);
+
+/// How long a webhook has to respond.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The header carrying the id of the event, the same across the retries of a delivery so that
+/// webhooks can ignore the events they have already received.
+pub const ID_HEADER: &str = "webhook-id";
+
+/// The header carrying the time a delivery was attempted, in seconds since the epoch.
+pub const TIMESTAMP_HEADER: &str = "webhook-timestamp";
+
+/// The header carrying the signature of a delivery, see [`sign`].
+pub const SIGNATURE_HEADER: &str = "webhook-signature";
+
+/// Publish a domain event of `event_type` (e.g. `attendance.recorded`) to the outbox, meant to
+/// be called with the transaction making the change so that the event is only published if the
+/// change is committed.
+///
+/// A delivery is queued for every active webhook subscribed to `event_type`, the deliveries
+/// are attempted by [`run`] once committed.
+pub async fn publish(
+    conn: &mut PgConnection,
+    event_type: &str,
+    payload: impl Serialize + Send + Sync,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO outbox_event (type, payload, actor, request_id)
+        VALUES (
+            $1,
+            $2,
+            NULLIF(current_setting('audit.actor', TRUE), ''),
+            NULLIF(current_setting('audit.request_id', TRUE), '')
+        )
+        "#,
+    )
+    .bind(event_type)
+    .bind(Json(payload))
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// The body posted to a webhook.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+struct Event {
+    id: i64,
+    #[serde(rename = "type")]
+    event_type: String,
+    payload: serde_json::Value,
+    actor: Option<String>,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct Delivery {
+    delivery_id: i64,
+    attempts: i32,
+    url: String,
+    secret: String,
+    #[sqlx(flatten)]
+    event: Event,
+}
+
+/// Sign the `body` of a delivery attempted at `timestamp` with the `secret` of its webhook,
+/// `v1=` followed by the hex encoded HMAC-SHA256 of `{timestamp}.{body}`.
+///
+/// Webhooks verify a delivery by computing the same signature from the `webhook-timestamp`
+/// header and the raw body, and should reject old timestamps to prevent replays.
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("v1={:x}", mac.finalize().into_bytes())
+}
+
+/// Attempt the deliveries that are due, returning how many were attempted.
+///
+/// Failed deliveries are retried after 30 seconds, doubling every attempt up to 6 hours, and
+/// are dead after [`MAX_ATTEMPTS`]. Deliveries are claimed with `SKIP LOCKED`, so any number of
+/// dispatchers can run at once. Events may arrive more than once and out of order.
+pub async fn dispatch(database: &Database, client: &reqwest::Client) -> Result<usize, sqlx::Error> {
+    let deliveries = sqlx::query_as::<_, Delivery>(
+        r#"
+        WITH due AS (
+            SELECT d.id
+            FROM webhook_delivery d
+            JOIN webhook w ON w.id = d.webhook_id
+            WHERE d.status = 'pending' AND d.next_attempt_at <= NOW() AND w.active
+            ORDER BY d.next_attempt_at
+            LIMIT $1
+            FOR UPDATE OF d SKIP LOCKED
+        )
+        UPDATE webhook_delivery d
+        SET next_attempt_at = NOW() + $2 * INTERVAL '1 second'
+        FROM due, webhook w, outbox_event e
+        WHERE d.id = due.id AND w.id = d.webhook_id AND e.id = d.event_id
+        RETURNING
+            d.id AS delivery_id,
+            d.attempts,
+            w.url,
+            w.secret,
+            e.id,
+            e.type AS event_type,
+            e.payload,
+            e.actor,
+            e.created_at
+        "#,
+    )
+    .bind(BATCH_SIZE)
+    .bind(LEASE.as_secs() as f64)
+    .fetch_all(&database.db)
+    .await?;
+
+    for delivery in &deliveries {
+        let result = deliver(client, delivery).await;
+        if let Err(e) = &result {
+            tracing::warn!(
+                delivery_id = delivery.delivery_id,
+                url = delivery.url,
+                "Webhook delivery failed: {}",
+                e
+            );
+        }
+
+        let status = match &result {
+            Ok(status) => Some(*status),
+            Err(e) => e.status().map(|status| status.as_u16()),
+        }
+        .map(i32::from);
+        sqlx::query(
+            r#"
+            UPDATE webhook_delivery SET
+                attempts             = attempts + 1,
+                last_attempt_at      = NOW(),
+                last_response_status = $2,
+                last_error           = $3,
+                status               = CASE
+                    WHEN $4 THEN 'delivered'
+                    WHEN attempts + 1 >= $5 THEN 'dead'
+                    ELSE 'pending'
+                END::webhook_delivery_status,
+                delivered_at         = CASE WHEN $4 THEN NOW() END,
+                next_attempt_at      = NOW() + LEAST(30 * 2 ^ attempts, 6 * 60 * 60) * INTERVAL '1 second'
+            WHERE id = $1
+            "#,
+        )
+        .bind(delivery.delivery_id)
+        .bind(status)
+        .bind(result.as_ref().err().map(|e| e.to_string()))
+        .bind(result.is_ok())
+        .bind(MAX_ATTEMPTS)
+        .execute(&database.db)
+        .await?;
+    }
+
+    Ok(deliveries.len())
+}
+
+/// Post an event to its webhook, returning the status of the response.
+async fn deliver(client: &reqwest::Client, delivery: &Delivery) -> Result<u16, reqwest::Error> {
+    let body = serde_json::to_vec(&delivery.event).expect("event is always serializable");
+    let timestamp = chrono::Utc::now().timestamp();
+
+    let response = client
+        .post(&delivery.url)
+        .timeout(TIMEOUT)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(ID_HEADER, delivery.event.id.to_string())
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(SIGNATURE_HEADER, sign(&delivery.secret, timestamp, &body))
+        .body(body)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    tracing::debug!(
+        delivery_id = delivery.delivery_id,
+        attempt = delivery.attempts + 1,
+        "Delivered event {} to {}",
+        delivery.event.id,
+        delivery.url
+    );
+
+    Ok(response.status().as_u16())
+}
+
+/// Dispatch the due deliveries every `interval`, meant to be spawned as a background job.
+pub async fn run(database: Database, interval: Duration) {
+    let client = reqwest::Client::new();
+    let mut interval = tokio::time::interval(interval);
+    loop {
+        interval.tick().await;
+
+        // Keep going while there is a backlog rather than waiting for the next tick.
+        loop {
+            match dispatch(&database, &client).await {
+                Ok(attempted) if attempted as i64 == BATCH_SIZE => continue,
+                Ok(_) => break,
+                Err(e) => {
+                    tracing::error!("Webhook dispatch failed: {}", e);
+                    break;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,327 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use poem_openapi::{
+    types::{ParseFromJSON, ToJSON},
+    Object,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgRow, FromRow, PgPool, Postgres, QueryBuilder, Row};
+
+use crate::crypto;
+
+/// The number of items returned when `limit` is not given.
+pub const DEFAULT_LIMIT: i64 = 50;
+
+/// The maximum number of items that can be requested in a single page.
+pub const MAX_LIMIT: i64 = 500;
+
+/// A single page of results returned by a list endpoint.
+#[derive(Object)]
+pub struct Page<T: ParseFromJSON + ToJSON> {
+    /// The items in this page.
+    pub items: Vec<T>,
+
+    /// Opaque cursor to pass as `cursor` to retrieve the next page, absent on the last page.
+    pub next_cursor: Option<String>,
+
+    /// Estimated number of items matching the filters across all pages, only counted for the
+    /// first page. Rows may be added or removed while paging, hence this is only an estimate.
+    pub total_estimate: Option<i64>,
+}
+
+/// The paging parameters accepted by every list endpoint.
+#[derive(Debug, Clone, Default)]
+pub struct Params {
+    /// The maximum number of items to return.
+    pub limit: Option<i64>,
+
+    /// The `next_cursor` returned by the previous page.
+    pub cursor: Option<String>,
+
+    /// The column to sort by, prefixed with `-` for descending order (e.g. `-created_at`).
+    pub sort: Option<String>,
+}
+
+/// A column that a list endpoint allows sorting on.
+#[derive(Debug, Clone, Copy)]
+pub struct Sortable {
+    /// The name exposed through the `sort` parameter.
+    pub name: &'static str,
+
+    /// The SQL expression to sort on, it must never be `NULL`.
+    pub expr: &'static str,
+
+    /// The SQL type of `expr`, used to cast the cursor value back.
+    pub ty: &'static str,
+}
+
+/// Describes how to page through a particular resource.
+#[derive(Debug, Clone, Copy)]
+pub struct Listing {
+    /// The columns to select (e.g. `u.*`).
+    pub select: &'static str,
+
+    /// The `FROM` clause including any joins (e.g. `"user" u`).
+    pub from: &'static str,
+
+    /// A unique SQL expression used to break ties between rows with equal sort values.
+    pub key: &'static str,
+
+    /// The columns that can be sorted on, the first one is the default.
+    pub sortable: &'static [Sortable],
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("limit must be between 1 and {}", MAX_LIMIT)]
+    InvalidLimit,
+
+    #[error("cannot sort by '{0}', expected one of: {1}")]
+    InvalidSort(String, String),
+
+    #[error("cursor is invalid or was issued for a different sort")]
+    InvalidCursor,
+
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+
+    #[error(transparent)]
+    Crypto(#[from] crypto::Error),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Cursor {
+    sort: String,
+    value: String,
+    key: String,
+}
+
+impl Cursor {
+    fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).expect("cursor is always serializable"))
+    }
+
+    fn decode(cursor: &str) -> Result<Self, Error> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(cursor)
+            .map_err(|_| Error::InvalidCursor)?;
+        serde_json::from_slice(&bytes).map_err(|_| Error::InvalidCursor)
+    }
+}
+
+/// A sort computed from the request rather than from columns alone, such as the relevance of a
+/// search.
+pub struct Rank<F> {
+    /// The name exposed through the `sort` parameter.
+    pub name: &'static str,
+
+    /// Pushes the SQL expression to sort on, it must never be `NULL`. It is pushed several
+    /// times, so the values it binds are bound again each time.
+    pub expr: F,
+
+    /// The SQL type of `expr`, used to cast the cursor value back.
+    pub ty: &'static str,
+}
+
+enum SortExpr<'a, F> {
+    Column(&'static str),
+    Rank(&'a F),
+}
+
+impl<F: Fn(&mut QueryBuilder<'static, Postgres>)> SortExpr<'_, F> {
+    fn push(&self, query: &mut QueryBuilder<'static, Postgres>) {
+        match self {
+            Self::Column(expr) => {
+                query.push(*expr);
+            }
+            Self::Rank(expr) => expr(query),
+        }
+    }
+}
+
+impl Listing {
+    /// Fetch a single page of `T` that matches the filters pushed by `filter`.
+    ///
+    /// The query is built as `WHERE TRUE`, so `filter` should push each condition prefixed with
+    /// `AND`. It is called twice, once for the page itself and once for the total estimate.
+    pub async fn fetch<T>(
+        &self,
+        db: &PgPool,
+        params: &Params,
+        filter: impl Fn(&mut QueryBuilder<'static, Postgres>),
+    ) -> Result<Page<T>, Error>
+    where
+        T: for<'r> FromRow<'r, PgRow> + ParseFromJSON + ToJSON,
+    {
+        self.fetch_ranked(
+            db,
+            params,
+            None::<Rank<fn(&mut QueryBuilder<'static, Postgres>)>>,
+            filter,
+        )
+        .await
+    }
+
+    /// Like [`Listing::fetch`], but also sortable on `rank` when given, in which case it is the
+    /// default sort in descending order.
+    pub async fn fetch_ranked<T, F>(
+        &self,
+        db: &PgPool,
+        params: &Params,
+        rank: Option<Rank<F>>,
+        filter: impl Fn(&mut QueryBuilder<'static, Postgres>),
+    ) -> Result<Page<T>, Error>
+    where
+        T: for<'r> FromRow<'r, PgRow> + ParseFromJSON + ToJSON,
+        F: Fn(&mut QueryBuilder<'static, Postgres>),
+    {
+        self.fetch_ranked_map(db, params, rank, filter, Ok).await
+    }
+
+    /// Like [`Listing::fetch`], but the rows are read as `R` and made into items by `map`, e.g.
+    /// to decrypt them.
+    pub async fn fetch_map<R, T>(
+        &self,
+        db: &PgPool,
+        params: &Params,
+        filter: impl Fn(&mut QueryBuilder<'static, Postgres>),
+        map: impl Fn(R) -> Result<T, crypto::Error>,
+    ) -> Result<Page<T>, Error>
+    where
+        R: for<'r> FromRow<'r, PgRow>,
+        T: ParseFromJSON + ToJSON,
+    {
+        self.fetch_ranked_map(
+            db,
+            params,
+            None::<Rank<fn(&mut QueryBuilder<'static, Postgres>)>>,
+            filter,
+            map,
+        )
+        .await
+    }
+
+    /// Like [`Listing::fetch_ranked`], but the rows are read as `R` and made into items by `map`.
+    pub async fn fetch_ranked_map<R, T, F>(
+        &self,
+        db: &PgPool,
+        params: &Params,
+        rank: Option<Rank<F>>,
+        filter: impl Fn(&mut QueryBuilder<'static, Postgres>),
+        map: impl Fn(R) -> Result<T, crypto::Error>,
+    ) -> Result<Page<T>, Error>
+    where
+        R: for<'r> FromRow<'r, PgRow>,
+        T: ParseFromJSON + ToJSON,
+        F: Fn(&mut QueryBuilder<'static, Postgres>),
+    {
+        let limit = params.limit.unwrap_or(DEFAULT_LIMIT);
+        if !(1..=MAX_LIMIT).contains(&limit) {
+            return Err(Error::InvalidLimit);
+        }
+
+        let sort_param = params.sort.clone().unwrap_or_else(|| match &rank {
+            Some(rank) => format!("-{}", rank.name),
+            None => self.sortable[0].name.to_string(),
+        });
+        let (descending, name) = match sort_param.strip_prefix('-') {
+            Some(name) => (true, name),
+            None => (false, sort_param.as_str()),
+        };
+        let (sort, sort_ty) = match rank.as_ref().filter(|rank| rank.name == name) {
+            Some(rank) => (SortExpr::Rank(&rank.expr), rank.ty),
+            None => {
+                let sortable = self
+                    .sortable
+                    .iter()
+                    .find(|sortable| sortable.name == name)
+                    .ok_or_else(|| {
+                        Error::InvalidSort(
+                            name.to_string(),
+                            self.sortable
+                                .iter()
+                                .map(|sortable| sortable.name)
+                                .chain(rank.as_ref().map(|rank| rank.name))
+                                .collect::<Vec<_>>()
+                                .join(", "),
+                        )
+                    })?;
+                (SortExpr::Column(sortable.expr), sortable.ty)
+            }
+        };
+
+        let cursor = params.cursor.as_deref().map(Cursor::decode).transpose()?;
+        if cursor
+            .as_ref()
+            .is_some_and(|cursor| cursor.sort != sort_param)
+        {
+            return Err(Error::InvalidCursor);
+        }
+
+        let mut query = QueryBuilder::new(format!("SELECT {}, (", self.select));
+        sort.push(&mut query);
+        query.push(format!(
+            ")::TEXT AS page_sort, ({})::TEXT AS page_key FROM {} WHERE TRUE",
+            self.key, self.from
+        ));
+        filter(&mut query);
+        if let Some(cursor) = cursor {
+            query.push(" AND (");
+            sort.push(&mut query);
+            query
+                .push(format!(
+                    ", {}) {} (",
+                    self.key,
+                    if descending { "<" } else { ">" }
+                ))
+                .push_bind(cursor.value)
+                .push(format!("::{}, ", sort_ty))
+                .push_bind(cursor.key)
+                .push(")");
+        }
+        let direction = if descending { "DESC" } else { "ASC" };
+        query.push(" ORDER BY ");
+        sort.push(&mut query);
+        query
+            .push(format!(" {direction}, {} {direction} LIMIT ", self.key))
+            .push_bind(limit + 1);
+
+        let mut rows = query.build().fetch_all(db).await?;
+
+        let next_cursor = if rows.len() as i64 > limit {
+            rows.truncate(limit as usize);
+            let last = rows.last().expect("limit is at least 1");
+            Some(
+                Cursor {
+                    sort: sort_param.clone(),
+                    value: last.try_get("page_sort")?,
+                    key: last.try_get("page_key")?,
+                }
+                .encode(),
+            )
+        } else {
+            None
+        };
+
+        let items = rows
+            .iter()
+            .map(|row| Ok(map(R::from_row(row)?)?))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        // Counting every matching row is as costly as listing them, clients paging through the
+        // results already have the count of the first page.
+        let total_estimate = if params.cursor.is_none() {
+            let mut count =
+                QueryBuilder::new(format!("SELECT COUNT(*) FROM {} WHERE TRUE", self.from));
+            filter(&mut count);
+            Some(count.build_query_scalar::<i64>().fetch_one(db).await?)
+        } else {
+            None
+        };
+
+        Ok(Page {
+            items,
+            next_cursor,
+            total_estimate,
+        })
+    }
+}
@@ -0,0 +1,156 @@
+use std::{borrow::Cow, collections::BTreeSet, marker::PhantomData};
+
+use poem_openapi::{
+    registry::{MetaSchema, MetaSchemaRef, Registry},
+    types::{ParseError, ParseFromJSON, ParseResult, ToJSON, Type},
+};
+use serde_json::Value;
+
+use crate::pagination::Page;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("unknown field '{0}', expected some of: {1}")]
+    UnknownField(String, String),
+}
+
+/// The fields of an entity to return, as given by the `fields` parameter of the endpoints
+/// retrieving entities: their names separated by commas (e.g. `id,name,avatar_url`). Every
+/// field is returned when it is not given.
+#[derive(Debug, Clone, Default)]
+pub struct Fields(Option<BTreeSet<String>>);
+
+/// An entity `T` with only the [`Fields`] asked for. It is documented as `T` with every field
+/// optional.
+pub struct Projected<T> {
+    value: Value,
+    entity: PhantomData<fn() -> T>,
+}
+
+impl Fields {
+    /// Parse the `fields` parameter, rejecting the names that are not fields of `T`.
+    pub fn parse<T: Type>(fields: Option<String>) -> Result<Self, Error> {
+        let Some(fields) = fields else {
+            return Ok(Self(None));
+        };
+
+        let known = properties::<T>();
+        let fields = fields
+            .split(',')
+            .map(str::trim)
+            .filter(|field| !field.is_empty())
+            .map(|field| {
+                if known.contains(&field) {
+                    Ok(field.to_string())
+                } else {
+                    Err(Error::UnknownField(field.to_string(), known.join(", ")))
+                }
+            })
+            .collect::<Result<BTreeSet<_>, _>>()?;
+
+        Ok(Self((!fields.is_empty()).then_some(fields)))
+    }
+
+    pub fn project<T: ToJSON>(&self, value: T) -> Projected<T> {
+        let mut value = value.to_json().unwrap_or_default();
+        if let (Some(fields), Value::Object(object)) = (&self.0, &mut value) {
+            object.retain(|name, _| fields.contains(name));
+        }
+
+        Projected {
+            value,
+            entity: PhantomData,
+        }
+    }
+
+    pub fn project_all<T: ToJSON>(&self, values: Vec<T>) -> Vec<Projected<T>> {
+        values
+            .into_iter()
+            .map(|value| self.project(value))
+            .collect()
+    }
+
+    pub fn project_page<T: ParseFromJSON + ToJSON>(&self, page: Page<T>) -> Page<Projected<T>> {
+        Page {
+            items: self.project_all(page.items),
+            next_cursor: page.next_cursor,
+            total_estimate: page.total_estimate,
+        }
+    }
+}
+
+/// The names of the fields of `T` as documented.
+fn properties<T: Type>() -> Vec<&'static str> {
+    let mut registry = Registry::new();
+    T::register(&mut registry);
+
+    let schema = match T::schema_ref() {
+        MetaSchemaRef::Inline(schema) => Some(*schema),
+        MetaSchemaRef::Reference(name) => registry.schemas.remove(&name),
+    };
+    schema
+        .map(|schema| {
+            schema
+                .properties
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+impl<T: Type> Type for Projected<T> {
+    const IS_REQUIRED: bool = true;
+
+    type RawValueType = Self;
+
+    type RawElementValueType = Self;
+
+    fn name() -> Cow<'static, str> {
+        format!("Partial{}", T::name()).into()
+    }
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Reference(Self::name().into_owned())
+    }
+
+    fn register(registry: &mut Registry) {
+        T::register(registry);
+        registry.create_schema::<Self, _>(Self::name().into_owned(), |registry| {
+            let mut schema = match T::schema_ref() {
+                MetaSchemaRef::Inline(schema) => *schema,
+                MetaSchemaRef::Reference(name) => registry
+                    .schemas
+                    .get(&name)
+                    .cloned()
+                    .unwrap_or_else(|| MetaSchema::new("object")),
+            };
+            schema.rust_typename = Some(std::any::type_name::<Self>());
+            schema.required.clear();
+            schema
+        });
+    }
+
+    fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+        Some(self)
+    }
+
+    fn raw_element_iter<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = &'a Self::RawElementValueType> + 'a> {
+        Box::new(self.as_raw_value().into_iter())
+    }
+}
+
+impl<T: ParseFromJSON + ToJSON> ParseFromJSON for Projected<T> {
+    fn parse_from_json(value: Option<Value>) -> ParseResult<Self> {
+        let value = T::parse_from_json(value).map_err(ParseError::propagate)?;
+        Ok(Fields::default().project(value))
+    }
+}
+
+impl<T: Type> ToJSON for Projected<T> {
+    fn to_json(&self) -> Option<Value> {
+        Some(self.value.clone())
+    }
+}
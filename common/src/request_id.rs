@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use poem::{http::HeaderValue, Endpoint, IntoResponse, Request, Response, Result};
+use tracing::Instrument;
+
+use crate::error::ErrorResponse;
+
+/// The header carrying the request id, an id sent by the client (e.g. by a load balancer) is
+/// reused instead of generating a new one.
+pub const HEADER: &str = "x-request-id";
+
+/// The id of the current request, available to handlers through `web::Data<&RequestId>`.
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+tokio::task_local! {
+    static CURRENT: String;
+}
+
+impl RequestId {
+    /// The id of the request being handled by the current task, if any, for code that has no
+    /// access to the request such as `crate::audit`.
+    pub fn current() -> Option<String> {
+        CURRENT.try_with(String::clone).ok()
+    }
+}
+
+/// Assign every request an id, which is attached to its logs, returned in the `X-Request-Id`
+/// header and filled into the `request_id` of error responses.
+pub async fn request_id<E: Endpoint>(next: Arc<E>, mut req: Request) -> Result<Response> {
+    let id = req
+        .headers()
+        .get(HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| !value.is_empty() && value.len() <= 128)
+        .map(str::to_string)
+        .unwrap_or_else(|| ulid::Ulid::new().to_string());
+    req.set_data(RequestId(id.clone()));
+
+    let mut resp = match CURRENT
+        .scope(id.clone(), next.call(req))
+        .instrument(tracing::info_span!("request", id = %id))
+        .await
+    {
+        Ok(resp) => resp.into_response(),
+        Err(e) => e.into_response(),
+    };
+
+    let is_error = resp.status().is_client_error() || resp.status().is_server_error();
+    let is_json = resp
+        .content_type()
+        .is_some_and(|content_type| content_type.starts_with("application/json"));
+    if is_error && is_json {
+        let body = resp.take_body().into_bytes().await?;
+        match serde_json::from_slice::<ErrorResponse>(&body) {
+            Ok(mut error) => {
+                error.request_id = Some(id.clone());
+                resp.set_body(serde_json::to_vec(&error).expect("error is always serializable"));
+            }
+            Err(_) => resp.set_body(body),
+        }
+    }
+
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        resp.headers_mut().insert(HEADER, value);
+    }
+
+    Ok(resp)
+}
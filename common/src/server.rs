@@ -0,0 +1,197 @@
+use std::{future::Future, marker::PhantomData, pin::Pin, sync::Arc};
+
+use clap::Parser;
+use poem::{
+    middleware::{Cors, Tracing},
+    Endpoint, EndpointExt, Response, Route,
+};
+use poem_openapi::{payload, LicenseObject, OpenApi, OpenApiService};
+
+use crate::{config::Config, crypto::Keyring, database::Database, request_id::request_id};
+
+/// Builds and runs an API service along with the endpoints and middlewares every service shares:
+/// `/docs`, `/ping`, `/specs` and `/license`, request tracing, request ids and CORS.
+///
+/// The server listens on the configured address, or runs on AWS Lambda when the `lambda`
+/// feature is enabled. It is configured by `C`, the shared [`Config`] unless the service has
+/// settings of its own, see [`Server::with_config`].
+pub struct Server<C = Config> {
+    title: String,
+    version: String,
+    log_target: String,
+    jobs: Vec<Job<C>>,
+    config: PhantomData<C>,
+}
+
+type Job<C> = Box<dyn FnOnce(Database, C) -> Pin<Box<dyn Future<Output = ()> + Send>>>;
+
+impl Server {
+    /// Create a server for the service named `title` (e.g. `FGACYC Identity`).
+    pub fn new(title: impl Into<String>, version: impl Into<String>) -> Self {
+        Self::with_config(title, version)
+    }
+}
+
+impl<C> Server<C>
+where
+    C: Parser + AsRef<Config> + Clone + Send + 'static,
+{
+    /// Create a server for the service named `title` configured by `C`, the configuration of the
+    /// service flattening the shared [`Config`].
+    pub fn with_config(title: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            version: version.into(),
+            log_target: String::new(),
+            jobs: Vec::new(),
+            config: PhantomData,
+        }
+    }
+
+    /// The tracing target of the service, usually its crate name, used to enable its logs when
+    /// `RUST_LOG` is not set.
+    pub fn log_target(mut self, log_target: impl Into<String>) -> Self {
+        self.log_target = log_target.into();
+        self
+    }
+
+    /// Spawn `job` with the configuration once connected to the database and leave it running
+    /// alongside the server, e.g. a loop doing periodic maintenance. Jobs are not run on AWS
+    /// Lambda, where nothing runs in between requests.
+    pub fn job<F, Fut>(mut self, job: F) -> Self
+    where
+        F: FnOnce(Database, C) -> Fut + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.jobs.push(Box::new(move |database, config| {
+            Box::pin(job(database, config))
+        }));
+        self
+    }
+
+    /// Run the server until it is stopped, `api` is called with the Auth0 management client and
+    /// the configuration to build the service's routes, failing to start when it fails.
+    pub async fn run<T, F>(mut self, api: F) -> Result<(), anyhow::Error>
+    where
+        T: OpenApi + 'static,
+        F: FnOnce(auth0::management::Api, &C) -> Result<T, anyhow::Error>,
+    {
+        // This returns an error if the `.env` file doesn't exist, but that's not what we want
+        // since we're not going to use a `.env` file if we deploy this application.
+        dotenvy::dotenv().ok();
+
+        // Set up tracing
+        if std::env::var_os("RUST_LOG").is_none() {
+            #[cfg(debug_assertions)]
+            let level = "debug";
+            #[cfg(not(debug_assertions))]
+            let level = "info";
+
+            let mut filter = format!("poem={level},common={level},auth0=info,sqlx=info");
+            if !self.log_target.is_empty() {
+                filter.push_str(&format!(",{}={level}", self.log_target));
+            }
+            std::env::set_var("RUST_LOG", filter);
+        }
+        tracing_subscriber::fmt::init();
+
+        // Parse configuration
+        let service_config = C::parse();
+        let config = service_config.as_ref();
+
+        let keyring = Keyring::from_config(config)?;
+
+        // Make a connection to the database
+        let database = Database::new(&config.database_url).await?;
+
+        // Make the api clients for Auth0
+        let auth = auth0::authentication::Api::init(
+            reqwest::Url::parse(&config.auth0_domain)?,
+            auth0::authentication::AuthenticationMethod::ClientIDClientSecret(
+                config.auth0_client_id.clone(),
+                config.auth0_client_secret.clone(),
+            ),
+        );
+        let management = auth0::management::Api::init(
+            reqwest::Url::parse(&config.auth0_domain)?,
+            config.auth0_client_id.clone(),
+            config.auth0_client_secret.clone(),
+        )
+        .await?;
+
+        let api = api(management, &service_config)?;
+
+        let jobs = std::mem::take(&mut self.jobs);
+        #[cfg(not(feature = "lambda"))]
+        for job in jobs {
+            tokio::spawn(job(database.clone(), service_config.clone()));
+        }
+        #[cfg(feature = "lambda")]
+        if !jobs.is_empty() {
+            tracing::warn!("Background jobs are not run on AWS Lambda");
+        }
+
+        let routes = self.endpoint(api, config, database, auth, keyring);
+
+        #[cfg(not(feature = "lambda"))]
+        poem::Server::new(poem::listener::TcpListener::bind(&format!(
+            "{}:{}",
+            config.address, config.port
+        )))
+        .run(routes)
+        .await?;
+
+        #[cfg(feature = "lambda")]
+        poem_lambda::run(routes)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed running on lambda: {:?}", e))?;
+
+        Ok(())
+    }
+
+    /// Build the endpoint serving `api` along with the shared endpoints and middlewares, without
+    /// binding to any address. Handlers encrypt sensitive data with `keyring`.
+    pub fn endpoint<T: OpenApi + 'static>(
+        self,
+        api: T,
+        config: &Config,
+        database: Database,
+        auth: auth0::authentication::Api,
+        keyring: Keyring,
+    ) -> impl Endpoint<Output = Response> {
+        let service = OpenApiService::new(api, self.title, self.version)
+            .server(&config.oai_address)
+            .license(LicenseObject::new("MIT").url(format!("{}/license", config.oai_address)));
+        let rapidoc = service.rapidoc();
+        let specs = service.spec();
+
+        Route::new()
+            .nest("/", service)
+            .nest("/docs", rapidoc)
+            .at(
+                "/ping",
+                poem::endpoint::make_sync(move |_| payload::PlainText("PONG")),
+            )
+            .at(
+                "/specs",
+                poem::endpoint::make_sync(move |_| {
+                    payload::Json(
+                        serde_json::from_str::<serde_json::Value>(&specs.clone()).unwrap(),
+                    )
+                }),
+            )
+            .at(
+                "/license",
+                poem::endpoint::make_sync(move |_| {
+                    payload::PlainText(include_str!("../../LICENSE"))
+                }),
+            )
+            .with(Tracing)
+            .with(Cors::new())
+            .around(request_id)
+            .data(database)
+            .data(Arc::new(auth))
+            .data(keyring)
+            .data(config.clone())
+    }
+}
@@ -0,0 +1,116 @@
+use std::{path::PathBuf, str::FromStr, sync::OnceLock};
+
+use sqlx::{
+    postgres::{PgConnectOptions, PgPoolOptions},
+    Connection, Executor, PgConnection,
+};
+use testcontainers_modules::{
+    postgres::Postgres,
+    testcontainers::{runners::AsyncRunner, ImageExt},
+};
+
+use crate::database::Database;
+
+/// The dbmate migrations shared by every service.
+const MIGRATIONS_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../db/migrations");
+
+/// Serializes `CREATE DATABASE`, which fails when another one is copying the template.
+const CREATE_DATABASE_LOCK: i64 = 0x666761637963;
+
+/// The URL of the Postgres server the test databases are created on.
+///
+/// `TEST_DATABASE_URL` points at an existing server, otherwise a Postgres container is started
+/// once per test binary and removed by testcontainers when the binary exits.
+fn server_url() -> &'static str {
+    static URL: OnceLock<String> = OnceLock::new();
+
+    URL.get_or_init(|| {
+        if let Ok(url) = std::env::var("TEST_DATABASE_URL") {
+            return url;
+        }
+
+        // Every test runs on its own runtime, the container is owned by a runtime of its own so
+        // that it outlives the test that happened to start it.
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("failed to build the runtime of the Postgres container")
+                .block_on(async move {
+                    let container = Postgres::default()
+                        .with_tag("16-alpine")
+                        .start()
+                        .await
+                        .expect(
+                            "failed to start a Postgres container, is Docker running? Set \
+                             TEST_DATABASE_URL to use an existing server instead",
+                        );
+                    let host = container.get_host().await.expect("container has a host");
+                    let port = container
+                        .get_host_port_ipv4(5432)
+                        .await
+                        .expect("container exposes Postgres");
+
+                    tx.send(format!(
+                        "postgres://postgres:postgres@{host}:{port}/postgres"
+                    ))
+                    .expect("test binary is waiting for the URL");
+
+                    std::future::pending::<()>().await
+                })
+        });
+
+        rx.recv().expect("the Postgres container failed to start")
+    })
+}
+
+/// Create a database with every migration applied, each test gets its own so that tests can run
+/// in parallel. Databases are left behind for inspection, the server is meant to be thrown away.
+pub(super) async fn create() -> Database {
+    let options = PgConnectOptions::from_str(server_url()).expect("TEST_DATABASE_URL is valid");
+    let name = format!("test_{}", ulid::Ulid::new().to_string().to_lowercase());
+
+    let mut connection = PgConnection::connect_with(&options)
+        .await
+        .expect("failed to connect to the test Postgres server");
+    sqlx::query("SELECT pg_advisory_lock($1)")
+        .bind(CREATE_DATABASE_LOCK)
+        .execute(&mut connection)
+        .await
+        .expect("failed to lock the test Postgres server");
+    connection
+        .execute(format!(r#"CREATE DATABASE "{name}""#).as_str())
+        .await
+        .expect("failed to create the test database");
+    connection.close().await.ok();
+
+    let db = PgPoolOptions::new()
+        .max_connections(5)
+        .connect_with(options.database(&name))
+        .await
+        .expect("failed to connect to the test database");
+
+    let mut migrations = std::fs::read_dir(MIGRATIONS_DIR)
+        .expect("failed to read the migrations")
+        .map(|entry| entry.expect("failed to read the migrations").path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "sql"))
+        .collect::<Vec<PathBuf>>();
+    migrations.sort();
+
+    for path in migrations {
+        let migration = std::fs::read_to_string(&path).expect("failed to read a migration");
+
+        // dbmate migrations hold both directions, separated by a `-- migrate:down` marker.
+        let up = migration
+            .split("-- migrate:down")
+            .next()
+            .unwrap_or_default();
+
+        db.execute(up)
+            .await
+            .unwrap_or_else(|e| panic!("failed to apply {}: {}", path.display(), e));
+    }
+
+    Database { db }
+}
@@ -0,0 +1,70 @@
+/// A `multipart/form-data` body, sent with [`super::TestRequest::multipart`].
+#[derive(Debug, Clone)]
+pub struct Form {
+    boundary: String,
+    body: Vec<u8>,
+}
+
+impl Default for Form {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Form {
+    pub fn new() -> Self {
+        Self {
+            boundary: format!("boundary{}", ulid::Ulid::new()),
+            body: Vec::new(),
+        }
+    }
+
+    /// Add a text field.
+    pub fn text(self, name: &str, value: impl Into<String>) -> Self {
+        self.part(
+            format!("form-data; name=\"{}\"", name),
+            None,
+            value.into().into_bytes(),
+        )
+    }
+
+    /// Add a file field named `file_name`.
+    pub fn file(
+        self,
+        name: &str,
+        file_name: &str,
+        content_type: &str,
+        content: impl Into<Vec<u8>>,
+    ) -> Self {
+        self.part(
+            format!("form-data; name=\"{}\"; filename=\"{}\"", name, file_name),
+            Some(content_type),
+            content.into(),
+        )
+    }
+
+    fn part(mut self, disposition: String, content_type: Option<&str>, content: Vec<u8>) -> Self {
+        self.body
+            .extend_from_slice(format!("--{}\r\n", self.boundary).as_bytes());
+        self.body
+            .extend_from_slice(format!("Content-Disposition: {}\r\n", disposition).as_bytes());
+        if let Some(content_type) = content_type {
+            self.body
+                .extend_from_slice(format!("Content-Type: {}\r\n", content_type).as_bytes());
+        }
+        self.body.extend_from_slice(b"\r\n");
+        self.body.extend_from_slice(&content);
+        self.body.extend_from_slice(b"\r\n");
+        self
+    }
+
+    pub(super) fn content_type(&self) -> String {
+        format!("multipart/form-data; boundary={}", self.boundary)
+    }
+
+    pub(super) fn into_body(mut self) -> Vec<u8> {
+        self.body
+            .extend_from_slice(format!("--{}--\r\n", self.boundary).as_bytes());
+        self.body
+    }
+}
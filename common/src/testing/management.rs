@@ -0,0 +1,287 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+use poem::{
+    handler,
+    http::StatusCode,
+    listener::{Acceptor, Listener, TcpListener},
+    web::{Data, Json, Path, Query},
+    EndpointExt, Route,
+};
+use serde::{Deserialize, Serialize};
+
+type Roles = Arc<Mutex<BTreeMap<String, Role>>>;
+
+type Users = Arc<Mutex<BTreeMap<String, serde_json::Value>>>;
+
+/// The ids of the users Auth0 was asked to send a verification email to.
+type Verifications = Arc<Mutex<Vec<String>>>;
+
+/// A role as stored by the mock, the subset of the Auth0 role the services use.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Role {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+}
+
+#[derive(Deserialize)]
+struct CreateRole {
+    name: String,
+    description: String,
+}
+
+#[derive(Deserialize)]
+struct UpdateRole {
+    name: Option<String>,
+    description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ListUsers {
+    per_page: usize,
+    q: Option<String>,
+}
+
+/// A local HTTP mock of the parts of the Auth0 management API the services call: the client
+/// credentials grant, the roles endpoints and the users endpoints.
+#[derive(Clone)]
+pub struct MockManagement {
+    url: String,
+    roles: Roles,
+    users: Users,
+    verifications: Verifications,
+}
+
+impl MockManagement {
+    /// Start the mock on a random local port, it stops along with the test runtime.
+    pub async fn start() -> Self {
+        let roles = Roles::default();
+        let users = Users::default();
+        let verifications = Verifications::default();
+
+        let app = Route::new()
+            .at("/oauth/token", poem::post(token))
+            .at("/api/v2/roles", poem::post(create_role))
+            .at(
+                "/api/v2/roles/:id",
+                poem::get(get_role).patch(update_role).delete(delete_role),
+            )
+            .at("/api/v2/users", poem::get(list_users))
+            .at(
+                "/api/v2/users/:id",
+                poem::get(get_user).patch(update_user).delete(delete_user),
+            )
+            .data(roles.clone())
+            .data(users.clone())
+            .data(verifications.clone());
+
+        let acceptor = TcpListener::bind("127.0.0.1:0")
+            .into_acceptor()
+            .await
+            .expect("failed to bind the Auth0 mock");
+        let url = format!(
+            "http://{}/",
+            acceptor.local_addr()[0]
+                .as_socket_addr()
+                .expect("Auth0 mock listens on TCP")
+        );
+
+        tokio::spawn(poem::Server::new_with_acceptor(acceptor).run(app));
+
+        Self {
+            url,
+            roles,
+            users,
+            verifications,
+        }
+    }
+
+    /// The base URL of the mock, configured as the Auth0 domain of the app.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// A management client of the mock, as the app is configured with, to run the jobs syncing
+    /// users with Auth0.
+    pub async fn client(&self) -> auth0::management::Api {
+        auth0::management::Api::init(
+            reqwest::Url::parse(&self.url).expect("mock URL is valid"),
+            "test".to_string(),
+            "test".to_string(),
+        )
+        .await
+        .expect("failed to initialise the Auth0 management client against the mock")
+    }
+
+    /// The roles currently stored by the mock.
+    pub fn roles(&self) -> Vec<Role> {
+        self.roles.lock().unwrap().values().cloned().collect()
+    }
+
+    pub fn role(&self, id: &str) -> Option<Role> {
+        self.roles.lock().unwrap().get(id).cloned()
+    }
+
+    /// Store `role` as if it was created on Auth0 beforehand.
+    pub fn insert_role(&self, role: Role) {
+        self.roles.lock().unwrap().insert(role.id.clone(), role);
+    }
+
+    /// The profile of the user `id` as currently stored by the mock.
+    pub fn user(&self, id: &str) -> Option<serde_json::Value> {
+        self.users.lock().unwrap().get(id).cloned()
+    }
+
+    /// Store the profile `user` as if the user signed up on Auth0 beforehand, it must have a
+    /// `user_id`. Users without a `created_at` sign up now.
+    pub fn insert_user(&self, mut user: serde_json::Value) {
+        let id = user["user_id"]
+            .as_str()
+            .expect("user has a user_id")
+            .to_string();
+        if user["created_at"].is_null() {
+            user["created_at"] = serde_json::json!(
+                chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+            );
+        }
+        self.users.lock().unwrap().insert(id, user);
+    }
+
+    /// The ids of the users Auth0 was asked to send a verification email to, in order.
+    pub fn verification_emails(&self) -> Vec<String> {
+        self.verifications.lock().unwrap().clone()
+    }
+}
+
+#[handler]
+fn token() -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "access_token": "test",
+        "token_type": "Bearer",
+        "expires_in": 86400,
+        "scope": concat!(
+            "read:roles create:roles update:roles delete:roles ",
+            "read:users update:users delete:users",
+        ),
+    }))
+}
+
+#[handler]
+fn create_role(roles: Data<&Roles>, Json(body): Json<CreateRole>) -> Json<Role> {
+    let role = Role {
+        id: format!("rol_{}", ulid::Ulid::new()),
+        name: body.name,
+        description: body.description,
+    };
+    roles.lock().unwrap().insert(role.id.clone(), role.clone());
+
+    Json(role)
+}
+
+#[handler]
+fn get_role(roles: Data<&Roles>, Path(id): Path<String>) -> Result<Json<Role>, StatusCode> {
+    match roles.lock().unwrap().get(&id) {
+        Some(role) => Ok(Json(role.clone())),
+        None => Err(StatusCode::NOT_FOUND),
+    }
+}
+
+#[handler]
+fn update_role(
+    roles: Data<&Roles>,
+    Path(id): Path<String>,
+    Json(body): Json<UpdateRole>,
+) -> Result<Json<Role>, StatusCode> {
+    let mut roles = roles.lock().unwrap();
+    let role = roles.get_mut(&id).ok_or(StatusCode::NOT_FOUND)?;
+
+    if let Some(name) = body.name {
+        role.name = name;
+    }
+    if let Some(description) = body.description {
+        role.description = description;
+    }
+
+    Ok(Json(role.clone()))
+}
+
+#[handler]
+fn delete_role(roles: Data<&Roles>, Path(id): Path<String>) -> Result<(), StatusCode> {
+    match roles.lock().unwrap().remove(&id) {
+        Some(_) => Ok(()),
+        None => Err(StatusCode::NOT_FOUND),
+    }
+}
+
+/// Only the searches the services make are supported: from a signup time onwards, in the order
+/// users signed up.
+#[handler]
+fn list_users(users: Data<&Users>, Query(query): Query<ListUsers>) -> Json<Vec<serde_json::Value>> {
+    let created_from = query.q.as_deref().map(|q| {
+        q.trim_start_matches("created_at:[\"")
+            .trim_end_matches("\" TO *]")
+            .to_string()
+    });
+    let created_at =
+        |user: &serde_json::Value| user["created_at"].as_str().unwrap_or_default().to_string();
+
+    let mut users: Vec<_> = users
+        .lock()
+        .unwrap()
+        .values()
+        .filter(|user| created_from.iter().all(|from| created_at(user) >= *from))
+        .cloned()
+        .collect();
+    users.sort_by_key(created_at);
+    users.truncate(query.per_page);
+
+    Json(users)
+}
+
+#[handler]
+fn get_user(
+    users: Data<&Users>,
+    Path(id): Path<String>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    match users.lock().unwrap().get(&id) {
+        Some(user) => Ok(Json(user.clone())),
+        None => Err(StatusCode::NOT_FOUND),
+    }
+}
+
+#[handler]
+fn update_user(
+    users: Data<&Users>,
+    verifications: Data<&Verifications>,
+    Path(id): Path<String>,
+    Json(body): Json<serde_json::Map<String, serde_json::Value>>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let mut users = users.lock().unwrap();
+    let user = users
+        .get_mut(&id)
+        .and_then(serde_json::Value::as_object_mut)
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    for (key, value) in body {
+        if key == "verify_email" {
+            if value == serde_json::Value::Bool(true) {
+                verifications.lock().unwrap().push(id.clone());
+            }
+        } else {
+            user.insert(key, value);
+        }
+    }
+
+    Ok(Json(serde_json::Value::Object(user.clone())))
+}
+
+#[handler]
+fn delete_user(users: Data<&Users>, Path(id): Path<String>) -> Result<(), StatusCode> {
+    match users.lock().unwrap().remove(&id) {
+        Some(_) => Ok(()),
+        None => Err(StatusCode::NOT_FOUND),
+    }
+}
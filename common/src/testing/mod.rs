@@ -0,0 +1,244 @@
+//! Support for the end-to-end tests of the services, enabled by the `testing` feature.
+//!
+//! A [`TestApp`] serves the routes of a service exactly like [`crate::server::Server`] does, but
+//! against a fresh database on a throwaway Postgres and a local mock of the Auth0 management API.
+//! Requests are authenticated with tokens minted by [`Token`], signed with a test key pair that
+//! the app is configured to trust.
+
+use poem::{
+    http::{header, HeaderMap, Method, StatusCode, Uri},
+    Endpoint, EndpointExt, Request,
+};
+use poem_openapi::OpenApi;
+use serde::Serialize;
+
+use crate::{config::Config, crypto::Keyring, database::Database, server::Server};
+
+mod database;
+mod form;
+mod management;
+mod token;
+
+pub use form::Form;
+pub use management::{MockManagement, Role};
+pub use token::Token;
+
+/// The `PII_KEYS` of every [`TestApp`], e.g. to rotate away from them. They must never be used
+/// outside of tests.
+pub const PII_KEYS: &str = "test:ivcHAr7loLbRUG0cjfn6BkNodpESkHFZ+5g8JmDvDs0=";
+
+/// The `PII_INDEX_KEY` of every [`TestApp`].
+pub const PII_INDEX_KEY: &str = "bgvwkAoO9NKWua9m46avZZqJ+cG4/qgr1p5kdqAeOxc=";
+
+/// A service under test, see the [module documentation](self).
+pub struct TestApp {
+    endpoint: poem::endpoint::BoxEndpoint<'static>,
+    issuer: String,
+
+    /// The database of this app, to seed or inspect rows directly.
+    pub database: Database,
+
+    /// The mock of the Auth0 management API this app talks to.
+    pub management: MockManagement,
+}
+
+impl TestApp {
+    /// Build the app of a service, `api` is called with the Auth0 management client to build the
+    /// service's routes, the same way [`Server::run`] calls it but without the configuration.
+    pub async fn new<T, F>(api: F) -> Self
+    where
+        T: OpenApi + 'static,
+        F: FnOnce(auth0::management::Api) -> T,
+    {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let database = database::create().await;
+        let management = MockManagement::start().await;
+
+        let config = Config {
+            address: "127.0.0.1".to_string(),
+            port: 0,
+            oai_address: "http://localhost".to_string(),
+            // The pool is created by the harness, see `database::create`.
+            database_url: String::new(),
+            auth0_domain: management.url().to_string(),
+            auth0_client_id: "test".to_string(),
+            auth0_client_secret: "test".to_string(),
+            auth0_public_key: token::PUBLIC_KEY.to_string(),
+            pii_keys: PII_KEYS.to_string(),
+            pii_index_key: PII_INDEX_KEY.to_string(),
+        };
+        let keyring = Keyring::from_config(&config).expect("test keys are valid");
+
+        let url = reqwest::Url::parse(&config.auth0_domain).expect("mock URL is valid");
+        let auth = auth0::authentication::Api::init(
+            url.clone(),
+            auth0::authentication::AuthenticationMethod::ClientIDClientSecret(
+                config.auth0_client_id.clone(),
+                config.auth0_client_secret.clone(),
+            ),
+        );
+        let client = auth0::management::Api::init(
+            url,
+            config.auth0_client_id.clone(),
+            config.auth0_client_secret.clone(),
+        )
+        .await
+        .expect("failed to initialise the Auth0 management client against the mock");
+
+        let endpoint = Server::new("Test", "0.0.0")
+            .endpoint(api(client), &config, database.clone(), auth, keyring)
+            .boxed();
+
+        Self {
+            endpoint,
+            issuer: config.auth0_domain,
+            database,
+            management,
+        }
+    }
+
+    /// A token for the user identified by `subject` that this app accepts.
+    pub fn token(&self, subject: impl Into<String>) -> Token {
+        Token::new(&self.issuer, subject)
+    }
+
+    pub fn get(&self, uri: impl Into<String>) -> TestRequest<'_> {
+        self.request(Method::GET, uri)
+    }
+
+    pub fn post(&self, uri: impl Into<String>) -> TestRequest<'_> {
+        self.request(Method::POST, uri)
+    }
+
+    pub fn put(&self, uri: impl Into<String>) -> TestRequest<'_> {
+        self.request(Method::PUT, uri)
+    }
+
+    pub fn patch(&self, uri: impl Into<String>) -> TestRequest<'_> {
+        self.request(Method::PATCH, uri)
+    }
+
+    pub fn delete(&self, uri: impl Into<String>) -> TestRequest<'_> {
+        self.request(Method::DELETE, uri)
+    }
+
+    pub fn request(&self, method: Method, uri: impl Into<String>) -> TestRequest<'_> {
+        TestRequest {
+            app: self,
+            method,
+            uri: uri.into(),
+            token: None,
+            body: None,
+        }
+    }
+}
+
+/// A request to a [`TestApp`], sent with [`TestRequest::send`].
+pub struct TestRequest<'a> {
+    app: &'a TestApp,
+    method: Method,
+    uri: String,
+    token: Option<String>,
+    /// The content type of the body along with the body itself.
+    body: Option<(String, Vec<u8>)>,
+}
+
+impl TestRequest<'_> {
+    /// Authenticate the request with `token`, usually minted by [`TestApp::token`].
+    pub fn bearer(mut self, token: &Token) -> Self {
+        self.token = Some(token.encode());
+        self
+    }
+
+    /// Send `body` as the JSON body of the request.
+    pub fn json(mut self, body: impl Serialize) -> Self {
+        self.body = Some((
+            "application/json".to_string(),
+            serde_json::to_vec(&body).expect("request body is serializable"),
+        ));
+        self
+    }
+
+    /// Send `form` as the `multipart/form-data` body of the request.
+    pub fn multipart(mut self, form: Form) -> Self {
+        self.body = Some((form.content_type(), form.into_body()));
+        self
+    }
+
+    pub async fn send(self) -> TestResponse {
+        let uri = self
+            .uri
+            .parse::<Uri>()
+            .unwrap_or_else(|e| panic!("invalid URI '{}': {}", self.uri, e));
+
+        let mut request = Request::builder().method(self.method).uri(uri);
+        if let Some(token) = self.token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let request = match self.body {
+            Some((content_type, body)) => request.content_type(&content_type).body(body),
+            None => request.finish(),
+        };
+
+        let response = self.app.endpoint.get_response(request).await;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response
+            .into_body()
+            .into_vec()
+            .await
+            .expect("failed to read the response body");
+
+        TestResponse {
+            status,
+            headers,
+            body: match body.is_empty() {
+                true => serde_json::Value::Null,
+                false => serde_json::from_slice(&body).unwrap_or_else(|_| {
+                    serde_json::Value::String(String::from_utf8_lossy(&body).into_owned())
+                }),
+            },
+        }
+    }
+}
+
+/// The response of a [`TestRequest`], a body that is not JSON is kept as a JSON string.
+#[derive(Debug)]
+pub struct TestResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: serde_json::Value,
+}
+
+impl TestResponse {
+    /// Assert that the response has `status`, showing the body when it does not.
+    #[track_caller]
+    pub fn assert_status(&self, status: StatusCode) -> &Self {
+        assert_eq!(
+            self.status, status,
+            "unexpected status, the response body was {:#}",
+            self.body
+        );
+        self
+    }
+
+    /// Assert that the response is an error with `status` and the stable error `code`.
+    #[track_caller]
+    pub fn assert_error(&self, status: StatusCode, code: &str) -> &Self {
+        self.assert_status(status);
+        assert_eq!(
+            self.body["code"], code,
+            "unexpected error code, the response body was {:#}",
+            self.body
+        );
+        self
+    }
+
+    /// Assert that the request succeeded and return its body.
+    #[track_caller]
+    pub fn ok(self) -> serde_json::Value {
+        self.assert_status(StatusCode::OK);
+        self.body
+    }
+}
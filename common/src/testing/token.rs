@@ -0,0 +1,113 @@
+use jsonwebtoken::{encode, get_current_timestamp, Algorithm, EncodingKey, Header};
+use serde::Serialize;
+
+/// The key pair test tokens are signed with, the public key is configured as
+/// `AUTH0_PUBLIC_KEY` of every [`super::TestApp`]. It must never be trusted outside of tests.
+const PRIVATE_KEY: &str = include_str!("private_key.pem");
+pub(super) const PUBLIC_KEY: &str = include_str!("public_key.pem");
+
+/// An access token shaped like the ones issued by Auth0, signed with the test key pair.
+///
+/// The token is valid for an hour by default and grants no permission, add some with
+/// [`Token::permissions`].
+#[derive(Debug, Clone)]
+pub struct Token {
+    issuer: String,
+    subject: String,
+    email: String,
+    scope: String,
+    permissions: Vec<String>,
+    issued_at: u64,
+    expires_at: u64,
+}
+
+#[derive(Serialize)]
+struct Claims<'a> {
+    email: &'a str,
+    iss: &'a str,
+    aud: &'a [&'a str],
+    sub: &'a str,
+    azp: &'a str,
+    exp: u64,
+    iat: u64,
+    scope: &'a str,
+    permissions: &'a [String],
+}
+
+impl Token {
+    pub fn new(issuer: impl Into<String>, subject: impl Into<String>) -> Self {
+        let subject = subject.into();
+        let now = get_current_timestamp();
+
+        Self {
+            issuer: issuer.into(),
+            email: format!(
+                "{}@example.com",
+                subject.replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+            ),
+            subject,
+            scope: "openid profile email".to_string(),
+            permissions: Vec::new(),
+            // The token must be issued in the past to be accepted.
+            issued_at: now - 60,
+            expires_at: now + 60 * 60,
+        }
+    }
+
+    pub fn email(mut self, email: impl Into<String>) -> Self {
+        self.email = email.into();
+        self
+    }
+
+    /// Replace the `scope` claim, which must include `openid` and `email` to be accepted.
+    pub fn scope(mut self, scope: impl Into<String>) -> Self {
+        self.scope = scope.into();
+        self
+    }
+
+    /// Add `permissions` to the `permissions` claim (e.g. `users:read`).
+    pub fn permissions<I, S>(mut self, permissions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.permissions
+            .extend(permissions.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.issuer = issuer.into();
+        self
+    }
+
+    /// Set when the token expires, in seconds since the epoch.
+    pub fn expires_at(mut self, expires_at: u64) -> Self {
+        self.expires_at = expires_at;
+        self
+    }
+
+    pub fn encode(&self) -> String {
+        let claims = Claims {
+            email: &self.email,
+            iss: &self.issuer,
+            aud: &[
+                "https://api.fgacyc.com",
+                "https://fgacyc.auth0.com/userinfo",
+            ],
+            sub: &self.subject,
+            azp: "test",
+            exp: self.expires_at,
+            iat: self.issued_at,
+            scope: &self.scope,
+            permissions: &self.permissions,
+        };
+
+        encode(
+            &Header::new(Algorithm::RS256),
+            &claims,
+            &EncodingKey::from_rsa_pem(PRIVATE_KEY.as_bytes()).expect("test private key is valid"),
+        )
+        .expect("failed to sign the test token")
+    }
+}
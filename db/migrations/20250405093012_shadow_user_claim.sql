@@ -0,0 +1,33 @@
+-- migrate:up
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+-- Compares phone numbers regardless of formatting and of the Malaysian country code, e.g.
+-- `+60 12-345 6789` and `012 3456789` are both normalised to `123456789`.
+CREATE FUNCTION normalise_phone_number(phone_number TEXT) RETURNS TEXT
+  LANGUAGE SQL IMMUTABLE STRICT PARALLEL SAFE
+  AS $$ SELECT NULLIF(regexp_replace(regexp_replace(phone_number, '\D', '', 'g'), '^(60|0)+', ''), '') $$;
+
+-- Compares IC numbers regardless of dashes and spaces, e.g. `900101-14-5678`.
+CREATE FUNCTION normalise_ic_number(ic_number TEXT) RETURNS TEXT
+  LANGUAGE SQL IMMUTABLE STRICT PARALLEL SAFE
+  AS $$ SELECT NULLIF(upper(regexp_replace(ic_number, '[^[:alnum:]]', '', 'g')), '') $$;
+
+ALTER TABLE shadow_user ADD COLUMN no SERIAL;
+ALTER TABLE shadow_user ADD CONSTRAINT shadow_user_no_key UNIQUE (no);
+ALTER TABLE shadow_user ADD COLUMN claimed_by TEXT;
+ALTER TABLE shadow_user ADD COLUMN claimed_at TIMESTAMPTZ;
+ALTER TABLE shadow_user ADD CONSTRAINT shadow_user_claimed_by_fkey
+  FOREIGN KEY (claimed_by) REFERENCES "user"(id) ON UPDATE CASCADE;
+COMMENT ON COLUMN shadow_user.no IS 'Sequence number of a shadow user, used to address it through the API.';
+COMMENT ON COLUMN shadow_user.claimed_by IS 'User that the shadow user was merged into, if any.';
+COMMENT ON COLUMN shadow_user.claimed_at IS 'Time the shadow user was merged into a user.';
+
+CREATE INDEX shadow_user_claimed_by_idx ON shadow_user (claimed_by);
+
+-- migrate:down
+DROP INDEX shadow_user_claimed_by_idx;
+ALTER TABLE shadow_user DROP COLUMN claimed_at;
+ALTER TABLE shadow_user DROP COLUMN claimed_by;
+ALTER TABLE shadow_user DROP COLUMN no;
+DROP FUNCTION normalise_ic_number;
+DROP FUNCTION normalise_phone_number;
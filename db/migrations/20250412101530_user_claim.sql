@@ -0,0 +1,56 @@
+-- migrate:up
+CREATE TYPE claim_channel AS ENUM ('email', 'phone');
+
+CREATE TABLE user_claim (
+  id TEXT,
+  user_id TEXT NOT NULL,
+  email TEXT NOT NULL,
+  shadow_user_no INTEGER,
+  claimed_user_id TEXT,
+  channel claim_channel NOT NULL,
+  destination TEXT NOT NULL,
+  code_hash TEXT NOT NULL,
+  attempts INTEGER NOT NULL DEFAULT 0,
+  expires_at TIMESTAMPTZ NOT NULL,
+  verified_at TIMESTAMPTZ,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (id),
+  FOREIGN KEY (shadow_user_no) REFERENCES shadow_user(no) ON DELETE CASCADE,
+  FOREIGN KEY (claimed_user_id) REFERENCES "user"(id) ON UPDATE CASCADE ON DELETE CASCADE,
+  CONSTRAINT user_claim_target_check CHECK ((shadow_user_no IS NULL) != (claimed_user_id IS NULL))
+);
+COMMENT ON COLUMN user_claim.id IS 'Unique identifier of a claim (e.g., user_claim_01H7JNPD7J67AA5AD87Q4SZDF9).';
+COMMENT ON COLUMN user_claim.user_id IS 'Auth0 user claiming the profile, who may not have a user yet.';
+COMMENT ON COLUMN user_claim.email IS 'Email of the Auth0 user, which the claimed profile takes over.';
+COMMENT ON COLUMN user_claim.shadow_user_no IS 'Shadow user being claimed, if any.';
+COMMENT ON COLUMN user_claim.claimed_user_id IS 'Soft-deleted user being claimed, if any.';
+COMMENT ON COLUMN user_claim.channel IS 'How the one-time code was delivered.';
+COMMENT ON COLUMN user_claim.destination IS 'Masked email or phone number the one-time code was sent to.';
+COMMENT ON COLUMN user_claim.code_hash IS 'SHA-256 of the claim id and the one-time code, the code itself is never stored.';
+COMMENT ON COLUMN user_claim.attempts IS 'Number of wrong codes submitted.';
+COMMENT ON COLUMN user_claim.verified_at IS 'Time the code was verified and the profile claimed.';
+
+CREATE INDEX user_claim_user_id_idx ON user_claim (user_id, created_at);
+
+-- Claiming a soft-deleted user moves it to the new Auth0 user by changing its id, which every
+-- reference to it must follow.
+ALTER TABLE user_relationship
+  DROP CONSTRAINT user_relationship_source_user_id_fkey,
+  ADD CONSTRAINT user_relationship_source_user_id_fkey
+    FOREIGN KEY (source_user_id) REFERENCES "user"(id) ON UPDATE CASCADE;
+ALTER TABLE user_relationship
+  DROP CONSTRAINT user_relationship_destination_user_id_fkey,
+  ADD CONSTRAINT user_relationship_destination_user_id_fkey
+    FOREIGN KEY (destination_user_id) REFERENCES "user"(id) ON UPDATE CASCADE;
+
+-- migrate:down
+ALTER TABLE user_relationship
+  DROP CONSTRAINT user_relationship_destination_user_id_fkey,
+  ADD CONSTRAINT user_relationship_destination_user_id_fkey
+    FOREIGN KEY (destination_user_id) REFERENCES "user"(id);
+ALTER TABLE user_relationship
+  DROP CONSTRAINT user_relationship_source_user_id_fkey,
+  ADD CONSTRAINT user_relationship_source_user_id_fkey
+    FOREIGN KEY (source_user_id) REFERENCES "user"(id);
+DROP TABLE user_claim;
+DROP TYPE claim_channel;
@@ -0,0 +1,33 @@
+-- migrate:up
+CREATE TABLE user_merge (
+  id TEXT,
+  user_id TEXT NOT NULL,
+  merged_user_id TEXT NOT NULL,
+  merged_by TEXT NOT NULL,
+  connect_groups_moved INTEGER NOT NULL,
+  connect_groups_dropped INTEGER NOT NULL,
+  ministries_moved INTEGER NOT NULL,
+  ministries_dropped INTEGER NOT NULL,
+  relationships_moved INTEGER NOT NULL,
+  relationships_dropped INTEGER NOT NULL,
+  attendance_moved INTEGER NOT NULL,
+  attendance_dropped INTEGER NOT NULL,
+  form_data_moved INTEGER NOT NULL,
+  form_data_dropped INTEGER NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (id),
+  FOREIGN KEY (user_id) REFERENCES "user"(id) ON UPDATE CASCADE,
+  FOREIGN KEY (merged_user_id) REFERENCES "user"(id) ON UPDATE CASCADE,
+  CONSTRAINT user_merge_self_check CHECK (user_id != merged_user_id)
+);
+COMMENT ON TABLE user_merge IS 'Audit trail of duplicate users merged into one another.';
+COMMENT ON COLUMN user_merge.user_id IS 'User that was kept.';
+COMMENT ON COLUMN user_merge.merged_user_id IS 'User that was merged into the kept one and soft-deleted.';
+COMMENT ON COLUMN user_merge.merged_by IS 'Subject of the access token that performed the merge.';
+COMMENT ON COLUMN user_merge.connect_groups_dropped IS 'Memberships dropped because the kept user was already in the connect group, the most senior role is kept.';
+
+CREATE INDEX user_merge_user_id_idx ON user_merge (user_id);
+CREATE INDEX user_merge_merged_user_id_idx ON user_merge (merged_user_id);
+
+-- migrate:down
+DROP TABLE user_merge;
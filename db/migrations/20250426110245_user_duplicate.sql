@@ -0,0 +1,46 @@
+-- migrate:up
+-- Compares emails regardless of case, dots and plus-aliases in the local part, e.g.
+-- `Alice.Tan+church@Gmail.com` is normalised to `alicetan@gmail.com`.
+CREATE FUNCTION normalise_email(email TEXT) RETURNS TEXT
+  LANGUAGE SQL IMMUTABLE STRICT PARALLEL SAFE
+  AS $$ SELECT NULLIF(replace(regexp_replace(lower(split_part(email, '@', 1)), '\+.*$', ''), '.', '') || '@' || lower(split_part(email, '@', 2)), '@') $$;
+
+CREATE INDEX user_name_trgm_idx ON "user" USING GIN (name gin_trgm_ops);
+CREATE INDEX user_normalised_email_idx ON "user" (normalise_email(email));
+CREATE INDEX user_normalised_phone_number_idx ON "user" (normalise_phone_number(phone_number));
+CREATE INDEX user_normalised_ic_number_idx ON "user" (normalise_ic_number(ic_number));
+
+CREATE TYPE matched_attribute AS ENUM ('phone_number', 'email', 'ic_number', 'name');
+CREATE TYPE user_duplicate_status AS ENUM ('pending', 'dismissed', 'merged');
+
+CREATE TABLE user_duplicate (
+  user_id TEXT,
+  duplicate_user_id TEXT,
+  score DOUBLE PRECISION NOT NULL,
+  matched_on matched_attribute[] NOT NULL,
+  status user_duplicate_status NOT NULL DEFAULT 'pending',
+  reviewed_by TEXT,
+  reviewed_at TIMESTAMPTZ,
+  detected_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (user_id, duplicate_user_id),
+  FOREIGN KEY (user_id) REFERENCES "user"(id) ON UPDATE CASCADE ON DELETE CASCADE,
+  FOREIGN KEY (duplicate_user_id) REFERENCES "user"(id) ON UPDATE CASCADE ON DELETE CASCADE,
+  CONSTRAINT user_duplicate_pair_check CHECK (user_id != duplicate_user_id)
+);
+COMMENT ON TABLE user_duplicate IS 'Pairs of users that are likely the same person, found by the duplicate detection.';
+COMMENT ON COLUMN user_duplicate.score IS 'Likelihood between 0 and 1 that both users are the same person.';
+COMMENT ON COLUMN user_duplicate.status IS 'Whether the pair is awaiting review, was dismissed as distinct people or was merged.';
+COMMENT ON COLUMN user_duplicate.reviewed_by IS 'Subject of the access token that dismissed or merged the pair.';
+COMMENT ON COLUMN user_duplicate.detected_at IS 'Time the pair was last found by the duplicate detection.';
+
+CREATE INDEX user_duplicate_duplicate_user_id_idx ON user_duplicate (duplicate_user_id);
+
+-- migrate:down
+DROP TABLE user_duplicate;
+DROP TYPE user_duplicate_status;
+DROP TYPE matched_attribute;
+DROP INDEX user_normalised_ic_number_idx;
+DROP INDEX user_normalised_phone_number_idx;
+DROP INDEX user_normalised_email_idx;
+DROP INDEX user_name_trgm_idx;
+DROP FUNCTION normalise_email;
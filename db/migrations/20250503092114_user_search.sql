@@ -0,0 +1,20 @@
+-- migrate:up
+-- The `simple` configuration is used since names are not words of any particular language and
+-- must not be stemmed.
+ALTER TABLE "user" ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS (
+  setweight(to_tsvector('simple', name), 'A') ||
+  setweight(to_tsvector('simple',
+    COALESCE(nickname, '') || ' ' ||
+    COALESCE(given_name, '') || ' ' ||
+    COALESCE(family_name, '') || ' ' ||
+    COALESCE(username, '')
+  ), 'B') ||
+  setweight(to_tsvector('simple', email), 'C')
+) STORED;
+COMMENT ON COLUMN "user".search_vector IS 'Names, username and email of a user for full text search, names weigh the most.';
+
+CREATE INDEX user_search_vector_idx ON "user" USING GIN (search_vector);
+
+-- migrate:down
+DROP INDEX user_search_vector_idx;
+ALTER TABLE "user" DROP COLUMN search_vector;
@@ -0,0 +1,138 @@
+-- migrate:up
+CREATE TYPE audit_action AS ENUM ('insert', 'update', 'delete');
+
+CREATE TABLE audit_log (
+  id BIGINT GENERATED ALWAYS AS IDENTITY,
+  actor TEXT,
+  request_id TEXT,
+  resource TEXT NOT NULL,
+  resource_id TEXT NOT NULL,
+  action audit_action NOT NULL,
+  before JSONB,
+  after JSONB,
+  changes JSONB,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (id)
+);
+COMMENT ON TABLE audit_log IS 'Append-only record of every change made to the audited tables.';
+COMMENT ON COLUMN audit_log.actor IS 'Subject of the access token that made the change, NULL for changes made outside of the API (e.g., migrations or background jobs).';
+COMMENT ON COLUMN audit_log.request_id IS 'Id of the request that made the change, as returned in the `X-Request-Id` header.';
+COMMENT ON COLUMN audit_log.resource IS 'Name of the table changed (e.g., user, user_connect_group, etc.).';
+COMMENT ON COLUMN audit_log.resource_id IS 'Primary key of the changed row, the columns of a composite key are joined by `/`.';
+COMMENT ON COLUMN audit_log.before IS 'The row before the change, NULL when inserted.';
+COMMENT ON COLUMN audit_log.after IS 'The row after the change, NULL when deleted.';
+COMMENT ON COLUMN audit_log.changes IS 'The columns changed by an update with their new values, NULL otherwise.';
+
+CREATE INDEX audit_log_resource_idx ON audit_log (resource, resource_id, created_at);
+CREATE INDEX audit_log_actor_idx ON audit_log (actor, created_at);
+CREATE INDEX audit_log_created_at_idx ON audit_log (created_at);
+
+CREATE FUNCTION audit_log_append_only() RETURNS TRIGGER
+  LANGUAGE plpgsql
+  AS $$
+BEGIN
+  RAISE EXCEPTION 'audit_log is append-only';
+END
+$$;
+
+CREATE TRIGGER audit_log_append_only
+  BEFORE UPDATE OR DELETE ON audit_log
+  FOR EACH ROW EXECUTE FUNCTION audit_log_append_only();
+CREATE TRIGGER audit_log_append_only_truncate
+  BEFORE TRUNCATE ON audit_log
+  FOR EACH STATEMENT EXECUTE FUNCTION audit_log_append_only();
+
+-- Records a changed row in the `audit_log`, the arguments of the trigger are the columns of the
+-- primary key of the table. The actor and request id are set by `common::audit::begin`.
+CREATE FUNCTION audit() RETURNS TRIGGER
+  LANGUAGE plpgsql
+  AS $$
+DECLARE
+  -- Generated columns such as the search vector of a user only repeat the other columns.
+  before JSONB := CASE WHEN TG_OP IN ('UPDATE', 'DELETE') THEN to_jsonb(OLD) - 'search_vector' END;
+  after JSONB := CASE WHEN TG_OP IN ('INSERT', 'UPDATE') THEN to_jsonb(NEW) - 'search_vector' END;
+  changes JSONB;
+  resource_id TEXT;
+BEGIN
+  IF TG_OP = 'UPDATE' THEN
+    SELECT jsonb_object_agg(a.key, a.value) INTO changes
+    FROM jsonb_each(after) a
+    WHERE a.key != 'updated_at' AND a.value IS DISTINCT FROM before -> a.key;
+
+    -- Nothing but the update time has changed.
+    IF changes IS NULL THEN
+      RETURN NULL;
+    END IF;
+  END IF;
+
+  SELECT string_agg(COALESCE(after, before) ->> k.key, '/' ORDER BY k.ord) INTO resource_id
+  FROM unnest(TG_ARGV) WITH ORDINALITY AS k(key, ord);
+
+  INSERT INTO audit_log (actor, request_id, resource, resource_id, action, before, after, changes)
+  VALUES (
+    NULLIF(current_setting('audit.actor', TRUE), ''),
+    NULLIF(current_setting('audit.request_id', TRUE), ''),
+    TG_TABLE_NAME,
+    resource_id,
+    lower(TG_OP)::audit_action,
+    before,
+    after,
+    changes
+  );
+
+  RETURN NULL;
+END
+$$;
+
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON "user" FOR EACH ROW EXECUTE FUNCTION audit('id');
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON user_connect_group FOR EACH ROW EXECUTE FUNCTION audit('user_id', 'connect_group_id');
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON user_ministry FOR EACH ROW EXECUTE FUNCTION audit('user_id', 'ministry_id');
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON user_relationship FOR EACH ROW EXECUTE FUNCTION audit('source_user_id', 'destination_user_id');
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON shadow_user FOR EACH ROW EXECUTE FUNCTION audit('name', 'phone_number');
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON satellite FOR EACH ROW EXECUTE FUNCTION audit('id');
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON connect_group FOR EACH ROW EXECUTE FUNCTION audit('id');
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON connect_group_category FOR EACH ROW EXECUTE FUNCTION audit('id');
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON ministry FOR EACH ROW EXECUTE FUNCTION audit('id');
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON ministry_team FOR EACH ROW EXECUTE FUNCTION audit('id');
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON ministry_department FOR EACH ROW EXECUTE FUNCTION audit('id');
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON ministry_role FOR EACH ROW EXECUTE FUNCTION audit('id');
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON pastoral_role FOR EACH ROW EXECUTE FUNCTION audit('id');
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON currency FOR EACH ROW EXECUTE FUNCTION audit('code');
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON event_type FOR EACH ROW EXECUTE FUNCTION audit('name');
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON event FOR EACH ROW EXECUTE FUNCTION audit('id');
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON price FOR EACH ROW EXECUTE FUNCTION audit('id');
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON "session" FOR EACH ROW EXECUTE FUNCTION audit('id');
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON attendance FOR EACH ROW EXECUTE FUNCTION audit('session_id', 'user_id');
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON form_field_type FOR EACH ROW EXECUTE FUNCTION audit('type');
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON registration FOR EACH ROW EXECUTE FUNCTION audit('id');
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON registration_form_field FOR EACH ROW EXECUTE FUNCTION audit('registration_id', 'name');
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON registration_form_field_data FOR EACH ROW EXECUTE FUNCTION audit('registration_id', 'name', 'user_id');
+
+-- migrate:down
+DROP TRIGGER audit ON registration_form_field_data;
+DROP TRIGGER audit ON registration_form_field;
+DROP TRIGGER audit ON registration;
+DROP TRIGGER audit ON form_field_type;
+DROP TRIGGER audit ON attendance;
+DROP TRIGGER audit ON "session";
+DROP TRIGGER audit ON price;
+DROP TRIGGER audit ON event;
+DROP TRIGGER audit ON event_type;
+DROP TRIGGER audit ON currency;
+DROP TRIGGER audit ON pastoral_role;
+DROP TRIGGER audit ON ministry_role;
+DROP TRIGGER audit ON ministry_department;
+DROP TRIGGER audit ON ministry_team;
+DROP TRIGGER audit ON ministry;
+DROP TRIGGER audit ON connect_group_category;
+DROP TRIGGER audit ON connect_group;
+DROP TRIGGER audit ON satellite;
+DROP TRIGGER audit ON shadow_user;
+DROP TRIGGER audit ON user_relationship;
+DROP TRIGGER audit ON user_ministry;
+DROP TRIGGER audit ON user_connect_group;
+DROP TRIGGER audit ON "user";
+DROP FUNCTION audit;
+DROP TABLE audit_log;
+DROP FUNCTION audit_log_append_only;
+DROP TYPE audit_action;
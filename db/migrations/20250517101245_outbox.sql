@@ -0,0 +1,83 @@
+-- migrate:up
+CREATE TABLE outbox_event (
+  id BIGINT GENERATED ALWAYS AS IDENTITY,
+  type TEXT NOT NULL,
+  payload JSONB NOT NULL,
+  actor TEXT,
+  request_id TEXT,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (id)
+);
+COMMENT ON TABLE outbox_event IS 'Domain events written in the same transaction as the change they describe, delivered to the webhooks by the dispatcher.';
+COMMENT ON COLUMN outbox_event.type IS 'Type of the event (e.g., connect_group.users_joined, attendance.recorded, etc.).';
+COMMENT ON COLUMN outbox_event.actor IS 'Subject of the access token that made the change, as set by `common::audit::begin`.';
+
+CREATE TABLE webhook (
+  id TEXT,
+  url TEXT NOT NULL,
+  secret TEXT NOT NULL,
+  event_types TEXT[] NOT NULL DEFAULT '{}',
+  description TEXT,
+  active BOOLEAN NOT NULL DEFAULT TRUE,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (id)
+);
+COMMENT ON TABLE webhook IS 'Endpoints subscribed to the domain events, not audited since it holds the signing secrets.';
+COMMENT ON COLUMN webhook.id IS 'Unique identifier for a webhook (e.g., webhook_01H7JNPD7J67AA5AD87Q4SZDF9).';
+COMMENT ON COLUMN webhook.secret IS 'Key of the HMAC-SHA256 signature sent along with every delivery.';
+COMMENT ON COLUMN webhook.event_types IS 'Types of the events delivered to the webhook, every type when empty.';
+COMMENT ON COLUMN webhook.active IS 'Whether events are delivered, deliveries of an inactive webhook wait until it is activated again.';
+
+CREATE TYPE webhook_delivery_status AS ENUM ('pending', 'delivered', 'dead');
+
+CREATE TABLE webhook_delivery (
+  id BIGINT GENERATED ALWAYS AS IDENTITY,
+  webhook_id TEXT NOT NULL,
+  event_id BIGINT NOT NULL,
+  status webhook_delivery_status NOT NULL DEFAULT 'pending',
+  attempts INTEGER NOT NULL DEFAULT 0,
+  next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  last_attempt_at TIMESTAMPTZ,
+  last_response_status INTEGER,
+  last_error TEXT,
+  delivered_at TIMESTAMPTZ,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (id),
+  UNIQUE (webhook_id, event_id),
+  FOREIGN KEY (webhook_id) REFERENCES webhook(id) ON DELETE CASCADE,
+  FOREIGN KEY (event_id) REFERENCES outbox_event(id)
+);
+COMMENT ON TABLE webhook_delivery IS 'Delivery of an event to a webhook, retried with an exponential backoff until it is delivered or dead.';
+COMMENT ON COLUMN webhook_delivery.status IS 'Dead once every attempt has failed, dead deliveries are only retried on request.';
+COMMENT ON COLUMN webhook_delivery.next_attempt_at IS 'Time the delivery is due, pushed back while a dispatcher is attempting it.';
+
+CREATE INDEX webhook_delivery_due_idx ON webhook_delivery (next_attempt_at) WHERE status = 'pending';
+CREATE INDEX webhook_delivery_event_id_idx ON webhook_delivery (event_id);
+
+-- Queues a delivery of every new event to each active webhook subscribed to its type, within the
+-- transaction publishing it.
+CREATE FUNCTION outbox_event_fan_out() RETURNS TRIGGER
+  LANGUAGE plpgsql
+  AS $$
+BEGIN
+  INSERT INTO webhook_delivery (webhook_id, event_id)
+  SELECT w.id, NEW.id
+  FROM webhook w
+  WHERE w.active AND (cardinality(w.event_types) = 0 OR NEW.type = ANY(w.event_types));
+
+  RETURN NULL;
+END
+$$;
+
+CREATE TRIGGER fan_out
+  AFTER INSERT ON outbox_event
+  FOR EACH ROW EXECUTE FUNCTION outbox_event_fan_out();
+
+-- migrate:down
+DROP TRIGGER fan_out ON outbox_event;
+DROP FUNCTION outbox_event_fan_out;
+DROP TABLE webhook_delivery;
+DROP TYPE webhook_delivery_status;
+DROP TABLE webhook;
+DROP TABLE outbox_event;
@@ -0,0 +1,137 @@
+-- migrate:up
+CREATE TABLE user_connect_group_history (
+  id BIGINT GENERATED ALWAYS AS IDENTITY,
+  user_id TEXT NOT NULL,
+  connect_group_id TEXT NOT NULL,
+  user_role TEXT NOT NULL,
+  joined_at TIMESTAMPTZ DEFAULT NOW(),
+  left_at TIMESTAMPTZ,
+  left_reason TEXT,
+  PRIMARY KEY (id),
+  FOREIGN KEY (user_id) REFERENCES "user"(id) ON UPDATE CASCADE ON DELETE CASCADE,
+  FOREIGN KEY (connect_group_id) REFERENCES connect_group(id) ON UPDATE CASCADE ON DELETE CASCADE,
+  CONSTRAINT user_connect_group_history_period_check CHECK (left_at >= joined_at)
+);
+COMMENT ON TABLE user_connect_group_history IS 'Periods a user was a member of a connect group with the same role, a role change ends a period and starts the next one.';
+COMMENT ON COLUMN user_connect_group_history.user_role IS 'The pastoral role held, not a foreign key so that the history outlives deleted roles.';
+COMMENT ON COLUMN user_connect_group_history.joined_at IS 'Start of the period, NULL for memberships that predate the history.';
+COMMENT ON COLUMN user_connect_group_history.left_at IS 'End of the period, NULL while ongoing.';
+COMMENT ON COLUMN user_connect_group_history.left_reason IS 'Why the user left, as given when removed from the connect group.';
+
+CREATE UNIQUE INDEX user_connect_group_history_current_idx ON user_connect_group_history (user_id, connect_group_id) WHERE left_at IS NULL;
+CREATE INDEX user_connect_group_history_connect_group_id_idx ON user_connect_group_history (connect_group_id, joined_at);
+CREATE INDEX user_connect_group_history_user_id_idx ON user_connect_group_history (user_id, joined_at);
+
+CREATE TABLE user_ministry_history (
+  id BIGINT GENERATED ALWAYS AS IDENTITY,
+  user_id TEXT NOT NULL,
+  ministry_id TEXT NOT NULL,
+  user_role TEXT NOT NULL,
+  joined_at TIMESTAMPTZ DEFAULT NOW(),
+  left_at TIMESTAMPTZ,
+  left_reason TEXT,
+  PRIMARY KEY (id),
+  FOREIGN KEY (user_id) REFERENCES "user"(id) ON UPDATE CASCADE ON DELETE CASCADE,
+  FOREIGN KEY (ministry_id) REFERENCES ministry(id) ON UPDATE CASCADE ON DELETE CASCADE,
+  CONSTRAINT user_ministry_history_period_check CHECK (left_at >= joined_at)
+);
+COMMENT ON TABLE user_ministry_history IS 'Periods a user was a member of a ministry with the same role, a role change ends a period and starts the next one.';
+COMMENT ON COLUMN user_ministry_history.user_role IS 'The ministry role held, not a foreign key so that the history outlives deleted roles.';
+COMMENT ON COLUMN user_ministry_history.joined_at IS 'Start of the period, NULL for memberships that predate the history.';
+COMMENT ON COLUMN user_ministry_history.left_at IS 'End of the period, NULL while ongoing.';
+COMMENT ON COLUMN user_ministry_history.left_reason IS 'Why the user left, as given when removed from the ministry.';
+
+CREATE UNIQUE INDEX user_ministry_history_current_idx ON user_ministry_history (user_id, ministry_id) WHERE left_at IS NULL;
+CREATE INDEX user_ministry_history_ministry_id_idx ON user_ministry_history (ministry_id, joined_at);
+CREATE INDEX user_ministry_history_user_id_idx ON user_ministry_history (user_id, joined_at);
+
+INSERT INTO user_connect_group_history (user_id, connect_group_id, user_role, joined_at)
+SELECT user_id, connect_group_id, user_role, NULL FROM user_connect_group;
+INSERT INTO user_ministry_history (user_id, ministry_id, user_role, joined_at)
+SELECT user_id, ministry_id, user_role, NULL FROM user_ministry;
+
+-- Keeps the history in step with the memberships. Leaving or moving to another connect group
+-- ends the period with the reason in the transaction-local `membership.left_reason` setting. A
+-- membership moved to another user, when re-keyed or merged, takes its ongoing period along.
+CREATE FUNCTION record_connect_group_membership() RETURNS TRIGGER
+  LANGUAGE plpgsql
+  AS $$
+BEGIN
+  IF TG_OP IN ('UPDATE', 'DELETE') THEN
+    IF TG_OP = 'UPDATE' AND OLD.user_id != NEW.user_id THEN
+      UPDATE user_connect_group_history SET user_id = NEW.user_id
+      WHERE user_id = OLD.user_id AND connect_group_id = OLD.connect_group_id AND left_at IS NULL;
+    END IF;
+
+    IF TG_OP = 'DELETE' OR OLD.connect_group_id != NEW.connect_group_id OR OLD.user_role != NEW.user_role THEN
+      UPDATE user_connect_group_history SET
+        left_at = NOW(),
+        left_reason = CASE
+          WHEN TG_OP = 'DELETE' OR OLD.connect_group_id != NEW.connect_group_id
+          THEN NULLIF(current_setting('membership.left_reason', TRUE), '')
+        END
+      WHERE
+        user_id = CASE WHEN TG_OP = 'DELETE' THEN OLD.user_id ELSE NEW.user_id END
+        AND connect_group_id = OLD.connect_group_id
+        AND left_at IS NULL;
+    END IF;
+  END IF;
+
+  IF TG_OP = 'INSERT' OR OLD.connect_group_id != NEW.connect_group_id OR OLD.user_role != NEW.user_role THEN
+    INSERT INTO user_connect_group_history (user_id, connect_group_id, user_role)
+    VALUES (NEW.user_id, NEW.connect_group_id, NEW.user_role)
+    ON CONFLICT (user_id, connect_group_id) WHERE left_at IS NULL DO NOTHING;
+  END IF;
+
+  RETURN NULL;
+END
+$$;
+
+CREATE FUNCTION record_ministry_membership() RETURNS TRIGGER
+  LANGUAGE plpgsql
+  AS $$
+BEGIN
+  IF TG_OP IN ('UPDATE', 'DELETE') THEN
+    IF TG_OP = 'UPDATE' AND OLD.user_id != NEW.user_id THEN
+      UPDATE user_ministry_history SET user_id = NEW.user_id
+      WHERE user_id = OLD.user_id AND ministry_id = OLD.ministry_id AND left_at IS NULL;
+    END IF;
+
+    IF TG_OP = 'DELETE' OR OLD.ministry_id != NEW.ministry_id OR OLD.user_role != NEW.user_role THEN
+      UPDATE user_ministry_history SET
+        left_at = NOW(),
+        left_reason = CASE
+          WHEN TG_OP = 'DELETE' OR OLD.ministry_id != NEW.ministry_id
+          THEN NULLIF(current_setting('membership.left_reason', TRUE), '')
+        END
+      WHERE
+        user_id = CASE WHEN TG_OP = 'DELETE' THEN OLD.user_id ELSE NEW.user_id END
+        AND ministry_id = OLD.ministry_id
+        AND left_at IS NULL;
+    END IF;
+  END IF;
+
+  IF TG_OP = 'INSERT' OR OLD.ministry_id != NEW.ministry_id OR OLD.user_role != NEW.user_role THEN
+    INSERT INTO user_ministry_history (user_id, ministry_id, user_role)
+    VALUES (NEW.user_id, NEW.ministry_id, NEW.user_role)
+    ON CONFLICT (user_id, ministry_id) WHERE left_at IS NULL DO NOTHING;
+  END IF;
+
+  RETURN NULL;
+END
+$$;
+
+CREATE TRIGGER record_membership
+  AFTER INSERT OR UPDATE OR DELETE ON user_connect_group
+  FOR EACH ROW EXECUTE FUNCTION record_connect_group_membership();
+CREATE TRIGGER record_membership
+  AFTER INSERT OR UPDATE OR DELETE ON user_ministry
+  FOR EACH ROW EXECUTE FUNCTION record_ministry_membership();
+
+-- migrate:down
+DROP TRIGGER record_membership ON user_ministry;
+DROP TRIGGER record_membership ON user_connect_group;
+DROP FUNCTION record_ministry_membership;
+DROP FUNCTION record_connect_group_membership;
+DROP TABLE user_ministry_history;
+DROP TABLE user_connect_group_history;
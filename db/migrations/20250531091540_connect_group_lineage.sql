@@ -0,0 +1,22 @@
+-- migrate:up
+CREATE TABLE connect_group_lineage (
+  child_id TEXT,
+  parent_id TEXT NOT NULL,
+  multiplied_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (child_id),
+  FOREIGN KEY (child_id) REFERENCES connect_group(id) ON UPDATE CASCADE ON DELETE CASCADE,
+  FOREIGN KEY (parent_id) REFERENCES connect_group(id) ON UPDATE CASCADE ON DELETE CASCADE,
+  CONSTRAINT connect_group_lineage_parent_check CHECK (parent_id != child_id)
+);
+COMMENT ON TABLE connect_group_lineage IS 'Connect groups multiplied out of another connect group, every connect group has at most one parent.';
+COMMENT ON COLUMN connect_group_lineage.child_id IS 'Connect group created by the multiplication.';
+COMMENT ON COLUMN connect_group_lineage.parent_id IS 'Connect group that multiplied.';
+COMMENT ON COLUMN connect_group_lineage.multiplied_at IS 'Time of the multiplication.';
+
+CREATE INDEX connect_group_lineage_parent_id_idx ON connect_group_lineage (parent_id);
+
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON connect_group_lineage FOR EACH ROW EXECUTE FUNCTION audit('child_id');
+
+-- migrate:down
+DROP TRIGGER audit ON connect_group_lineage;
+DROP TABLE connect_group_lineage;
@@ -0,0 +1,22 @@
+-- migrate:up
+CREATE TABLE user_oversight (
+  user_id TEXT,
+  overseer_id TEXT NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (user_id),
+  FOREIGN KEY (user_id) REFERENCES "user"(id) ON UPDATE CASCADE ON DELETE CASCADE,
+  FOREIGN KEY (overseer_id) REFERENCES "user"(id) ON UPDATE CASCADE ON DELETE CASCADE,
+  CONSTRAINT user_oversight_self_check CHECK (user_id != overseer_id)
+);
+COMMENT ON TABLE user_oversight IS 'Leaders overseen by a more senior leader (e.g., a CGL by their coach, a coach by their pastor), every leader has at most one overseer.';
+COMMENT ON COLUMN user_oversight.user_id IS 'The leader overseen.';
+COMMENT ON COLUMN user_oversight.overseer_id IS 'The leader overseeing them.';
+
+CREATE INDEX user_oversight_overseer_id_idx ON user_oversight (overseer_id);
+
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON user_oversight FOR EACH ROW EXECUTE FUNCTION audit('user_id');
+
+-- migrate:down
+DROP TRIGGER audit ON user_oversight;
+DROP TABLE user_oversight;
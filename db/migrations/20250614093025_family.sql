@@ -0,0 +1,93 @@
+-- migrate:up
+CREATE TABLE relationship_type (
+  name TEXT,
+  inverse TEXT NOT NULL,
+  description TEXT NOT NULL,
+  PRIMARY KEY (name),
+  FOREIGN KEY (inverse) REFERENCES relationship_type(name)
+);
+COMMENT ON TABLE relationship_type IS 'Vocabulary of the relationships between users.';
+COMMENT ON COLUMN relationship_type.name IS 'Relationship of the source user to the destination user (e.g., parent).';
+COMMENT ON COLUMN relationship_type.inverse IS 'Relationship of the destination user back to the source user (e.g., child), the relationship itself when symmetric.';
+
+INSERT INTO relationship_type (name, inverse, description) VALUES
+  ('parent', 'child', 'Father or mother of the destination user.'),
+  ('child', 'parent', 'Son or daughter of the destination user.'),
+  ('guardian', 'ward', 'Legal guardian of the destination user.'),
+  ('ward', 'guardian', 'Under the legal guardianship of the destination user.'),
+  ('grandparent', 'grandchild', 'Grandfather or grandmother of the destination user.'),
+  ('grandchild', 'grandparent', 'Grandson or granddaughter of the destination user.'),
+  ('spouse', 'spouse', 'Husband or wife of the destination user.'),
+  ('sibling', 'sibling', 'Brother or sister of the destination user.'),
+  ('relative', 'relative', 'Any other family member of the destination user.');
+
+-- Relationships used to be free text, the common spellings are mapped onto the vocabulary and
+-- anything else is kept as a symmetric relationship of its own.
+UPDATE user_relationship SET relationship = CASE lower(trim(relationship))
+  WHEN 'father' THEN 'parent'
+  WHEN 'mother' THEN 'parent'
+  WHEN 'son' THEN 'child'
+  WHEN 'daughter' THEN 'child'
+  WHEN 'husband' THEN 'spouse'
+  WHEN 'wife' THEN 'spouse'
+  WHEN 'brother' THEN 'sibling'
+  WHEN 'sister' THEN 'sibling'
+  WHEN 'grandfather' THEN 'grandparent'
+  WHEN 'grandmother' THEN 'grandparent'
+  WHEN 'grandson' THEN 'grandchild'
+  WHEN 'granddaughter' THEN 'grandchild'
+  ELSE lower(trim(relationship))
+END;
+INSERT INTO relationship_type (name, inverse, description)
+SELECT DISTINCT relationship, relationship, 'Carried over from a free-text relationship.'
+FROM user_relationship
+ON CONFLICT (name) DO NOTHING;
+
+ALTER TABLE user_relationship
+  ADD CONSTRAINT user_relationship_relationship_fkey
+    FOREIGN KEY (relationship) REFERENCES relationship_type(name) ON UPDATE CASCADE;
+ALTER TABLE user_relationship ADD COLUMN can_pick_up BOOLEAN NOT NULL DEFAULT FALSE;
+COMMENT ON COLUMN user_relationship.relationship IS 'Relationship of the source user to the destination user, the destination user holds the inverse relationship back.';
+COMMENT ON COLUMN user_relationship.can_pick_up IS 'Whether the source user may pick up the destination user (e.g., a child from the kids ministry).';
+
+-- Every relationship is now stored along with its inverse.
+INSERT INTO user_relationship (source_user_id, destination_user_id, relationship)
+SELECT r.destination_user_id, r.source_user_id, t.inverse
+FROM user_relationship r INNER JOIN relationship_type t ON t.name = r.relationship
+ON CONFLICT (source_user_id, destination_user_id) DO NOTHING;
+
+CREATE TABLE household (
+  id TEXT,
+  name TEXT NOT NULL,
+  address address,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (id)
+);
+COMMENT ON TABLE household IS 'Users living together, sharing an address.';
+COMMENT ON COLUMN household.name IS 'Name of a household (e.g., the Tan family).';
+COMMENT ON COLUMN household.address IS 'Address shared by the members of a household.';
+
+CREATE TABLE user_household (
+  household_id TEXT,
+  user_id TEXT,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (household_id, user_id),
+  FOREIGN KEY (household_id) REFERENCES household(id) ON DELETE CASCADE,
+  FOREIGN KEY (user_id) REFERENCES "user"(id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+COMMENT ON TABLE user_household IS 'Members of a household, a user may belong to several (e.g., a child of separated parents).';
+
+CREATE INDEX user_household_user_id_idx ON user_household (user_id);
+
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON household FOR EACH ROW EXECUTE FUNCTION audit('id');
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON user_household FOR EACH ROW EXECUTE FUNCTION audit('household_id', 'user_id');
+
+-- migrate:down
+DROP TRIGGER audit ON user_household;
+DROP TRIGGER audit ON household;
+DROP TABLE user_household;
+DROP TABLE household;
+ALTER TABLE user_relationship DROP COLUMN can_pick_up;
+ALTER TABLE user_relationship DROP CONSTRAINT user_relationship_relationship_fkey;
+DROP TABLE relationship_type;
@@ -0,0 +1,54 @@
+-- migrate:up
+CREATE TABLE user_erasure (
+  user_id TEXT,
+  requested_by TEXT NOT NULL,
+  reason TEXT,
+  scheduled_at TIMESTAMPTZ NOT NULL,
+  erased_at TIMESTAMPTZ,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (user_id),
+  FOREIGN KEY (user_id) REFERENCES "user"(id) ON UPDATE CASCADE
+);
+COMMENT ON TABLE user_erasure IS 'Requests to erase the personal data of a user, kept once carried out as a record of the erasure.';
+COMMENT ON COLUMN user_erasure.requested_by IS 'Subject of the access token that requested the erasure.';
+COMMENT ON COLUMN user_erasure.scheduled_at IS 'Time the erasure is carried out, the request may be cancelled until then.';
+COMMENT ON COLUMN user_erasure.erased_at IS 'Time the personal data of the user was erased, NULL while pending.';
+
+CREATE INDEX user_erasure_pending_idx ON user_erasure (scheduled_at) WHERE erased_at IS NULL;
+
+CREATE TRIGGER audit AFTER INSERT OR UPDATE OR DELETE ON user_erasure FOR EACH ROW EXECUTE FUNCTION audit('user_id');
+
+-- Erasing a user redacts the personal data held in the rows of the `audit_log` recording their
+-- changes, which is only allowed within a transaction setting `audit.redact`. Nothing but the
+-- recorded rows (and the id of a row that is itself personal data) may change.
+CREATE OR REPLACE FUNCTION audit_log_append_only() RETURNS TRIGGER
+  LANGUAGE plpgsql
+  AS $$
+BEGIN
+  IF TG_OP = 'UPDATE'
+    AND current_setting('audit.redact', TRUE) = 'on'
+    AND NEW.id = OLD.id
+    AND NEW.actor IS NOT DISTINCT FROM OLD.actor
+    AND NEW.request_id IS NOT DISTINCT FROM OLD.request_id
+    AND NEW.resource = OLD.resource
+    AND NEW.action = OLD.action
+    AND NEW.created_at = OLD.created_at
+  THEN
+    RETURN NEW;
+  END IF;
+
+  RAISE EXCEPTION 'audit_log is append-only';
+END
+$$;
+
+-- migrate:down
+CREATE OR REPLACE FUNCTION audit_log_append_only() RETURNS TRIGGER
+  LANGUAGE plpgsql
+  AS $$
+BEGIN
+  RAISE EXCEPTION 'audit_log is append-only';
+END
+$$;
+
+DROP TRIGGER audit ON user_erasure;
+DROP TABLE user_erasure;
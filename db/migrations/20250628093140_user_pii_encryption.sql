@@ -0,0 +1,50 @@
+-- migrate:up
+-- The sensitive details of users are encrypted by the identity service with keys the database
+-- never sees, so they are stored as opaque bytes next to the plaintext columns. Those are only
+-- kept until `rotate-pii-keys` has encrypted the values they held before this migration.
+ALTER TABLE "user"
+  ADD COLUMN ic_number_encrypted BYTEA,
+  ADD COLUMN ic_number_index BYTEA,
+  ADD COLUMN phone_number_encrypted BYTEA,
+  ADD COLUMN phone_number_index BYTEA,
+  ADD COLUMN address_encrypted BYTEA,
+  ADD COLUMN date_of_birth_encrypted BYTEA,
+  ADD COLUMN year_of_birth INTEGER;
+COMMENT ON COLUMN "user".ic_number_encrypted IS 'IC number of a user, encrypted by the identity service.';
+COMMENT ON COLUMN "user".ic_number_index IS 'Keyed hash of the normalised IC number of a user, to look users up by it.';
+COMMENT ON COLUMN "user".phone_number_encrypted IS 'Phone number of a user, encrypted by the identity service.';
+COMMENT ON COLUMN "user".phone_number_index IS 'Keyed hash of the normalised phone number of a user, to look users up by it.';
+COMMENT ON COLUMN "user".address_encrypted IS 'Address of a user, encrypted by the identity service.';
+COMMENT ON COLUMN "user".date_of_birth_encrypted IS 'Date of birth of a user, encrypted by the identity service.';
+COMMENT ON COLUMN "user".year_of_birth IS 'Year a user was born in, left in plaintext to filter users by age.';
+COMMENT ON COLUMN "user".ic_number IS 'IC number stored before encryption, cleared once encrypted.';
+COMMENT ON COLUMN "user".phone_number IS 'Phone number stored before encryption, cleared once encrypted.';
+COMMENT ON COLUMN "user".address IS 'Address stored before encryption, cleared once encrypted.';
+COMMENT ON COLUMN "user".date_of_birth IS 'Date of birth stored before encryption, cleared once encrypted.';
+
+UPDATE "user" SET year_of_birth = EXTRACT(YEAR FROM date_of_birth AT TIME ZONE 'UTC');
+
+DROP INDEX user_normalised_phone_number_idx;
+DROP INDEX user_normalised_ic_number_idx;
+CREATE INDEX user_ic_number_index_idx ON "user" (ic_number_index);
+CREATE INDEX user_phone_number_index_idx ON "user" (phone_number_index);
+
+-- migrate:down
+-- The encrypted values cannot be decrypted by the database, they are lost.
+DROP INDEX user_phone_number_index_idx;
+DROP INDEX user_ic_number_index_idx;
+CREATE INDEX user_normalised_phone_number_idx ON "user" (normalise_phone_number(phone_number));
+CREATE INDEX user_normalised_ic_number_idx ON "user" (normalise_ic_number(ic_number));
+
+COMMENT ON COLUMN "user".ic_number IS NULL;
+COMMENT ON COLUMN "user".phone_number IS NULL;
+COMMENT ON COLUMN "user".address IS NULL;
+COMMENT ON COLUMN "user".date_of_birth IS NULL;
+ALTER TABLE "user"
+  DROP COLUMN year_of_birth,
+  DROP COLUMN date_of_birth_encrypted,
+  DROP COLUMN address_encrypted,
+  DROP COLUMN phone_number_index,
+  DROP COLUMN phone_number_encrypted,
+  DROP COLUMN ic_number_index,
+  DROP COLUMN ic_number_encrypted;
@@ -16,6 +16,20 @@ SET row_security = off;
 CREATE SCHEMA migrations;
 
 
+--
+-- Name: pg_trgm; Type: EXTENSION; Schema: -; Owner: -
+--
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm WITH SCHEMA public;
+
+
+--
+-- Name: EXTENSION pg_trgm; Type: COMMENT; Schema: -; Owner: -
+--
+
+COMMENT ON EXTENSION pg_trgm IS 'text similarity measurement and index searching based on trigrams';
+
+
 --
 -- Name: _address; Type: TYPE; Schema: public; Owner: -
 --
@@ -80,6 +94,27 @@ CREATE DOMAIN public.address AS public._address
 	CONSTRAINT address_check CHECK (((((VALUE).line_one IS NOT NULL) AND ((VALUE).city IS NOT NULL) AND ((VALUE).state IS NOT NULL) AND ((VALUE).country IS NOT NULL) AND ((VALUE).postal_code IS NOT NULL)) OR (VALUE IS NULL)));
 
 
+--
+-- Name: audit_action; Type: TYPE; Schema: public; Owner: -
+--
+
+CREATE TYPE public.audit_action AS ENUM (
+    'insert',
+    'update',
+    'delete'
+);
+
+
+--
+-- Name: claim_channel; Type: TYPE; Schema: public; Owner: -
+--
+
+CREATE TYPE public.claim_channel AS ENUM (
+    'email',
+    'phone'
+);
+
+
 --
 -- Name: gender; Type: TYPE; Schema: public; Owner: -
 --
@@ -90,6 +125,233 @@ CREATE TYPE public.gender AS ENUM (
 );
 
 
+--
+-- Name: matched_attribute; Type: TYPE; Schema: public; Owner: -
+--
+
+CREATE TYPE public.matched_attribute AS ENUM (
+    'phone_number',
+    'email',
+    'ic_number',
+    'name'
+);
+
+
+--
+-- Name: user_duplicate_status; Type: TYPE; Schema: public; Owner: -
+--
+
+CREATE TYPE public.user_duplicate_status AS ENUM (
+    'pending',
+    'dismissed',
+    'merged'
+);
+
+
+--
+-- Name: webhook_delivery_status; Type: TYPE; Schema: public; Owner: -
+--
+
+CREATE TYPE public.webhook_delivery_status AS ENUM (
+    'pending',
+    'delivered',
+    'dead'
+);
+
+
+--
+-- Name: audit(); Type: FUNCTION; Schema: public; Owner: -
+--
+
+CREATE FUNCTION public.audit() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+DECLARE
+  -- Generated columns such as the search vector of a user only repeat the other columns.
+  before JSONB := CASE WHEN TG_OP IN ('UPDATE', 'DELETE') THEN to_jsonb(OLD) - 'search_vector' END;
+  after JSONB := CASE WHEN TG_OP IN ('INSERT', 'UPDATE') THEN to_jsonb(NEW) - 'search_vector' END;
+  changes JSONB;
+  resource_id TEXT;
+BEGIN
+  IF TG_OP = 'UPDATE' THEN
+    SELECT jsonb_object_agg(a.key, a.value) INTO changes
+    FROM jsonb_each(after) a
+    WHERE a.key != 'updated_at' AND a.value IS DISTINCT FROM before -> a.key;
+
+    -- Nothing but the update time has changed.
+    IF changes IS NULL THEN
+      RETURN NULL;
+    END IF;
+  END IF;
+
+  SELECT string_agg(COALESCE(after, before) ->> k.key, '/' ORDER BY k.ord) INTO resource_id
+  FROM unnest(TG_ARGV) WITH ORDINALITY AS k(key, ord);
+
+  INSERT INTO audit_log (actor, request_id, resource, resource_id, action, before, after, changes)
+  VALUES (
+    NULLIF(current_setting('audit.actor', TRUE), ''),
+    NULLIF(current_setting('audit.request_id', TRUE), ''),
+    TG_TABLE_NAME,
+    resource_id,
+    lower(TG_OP)::audit_action,
+    before,
+    after,
+    changes
+  );
+
+  RETURN NULL;
+END
+$$;
+
+
+--
+-- Name: audit_log_append_only(); Type: FUNCTION; Schema: public; Owner: -
+--
+
+CREATE FUNCTION public.audit_log_append_only() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+  IF TG_OP = 'UPDATE'
+    AND current_setting('audit.redact', TRUE) = 'on'
+    AND NEW.id = OLD.id
+    AND NEW.actor IS NOT DISTINCT FROM OLD.actor
+    AND NEW.request_id IS NOT DISTINCT FROM OLD.request_id
+    AND NEW.resource = OLD.resource
+    AND NEW.action = OLD.action
+    AND NEW.created_at = OLD.created_at
+  THEN
+    RETURN NEW;
+  END IF;
+
+  RAISE EXCEPTION 'audit_log is append-only';
+END
+$$;
+
+
+--
+-- Name: normalise_email(text); Type: FUNCTION; Schema: public; Owner: -
+--
+
+CREATE FUNCTION public.normalise_email(email text) RETURNS text
+    LANGUAGE sql IMMUTABLE STRICT PARALLEL SAFE
+    AS $_$ SELECT NULLIF(replace(regexp_replace(lower(split_part(email, '@', 1)), '\+.*$', ''), '.', '') || '@' || lower(split_part(email, '@', 2)), '@') $_$;
+
+
+--
+-- Name: normalise_ic_number(text); Type: FUNCTION; Schema: public; Owner: -
+--
+
+CREATE FUNCTION public.normalise_ic_number(ic_number text) RETURNS text
+    LANGUAGE sql IMMUTABLE STRICT PARALLEL SAFE
+    AS $$ SELECT NULLIF(upper(regexp_replace(ic_number, '[^[:alnum:]]', '', 'g')), '') $$;
+
+
+--
+-- Name: normalise_phone_number(text); Type: FUNCTION; Schema: public; Owner: -
+--
+
+CREATE FUNCTION public.normalise_phone_number(phone_number text) RETURNS text
+    LANGUAGE sql IMMUTABLE STRICT PARALLEL SAFE
+    AS $$ SELECT NULLIF(regexp_replace(regexp_replace(phone_number, '\D', '', 'g'), '^(60|0)+', ''), '') $$;
+
+
+--
+-- Name: outbox_event_fan_out(); Type: FUNCTION; Schema: public; Owner: -
+--
+
+CREATE FUNCTION public.outbox_event_fan_out() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+  INSERT INTO webhook_delivery (webhook_id, event_id)
+  SELECT w.id, NEW.id
+  FROM webhook w
+  WHERE w.active AND (cardinality(w.event_types) = 0 OR NEW.type = ANY(w.event_types));
+
+  RETURN NULL;
+END
+$$;
+
+
+--
+-- Name: record_connect_group_membership(); Type: FUNCTION; Schema: public; Owner: -
+--
+
+CREATE FUNCTION public.record_connect_group_membership() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+  IF TG_OP IN ('UPDATE', 'DELETE') THEN
+    IF TG_OP = 'UPDATE' AND OLD.user_id != NEW.user_id THEN
+      UPDATE user_connect_group_history SET user_id = NEW.user_id
+      WHERE user_id = OLD.user_id AND connect_group_id = OLD.connect_group_id AND left_at IS NULL;
+    END IF;
+
+    IF TG_OP = 'DELETE' OR OLD.connect_group_id != NEW.connect_group_id OR OLD.user_role != NEW.user_role THEN
+      UPDATE user_connect_group_history SET
+        left_at = NOW(),
+        left_reason = CASE
+          WHEN TG_OP = 'DELETE' OR OLD.connect_group_id != NEW.connect_group_id
+          THEN NULLIF(current_setting('membership.left_reason', TRUE), '')
+        END
+      WHERE
+        user_id = CASE WHEN TG_OP = 'DELETE' THEN OLD.user_id ELSE NEW.user_id END
+        AND connect_group_id = OLD.connect_group_id
+        AND left_at IS NULL;
+    END IF;
+  END IF;
+
+  IF TG_OP = 'INSERT' OR OLD.connect_group_id != NEW.connect_group_id OR OLD.user_role != NEW.user_role THEN
+    INSERT INTO user_connect_group_history (user_id, connect_group_id, user_role)
+    VALUES (NEW.user_id, NEW.connect_group_id, NEW.user_role)
+    ON CONFLICT (user_id, connect_group_id) WHERE left_at IS NULL DO NOTHING;
+  END IF;
+
+  RETURN NULL;
+END
+$$;
+
+
+--
+-- Name: record_ministry_membership(); Type: FUNCTION; Schema: public; Owner: -
+--
+
+CREATE FUNCTION public.record_ministry_membership() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+  IF TG_OP IN ('UPDATE', 'DELETE') THEN
+    IF TG_OP = 'UPDATE' AND OLD.user_id != NEW.user_id THEN
+      UPDATE user_ministry_history SET user_id = NEW.user_id
+      WHERE user_id = OLD.user_id AND ministry_id = OLD.ministry_id AND left_at IS NULL;
+    END IF;
+
+    IF TG_OP = 'DELETE' OR OLD.ministry_id != NEW.ministry_id OR OLD.user_role != NEW.user_role THEN
+      UPDATE user_ministry_history SET
+        left_at = NOW(),
+        left_reason = CASE
+          WHEN TG_OP = 'DELETE' OR OLD.ministry_id != NEW.ministry_id
+          THEN NULLIF(current_setting('membership.left_reason', TRUE), '')
+        END
+      WHERE
+        user_id = CASE WHEN TG_OP = 'DELETE' THEN OLD.user_id ELSE NEW.user_id END
+        AND ministry_id = OLD.ministry_id
+        AND left_at IS NULL;
+    END IF;
+  END IF;
+
+  IF TG_OP = 'INSERT' OR OLD.ministry_id != NEW.ministry_id OR OLD.user_role != NEW.user_role THEN
+    INSERT INTO user_ministry_history (user_id, ministry_id, user_role)
+    VALUES (NEW.user_id, NEW.ministry_id, NEW.user_role)
+    ON CONFLICT (user_id, ministry_id) WHERE left_at IS NULL DO NOTHING;
+  END IF;
+
+  RETURN NULL;
+END
+$$;
+
+
 SET default_tablespace = '';
 
 SET default_table_access_method = heap;
@@ -115,6 +377,94 @@ CREATE TABLE public.attendance (
 );
 
 
+--
+-- Name: audit_log; Type: TABLE; Schema: public; Owner: -
+--
+
+CREATE TABLE public.audit_log (
+    id bigint NOT NULL,
+    actor text,
+    request_id text,
+    resource text NOT NULL,
+    resource_id text NOT NULL,
+    action public.audit_action NOT NULL,
+    before jsonb,
+    after jsonb,
+    changes jsonb,
+    created_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+
+--
+-- Name: TABLE audit_log; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON TABLE public.audit_log IS 'Append-only record of every change made to the audited tables.';
+
+
+--
+-- Name: COLUMN audit_log.actor; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.audit_log.actor IS 'Subject of the access token that made the change, NULL for changes made outside of the API (e.g., migrations or background jobs).';
+
+
+--
+-- Name: COLUMN audit_log.request_id; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.audit_log.request_id IS 'Id of the request that made the change, as returned in the `X-Request-Id` header.';
+
+
+--
+-- Name: COLUMN audit_log.resource; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.audit_log.resource IS 'Name of the table changed (e.g., user, user_connect_group, etc.).';
+
+
+--
+-- Name: COLUMN audit_log.resource_id; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.audit_log.resource_id IS 'Primary key of the changed row, the columns of a composite key are joined by `/`.';
+
+
+--
+-- Name: COLUMN audit_log.before; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.audit_log.before IS 'The row before the change, NULL when inserted.';
+
+
+--
+-- Name: COLUMN audit_log.after; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.audit_log.after IS 'The row after the change, NULL when deleted.';
+
+
+--
+-- Name: COLUMN audit_log.changes; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.audit_log.changes IS 'The columns changed by an update with their new values, NULL otherwise.';
+
+
+--
+-- Name: audit_log_id_seq; Type: SEQUENCE; Schema: public; Owner: -
+--
+
+ALTER TABLE public.audit_log ALTER COLUMN id ADD GENERATED ALWAYS AS IDENTITY (
+    SEQUENCE NAME public.audit_log_id_seq
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1
+);
+
+
 --
 -- Name: connect_group; Type: TABLE; Schema: public; Owner: -
 --
@@ -126,7 +476,11 @@ CREATE TABLE public.connect_group (
     variant character(2),
     satellite_id text NOT NULL,
     created_at timestamp with time zone DEFAULT now() NOT NULL,
-    updated_at timestamp with time zone DEFAULT now() NOT NULL
+    updated_at timestamp with time zone DEFAULT now() NOT NULL,
+    category_id text NOT NULL,
+    active boolean DEFAULT true NOT NULL,
+    closed_at timestamp with time zone,
+    image_url text
 );
 
 
@@ -134,7 +488,7 @@ CREATE TABLE public.connect_group (
 -- Name: COLUMN connect_group.id; Type: COMMENT; Schema: public; Owner: -
 --
 
-COMMENT ON COLUMN public.connect_group.id IS 'Unique identifier of a connect_group (e.g., connect_group_01H7JNPD7J67AA5AD87Q4SZDF9).';
+COMMENT ON COLUMN public.connect_group.id IS 'Unique identifier of a connect group (e.g., connect_group_01H7JNPD7J67AA5AD87Q4SZDF9).';
 
 
 --
@@ -179,6 +533,70 @@ COMMENT ON COLUMN public.connect_group.created_at IS 'Creation time of a connect
 COMMENT ON COLUMN public.connect_group.updated_at IS 'Last updated time of a connect group.';
 
 
+--
+-- Name: connect_group_category; Type: TABLE; Schema: public; Owner: -
+--
+
+CREATE TABLE public.connect_group_category (
+    id text NOT NULL,
+    name text NOT NULL
+);
+
+
+--
+-- Name: COLUMN connect_group_category.id; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.connect_group_category.id IS 'Unique identifier of a connect group category (e.g., connect_group_category_01H7JNPD7J67AA5AD87Q4SZDF9)';
+
+
+--
+-- Name: COLUMN connect_group_category.name; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.connect_group_category.name IS 'Name for a connect group category.';
+
+
+--
+-- Name: connect_group_lineage; Type: TABLE; Schema: public; Owner: -
+--
+
+CREATE TABLE public.connect_group_lineage (
+    child_id text NOT NULL,
+    parent_id text NOT NULL,
+    multiplied_at timestamp with time zone DEFAULT now() NOT NULL,
+    CONSTRAINT connect_group_lineage_parent_check CHECK ((parent_id <> child_id))
+);
+
+
+--
+-- Name: TABLE connect_group_lineage; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON TABLE public.connect_group_lineage IS 'Connect groups multiplied out of another connect group, every connect group has at most one parent.';
+
+
+--
+-- Name: COLUMN connect_group_lineage.child_id; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.connect_group_lineage.child_id IS 'Connect group created by the multiplication.';
+
+
+--
+-- Name: COLUMN connect_group_lineage.parent_id; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.connect_group_lineage.parent_id IS 'Connect group that multiplied.';
+
+
+--
+-- Name: COLUMN connect_group_lineage.multiplied_at; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.connect_group_lineage.multiplied_at IS 'Time of the multiplication.';
+
+
 --
 -- Name: connect_group_no_seq; Type: SEQUENCE; Schema: public; Owner: -
 --
@@ -305,6 +723,40 @@ CREATE TABLE public.form_field_type (
 );
 
 
+--
+-- Name: household; Type: TABLE; Schema: public; Owner: -
+--
+
+CREATE TABLE public.household (
+    id text NOT NULL,
+    name text NOT NULL,
+    address public.address,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+
+--
+-- Name: TABLE household; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON TABLE public.household IS 'Users living together, sharing an address.';
+
+
+--
+-- Name: COLUMN household.name; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.household.name IS 'Name of a household (e.g., the Tan family).';
+
+
+--
+-- Name: COLUMN household.address; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.household.address IS 'Address shared by the members of a household.';
+
+
 --
 -- Name: ministry; Type: TABLE; Schema: public; Owner: -
 --
@@ -513,6 +965,55 @@ COMMENT ON COLUMN public.ministry_team.created_at IS 'Creation time of a ministr
 COMMENT ON COLUMN public.ministry_team.updated_at IS 'Last updated time of a ministry team.';
 
 
+--
+-- Name: outbox_event; Type: TABLE; Schema: public; Owner: -
+--
+
+CREATE TABLE public.outbox_event (
+    id bigint NOT NULL,
+    type text NOT NULL,
+    payload jsonb NOT NULL,
+    actor text,
+    request_id text,
+    created_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+
+--
+-- Name: TABLE outbox_event; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON TABLE public.outbox_event IS 'Domain events written in the same transaction as the change they describe, delivered to the webhooks by the dispatcher.';
+
+
+--
+-- Name: COLUMN outbox_event.type; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.outbox_event.type IS 'Type of the event (e.g., connect_group.users_joined, attendance.recorded, etc.).';
+
+
+--
+-- Name: COLUMN outbox_event.actor; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.outbox_event.actor IS 'Subject of the access token that made the change, as set by `common::audit::begin`.';
+
+
+--
+-- Name: outbox_event_id_seq; Type: SEQUENCE; Schema: public; Owner: -
+--
+
+ALTER TABLE public.outbox_event ALTER COLUMN id ADD GENERATED ALWAYS AS IDENTITY (
+    SEQUENCE NAME public.outbox_event_id_seq
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1
+);
+
+
 --
 -- Name: pastoral_role; Type: TABLE; Schema: public; Owner: -
 --
@@ -640,15 +1141,47 @@ ALTER SEQUENCE public.registration_form_field_weight_seq OWNED BY public.registr
 
 
 --
--- Name: satellite; Type: TABLE; Schema: public; Owner: -
+-- Name: relationship_type; Type: TABLE; Schema: public; Owner: -
 --
 
-CREATE TABLE public.satellite (
-    id text NOT NULL,
-    no integer NOT NULL,
+CREATE TABLE public.relationship_type (
     name text NOT NULL,
-    address public.address NOT NULL,
-    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    inverse text NOT NULL,
+    description text NOT NULL
+);
+
+
+--
+-- Name: TABLE relationship_type; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON TABLE public.relationship_type IS 'Vocabulary of the relationships between users.';
+
+
+--
+-- Name: COLUMN relationship_type.name; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.relationship_type.name IS 'Relationship of the source user to the destination user (e.g., parent).';
+
+
+--
+-- Name: COLUMN relationship_type.inverse; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.relationship_type.inverse IS 'Relationship of the destination user back to the source user (e.g., child), the relationship itself when symmetric.';
+
+
+--
+-- Name: satellite; Type: TABLE; Schema: public; Owner: -
+--
+
+CREATE TABLE public.satellite (
+    id text NOT NULL,
+    no integer NOT NULL,
+    name text NOT NULL,
+    address public.address NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
     updated_at timestamp with time zone DEFAULT now() NOT NULL
 );
 
@@ -735,65 +1268,60 @@ CREATE TABLE public.session (
 
 
 --
--- Name: user; Type: TABLE; Schema: public; Owner: -
+-- Name: shadow_user; Type: TABLE; Schema: public; Owner: -
 --
 
-CREATE TABLE public."user" (
-    id text NOT NULL,
-    no integer NOT NULL,
-    email text NOT NULL,
-    email_verified boolean DEFAULT false NOT NULL,
+CREATE TABLE public.shadow_user (
     name text NOT NULL,
+    email text,
     username text,
     given_name text,
     family_name text,
     gender public.gender,
     ic_number text,
-    phone_number text,
-    phone_number_verified boolean DEFAULT false,
+    phone_number text NOT NULL,
     nickname text,
     avatar_url text,
     address public.address,
     date_of_birth timestamp with time zone,
+    cg text,
+    pastoral_status text,
+    ministry text,
+    deleted boolean,
     created_at timestamp with time zone DEFAULT now() NOT NULL,
-    updated_at timestamp with time zone DEFAULT now() NOT NULL
+    updated_at timestamp with time zone DEFAULT now() NOT NULL,
+    no integer NOT NULL,
+    claimed_by text,
+    claimed_at timestamp with time zone
 );
 
 
 --
--- Name: COLUMN "user".id; Type: COMMENT; Schema: public; Owner: -
+-- Name: COLUMN shadow_user.no; Type: COMMENT; Schema: public; Owner: -
 --
 
-COMMENT ON COLUMN public."user".id IS 'Unique identifier of a user. (e.g., auth0|01H7JNPD7J67AA5AD87Q4SZDF9)';
+COMMENT ON COLUMN public.shadow_user.no IS 'Sequence number of a shadow user, used to address it through the API.';
 
 
 --
--- Name: user_connect_group; Type: TABLE; Schema: public; Owner: -
+-- Name: COLUMN shadow_user.claimed_by; Type: COMMENT; Schema: public; Owner: -
 --
 
-CREATE TABLE public.user_connect_group (
-    user_id text NOT NULL,
-    connect_group_id text NOT NULL,
-    user_role text NOT NULL
-);
+COMMENT ON COLUMN public.shadow_user.claimed_by IS 'User that the shadow user was merged into, if any.';
 
 
 --
--- Name: user_ministry; Type: TABLE; Schema: public; Owner: -
+-- Name: COLUMN shadow_user.claimed_at; Type: COMMENT; Schema: public; Owner: -
 --
 
-CREATE TABLE public.user_ministry (
-    user_id text NOT NULL,
-    ministry_id text NOT NULL,
-    user_role text NOT NULL
-);
+COMMENT ON COLUMN public.shadow_user.claimed_at IS 'Time the shadow user was merged into a user.';
 
 
 --
--- Name: user_no_seq; Type: SEQUENCE; Schema: public; Owner: -
+-- Name: shadow_user_no_seq; Type: SEQUENCE; Schema: public; Owner: -
 --
 
-CREATE SEQUENCE public.user_no_seq
+CREATE SEQUENCE public.shadow_user_no_seq
     AS integer
     START WITH 1
     INCREMENT BY 1
@@ -803,414 +1331,1867 @@ CREATE SEQUENCE public.user_no_seq
 
 
 --
--- Name: user_no_seq; Type: SEQUENCE OWNED BY; Schema: public; Owner: -
+-- Name: shadow_user_no_seq; Type: SEQUENCE OWNED BY; Schema: public; Owner: -
 --
 
-ALTER SEQUENCE public.user_no_seq OWNED BY public."user".no;
+ALTER SEQUENCE public.shadow_user_no_seq OWNED BY public.shadow_user.no;
 
 
 --
--- Name: connect_group no; Type: DEFAULT; Schema: public; Owner: -
+-- Name: user; Type: TABLE; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.connect_group ALTER COLUMN no SET DEFAULT nextval('public.connect_group_no_seq'::regclass);
+CREATE TABLE public."user" (
+    id text NOT NULL,
+    no integer NOT NULL,
+    email text NOT NULL,
+    email_verified boolean DEFAULT false NOT NULL,
+    name text NOT NULL,
+    username text,
+    given_name text,
+    family_name text,
+    gender public.gender,
+    ic_number text,
+    phone_number text,
+    phone_number_verified boolean DEFAULT false,
+    nickname text,
+    avatar_url text,
+    address public.address,
+    date_of_birth timestamp with time zone,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone DEFAULT now() NOT NULL,
+    deleted boolean DEFAULT false NOT NULL,
+    search_vector tsvector GENERATED ALWAYS AS (((setweight(to_tsvector('simple'::regconfig, name), 'A'::"char") || setweight(to_tsvector('simple'::regconfig, ((((((COALESCE(nickname, ''::text) || ' '::text) || COALESCE(given_name, ''::text)) || ' '::text) || COALESCE(family_name, ''::text)) || ' '::text) || COALESCE(username, ''::text))), 'B'::"char")) || setweight(to_tsvector('simple'::regconfig, email), 'C'::"char"))) STORED,
+    ic_number_encrypted bytea,
+    ic_number_index bytea,
+    phone_number_encrypted bytea,
+    phone_number_index bytea,
+    address_encrypted bytea,
+    date_of_birth_encrypted bytea,
+    year_of_birth integer
+);
 
 
 --
--- Name: registration_form_field weight; Type: DEFAULT; Schema: public; Owner: -
+-- Name: COLUMN "user".id; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.registration_form_field ALTER COLUMN weight SET DEFAULT nextval('public.registration_form_field_weight_seq'::regclass);
+COMMENT ON COLUMN public."user".id IS 'Unique identifier of a user. (e.g., auth0|01H7JNPD7J67AA5AD87Q4SZDF9)';
 
 
 --
--- Name: satellite no; Type: DEFAULT; Schema: public; Owner: -
+-- Name: COLUMN "user".ic_number; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.satellite ALTER COLUMN no SET DEFAULT nextval('public.satellite_no_seq'::regclass);
+COMMENT ON COLUMN public."user".ic_number IS 'IC number stored before encryption, cleared once encrypted.';
 
 
 --
--- Name: user no; Type: DEFAULT; Schema: public; Owner: -
+-- Name: COLUMN "user".phone_number; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public."user" ALTER COLUMN no SET DEFAULT nextval('public.user_no_seq'::regclass);
+COMMENT ON COLUMN public."user".phone_number IS 'Phone number stored before encryption, cleared once encrypted.';
 
 
 --
--- Name: schema_migrations schema_migrations_pkey; Type: CONSTRAINT; Schema: migrations; Owner: -
+-- Name: COLUMN "user".address; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY migrations.schema_migrations
-    ADD CONSTRAINT schema_migrations_pkey PRIMARY KEY (version);
+COMMENT ON COLUMN public."user".address IS 'Address stored before encryption, cleared once encrypted.';
 
 
 --
--- Name: attendance attendance_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN "user".date_of_birth; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.attendance
-    ADD CONSTRAINT attendance_pkey PRIMARY KEY (session_id, user_id);
+COMMENT ON COLUMN public."user".date_of_birth IS 'Date of birth stored before encryption, cleared once encrypted.';
 
 
 --
--- Name: connect_group connect_group_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN "user".search_vector; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.connect_group
-    ADD CONSTRAINT connect_group_pkey PRIMARY KEY (id);
+COMMENT ON COLUMN public."user".search_vector IS 'Names, username and email of a user for full text search, names weigh the most.';
 
 
 --
--- Name: connect_group connect_group_satellite_id_no_variant_key; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN "user".ic_number_encrypted; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.connect_group
-    ADD CONSTRAINT connect_group_satellite_id_no_variant_key UNIQUE (satellite_id, no, variant);
+COMMENT ON COLUMN public."user".ic_number_encrypted IS 'IC number of a user, encrypted by the identity service.';
 
 
 --
--- Name: currency currency_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN "user".ic_number_index; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.currency
-    ADD CONSTRAINT currency_pkey PRIMARY KEY (code);
+COMMENT ON COLUMN public."user".ic_number_index IS 'Keyed hash of the normalised IC number of a user, to look users up by it.';
 
 
 --
--- Name: event event_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN "user".phone_number_encrypted; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.event
-    ADD CONSTRAINT event_pkey PRIMARY KEY (id);
+COMMENT ON COLUMN public."user".phone_number_encrypted IS 'Phone number of a user, encrypted by the identity service.';
 
 
 --
--- Name: event_type event_type_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN "user".phone_number_index; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.event_type
-    ADD CONSTRAINT event_type_pkey PRIMARY KEY (name);
+COMMENT ON COLUMN public."user".phone_number_index IS 'Keyed hash of the normalised phone number of a user, to look users up by it.';
 
 
 --
--- Name: form_field_type form_field_type_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN "user".address_encrypted; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.form_field_type
-    ADD CONSTRAINT form_field_type_pkey PRIMARY KEY (type);
+COMMENT ON COLUMN public."user".address_encrypted IS 'Address of a user, encrypted by the identity service.';
 
 
 --
--- Name: ministry_department ministry_department_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN "user".date_of_birth_encrypted; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.ministry_department
-    ADD CONSTRAINT ministry_department_pkey PRIMARY KEY (id);
+COMMENT ON COLUMN public."user".date_of_birth_encrypted IS 'Date of birth of a user, encrypted by the identity service.';
 
 
 --
--- Name: ministry ministry_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN "user".year_of_birth; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.ministry
-    ADD CONSTRAINT ministry_pkey PRIMARY KEY (id);
+COMMENT ON COLUMN public."user".year_of_birth IS 'Year a user was born in, left in plaintext to filter users by age.';
 
 
 --
--- Name: ministry_role ministry_role_name_key; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: user_claim; Type: TABLE; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.ministry_role
-    ADD CONSTRAINT ministry_role_name_key UNIQUE (name);
+CREATE TABLE public.user_claim (
+    id text NOT NULL,
+    user_id text NOT NULL,
+    email text NOT NULL,
+    shadow_user_no integer,
+    claimed_user_id text,
+    channel public.claim_channel NOT NULL,
+    destination text NOT NULL,
+    code_hash text NOT NULL,
+    attempts integer DEFAULT 0 NOT NULL,
+    expires_at timestamp with time zone NOT NULL,
+    verified_at timestamp with time zone,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    CONSTRAINT user_claim_target_check CHECK (((shadow_user_no IS NULL) <> (claimed_user_id IS NULL)))
+);
 
 
 --
--- Name: ministry_role ministry_role_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN user_claim.id; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.ministry_role
-    ADD CONSTRAINT ministry_role_pkey PRIMARY KEY (id);
+COMMENT ON COLUMN public.user_claim.id IS 'Unique identifier of a claim (e.g., user_claim_01H7JNPD7J67AA5AD87Q4SZDF9).';
 
 
 --
--- Name: ministry_team ministry_team_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN user_claim.user_id; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.ministry_team
-    ADD CONSTRAINT ministry_team_pkey PRIMARY KEY (id);
+COMMENT ON COLUMN public.user_claim.user_id IS 'Auth0 user claiming the profile, who may not have a user yet.';
 
 
 --
--- Name: pastoral_role pastoral_role_name_key; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN user_claim.email; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.pastoral_role
-    ADD CONSTRAINT pastoral_role_name_key UNIQUE (name);
+COMMENT ON COLUMN public.user_claim.email IS 'Email of the Auth0 user, which the claimed profile takes over.';
 
 
 --
--- Name: pastoral_role pastoral_role_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN user_claim.shadow_user_no; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.pastoral_role
-    ADD CONSTRAINT pastoral_role_pkey PRIMARY KEY (id);
+COMMENT ON COLUMN public.user_claim.shadow_user_no IS 'Shadow user being claimed, if any.';
 
 
 --
--- Name: price price_event_id_name_key; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN user_claim.claimed_user_id; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.price
-    ADD CONSTRAINT price_event_id_name_key UNIQUE (event_id, name);
+COMMENT ON COLUMN public.user_claim.claimed_user_id IS 'Soft-deleted user being claimed, if any.';
 
 
 --
--- Name: price price_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN user_claim.channel; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.price
-    ADD CONSTRAINT price_pkey PRIMARY KEY (id);
+COMMENT ON COLUMN public.user_claim.channel IS 'How the one-time code was delivered.';
 
 
 --
--- Name: registration registration_event_id_name_key; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN user_claim.destination; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.registration
-    ADD CONSTRAINT registration_event_id_name_key UNIQUE (event_id, name);
+COMMENT ON COLUMN public.user_claim.destination IS 'Masked email or phone number the one-time code was sent to.';
 
 
 --
--- Name: registration_form_field_data registration_form_field_data_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN user_claim.code_hash; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.registration_form_field_data
-    ADD CONSTRAINT registration_form_field_data_pkey PRIMARY KEY (registration_id, name, user_id);
+COMMENT ON COLUMN public.user_claim.code_hash IS 'SHA-256 of the claim id and the one-time code, the code itself is never stored.';
 
 
 --
--- Name: registration_form_field registration_form_field_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN user_claim.attempts; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.registration_form_field
-    ADD CONSTRAINT registration_form_field_pkey PRIMARY KEY (registration_id, name);
+COMMENT ON COLUMN public.user_claim.attempts IS 'Number of wrong codes submitted.';
 
 
 --
--- Name: registration_form_field registration_form_field_registration_id_weight_key; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN user_claim.verified_at; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.registration_form_field
-    ADD CONSTRAINT registration_form_field_registration_id_weight_key UNIQUE (registration_id, weight);
+COMMENT ON COLUMN public.user_claim.verified_at IS 'Time the code was verified and the profile claimed.';
 
 
 --
--- Name: registration registration_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: user_connect_group; Type: TABLE; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.registration
-    ADD CONSTRAINT registration_pkey PRIMARY KEY (id);
+CREATE TABLE public.user_connect_group (
+    user_id text NOT NULL,
+    connect_group_id text NOT NULL,
+    user_role text NOT NULL
+);
 
 
 --
--- Name: satellite satellite_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: user_connect_group_history; Type: TABLE; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.satellite
-    ADD CONSTRAINT satellite_pkey PRIMARY KEY (id);
+CREATE TABLE public.user_connect_group_history (
+    id bigint NOT NULL,
+    user_id text NOT NULL,
+    connect_group_id text NOT NULL,
+    user_role text NOT NULL,
+    joined_at timestamp with time zone DEFAULT now(),
+    left_at timestamp with time zone,
+    left_reason text,
+    CONSTRAINT user_connect_group_history_period_check CHECK ((left_at >= joined_at))
+);
 
 
 --
--- Name: session session_event_id_name_key; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: TABLE user_connect_group_history; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.session
-    ADD CONSTRAINT session_event_id_name_key UNIQUE (event_id, name);
+COMMENT ON TABLE public.user_connect_group_history IS 'Periods a user was a member of a connect group with the same role, a role change ends a period and starts the next one.';
 
 
 --
--- Name: session session_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN user_connect_group_history.user_role; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.session
-    ADD CONSTRAINT session_pkey PRIMARY KEY (id);
+COMMENT ON COLUMN public.user_connect_group_history.user_role IS 'The pastoral role held, not a foreign key so that the history outlives deleted roles.';
 
 
 --
--- Name: user_connect_group user_connect_group_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN user_connect_group_history.joined_at; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.user_connect_group
-    ADD CONSTRAINT user_connect_group_pkey PRIMARY KEY (user_id, connect_group_id);
+COMMENT ON COLUMN public.user_connect_group_history.joined_at IS 'Start of the period, NULL for memberships that predate the history.';
 
 
 --
--- Name: user user_email_key; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN user_connect_group_history.left_at; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public."user"
-    ADD CONSTRAINT user_email_key UNIQUE (email);
+COMMENT ON COLUMN public.user_connect_group_history.left_at IS 'End of the period, NULL while ongoing.';
 
 
 --
--- Name: user_ministry user_ministry_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN user_connect_group_history.left_reason; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.user_ministry
-    ADD CONSTRAINT user_ministry_pkey PRIMARY KEY (user_id, ministry_id);
+COMMENT ON COLUMN public.user_connect_group_history.left_reason IS 'Why the user left, as given when removed from the connect group.';
 
 
 --
--- Name: user user_no_key; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: user_connect_group_history_id_seq; Type: SEQUENCE; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public."user"
-    ADD CONSTRAINT user_no_key UNIQUE (no);
+ALTER TABLE public.user_connect_group_history ALTER COLUMN id ADD GENERATED ALWAYS AS IDENTITY (
+    SEQUENCE NAME public.user_connect_group_history_id_seq
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1
+);
 
 
 --
--- Name: user user_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: user_duplicate; Type: TABLE; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public."user"
-    ADD CONSTRAINT user_pkey PRIMARY KEY (id);
+CREATE TABLE public.user_duplicate (
+    user_id text NOT NULL,
+    duplicate_user_id text NOT NULL,
+    score double precision NOT NULL,
+    matched_on public.matched_attribute[] NOT NULL,
+    status public.user_duplicate_status DEFAULT 'pending'::public.user_duplicate_status NOT NULL,
+    reviewed_by text,
+    reviewed_at timestamp with time zone,
+    detected_at timestamp with time zone DEFAULT now() NOT NULL,
+    CONSTRAINT user_duplicate_pair_check CHECK ((user_id <> duplicate_user_id))
+);
 
 
 --
--- Name: user user_username_key; Type: CONSTRAINT; Schema: public; Owner: -
+-- Name: TABLE user_duplicate; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public."user"
-    ADD CONSTRAINT user_username_key UNIQUE (username);
+COMMENT ON TABLE public.user_duplicate IS 'Pairs of users that are likely the same person, found by the duplicate detection.';
 
 
 --
--- Name: attendance attendance_session_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN user_duplicate.score; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.attendance
-    ADD CONSTRAINT attendance_session_id_fkey FOREIGN KEY (session_id) REFERENCES public.session(id);
+COMMENT ON COLUMN public.user_duplicate.score IS 'Likelihood between 0 and 1 that both users are the same person.';
 
 
 --
--- Name: connect_group connect_group_satellite_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN user_duplicate.status; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.connect_group
-    ADD CONSTRAINT connect_group_satellite_id_fkey FOREIGN KEY (satellite_id) REFERENCES public.satellite(id) ON UPDATE CASCADE;
+COMMENT ON COLUMN public.user_duplicate.status IS 'Whether the pair is awaiting review, was dismissed as distinct people or was merged.';
 
 
 --
--- Name: event event_type_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN user_duplicate.reviewed_by; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.event
-    ADD CONSTRAINT event_type_fkey FOREIGN KEY (type) REFERENCES public.event_type(name);
+COMMENT ON COLUMN public.user_duplicate.reviewed_by IS 'Subject of the access token that dismissed or merged the pair.';
 
 
 --
--- Name: ministry ministry_department_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN user_duplicate.detected_at; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.ministry
-    ADD CONSTRAINT ministry_department_id_fkey FOREIGN KEY (department_id) REFERENCES public.ministry_department(id) ON UPDATE CASCADE;
+COMMENT ON COLUMN public.user_duplicate.detected_at IS 'Time the pair was last found by the duplicate detection.';
 
 
 --
--- Name: ministry ministry_satellite_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+-- Name: user_erasure; Type: TABLE; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.ministry
-    ADD CONSTRAINT ministry_satellite_id_fkey FOREIGN KEY (satellite_id) REFERENCES public.satellite(id) ON UPDATE CASCADE;
+CREATE TABLE public.user_erasure (
+    user_id text NOT NULL,
+    requested_by text NOT NULL,
+    reason text,
+    scheduled_at timestamp with time zone NOT NULL,
+    erased_at timestamp with time zone,
+    created_at timestamp with time zone DEFAULT now() NOT NULL
+);
 
 
 --
--- Name: ministry ministry_team_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+-- Name: TABLE user_erasure; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.ministry
-    ADD CONSTRAINT ministry_team_id_fkey FOREIGN KEY (team_id) REFERENCES public.ministry_team(id) ON UPDATE CASCADE;
+COMMENT ON TABLE public.user_erasure IS 'Requests to erase the personal data of a user, kept once carried out as a record of the erasure.';
 
 
 --
--- Name: price price_currency_code_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN user_erasure.requested_by; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.price
-    ADD CONSTRAINT price_currency_code_fkey FOREIGN KEY (currency_code) REFERENCES public.currency(code);
+COMMENT ON COLUMN public.user_erasure.requested_by IS 'Subject of the access token that requested the erasure.';
 
 
 --
--- Name: price price_event_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN user_erasure.scheduled_at; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.price
-    ADD CONSTRAINT price_event_id_fkey FOREIGN KEY (event_id) REFERENCES public.event(id);
+COMMENT ON COLUMN public.user_erasure.scheduled_at IS 'Time the erasure is carried out, the request may be cancelled until then.';
 
 
 --
--- Name: registration registration_event_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN user_erasure.erased_at; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.registration
-    ADD CONSTRAINT registration_event_id_fkey FOREIGN KEY (event_id) REFERENCES public.event(id);
+COMMENT ON COLUMN public.user_erasure.erased_at IS 'Time the personal data of the user was erased, NULL while pending.';
 
 
 --
--- Name: registration_form_field_data registration_form_field_data_registration_id_name_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+-- Name: user_household; Type: TABLE; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.registration_form_field_data
-    ADD CONSTRAINT registration_form_field_data_registration_id_name_fkey FOREIGN KEY (registration_id, name) REFERENCES public.registration_form_field(registration_id, name);
+CREATE TABLE public.user_household (
+    household_id text NOT NULL,
+    user_id text NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL
+);
 
 
 --
--- Name: registration_form_field registration_form_field_registration_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+-- Name: TABLE user_household; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.registration_form_field
-    ADD CONSTRAINT registration_form_field_registration_id_fkey FOREIGN KEY (registration_id) REFERENCES public.registration(id);
+COMMENT ON TABLE public.user_household IS 'Members of a household, a user may belong to several (e.g., a child of separated parents).';
 
 
 --
--- Name: registration_form_field registration_form_field_type_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+-- Name: user_merge; Type: TABLE; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.registration_form_field
-    ADD CONSTRAINT registration_form_field_type_fkey FOREIGN KEY (type) REFERENCES public.form_field_type(type);
+CREATE TABLE public.user_merge (
+    id text NOT NULL,
+    user_id text NOT NULL,
+    merged_user_id text NOT NULL,
+    merged_by text NOT NULL,
+    connect_groups_moved integer NOT NULL,
+    connect_groups_dropped integer NOT NULL,
+    ministries_moved integer NOT NULL,
+    ministries_dropped integer NOT NULL,
+    relationships_moved integer NOT NULL,
+    relationships_dropped integer NOT NULL,
+    attendance_moved integer NOT NULL,
+    attendance_dropped integer NOT NULL,
+    form_data_moved integer NOT NULL,
+    form_data_dropped integer NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    CONSTRAINT user_merge_self_check CHECK ((user_id <> merged_user_id))
+);
 
 
 --
--- Name: session session_event_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+-- Name: TABLE user_merge; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.session
-    ADD CONSTRAINT session_event_id_fkey FOREIGN KEY (event_id) REFERENCES public.event(id);
+COMMENT ON TABLE public.user_merge IS 'Audit trail of duplicate users merged into one another.';
 
 
 --
--- Name: user_connect_group user_connect_group_connect_group_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN user_merge.user_id; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.user_connect_group
-    ADD CONSTRAINT user_connect_group_connect_group_id_fkey FOREIGN KEY (connect_group_id) REFERENCES public.connect_group(id) ON UPDATE CASCADE;
+COMMENT ON COLUMN public.user_merge.user_id IS 'User that was kept.';
 
 
 --
--- Name: user_connect_group user_connect_group_user_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN user_merge.merged_user_id; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.user_connect_group
-    ADD CONSTRAINT user_connect_group_user_id_fkey FOREIGN KEY (user_id) REFERENCES public."user"(id) ON UPDATE CASCADE;
+COMMENT ON COLUMN public.user_merge.merged_user_id IS 'User that was merged into the kept one and soft-deleted.';
 
 
 --
--- Name: user_connect_group user_connect_group_user_role_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+-- Name: COLUMN user_merge.merged_by; Type: COMMENT; Schema: public; Owner: -
 --
 
-ALTER TABLE ONLY public.user_connect_group
-    ADD CONSTRAINT user_connect_group_user_role_fkey FOREIGN KEY (user_role) REFERENCES public.pastoral_role(id) ON UPDATE CASCADE;
+COMMENT ON COLUMN public.user_merge.merged_by IS 'Subject of the access token that performed the merge.';
+
+
+--
+-- Name: COLUMN user_merge.connect_groups_dropped; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.user_merge.connect_groups_dropped IS 'Memberships dropped because the kept user was already in the connect group, the most senior role is kept.';
+
+
+--
+-- Name: user_ministry; Type: TABLE; Schema: public; Owner: -
+--
+
+CREATE TABLE public.user_ministry (
+    user_id text NOT NULL,
+    ministry_id text NOT NULL,
+    user_role text NOT NULL
+);
+
+
+--
+-- Name: user_ministry_history; Type: TABLE; Schema: public; Owner: -
+--
+
+CREATE TABLE public.user_ministry_history (
+    id bigint NOT NULL,
+    user_id text NOT NULL,
+    ministry_id text NOT NULL,
+    user_role text NOT NULL,
+    joined_at timestamp with time zone DEFAULT now(),
+    left_at timestamp with time zone,
+    left_reason text,
+    CONSTRAINT user_ministry_history_period_check CHECK ((left_at >= joined_at))
+);
+
+
+--
+-- Name: TABLE user_ministry_history; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON TABLE public.user_ministry_history IS 'Periods a user was a member of a ministry with the same role, a role change ends a period and starts the next one.';
+
+
+--
+-- Name: COLUMN user_ministry_history.user_role; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.user_ministry_history.user_role IS 'The ministry role held, not a foreign key so that the history outlives deleted roles.';
+
+
+--
+-- Name: COLUMN user_ministry_history.joined_at; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.user_ministry_history.joined_at IS 'Start of the period, NULL for memberships that predate the history.';
+
+
+--
+-- Name: COLUMN user_ministry_history.left_at; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.user_ministry_history.left_at IS 'End of the period, NULL while ongoing.';
+
+
+--
+-- Name: COLUMN user_ministry_history.left_reason; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.user_ministry_history.left_reason IS 'Why the user left, as given when removed from the ministry.';
+
+
+--
+-- Name: user_ministry_history_id_seq; Type: SEQUENCE; Schema: public; Owner: -
+--
+
+ALTER TABLE public.user_ministry_history ALTER COLUMN id ADD GENERATED ALWAYS AS IDENTITY (
+    SEQUENCE NAME public.user_ministry_history_id_seq
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1
+);
+
+
+--
+-- Name: user_no_seq; Type: SEQUENCE; Schema: public; Owner: -
+--
+
+CREATE SEQUENCE public.user_no_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+
+--
+-- Name: user_no_seq; Type: SEQUENCE OWNED BY; Schema: public; Owner: -
+--
+
+ALTER SEQUENCE public.user_no_seq OWNED BY public."user".no;
+
+
+--
+-- Name: user_oversight; Type: TABLE; Schema: public; Owner: -
+--
+
+CREATE TABLE public.user_oversight (
+    user_id text NOT NULL,
+    overseer_id text NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone DEFAULT now() NOT NULL,
+    CONSTRAINT user_oversight_self_check CHECK ((user_id <> overseer_id))
+);
+
+
+--
+-- Name: TABLE user_oversight; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON TABLE public.user_oversight IS 'Leaders overseen by a more senior leader (e.g., a CGL by their coach, a coach by their pastor), every leader has at most one overseer.';
+
+
+--
+-- Name: COLUMN user_oversight.user_id; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.user_oversight.user_id IS 'The leader overseen.';
+
+
+--
+-- Name: COLUMN user_oversight.overseer_id; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.user_oversight.overseer_id IS 'The leader overseeing them.';
+
+
+--
+-- Name: user_relationship; Type: TABLE; Schema: public; Owner: -
+--
+
+CREATE TABLE public.user_relationship (
+    source_user_id text NOT NULL,
+    destination_user_id text NOT NULL,
+    relationship text NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone DEFAULT now() NOT NULL,
+    can_pick_up boolean DEFAULT false NOT NULL,
+    CONSTRAINT source_destination_check CHECK ((source_user_id <> destination_user_id))
+);
+
+
+--
+-- Name: COLUMN user_relationship.relationship; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.user_relationship.relationship IS 'Relationship of the source user to the destination user, the destination user holds the inverse relationship back.';
+
+
+--
+-- Name: COLUMN user_relationship.can_pick_up; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.user_relationship.can_pick_up IS 'Whether the source user may pick up the destination user (e.g., a child from the kids ministry).';
+
+
+--
+-- Name: webhook; Type: TABLE; Schema: public; Owner: -
+--
+
+CREATE TABLE public.webhook (
+    id text NOT NULL,
+    url text NOT NULL,
+    secret text NOT NULL,
+    event_types text[] DEFAULT '{}'::text[] NOT NULL,
+    description text,
+    active boolean DEFAULT true NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+
+--
+-- Name: TABLE webhook; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON TABLE public.webhook IS 'Endpoints subscribed to the domain events, not audited since it holds the signing secrets.';
+
+
+--
+-- Name: COLUMN webhook.id; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.webhook.id IS 'Unique identifier for a webhook (e.g., webhook_01H7JNPD7J67AA5AD87Q4SZDF9).';
+
+
+--
+-- Name: COLUMN webhook.secret; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.webhook.secret IS 'Key of the HMAC-SHA256 signature sent along with every delivery.';
+
+
+--
+-- Name: COLUMN webhook.event_types; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.webhook.event_types IS 'Types of the events delivered to the webhook, every type when empty.';
+
+
+--
+-- Name: COLUMN webhook.active; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.webhook.active IS 'Whether events are delivered, deliveries of an inactive webhook wait until it is activated again.';
+
+
+--
+-- Name: webhook_delivery; Type: TABLE; Schema: public; Owner: -
+--
+
+CREATE TABLE public.webhook_delivery (
+    id bigint NOT NULL,
+    webhook_id text NOT NULL,
+    event_id bigint NOT NULL,
+    status public.webhook_delivery_status DEFAULT 'pending'::public.webhook_delivery_status NOT NULL,
+    attempts integer DEFAULT 0 NOT NULL,
+    next_attempt_at timestamp with time zone DEFAULT now() NOT NULL,
+    last_attempt_at timestamp with time zone,
+    last_response_status integer,
+    last_error text,
+    delivered_at timestamp with time zone,
+    created_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+
+--
+-- Name: TABLE webhook_delivery; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON TABLE public.webhook_delivery IS 'Delivery of an event to a webhook, retried with an exponential backoff until it is delivered or dead.';
+
+
+--
+-- Name: COLUMN webhook_delivery.status; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.webhook_delivery.status IS 'Dead once every attempt has failed, dead deliveries are only retried on request.';
+
+
+--
+-- Name: COLUMN webhook_delivery.next_attempt_at; Type: COMMENT; Schema: public; Owner: -
+--
+
+COMMENT ON COLUMN public.webhook_delivery.next_attempt_at IS 'Time the delivery is due, pushed back while a dispatcher is attempting it.';
+
+
+--
+-- Name: webhook_delivery_id_seq; Type: SEQUENCE; Schema: public; Owner: -
+--
+
+ALTER TABLE public.webhook_delivery ALTER COLUMN id ADD GENERATED ALWAYS AS IDENTITY (
+    SEQUENCE NAME public.webhook_delivery_id_seq
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1
+);
+
+
+--
+-- Name: connect_group no; Type: DEFAULT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.connect_group ALTER COLUMN no SET DEFAULT nextval('public.connect_group_no_seq'::regclass);
+
+
+--
+-- Name: registration_form_field weight; Type: DEFAULT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.registration_form_field ALTER COLUMN weight SET DEFAULT nextval('public.registration_form_field_weight_seq'::regclass);
+
+
+--
+-- Name: satellite no; Type: DEFAULT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.satellite ALTER COLUMN no SET DEFAULT nextval('public.satellite_no_seq'::regclass);
+
+
+--
+-- Name: shadow_user no; Type: DEFAULT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.shadow_user ALTER COLUMN no SET DEFAULT nextval('public.shadow_user_no_seq'::regclass);
+
+
+--
+-- Name: user no; Type: DEFAULT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public."user" ALTER COLUMN no SET DEFAULT nextval('public.user_no_seq'::regclass);
+
+
+--
+-- Name: schema_migrations schema_migrations_pkey; Type: CONSTRAINT; Schema: migrations; Owner: -
+--
+
+ALTER TABLE ONLY migrations.schema_migrations
+    ADD CONSTRAINT schema_migrations_pkey PRIMARY KEY (version);
+
+
+--
+-- Name: attendance attendance_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.attendance
+    ADD CONSTRAINT attendance_pkey PRIMARY KEY (session_id, user_id);
+
+
+--
+-- Name: audit_log audit_log_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.audit_log
+    ADD CONSTRAINT audit_log_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: connect_group_category connect_group_category_name_key; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.connect_group_category
+    ADD CONSTRAINT connect_group_category_name_key UNIQUE (name);
+
+
+--
+-- Name: connect_group_category connect_group_category_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.connect_group_category
+    ADD CONSTRAINT connect_group_category_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: connect_group_lineage connect_group_lineage_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.connect_group_lineage
+    ADD CONSTRAINT connect_group_lineage_pkey PRIMARY KEY (child_id);
+
+
+--
+-- Name: connect_group connect_group_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.connect_group
+    ADD CONSTRAINT connect_group_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: connect_group connect_group_satellite_id_no_variant_key; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.connect_group
+    ADD CONSTRAINT connect_group_satellite_id_no_variant_key UNIQUE (satellite_id, no, variant);
+
+
+--
+-- Name: currency currency_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.currency
+    ADD CONSTRAINT currency_pkey PRIMARY KEY (code);
+
+
+--
+-- Name: event event_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.event
+    ADD CONSTRAINT event_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: event_type event_type_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.event_type
+    ADD CONSTRAINT event_type_pkey PRIMARY KEY (name);
+
+
+--
+-- Name: form_field_type form_field_type_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.form_field_type
+    ADD CONSTRAINT form_field_type_pkey PRIMARY KEY (type);
+
+
+--
+-- Name: household household_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.household
+    ADD CONSTRAINT household_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: ministry_department ministry_department_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.ministry_department
+    ADD CONSTRAINT ministry_department_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: ministry ministry_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.ministry
+    ADD CONSTRAINT ministry_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: ministry_role ministry_role_name_key; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.ministry_role
+    ADD CONSTRAINT ministry_role_name_key UNIQUE (name);
+
+
+--
+-- Name: ministry_role ministry_role_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.ministry_role
+    ADD CONSTRAINT ministry_role_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: ministry_team ministry_team_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.ministry_team
+    ADD CONSTRAINT ministry_team_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: outbox_event outbox_event_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.outbox_event
+    ADD CONSTRAINT outbox_event_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: pastoral_role pastoral_role_name_key; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.pastoral_role
+    ADD CONSTRAINT pastoral_role_name_key UNIQUE (name);
+
+
+--
+-- Name: pastoral_role pastoral_role_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.pastoral_role
+    ADD CONSTRAINT pastoral_role_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: price price_event_id_name_key; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.price
+    ADD CONSTRAINT price_event_id_name_key UNIQUE (event_id, name);
+
+
+--
+-- Name: price price_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.price
+    ADD CONSTRAINT price_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: registration registration_event_id_name_key; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.registration
+    ADD CONSTRAINT registration_event_id_name_key UNIQUE (event_id, name);
+
+
+--
+-- Name: registration_form_field_data registration_form_field_data_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.registration_form_field_data
+    ADD CONSTRAINT registration_form_field_data_pkey PRIMARY KEY (registration_id, name, user_id);
+
+
+--
+-- Name: registration_form_field registration_form_field_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.registration_form_field
+    ADD CONSTRAINT registration_form_field_pkey PRIMARY KEY (registration_id, name);
+
+
+--
+-- Name: registration_form_field registration_form_field_registration_id_weight_key; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.registration_form_field
+    ADD CONSTRAINT registration_form_field_registration_id_weight_key UNIQUE (registration_id, weight);
+
+
+--
+-- Name: registration registration_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.registration
+    ADD CONSTRAINT registration_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: relationship_type relationship_type_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.relationship_type
+    ADD CONSTRAINT relationship_type_pkey PRIMARY KEY (name);
+
+
+--
+-- Name: satellite satellite_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.satellite
+    ADD CONSTRAINT satellite_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: session session_event_id_name_key; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.session
+    ADD CONSTRAINT session_event_id_name_key UNIQUE (event_id, name);
+
+
+--
+-- Name: session session_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.session
+    ADD CONSTRAINT session_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: shadow_user shadow_user_no_key; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.shadow_user
+    ADD CONSTRAINT shadow_user_no_key UNIQUE (no);
+
+
+--
+-- Name: shadow_user shadow_user_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.shadow_user
+    ADD CONSTRAINT shadow_user_pkey PRIMARY KEY (name, phone_number);
+
+
+--
+-- Name: user_claim user_claim_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_claim
+    ADD CONSTRAINT user_claim_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: user_connect_group_history user_connect_group_history_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_connect_group_history
+    ADD CONSTRAINT user_connect_group_history_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: user_connect_group user_connect_group_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_connect_group
+    ADD CONSTRAINT user_connect_group_pkey PRIMARY KEY (user_id, connect_group_id);
+
+
+--
+-- Name: user_duplicate user_duplicate_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_duplicate
+    ADD CONSTRAINT user_duplicate_pkey PRIMARY KEY (user_id, duplicate_user_id);
+
+
+--
+-- Name: user user_email_key; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public."user"
+    ADD CONSTRAINT user_email_key UNIQUE (email);
+
+
+--
+-- Name: user_erasure user_erasure_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_erasure
+    ADD CONSTRAINT user_erasure_pkey PRIMARY KEY (user_id);
+
+
+--
+-- Name: user_household user_household_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_household
+    ADD CONSTRAINT user_household_pkey PRIMARY KEY (household_id, user_id);
+
+
+--
+-- Name: user_merge user_merge_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_merge
+    ADD CONSTRAINT user_merge_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: user_ministry_history user_ministry_history_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_ministry_history
+    ADD CONSTRAINT user_ministry_history_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: user_ministry user_ministry_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_ministry
+    ADD CONSTRAINT user_ministry_pkey PRIMARY KEY (user_id, ministry_id);
+
+
+--
+-- Name: user user_no_key; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public."user"
+    ADD CONSTRAINT user_no_key UNIQUE (no);
+
+
+--
+-- Name: user_oversight user_oversight_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_oversight
+    ADD CONSTRAINT user_oversight_pkey PRIMARY KEY (user_id);
+
+
+--
+-- Name: user user_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public."user"
+    ADD CONSTRAINT user_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: user_relationship user_relationship_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_relationship
+    ADD CONSTRAINT user_relationship_pkey PRIMARY KEY (source_user_id, destination_user_id);
+
+
+--
+-- Name: user user_username_key; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public."user"
+    ADD CONSTRAINT user_username_key UNIQUE (username);
+
+
+--
+-- Name: webhook_delivery webhook_delivery_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.webhook_delivery
+    ADD CONSTRAINT webhook_delivery_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: webhook_delivery webhook_delivery_webhook_id_event_id_key; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.webhook_delivery
+    ADD CONSTRAINT webhook_delivery_webhook_id_event_id_key UNIQUE (webhook_id, event_id);
+
+
+--
+-- Name: webhook webhook_pkey; Type: CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.webhook
+    ADD CONSTRAINT webhook_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: audit_log_actor_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX audit_log_actor_idx ON public.audit_log USING btree (actor, created_at);
+
+
+--
+-- Name: audit_log_created_at_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX audit_log_created_at_idx ON public.audit_log USING btree (created_at);
+
+
+--
+-- Name: audit_log_resource_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX audit_log_resource_idx ON public.audit_log USING btree (resource, resource_id, created_at);
+
+
+--
+-- Name: connect_group_lineage_parent_id_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX connect_group_lineage_parent_id_idx ON public.connect_group_lineage USING btree (parent_id);
+
+
+--
+-- Name: shadow_user_claimed_by_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX shadow_user_claimed_by_idx ON public.shadow_user USING btree (claimed_by);
+
+
+--
+-- Name: user_claim_user_id_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX user_claim_user_id_idx ON public.user_claim USING btree (user_id, created_at);
+
+
+--
+-- Name: user_connect_group_history_connect_group_id_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX user_connect_group_history_connect_group_id_idx ON public.user_connect_group_history USING btree (connect_group_id, joined_at);
+
+
+--
+-- Name: user_connect_group_history_current_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE UNIQUE INDEX user_connect_group_history_current_idx ON public.user_connect_group_history USING btree (user_id, connect_group_id) WHERE (left_at IS NULL);
+
+
+--
+-- Name: user_connect_group_history_user_id_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX user_connect_group_history_user_id_idx ON public.user_connect_group_history USING btree (user_id, joined_at);
+
+
+--
+-- Name: user_duplicate_duplicate_user_id_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX user_duplicate_duplicate_user_id_idx ON public.user_duplicate USING btree (duplicate_user_id);
+
+
+--
+-- Name: user_erasure_pending_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX user_erasure_pending_idx ON public.user_erasure USING btree (scheduled_at) WHERE (erased_at IS NULL);
+
+
+--
+-- Name: user_household_user_id_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX user_household_user_id_idx ON public.user_household USING btree (user_id);
+
+
+--
+-- Name: user_ic_number_index_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX user_ic_number_index_idx ON public."user" USING btree (ic_number_index);
+
+
+--
+-- Name: user_merge_merged_user_id_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX user_merge_merged_user_id_idx ON public.user_merge USING btree (merged_user_id);
+
+
+--
+-- Name: user_merge_user_id_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX user_merge_user_id_idx ON public.user_merge USING btree (user_id);
+
+
+--
+-- Name: user_ministry_history_current_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE UNIQUE INDEX user_ministry_history_current_idx ON public.user_ministry_history USING btree (user_id, ministry_id) WHERE (left_at IS NULL);
+
+
+--
+-- Name: user_ministry_history_ministry_id_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX user_ministry_history_ministry_id_idx ON public.user_ministry_history USING btree (ministry_id, joined_at);
+
+
+--
+-- Name: user_ministry_history_user_id_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX user_ministry_history_user_id_idx ON public.user_ministry_history USING btree (user_id, joined_at);
+
+
+--
+-- Name: user_name_trgm_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX user_name_trgm_idx ON public."user" USING gin (name public.gin_trgm_ops);
+
+
+--
+-- Name: user_normalised_email_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX user_normalised_email_idx ON public."user" USING btree (public.normalise_email(email));
+
+
+--
+-- Name: user_oversight_overseer_id_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX user_oversight_overseer_id_idx ON public.user_oversight USING btree (overseer_id);
+
+
+--
+-- Name: user_phone_number_index_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX user_phone_number_index_idx ON public."user" USING btree (phone_number_index);
+
+
+--
+-- Name: user_search_vector_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX user_search_vector_idx ON public."user" USING gin (search_vector);
+
+
+--
+-- Name: webhook_delivery_due_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX webhook_delivery_due_idx ON public.webhook_delivery USING btree (next_attempt_at) WHERE (status = 'pending'::public.webhook_delivery_status);
+
+
+--
+-- Name: webhook_delivery_event_id_idx; Type: INDEX; Schema: public; Owner: -
+--
+
+CREATE INDEX webhook_delivery_event_id_idx ON public.webhook_delivery USING btree (event_id);
+
+
+--
+-- Name: attendance audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public.attendance FOR EACH ROW EXECUTE FUNCTION public.audit('session_id', 'user_id');
+
+
+--
+-- Name: connect_group audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public.connect_group FOR EACH ROW EXECUTE FUNCTION public.audit('id');
+
+
+--
+-- Name: connect_group_category audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public.connect_group_category FOR EACH ROW EXECUTE FUNCTION public.audit('id');
+
+
+--
+-- Name: connect_group_lineage audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public.connect_group_lineage FOR EACH ROW EXECUTE FUNCTION public.audit('child_id');
+
+
+--
+-- Name: currency audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public.currency FOR EACH ROW EXECUTE FUNCTION public.audit('code');
+
+
+--
+-- Name: event audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public.event FOR EACH ROW EXECUTE FUNCTION public.audit('id');
+
+
+--
+-- Name: event_type audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public.event_type FOR EACH ROW EXECUTE FUNCTION public.audit('name');
+
+
+--
+-- Name: form_field_type audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public.form_field_type FOR EACH ROW EXECUTE FUNCTION public.audit('type');
+
+
+--
+-- Name: household audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public.household FOR EACH ROW EXECUTE FUNCTION public.audit('id');
+
+
+--
+-- Name: ministry audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public.ministry FOR EACH ROW EXECUTE FUNCTION public.audit('id');
+
+
+--
+-- Name: ministry_department audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public.ministry_department FOR EACH ROW EXECUTE FUNCTION public.audit('id');
+
+
+--
+-- Name: ministry_role audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public.ministry_role FOR EACH ROW EXECUTE FUNCTION public.audit('id');
+
+
+--
+-- Name: ministry_team audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public.ministry_team FOR EACH ROW EXECUTE FUNCTION public.audit('id');
+
+
+--
+-- Name: pastoral_role audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public.pastoral_role FOR EACH ROW EXECUTE FUNCTION public.audit('id');
+
+
+--
+-- Name: price audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public.price FOR EACH ROW EXECUTE FUNCTION public.audit('id');
+
+
+--
+-- Name: registration audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public.registration FOR EACH ROW EXECUTE FUNCTION public.audit('id');
+
+
+--
+-- Name: registration_form_field audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public.registration_form_field FOR EACH ROW EXECUTE FUNCTION public.audit('registration_id', 'name');
+
+
+--
+-- Name: registration_form_field_data audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public.registration_form_field_data FOR EACH ROW EXECUTE FUNCTION public.audit('registration_id', 'name', 'user_id');
+
+
+--
+-- Name: satellite audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public.satellite FOR EACH ROW EXECUTE FUNCTION public.audit('id');
+
+
+--
+-- Name: session audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public.session FOR EACH ROW EXECUTE FUNCTION public.audit('id');
+
+
+--
+-- Name: shadow_user audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public.shadow_user FOR EACH ROW EXECUTE FUNCTION public.audit('name', 'phone_number');
+
+
+--
+-- Name: user audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public."user" FOR EACH ROW EXECUTE FUNCTION public.audit('id');
+
+
+--
+-- Name: user_connect_group audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public.user_connect_group FOR EACH ROW EXECUTE FUNCTION public.audit('user_id', 'connect_group_id');
+
+
+--
+-- Name: user_erasure audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public.user_erasure FOR EACH ROW EXECUTE FUNCTION public.audit('user_id');
+
+
+--
+-- Name: user_household audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public.user_household FOR EACH ROW EXECUTE FUNCTION public.audit('household_id', 'user_id');
+
+
+--
+-- Name: user_ministry audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public.user_ministry FOR EACH ROW EXECUTE FUNCTION public.audit('user_id', 'ministry_id');
+
+
+--
+-- Name: user_oversight audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public.user_oversight FOR EACH ROW EXECUTE FUNCTION public.audit('user_id');
+
+
+--
+-- Name: user_relationship audit; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit AFTER INSERT OR DELETE OR UPDATE ON public.user_relationship FOR EACH ROW EXECUTE FUNCTION public.audit('source_user_id', 'destination_user_id');
+
+
+--
+-- Name: audit_log audit_log_append_only; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit_log_append_only BEFORE DELETE OR UPDATE ON public.audit_log FOR EACH ROW EXECUTE FUNCTION public.audit_log_append_only();
+
+
+--
+-- Name: audit_log audit_log_append_only_truncate; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER audit_log_append_only_truncate BEFORE TRUNCATE ON public.audit_log FOR EACH STATEMENT EXECUTE FUNCTION public.audit_log_append_only();
+
+
+--
+-- Name: outbox_event fan_out; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER fan_out AFTER INSERT ON public.outbox_event FOR EACH ROW EXECUTE FUNCTION public.outbox_event_fan_out();
+
+
+--
+-- Name: user_connect_group record_membership; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER record_membership AFTER INSERT OR DELETE OR UPDATE ON public.user_connect_group FOR EACH ROW EXECUTE FUNCTION public.record_connect_group_membership();
+
+
+--
+-- Name: user_ministry record_membership; Type: TRIGGER; Schema: public; Owner: -
+--
+
+CREATE TRIGGER record_membership AFTER INSERT OR DELETE OR UPDATE ON public.user_ministry FOR EACH ROW EXECUTE FUNCTION public.record_ministry_membership();
+
+
+--
+-- Name: attendance attendance_session_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.attendance
+    ADD CONSTRAINT attendance_session_id_fkey FOREIGN KEY (session_id) REFERENCES public.session(id);
+
+
+--
+-- Name: connect_group connect_group_category_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.connect_group
+    ADD CONSTRAINT connect_group_category_id_fkey FOREIGN KEY (category_id) REFERENCES public.connect_group_category(id);
+
+
+--
+-- Name: connect_group_lineage connect_group_lineage_child_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.connect_group_lineage
+    ADD CONSTRAINT connect_group_lineage_child_id_fkey FOREIGN KEY (child_id) REFERENCES public.connect_group(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+
+--
+-- Name: connect_group_lineage connect_group_lineage_parent_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.connect_group_lineage
+    ADD CONSTRAINT connect_group_lineage_parent_id_fkey FOREIGN KEY (parent_id) REFERENCES public.connect_group(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+
+--
+-- Name: connect_group connect_group_satellite_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.connect_group
+    ADD CONSTRAINT connect_group_satellite_id_fkey FOREIGN KEY (satellite_id) REFERENCES public.satellite(id) ON UPDATE CASCADE;
+
+
+--
+-- Name: event event_type_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.event
+    ADD CONSTRAINT event_type_fkey FOREIGN KEY (type) REFERENCES public.event_type(name);
+
+
+--
+-- Name: ministry ministry_department_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.ministry
+    ADD CONSTRAINT ministry_department_id_fkey FOREIGN KEY (department_id) REFERENCES public.ministry_department(id) ON UPDATE CASCADE;
+
+
+--
+-- Name: ministry ministry_satellite_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.ministry
+    ADD CONSTRAINT ministry_satellite_id_fkey FOREIGN KEY (satellite_id) REFERENCES public.satellite(id) ON UPDATE CASCADE;
+
+
+--
+-- Name: ministry ministry_team_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.ministry
+    ADD CONSTRAINT ministry_team_id_fkey FOREIGN KEY (team_id) REFERENCES public.ministry_team(id) ON UPDATE CASCADE;
+
+
+--
+-- Name: price price_currency_code_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.price
+    ADD CONSTRAINT price_currency_code_fkey FOREIGN KEY (currency_code) REFERENCES public.currency(code);
+
+
+--
+-- Name: price price_event_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.price
+    ADD CONSTRAINT price_event_id_fkey FOREIGN KEY (event_id) REFERENCES public.event(id);
+
+
+--
+-- Name: registration registration_event_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.registration
+    ADD CONSTRAINT registration_event_id_fkey FOREIGN KEY (event_id) REFERENCES public.event(id);
+
+
+--
+-- Name: registration_form_field_data registration_form_field_data_registration_id_name_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.registration_form_field_data
+    ADD CONSTRAINT registration_form_field_data_registration_id_name_fkey FOREIGN KEY (registration_id, name) REFERENCES public.registration_form_field(registration_id, name);
+
+
+--
+-- Name: registration_form_field registration_form_field_registration_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.registration_form_field
+    ADD CONSTRAINT registration_form_field_registration_id_fkey FOREIGN KEY (registration_id) REFERENCES public.registration(id);
+
+
+--
+-- Name: registration_form_field registration_form_field_type_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.registration_form_field
+    ADD CONSTRAINT registration_form_field_type_fkey FOREIGN KEY (type) REFERENCES public.form_field_type(type);
+
+
+--
+-- Name: relationship_type relationship_type_inverse_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.relationship_type
+    ADD CONSTRAINT relationship_type_inverse_fkey FOREIGN KEY (inverse) REFERENCES public.relationship_type(name);
+
+
+--
+-- Name: session session_event_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.session
+    ADD CONSTRAINT session_event_id_fkey FOREIGN KEY (event_id) REFERENCES public.event(id);
+
+
+--
+-- Name: shadow_user shadow_user_claimed_by_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.shadow_user
+    ADD CONSTRAINT shadow_user_claimed_by_fkey FOREIGN KEY (claimed_by) REFERENCES public."user"(id) ON UPDATE CASCADE;
+
+
+--
+-- Name: user_claim user_claim_claimed_user_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_claim
+    ADD CONSTRAINT user_claim_claimed_user_id_fkey FOREIGN KEY (claimed_user_id) REFERENCES public."user"(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+
+--
+-- Name: user_claim user_claim_shadow_user_no_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_claim
+    ADD CONSTRAINT user_claim_shadow_user_no_fkey FOREIGN KEY (shadow_user_no) REFERENCES public.shadow_user(no) ON DELETE CASCADE;
+
+
+--
+-- Name: user_connect_group user_connect_group_connect_group_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_connect_group
+    ADD CONSTRAINT user_connect_group_connect_group_id_fkey FOREIGN KEY (connect_group_id) REFERENCES public.connect_group(id) ON UPDATE CASCADE;
+
+
+--
+-- Name: user_connect_group_history user_connect_group_history_connect_group_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_connect_group_history
+    ADD CONSTRAINT user_connect_group_history_connect_group_id_fkey FOREIGN KEY (connect_group_id) REFERENCES public.connect_group(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+
+--
+-- Name: user_connect_group_history user_connect_group_history_user_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_connect_group_history
+    ADD CONSTRAINT user_connect_group_history_user_id_fkey FOREIGN KEY (user_id) REFERENCES public."user"(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+
+--
+-- Name: user_connect_group user_connect_group_user_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_connect_group
+    ADD CONSTRAINT user_connect_group_user_id_fkey FOREIGN KEY (user_id) REFERENCES public."user"(id) ON UPDATE CASCADE;
+
+
+--
+-- Name: user_connect_group user_connect_group_user_role_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_connect_group
+    ADD CONSTRAINT user_connect_group_user_role_fkey FOREIGN KEY (user_role) REFERENCES public.pastoral_role(id) ON UPDATE CASCADE;
+
+
+--
+-- Name: user_duplicate user_duplicate_duplicate_user_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_duplicate
+    ADD CONSTRAINT user_duplicate_duplicate_user_id_fkey FOREIGN KEY (duplicate_user_id) REFERENCES public."user"(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+
+--
+-- Name: user_duplicate user_duplicate_user_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_duplicate
+    ADD CONSTRAINT user_duplicate_user_id_fkey FOREIGN KEY (user_id) REFERENCES public."user"(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+
+--
+-- Name: user_erasure user_erasure_user_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_erasure
+    ADD CONSTRAINT user_erasure_user_id_fkey FOREIGN KEY (user_id) REFERENCES public."user"(id) ON UPDATE CASCADE;
+
+
+--
+-- Name: user_household user_household_household_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_household
+    ADD CONSTRAINT user_household_household_id_fkey FOREIGN KEY (household_id) REFERENCES public.household(id) ON DELETE CASCADE;
+
+
+--
+-- Name: user_household user_household_user_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_household
+    ADD CONSTRAINT user_household_user_id_fkey FOREIGN KEY (user_id) REFERENCES public."user"(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+
+--
+-- Name: user_merge user_merge_merged_user_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_merge
+    ADD CONSTRAINT user_merge_merged_user_id_fkey FOREIGN KEY (merged_user_id) REFERENCES public."user"(id) ON UPDATE CASCADE;
+
+
+--
+-- Name: user_merge user_merge_user_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_merge
+    ADD CONSTRAINT user_merge_user_id_fkey FOREIGN KEY (user_id) REFERENCES public."user"(id) ON UPDATE CASCADE;
+
+
+--
+-- Name: user_ministry_history user_ministry_history_ministry_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_ministry_history
+    ADD CONSTRAINT user_ministry_history_ministry_id_fkey FOREIGN KEY (ministry_id) REFERENCES public.ministry(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+
+--
+-- Name: user_ministry_history user_ministry_history_user_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_ministry_history
+    ADD CONSTRAINT user_ministry_history_user_id_fkey FOREIGN KEY (user_id) REFERENCES public."user"(id) ON UPDATE CASCADE ON DELETE CASCADE;
 
 
 --
@@ -1237,6 +3218,62 @@ ALTER TABLE ONLY public.user_ministry
     ADD CONSTRAINT user_ministry_user_role_fkey FOREIGN KEY (user_role) REFERENCES public.ministry_role(id) ON UPDATE CASCADE;
 
 
+--
+-- Name: user_oversight user_oversight_overseer_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_oversight
+    ADD CONSTRAINT user_oversight_overseer_id_fkey FOREIGN KEY (overseer_id) REFERENCES public."user"(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+
+--
+-- Name: user_oversight user_oversight_user_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_oversight
+    ADD CONSTRAINT user_oversight_user_id_fkey FOREIGN KEY (user_id) REFERENCES public."user"(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+
+--
+-- Name: user_relationship user_relationship_destination_user_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_relationship
+    ADD CONSTRAINT user_relationship_destination_user_id_fkey FOREIGN KEY (destination_user_id) REFERENCES public."user"(id) ON UPDATE CASCADE;
+
+
+--
+-- Name: user_relationship user_relationship_relationship_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_relationship
+    ADD CONSTRAINT user_relationship_relationship_fkey FOREIGN KEY (relationship) REFERENCES public.relationship_type(name) ON UPDATE CASCADE;
+
+
+--
+-- Name: user_relationship user_relationship_source_user_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.user_relationship
+    ADD CONSTRAINT user_relationship_source_user_id_fkey FOREIGN KEY (source_user_id) REFERENCES public."user"(id) ON UPDATE CASCADE;
+
+
+--
+-- Name: webhook_delivery webhook_delivery_event_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.webhook_delivery
+    ADD CONSTRAINT webhook_delivery_event_id_fkey FOREIGN KEY (event_id) REFERENCES public.outbox_event(id);
+
+
+--
+-- Name: webhook_delivery webhook_delivery_webhook_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: -
+--
+
+ALTER TABLE ONLY public.webhook_delivery
+    ADD CONSTRAINT webhook_delivery_webhook_id_fkey FOREIGN KEY (webhook_id) REFERENCES public.webhook(id) ON DELETE CASCADE;
+
+
 --
 -- PostgreSQL database dump complete
 --
@@ -1247,4 +3284,22 @@ ALTER TABLE ONLY public.user_ministry
 --
 
 INSERT INTO migrations.schema_migrations (version) VALUES
-    ('20240131155724');
+    ('20240131155724'),
+    ('20240519192256'),
+    ('20240519194345'),
+    ('20240730155734'),
+    ('20250224084109'),
+    ('20250322083514'),
+    ('20250405093012'),
+    ('20250412101530'),
+    ('20250419143027'),
+    ('20250426110245'),
+    ('20250503092114'),
+    ('20250510094536'),
+    ('20250517101245'),
+    ('20250524103318'),
+    ('20250531091540'),
+    ('20250607094210'),
+    ('20250614093025'),
+    ('20250621094512'),
+    ('20250628093140');
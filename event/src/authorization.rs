@@ -0,0 +1,31 @@
+use common::authorization;
+
+/// The permissions required by the endpoints of this service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    EventsWrite,
+    EventsAdmin,
+    AttendanceWrite,
+}
+
+impl authorization::Permission for Permission {
+    const PASTORAL_ROLE_GRANTS: &'static [(i32, &'static [Self])] = &[
+        (4, &[Permission::AttendanceWrite]),
+        (2, &[Permission::EventsWrite]),
+    ];
+
+    const MINISTRY_ROLE_GRANTS: &'static [(i32, &'static [Self])] = &[
+        (3, &[Permission::AttendanceWrite]),
+        (2, &[Permission::EventsWrite]),
+    ];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Permission::EventsWrite => "events:write",
+            Permission::EventsAdmin => "events:admin",
+            Permission::AttendanceWrite => "attendance:write",
+        }
+    }
+}
+
+pub type Error = authorization::Error<Permission>;
@@ -0,0 +1,6 @@
+//! The event service, built as a library so that its routes can be served by the end-to-end
+//! tests as well as by the binary.
+
+pub mod authorization;
+pub mod entities;
+pub mod routes;
@@ -2,7 +2,9 @@ use poem::web;
 use poem_openapi::{payload, Object};
 use serde::{Deserialize, Serialize};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{audit, auth::BearerAuth, database::Database, error::Error, outbox};
+
+use crate::{authorization::Permission, entities};
 
 #[derive(Debug, Clone, Deserialize, Serialize, Object)]
 #[oai(rename = "DeleteAttendanceRequest")]
@@ -17,24 +19,17 @@ pub enum Response {
     Ok(payload::Json<entities::Attendance>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
-}
-
 impl crate::routes::Routes {
     pub async fn _delete_attendance(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         body: payload::Json<Request>,
     ) -> Result<Response, Error> {
+        auth.require(&db, Permission::AttendanceWrite).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
         let attendance = sqlx::query_as!(
             entities::Attendance,
             r#"
@@ -46,20 +41,23 @@ impl crate::routes::Routes {
             &body.session_id,
             &body.user_id
         )
-        .fetch_one(&db.db)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| match e {
-            sqlx::error::Error::RowNotFound => Error::NotFound(payload::Json(ErrorResponse {
-                message: format!(
+            sqlx::Error::RowNotFound => Error::not_found(
+                "ATTENDANCE_NOT_FOUND",
+                format!(
                     "Attendance with session_id '{}' and user_id '{}' not found",
                     &body.session_id, &body.user_id
                 ),
-            })),
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
+            ),
+            _ => Error::from(e),
         })?;
 
+        outbox::publish(&mut tx, "attendance.deleted", &attendance).await?;
+
+        tx.commit().await?;
+
         Ok(Response::Ok(payload::Json(attendance)))
     }
 }
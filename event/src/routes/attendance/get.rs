@@ -2,7 +2,13 @@ use poem::web;
 use poem_openapi::{payload, Object};
 use serde::{Deserialize, Serialize};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{
+    database::Database,
+    error::Error,
+    projection::{Fields, Projected},
+};
+
+use crate::entities;
 
 #[derive(Debug, Clone, Deserialize, Serialize, Object)]
 #[oai(rename = "GetAttendanceRequest")]
@@ -14,19 +20,7 @@ pub struct Request {
 #[derive(poem_openapi::ApiResponse)]
 pub enum Response {
     #[oai(status = 200)]
-    OK(payload::Json<entities::Attendance>),
-}
-
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServerError(payload::Json<ErrorResponse>),
+    OK(payload::Json<Projected<entities::Attendance>>),
 }
 
 impl crate::routes::Routes {
@@ -34,7 +28,10 @@ impl crate::routes::Routes {
         &self,
         db: web::Data<&Database>,
         body: payload::Json<Request>,
+        fields: Option<String>,
     ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::Attendance>(fields)?;
+
         let attendance = sqlx::query_as!(
             entities::Attendance,
             r#"
@@ -48,17 +45,16 @@ impl crate::routes::Routes {
         .fetch_one(&db.db)
         .await
         .map_err(|e| match e {
-            sqlx::error::Error::RowNotFound => Error::NotFound(payload::Json(ErrorResponse {
-                message: format!(
+            sqlx::Error::RowNotFound => Error::not_found(
+                "ATTENDANCE_NOT_FOUND",
+                format!(
                     "Attendance with session_id '{}' and user_id '{}' not found",
                     &body.session_id, &body.user_id
                 ),
-            })),
-            _ => Error::InternalServerError(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
+            ),
+            _ => Error::from(e),
         })?;
 
-        Ok(Response::OK(payload::Json(attendance)))
+        Ok(Response::OK(payload::Json(fields.project(attendance))))
     }
 }
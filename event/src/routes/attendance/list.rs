@@ -1,39 +1,72 @@
 use poem::web;
 use poem_openapi::payload;
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{
+    database::Database,
+    error::Error,
+    pagination::{self, Listing, Page, Sortable},
+    projection::{Fields, Projected},
+};
+
+use crate::entities;
+
+const LISTING: Listing = Listing {
+    select: "a.*",
+    from: "attendance a",
+    key: "a.session_id || '/' || a.user_id",
+    sortable: &[
+        Sortable {
+            name: "created_at",
+            expr: "a.created_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "updated_at",
+            expr: "a.updated_at",
+            ty: "TIMESTAMPTZ",
+        },
+    ],
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub session_id: Option<String>,
+    pub user_id: Option<String>,
+    pub created_after: Option<chrono::DateTime<chrono::Utc>>,
+}
 
 #[derive(poem_openapi::ApiResponse)]
 pub enum Response {
     #[oai(status = 200)]
-    Ok(payload::Json<Vec<entities::Attendance>>),
+    Ok(payload::Json<Page<Projected<entities::Attendance>>>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
+impl crate::routes::Routes {
+    pub async fn _list_attendance(
+        &self,
+        db: web::Data<&Database>,
+        params: pagination::Params,
+        filter: Filter,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::Attendance>(fields)?;
 
-    #[oai(status = 500)]
-    InternalServerError(payload::Json<ErrorResponse>),
-}
+        let attendance = LISTING
+            .fetch(&db.db, &params, |query| {
+                if let Some(session_id) = &filter.session_id {
+                    query
+                        .push(" AND a.session_id = ")
+                        .push_bind(session_id.clone());
+                }
+                if let Some(user_id) = &filter.user_id {
+                    query.push(" AND a.user_id = ").push_bind(user_id.clone());
+                }
+                if let Some(created_after) = filter.created_after {
+                    query.push(" AND a.created_at > ").push_bind(created_after);
+                }
+            })
+            .await?;
 
-impl crate::routes::Routes {
-    pub async fn _list_attendance(&self, db: web::Data<&Database>) -> Result<Response, Error> {
-        let attendance = sqlx::query_as_unchecked!(
-            entities::Attendance,
-            r#"
-            SELECT * from "attendance"
-            "#,
-        )
-        .fetch_all(&db.db)
-        .await
-        .map_err(|e| match e {
-            _ => Error::InternalServerError(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
-
-        Ok(Response::Ok(payload::Json(attendance)))
+        Ok(Response::Ok(payload::Json(fields.project_page(attendance))))
     }
 }
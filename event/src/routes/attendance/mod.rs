@@ -1,4 +1,4 @@
 pub mod create;
-pub mod list;
+pub mod delete;
 pub mod get;
-pub mod delete;
\ No newline at end of file
+pub mod list;
@@ -2,7 +2,9 @@ use poem::web;
 use poem_openapi::{payload, Object};
 use serde::{Deserialize, Serialize};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{audit, auth::BearerAuth, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities};
 
 #[derive(Debug, Clone, Deserialize, Serialize, Object)]
 #[oai(rename = "CreateCurrencyRequest")]
@@ -20,24 +22,17 @@ pub enum Response {
     Ok(payload::Json<entities::Currency>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServerError(payload::Json<ErrorResponse>),
-}
-
 impl crate::routes::Routes {
     pub async fn _create_currency(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         body: payload::Json<Request>,
     ) -> Result<Response, Error> {
+        auth.require(&db, Permission::EventsAdmin).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
         let currency = sqlx::query_as!(
             entities::Currency,
             r#"
@@ -62,22 +57,10 @@ impl crate::routes::Routes {
             &body.name,
             &body.countries,
         )
-        .fetch_one(&db.db)
-        .await
-        .map_err(|e| match e {
-            sqlx::Error::Database(e)
-                if e.is_unique_violation()
-                    && e.constraint()
-                        .is_some_and(|constraint| constraint == "currency_pkey") =>
-            {
-                Error::BadRequest(payload::Json(ErrorResponse {
-                    message: format!("Code '{}' already exists", body.code),
-                }))
-            }
-            _ => Error::InternalServerError(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
 
         Ok(Response::Ok(payload::Json(currency)))
     }
@@ -1,39 +1,70 @@
 use poem::web;
 use poem_openapi::payload;
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{
+    database::Database,
+    error::Error,
+    pagination::{self, Listing, Page, Sortable},
+    projection::{Fields, Projected},
+};
+
+use crate::entities;
+
+const LISTING: Listing = Listing {
+    select: "*",
+    from: "currency",
+    key: "code",
+    sortable: &[
+        Sortable {
+            name: "code",
+            expr: "code",
+            ty: "TEXT",
+        },
+        Sortable {
+            name: "name",
+            expr: "name",
+            ty: "TEXT",
+        },
+        Sortable {
+            name: "num",
+            expr: "num",
+            ty: "INTEGER",
+        },
+    ],
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub country: Option<String>,
+}
 
 #[derive(poem_openapi::ApiResponse)]
 pub enum Response {
     #[oai(status = 200)]
-    Ok(payload::Json<Vec<entities::Currency>>),
+    Ok(payload::Json<Page<Projected<entities::Currency>>>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
+impl crate::routes::Routes {
+    pub async fn _list_currency(
+        &self,
+        db: web::Data<&Database>,
+        params: pagination::Params,
+        filter: Filter,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::Currency>(fields)?;
 
-    #[oai(status = 500)]
-    InternalServerError(payload::Json<ErrorResponse>),
-}
+        let currency = LISTING
+            .fetch(&db.db, &params, |query| {
+                if let Some(country) = &filter.country {
+                    query
+                        .push(" AND countries @> ARRAY[")
+                        .push_bind(country.clone())
+                        .push("]::TEXT[]");
+                }
+            })
+            .await?;
 
-impl crate::routes::Routes {
-    pub async fn _list_currency(&self, db: web::Data<&Database>) -> Result<Response, Error> {
-        let currency = sqlx::query_as_unchecked!(
-            entities::Currency,
-            r#"
-            SELECT * from "currency"
-            "#,
-        )
-        .fetch_all(&db.db)
-        .await
-        .map_err(|e| match e {
-            _ => Error::InternalServerError(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
-
-        Ok(Response::Ok(payload::Json(currency)))
+        Ok(Response::Ok(payload::Json(fields.project_page(currency))))
     }
 }
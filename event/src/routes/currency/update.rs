@@ -1,7 +1,9 @@
 use poem::web;
 use poem_openapi::{param::Path, payload, Object};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{audit, auth::BearerAuth, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Deserialize, Serialize, Object)]
@@ -19,25 +21,18 @@ pub enum Response {
     Ok(payload::Json<entities::Currency>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
-}
-
 impl crate::routes::Routes {
     pub async fn _update_currency(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         code: Path<String>,
         body: payload::Json<Request>,
     ) -> Result<Response, Error> {
+        auth.require(&db, Permission::EventsAdmin).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
         let currency = sqlx::query_as_unchecked!(
             entities::Currency,
             r#"
@@ -55,17 +50,18 @@ impl crate::routes::Routes {
             &body.countries,
             &*code,
         )
-        .fetch_one(&db.db)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| match e {
-            sqlx::error::Error::RowNotFound => Error::NotFound(payload::Json(ErrorResponse {
-                message: format!("Currency with code '{}' not found", &*code),
-            })),
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
+            sqlx::Error::RowNotFound => Error::not_found(
+                "CURRENCY_NOT_FOUND",
+                format!("Currency with code '{}' not found", &*code),
+            ),
+            _ => Error::from(e),
         })?;
 
+        tx.commit().await?;
+
         Ok(Response::Ok(payload::Json(currency)))
     }
 }
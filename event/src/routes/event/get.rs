@@ -1,24 +1,18 @@
 use poem::web;
 use poem_openapi::{param::Path, payload};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{
+    database::Database,
+    error::Error,
+    projection::{Fields, Projected},
+};
+
+use crate::entities;
 
 #[derive(poem_openapi::ApiResponse)]
 pub enum Response {
     #[oai(status = 200)]
-    Ok(payload::Json<entities::Event>),
-}
-
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
+    Ok(payload::Json<Projected<entities::Event>>),
 }
 
 impl crate::routes::Routes {
@@ -26,7 +20,10 @@ impl crate::routes::Routes {
         &self,
         db: web::Data<&Database>,
         id: Path<String>,
+        fields: Option<String>,
     ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::Event>(fields)?;
+
         let event = sqlx::query_as!(
             entities::Event,
             r#"
@@ -37,14 +34,13 @@ impl crate::routes::Routes {
         .fetch_one(&db.db)
         .await
         .map_err(|e| match e {
-            sqlx::error::Error::RowNotFound => Error::NotFound(payload::Json(ErrorResponse {
-                message: format!("Event with id '{}' not found", &*id),
-            })),
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
+            sqlx::Error::RowNotFound => Error::not_found(
+                "EVENT_NOT_FOUND",
+                format!("Event with id '{}' not found", &*id),
+            ),
+            _ => Error::from(e),
         })?;
 
-        Ok(Response::Ok(payload::Json(event)))
+        Ok(Response::Ok(payload::Json(fields.project(event))))
     }
 }
@@ -1,39 +1,73 @@
 use poem::web;
 use poem_openapi::payload;
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{
+    database::Database,
+    error::Error,
+    pagination::{self, Listing, Page, Sortable},
+    projection::{Fields, Projected},
+};
+
+use crate::entities;
+
+const LISTING: Listing = Listing {
+    select: "*",
+    from: "event",
+    key: "id",
+    sortable: &[
+        Sortable {
+            name: "created_at",
+            expr: "created_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "updated_at",
+            expr: "updated_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "name",
+            expr: "name",
+            ty: "TEXT",
+        },
+    ],
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub event_type: Option<String>,
+    pub created_after: Option<chrono::DateTime<chrono::Utc>>,
+}
 
 #[derive(poem_openapi::ApiResponse)]
 pub enum Response {
     #[oai(status = 200)]
-    Ok(payload::Json<Vec<entities::Event>>),
+    Ok(payload::Json<Page<Projected<entities::Event>>>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
+impl crate::routes::Routes {
+    pub async fn _list_event(
+        &self,
+        db: web::Data<&Database>,
+        params: pagination::Params,
+        filter: Filter,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::Event>(fields)?;
 
-    #[oai(status = 500)]
-    InternalServerError(payload::Json<ErrorResponse>),
-}
+        let events = LISTING
+            .fetch(&db.db, &params, |query| {
+                if let Some(event_type) = &filter.event_type {
+                    query
+                        .push(r#" AND "type" = "#)
+                        .push_bind(event_type.clone());
+                }
+                if let Some(created_after) = filter.created_after {
+                    query.push(" AND created_at > ").push_bind(created_after);
+                }
+            })
+            .await?;
 
-impl crate::routes::Routes {
-    pub async fn _list_event(&self, db: web::Data<&Database>) -> Result<Response, Error> {
-        let event = sqlx::query_as!(
-            entities::Event,
-            r#"
-            SELECT * from event
-            "#,
-        )
-        .fetch_all(&db.db)
-        .await
-        .map_err(|e| match e {
-            _ => Error::InternalServerError(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
-
-        Ok(Response::Ok(payload::Json(event)))
+        Ok(Response::Ok(payload::Json(fields.project_page(events))))
     }
 }
@@ -1,24 +1,52 @@
 use poem::web;
 use poem_openapi::{param::Path, payload};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{
+    database::Database,
+    error::Error,
+    pagination::{self, Listing, Page, Sortable},
+    projection::{Fields, Projected},
+};
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Response {
-    #[oai(status = 200)]
-    Ok(payload::Json<Vec<entities::Price>>),
-}
+use crate::entities;
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
+const LISTING: Listing = Listing {
+    select: "*",
+    from: "price",
+    key: "id",
+    sortable: &[
+        Sortable {
+            name: "created_at",
+            expr: "created_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "updated_at",
+            expr: "updated_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "name",
+            expr: "name",
+            ty: "TEXT",
+        },
+        Sortable {
+            name: "fee",
+            expr: "fee",
+            ty: "INTEGER",
+        },
+    ],
+};
 
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub currency_code: Option<String>,
+}
 
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Page<Projected<entities::Price>>>),
 }
 
 impl crate::routes::Routes {
@@ -26,22 +54,23 @@ impl crate::routes::Routes {
         &self,
         db: web::Data<&Database>,
         id: Path<String>,
+        params: pagination::Params,
+        filter: Filter,
+        fields: Option<String>,
     ) -> Result<Response, Error> {
-        let prices = sqlx::query_as!(
-            entities::Price,
-            r#"
-            SELECT * FROM price WHERE event_id = $1::TEXT
-            "#,
-            &*id
-        )
-        .fetch_all(&db.db)
-        .await
-        .map_err(|e| match e {
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
-
-        Ok(Response::Ok(payload::Json(prices)))
+        let fields = Fields::parse::<entities::Price>(fields)?;
+
+        let prices = LISTING
+            .fetch(&db.db, &params, |query| {
+                query.push(" AND event_id = ").push_bind(id.0.clone());
+                if let Some(currency_code) = &filter.currency_code {
+                    query
+                        .push(" AND currency_code = ")
+                        .push_bind(currency_code.clone());
+                }
+            })
+            .await?;
+
+        Ok(Response::Ok(payload::Json(fields.project_page(prices))))
     }
 }
@@ -1,24 +1,52 @@
 use poem::web;
 use poem_openapi::{param::Path, payload};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{
+    database::Database,
+    error::Error,
+    pagination::{self, Listing, Page, Sortable},
+    projection::{Fields, Projected},
+};
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Response {
-    #[oai(status = 200)]
-    Ok(payload::Json<Vec<entities::Registration>>),
-}
+use crate::entities;
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
+const LISTING: Listing = Listing {
+    select: "*",
+    from: "registration",
+    key: "id",
+    sortable: &[
+        Sortable {
+            name: "created_at",
+            expr: "created_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "updated_at",
+            expr: "updated_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "close_at",
+            expr: "close_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "name",
+            expr: "name",
+            ty: "TEXT",
+        },
+    ],
+};
 
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub closes_after: Option<chrono::DateTime<chrono::Utc>>,
+}
 
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Page<Projected<entities::Registration>>>),
 }
 
 impl crate::routes::Routes {
@@ -26,22 +54,23 @@ impl crate::routes::Routes {
         &self,
         db: web::Data<&Database>,
         id: Path<String>,
+        params: pagination::Params,
+        filter: Filter,
+        fields: Option<String>,
     ) -> Result<Response, Error> {
-        let registrations = sqlx::query_as!(
-            entities::Registration,
-            r#"
-            SELECT * FROM registration WHERE event_id = $1::TEXT
-            "#,
-            &*id
-        )
-        .fetch_all(&db.db)
-        .await
-        .map_err(|e| match e {
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
-
-        Ok(Response::Ok(payload::Json(registrations)))
+        let fields = Fields::parse::<entities::Registration>(fields)?;
+
+        let registrations = LISTING
+            .fetch(&db.db, &params, |query| {
+                query.push(" AND event_id = ").push_bind(id.0.clone());
+                if let Some(closes_after) = filter.closes_after {
+                    query.push(" AND close_at > ").push_bind(closes_after);
+                }
+            })
+            .await?;
+
+        Ok(Response::Ok(payload::Json(
+            fields.project_page(registrations),
+        )))
     }
 }
@@ -1,24 +1,58 @@
 use poem::web;
 use poem_openapi::{param::Path, payload};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{
+    database::Database,
+    error::Error,
+    pagination::{self, Listing, Page, Sortable},
+    projection::{Fields, Projected},
+};
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Response {
-    #[oai(status = 200)]
-    Ok(payload::Json<Vec<entities::Session>>),
-}
+use crate::entities;
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
+const LISTING: Listing = Listing {
+    select: "*",
+    from: r#""session""#,
+    key: "id",
+    sortable: &[
+        Sortable {
+            name: "start_at",
+            expr: "start_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "end_at",
+            expr: "end_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "created_at",
+            expr: "created_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "updated_at",
+            expr: "updated_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "name",
+            expr: "name",
+            ty: "TEXT",
+        },
+    ],
+};
 
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub starts_after: Option<chrono::DateTime<chrono::Utc>>,
+    pub starts_before: Option<chrono::DateTime<chrono::Utc>>,
+}
 
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Page<Projected<entities::Session>>>),
 }
 
 impl crate::routes::Routes {
@@ -26,22 +60,24 @@ impl crate::routes::Routes {
         &self,
         db: web::Data<&Database>,
         id: Path<String>,
+        params: pagination::Params,
+        filter: Filter,
+        fields: Option<String>,
     ) -> Result<Response, Error> {
-        let sessions = sqlx::query_as!(
-            entities::Session,
-            r#"
-            SELECT * from session WHERE event_id = $1::TEXT
-            "#,
-            &*id
-        )
-        .fetch_all(&db.db)
-        .await
-        .map_err(|e| match e {
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
-
-        Ok(Response::Ok(payload::Json(sessions)))
+        let fields = Fields::parse::<entities::Session>(fields)?;
+
+        let sessions = LISTING
+            .fetch(&db.db, &params, |query| {
+                query.push(" AND event_id = ").push_bind(id.0.clone());
+                if let Some(starts_after) = filter.starts_after {
+                    query.push(" AND start_at > ").push_bind(starts_after);
+                }
+                if let Some(starts_before) = filter.starts_before {
+                    query.push(" AND start_at < ").push_bind(starts_before);
+                }
+            })
+            .await?;
+
+        Ok(Response::Ok(payload::Json(fields.project_page(sessions))))
     }
 }
@@ -2,8 +2,8 @@ pub mod create;
 pub mod delete;
 pub mod get;
 pub mod list;
+pub mod list_attendance;
 pub mod list_price;
 pub mod list_registration;
 pub mod list_session;
-pub mod list_attendance;
 pub mod update;
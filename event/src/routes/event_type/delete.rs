@@ -1,7 +1,9 @@
 use poem::web;
 use poem_openapi::{param::Path, payload};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{audit, auth::BearerAuth, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities};
 
 #[derive(poem_openapi::ApiResponse)]
 pub enum Response {
@@ -9,24 +11,17 @@ pub enum Response {
     Ok(payload::Json<entities::EventType>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
-}
-
 impl crate::routes::Routes {
     pub async fn _delete_event_type(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         name: Path<String>,
     ) -> Result<Response, Error> {
+        auth.require(&db, Permission::EventsAdmin).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
         let event_type = sqlx::query_as!(
             entities::EventType,
             r#"
@@ -36,17 +31,18 @@ impl crate::routes::Routes {
             "#,
             &*name,
         )
-        .fetch_one(&db.db)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| match e {
-            sqlx::error::Error::RowNotFound => Error::NotFound(payload::Json(ErrorResponse {
-                message: format!("Event type of name '{}'not found", &*name),
-            })),
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
+            sqlx::Error::RowNotFound => Error::not_found(
+                "EVENT_TYPE_NOT_FOUND",
+                format!("Event type of name '{}'not found", &*name),
+            ),
+            _ => Error::from(e),
         })?;
 
+        tx.commit().await?;
+
         Ok(Response::Ok(payload::Json(event_type)))
     }
 }
@@ -1,24 +1,18 @@
 use poem::web;
 use poem_openapi::{param::Path, payload};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{
+    database::Database,
+    error::Error,
+    projection::{Fields, Projected},
+};
+
+use crate::entities;
 
 #[derive(poem_openapi::ApiResponse)]
 pub enum Response {
     #[oai(status = 200)]
-    OK(payload::Json<entities::EventType>),
-}
-
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServerError(payload::Json<ErrorResponse>),
+    OK(payload::Json<Projected<entities::EventType>>),
 }
 
 impl crate::routes::Routes {
@@ -26,7 +20,10 @@ impl crate::routes::Routes {
         &self,
         db: web::Data<&Database>,
         name: Path<String>,
+        fields: Option<String>,
     ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::EventType>(fields)?;
+
         let event_type = sqlx::query_as!(
             entities::EventType,
             r#"
@@ -38,15 +35,13 @@ impl crate::routes::Routes {
         .fetch_one(&db.db)
         .await
         .map_err(|e| match e {
-            sqlx::error::Error::RowNotFound => Error::NotFound(payload::Json(ErrorResponse {
-                message: format!("Event type of name '{}' not found", &*name),
-            })),
-            _ => Error::InternalServerError(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
+            sqlx::Error::RowNotFound => Error::not_found(
+                "EVENT_TYPE_NOT_FOUND",
+                format!("Event type of name '{}' not found", &*name),
+            ),
+            _ => Error::from(e),
         })?;
 
-        Ok(Response::OK(payload::Json(event_type)))
+        Ok(Response::OK(payload::Json(fields.project(event_type))))
     }
 }
-
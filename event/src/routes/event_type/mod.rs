@@ -1,5 +1,5 @@
 pub mod create;
-pub mod list;
+pub mod delete;
 pub mod get;
+pub mod list;
 pub mod update;
-pub mod delete;
\ No newline at end of file
@@ -2,7 +2,9 @@ use poem::web;
 use poem_openapi::{payload, Object};
 use serde::{Deserialize, Serialize};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{audit, auth::BearerAuth, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities};
 
 #[derive(Debug, Clone, Deserialize, Serialize, Object)]
 #[oai(rename = "CreateFormFieldTypeRequest")]
@@ -17,24 +19,17 @@ pub enum Response {
     Ok(payload::Json<entities::FormFieldType>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServerError(payload::Json<ErrorResponse>),
-}
-
 impl crate::routes::Routes {
     pub async fn _create_form_field_type(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         body: payload::Json<Request>,
     ) -> Result<Response, Error> {
+        auth.require(&db, Permission::EventsAdmin).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
         let form_field_type = sqlx::query_as!(
             entities::FormFieldType,
             r#"
@@ -50,22 +45,10 @@ impl crate::routes::Routes {
             &body.r#type,
             &body.description,
         )
-        .fetch_one(&db.db)
-        .await
-        .map_err(|e| match e {
-            sqlx::Error::Database(e)
-                if e.is_unique_violation()
-                    && e.constraint()
-                        .is_some_and(|constraint| constraint == "form_field_type_pkey") =>
-            {
-                Error::BadRequest(payload::Json(ErrorResponse {
-                    message: format!("Form field type '{}' already exists", body.r#type),
-                }))
-            }
-            _ => Error::InternalServerError(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
 
         Ok(Response::Ok(payload::Json(form_field_type)))
     }
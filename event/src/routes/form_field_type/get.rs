@@ -1,24 +1,18 @@
 use poem::web;
 use poem_openapi::{param::Path, payload};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{
+    database::Database,
+    error::Error,
+    projection::{Fields, Projected},
+};
+
+use crate::entities;
 
 #[derive(poem_openapi::ApiResponse)]
 pub enum Response {
     #[oai(status = 200)]
-    OK(payload::Json<entities::FormFieldType>),
-}
-
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServerError(payload::Json<ErrorResponse>),
+    OK(payload::Json<Projected<entities::FormFieldType>>),
 }
 
 impl crate::routes::Routes {
@@ -26,7 +20,10 @@ impl crate::routes::Routes {
         &self,
         db: web::Data<&Database>,
         r#type: Path<String>,
+        fields: Option<String>,
     ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::FormFieldType>(fields)?;
+
         let form_field_type = sqlx::query_as!(
             entities::FormFieldType,
             r#"
@@ -38,15 +35,13 @@ impl crate::routes::Routes {
         .fetch_one(&db.db)
         .await
         .map_err(|e| match e {
-            sqlx::error::Error::RowNotFound => Error::NotFound(payload::Json(ErrorResponse {
-                message: format!("Form field type '{}' not found", &*r#type),
-            })),
-            _ => Error::InternalServerError(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
+            sqlx::Error::RowNotFound => Error::not_found(
+                "FORM_FIELD_TYPE_NOT_FOUND",
+                format!("Form field type '{}' not found", &*r#type),
+            ),
+            _ => Error::from(e),
         })?;
 
-        Ok(Response::OK(payload::Json(form_field_type)))
+        Ok(Response::OK(payload::Json(fields.project(form_field_type))))
     }
 }
-
@@ -1,39 +1,57 @@
 use poem::web;
 use poem_openapi::payload;
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{
+    database::Database,
+    error::Error,
+    pagination::{self, Listing, Page, Sortable},
+    projection::{Fields, Projected},
+};
+
+use crate::entities;
+
+const LISTING: Listing = Listing {
+    select: "*",
+    from: "form_field_type",
+    key: r#""type""#,
+    sortable: &[
+        Sortable {
+            name: "created_at",
+            expr: "created_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "updated_at",
+            expr: "updated_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "type",
+            expr: r#""type""#,
+            ty: "TEXT",
+        },
+    ],
+};
 
 #[derive(poem_openapi::ApiResponse)]
 pub enum Response {
     #[oai(status = 200)]
-    Ok(payload::Json<Vec<entities::FormFieldType>>),
-}
-
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServerError(payload::Json<ErrorResponse>),
+    Ok(payload::Json<Page<Projected<entities::FormFieldType>>>),
 }
 
 impl crate::routes::Routes {
-    pub async fn _list_form_field_type(&self, db: web::Data<&Database>) -> Result<Response, Error> {
-        let form_field_type = sqlx::query_as_unchecked!(
-            entities::FormFieldType,
-            r#"
-            SELECT * from form_field_type
-            "#,
-        )
-        .fetch_all(&db.db)
-        .await
-        .map_err(|e| match e {
-            _ => Error::InternalServerError(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
+    pub async fn _list_form_field_type(
+        &self,
+        db: web::Data<&Database>,
+        params: pagination::Params,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::FormFieldType>(fields)?;
+
+        let form_field_type = LISTING.fetch(&db.db, &params, |_| {}).await?;
 
-        Ok(Response::Ok(payload::Json(form_field_type)))
+        Ok(Response::Ok(payload::Json(
+            fields.project_page(form_field_type),
+        )))
     }
 }
@@ -1,7 +1,9 @@
 use poem::web;
 use poem_openapi::{param::Path, payload, Object};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{audit, auth::BearerAuth, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Deserialize, Serialize, Object)]
@@ -17,25 +19,18 @@ pub enum Response {
     Ok(payload::Json<entities::FormFieldType>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
-}
-
 impl crate::routes::Routes {
     pub async fn _update_form_field_type(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         r#type: Path<String>,
         body: payload::Json<Request>,
     ) -> Result<Response, Error> {
+        auth.require(&db, Permission::EventsAdmin).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
         let form_field_type = sqlx::query_as_unchecked!(
             entities::FormFieldType,
             r#"
@@ -50,17 +45,18 @@ impl crate::routes::Routes {
             &body.description,
             &*r#type,
         )
-        .fetch_one(&db.db)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| match e {
-            sqlx::error::Error::RowNotFound => Error::NotFound(payload::Json(ErrorResponse {
-                message: format!("Form field type '{:?}' not found", &body.r#type),
-            })),
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
+            sqlx::Error::RowNotFound => Error::not_found(
+                "FORM_FIELD_TYPE_NOT_FOUND",
+                format!("Form field type '{:?}' not found", &body.r#type),
+            ),
+            _ => Error::from(e),
         })?;
 
+        tx.commit().await?;
+
         Ok(Response::Ok(payload::Json(form_field_type)))
     }
 }
@@ -1,7 +1,10 @@
 use poem::web;
-use poem_openapi::{param::Path, payload, OpenApi, Tags};
+use poem_openapi::{
+    param::{Path, Query},
+    payload, OpenApi, Tags,
+};
 
-use crate::{auth::BearerAuth, database::Database};
+use common::{auth::BearerAuth, database::Database, error::Error, pagination};
 
 mod attendance;
 mod currency;
@@ -55,6 +58,8 @@ impl Routes {
     /* Event */
 
     /// Create an event
+    ///
+    /// Requires the `events:write` permission.
     #[oai(
         path = "/event",
         method = "post",
@@ -63,14 +68,18 @@ impl Routes {
     )]
     async fn create_event(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         body: payload::Json<event::create::Request>,
-    ) -> Result<event::create::Response, event::create::Error> {
-        self._create_event(db, body).await
+    ) -> Result<event::create::Response, Error> {
+        self._create_event(auth, db, body).await
     }
 
     /// List or search event
+    ///
+    /// Retrieve a list of events, optionally filtered by their type or creation time. Results are
+    /// paged, pass the returned `next_cursor` as `cursor` to retrieve the next page. Sortable on
+    /// `created_at`, `updated_at` and `name`, prefix with `-` for descending order.
     #[oai(
         path = "/event",
         method = "get",
@@ -81,8 +90,27 @@ impl Routes {
         &self,
         _auth: BearerAuth,
         db: web::Data<&Database>,
-    ) -> Result<event::list::Response, event::list::Error> {
-        self._list_event(db).await
+        limit: Query<Option<i64>>,
+        cursor: Query<Option<String>>,
+        sort: Query<Option<String>>,
+        event_type: Query<Option<String>>,
+        created_after: Query<Option<chrono::DateTime<chrono::Utc>>>,
+        fields: Query<Option<String>>,
+    ) -> Result<event::list::Response, Error> {
+        self._list_event(
+            db,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            event::list::Filter {
+                event_type: event_type.0,
+                created_after: created_after.0,
+            },
+            fields.0,
+        )
+        .await
     }
 
     /// Get an event
@@ -97,11 +125,14 @@ impl Routes {
         _auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
-    ) -> Result<event::get::Response, event::get::Error> {
-        self._get_event(db, id).await
+        fields: Query<Option<String>>,
+    ) -> Result<event::get::Response, Error> {
+        self._get_event(db, id, fields.0).await
     }
 
     /// Update an event
+    ///
+    /// Requires the `events:write` permission.
     #[oai(
         path = "/event/:id",
         method = "patch",
@@ -110,15 +141,17 @@ impl Routes {
     )]
     async fn update_event(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
         body: payload::Json<event::update::Request>,
-    ) -> Result<event::update::Response, event::update::Error> {
-        self._update_event(db, id, body).await
+    ) -> Result<event::update::Response, Error> {
+        self._update_event(auth, db, id, body).await
     }
 
     /// Delete an event
+    ///
+    /// Requires the `events:admin` permission.
     #[oai(
         path = "/event/:id",
         method = "delete",
@@ -127,14 +160,18 @@ impl Routes {
     )]
     async fn delete_event(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
-    ) -> Result<event::delete::Response, event::delete::Error> {
-        self._delete_event(db, id).await
+    ) -> Result<event::delete::Response, Error> {
+        self._delete_event(auth, db, id).await
     }
 
     /// List an event's registrations
+    ///
+    /// Results are paged, pass the returned `next_cursor` as `cursor` to retrieve the next page.
+    /// Sortable on `created_at`, `updated_at`, `close_at` and `name`, prefix with `-` for
+    /// descending order.
     #[oai(
         path = "/event/:id/registration",
         method = "get",
@@ -146,11 +183,33 @@ impl Routes {
         _auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
-    ) -> Result<event::list_registration::Response, event::list_registration::Error> {
-        self._list_event_registration(db, id).await
+        limit: Query<Option<i64>>,
+        cursor: Query<Option<String>>,
+        sort: Query<Option<String>>,
+        closes_after: Query<Option<chrono::DateTime<chrono::Utc>>>,
+        fields: Query<Option<String>>,
+    ) -> Result<event::list_registration::Response, Error> {
+        self._list_event_registration(
+            db,
+            id,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            event::list_registration::Filter {
+                closes_after: closes_after.0,
+            },
+            fields.0,
+        )
+        .await
     }
 
     /// List an event's price
+    ///
+    /// Results are paged, pass the returned `next_cursor` as `cursor` to retrieve the next page.
+    /// Sortable on `created_at`, `updated_at`, `name` and `fee`, prefix with `-` for descending
+    /// order.
     #[oai(
         path = "/event/:id/price",
         method = "get",
@@ -162,11 +221,33 @@ impl Routes {
         _auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
-    ) -> Result<event::list_price::Response, event::list_price::Error> {
-        self._list_event_price(db, id).await
+        limit: Query<Option<i64>>,
+        cursor: Query<Option<String>>,
+        sort: Query<Option<String>>,
+        currency_code: Query<Option<String>>,
+        fields: Query<Option<String>>,
+    ) -> Result<event::list_price::Response, Error> {
+        self._list_event_price(
+            db,
+            id,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            event::list_price::Filter {
+                currency_code: currency_code.0,
+            },
+            fields.0,
+        )
+        .await
     }
 
     /// List an event's sessions
+    ///
+    /// Results are paged, pass the returned `next_cursor` as `cursor` to retrieve the next page.
+    /// Sortable on `start_at`, `end_at`, `created_at`, `updated_at` and `name`, prefix with `-` for
+    /// descending order.
     #[oai(
         path = "/event/:id/session",
         method = "get",
@@ -178,11 +259,34 @@ impl Routes {
         _auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
-    ) -> Result<event::list_session::Response, event::list_session::Error> {
-        self._list_event_session(db, id).await
+        limit: Query<Option<i64>>,
+        cursor: Query<Option<String>>,
+        sort: Query<Option<String>>,
+        starts_after: Query<Option<chrono::DateTime<chrono::Utc>>>,
+        starts_before: Query<Option<chrono::DateTime<chrono::Utc>>>,
+        fields: Query<Option<String>>,
+    ) -> Result<event::list_session::Response, Error> {
+        self._list_event_session(
+            db,
+            id,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            event::list_session::Filter {
+                starts_after: starts_after.0,
+                starts_before: starts_before.0,
+            },
+            fields.0,
+        )
+        .await
     }
 
     /// List an event's attendance
+    ///
+    /// Results are paged, pass the returned `next_cursor` as `cursor` to retrieve the next page.
+    /// Sortable on `created_at` and `updated_at`, prefix with `-` for descending order.
     #[oai(
         path = "/event/:id/attendance",
         method = "get",
@@ -194,13 +298,35 @@ impl Routes {
         _auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
-    ) -> Result<event::list_attendance::Response, event::list_attendance::Error> {
-        self._list_event_attendance(db, id).await
+        limit: Query<Option<i64>>,
+        cursor: Query<Option<String>>,
+        sort: Query<Option<String>>,
+        user_id: Query<Option<String>>,
+        created_after: Query<Option<chrono::DateTime<chrono::Utc>>>,
+        fields: Query<Option<String>>,
+    ) -> Result<event::list_attendance::Response, Error> {
+        self._list_event_attendance(
+            db,
+            id,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            event::list_attendance::Filter {
+                user_id: user_id.0,
+                created_after: created_after.0,
+            },
+            fields.0,
+        )
+        .await
     }
 
     /* Registration */
 
     /// Create registration
+    ///
+    /// Requires the `events:write` permission.
     #[oai(
         path = "/registration",
         method = "post",
@@ -209,14 +335,18 @@ impl Routes {
     )]
     async fn create_registration(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         body: payload::Json<registration::create::Request>,
-    ) -> Result<registration::create::Response, registration::create::Error> {
-        self._create_registration(db, body).await
+    ) -> Result<registration::create::Response, Error> {
+        self._create_registration(auth, db, body).await
     }
 
     /// List or search registration
+    ///
+    /// Results are paged, pass the returned `next_cursor` as `cursor` to retrieve the next page.
+    /// Sortable on `created_at`, `updated_at`, `close_at` and `name`, prefix with `-` for
+    /// descending order.
     #[oai(
         path = "/registration",
         method = "get",
@@ -227,8 +357,29 @@ impl Routes {
         &self,
         _auth: BearerAuth,
         db: web::Data<&Database>,
-    ) -> Result<registration::list::Response, registration::list::Error> {
-        self._list_registrations(db).await
+        limit: Query<Option<i64>>,
+        cursor: Query<Option<String>>,
+        sort: Query<Option<String>>,
+        event_id: Query<Option<String>>,
+        closes_after: Query<Option<chrono::DateTime<chrono::Utc>>>,
+        created_after: Query<Option<chrono::DateTime<chrono::Utc>>>,
+        fields: Query<Option<String>>,
+    ) -> Result<registration::list::Response, Error> {
+        self._list_registrations(
+            db,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            registration::list::Filter {
+                event_id: event_id.0,
+                closes_after: closes_after.0,
+                created_after: created_after.0,
+            },
+            fields.0,
+        )
+        .await
     }
 
     /// Get a registration
@@ -243,11 +394,14 @@ impl Routes {
         _auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
-    ) -> Result<registration::get::Response, registration::get::Error> {
-        self._get_registration(db, id).await
+        fields: Query<Option<String>>,
+    ) -> Result<registration::get::Response, Error> {
+        self._get_registration(db, id, fields.0).await
     }
 
     /// Update a registration
+    ///
+    /// Requires the `events:write` permission.
     #[oai(
         path = "/registration/:id",
         method = "patch",
@@ -256,15 +410,17 @@ impl Routes {
     )]
     async fn update_registration(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
         body: payload::Json<registration::update::Request>,
-    ) -> Result<registration::update::Response, registration::update::Error> {
-        self._update_registration(db, id, body).await
+    ) -> Result<registration::update::Response, Error> {
+        self._update_registration(auth, db, id, body).await
     }
 
     /// Delete a registration
+    ///
+    /// Requires the `events:admin` permission.
     #[oai(
         path = "/registration/:id",
         method = "delete",
@@ -273,14 +429,16 @@ impl Routes {
     )]
     async fn delete_registration(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
-    ) -> Result<registration::delete::Response, registration::delete::Error> {
-        self._delete_registration(db, id).await
+    ) -> Result<registration::delete::Response, Error> {
+        self._delete_registration(auth, db, id).await
     }
 
     /// Create a form field for a registration
+    ///
+    /// Requires the `events:write` permission.
     #[oai(
         path = "/registration/:id/form-field",
         method = "post",
@@ -289,16 +447,20 @@ impl Routes {
     )]
     async fn create_registration_form_field(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
         body: payload::Json<registration::create_form_field::Request>,
-    ) -> Result<registration::create_form_field::Response, registration::create_form_field::Error>
-    {
-        self._create_registration_form_field(db, id, body).await
+    ) -> Result<registration::create_form_field::Response, Error> {
+        self._create_registration_form_field(auth, db, id, body)
+            .await
     }
 
     /// List all form fields for a registration
+    ///
+    /// Results are paged, pass the returned `next_cursor` as `cursor` to retrieve the next page.
+    /// Sortable on `weight`, `name`, `created_at` and `updated_at`, prefix with `-` for descending
+    /// order.
     #[oai(
         path = "/registration/:id/form-field",
         method = "get",
@@ -310,9 +472,22 @@ impl Routes {
         _auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
-    ) -> Result<registration::list_form_fields::Response, registration::list_form_fields::Error>
-    {
-        self._list_registration_form_fields(db, id).await
+        limit: Query<Option<i64>>,
+        cursor: Query<Option<String>>,
+        sort: Query<Option<String>>,
+        fields: Query<Option<String>>,
+    ) -> Result<registration::list_form_fields::Response, Error> {
+        self._list_registration_form_fields(
+            db,
+            id,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            fields.0,
+        )
+        .await
     }
 
     /// Get a form field for a registration
@@ -328,11 +503,14 @@ impl Routes {
         db: web::Data<&Database>,
         id: Path<String>,
         name: Path<String>,
-    ) -> Result<registration::get_form_field::Response, registration::get_form_field::Error> {
-        self._get_registration_form_field(db, id, name).await
+        fields: Query<Option<String>>,
+    ) -> Result<registration::get_form_field::Response, Error> {
+        self._get_registration_form_field(db, id, name, fields.0).await
     }
 
     /// Update a form field for a registration
+    ///
+    /// Requires the `events:write` permission.
     #[oai(
         path = "/registration/:id/form-field/:name",
         method = "patch",
@@ -341,18 +519,19 @@ impl Routes {
     )]
     async fn update_registration_form_field(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
         name: Path<String>,
         body: payload::Json<registration::update_form_field::Request>,
-    ) -> Result<registration::update_form_field::Response, registration::update_form_field::Error>
-    {
-        self._update_registration_form_field(db, id, name, body)
+    ) -> Result<registration::update_form_field::Response, Error> {
+        self._update_registration_form_field(auth, db, id, name, body)
             .await
     }
 
     /// Delete a form field for a registration
+    ///
+    /// Requires the `events:admin` permission.
     #[oai(
         path = "/registration/:id/form-field/:name",
         method = "delete",
@@ -361,13 +540,13 @@ impl Routes {
     )]
     async fn delete_registration_form_field(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
         name: Path<String>,
-    ) -> Result<registration::delete_form_field::Response, registration::delete_form_field::Error>
-    {
-        self._delete_registration_form_field(db, id, name).await
+    ) -> Result<registration::delete_form_field::Response, Error> {
+        self._delete_registration_form_field(auth, db, id, name)
+            .await
     }
 
     /// Create a form field data for a registration
@@ -383,15 +562,15 @@ impl Routes {
         db: web::Data<&Database>,
         id: Path<String>,
         body: payload::Json<registration::create_form_field_data::Request>,
-    ) -> Result<
-        registration::create_form_field_data::Response,
-        registration::create_form_field_data::Error,
-    > {
+    ) -> Result<registration::create_form_field_data::Response, Error> {
         self._create_registration_form_field_data(auth, db, id, body)
             .await
     }
 
     /// List all form field datas for a registration
+    ///
+    /// Results are paged, pass the returned `next_cursor` as `cursor` to retrieve the next page.
+    /// Sortable on `name`, `created_at` and `updated_at`, prefix with `-` for descending order.
     #[oai(
         path = "/registration/:id/form-field-data",
         method = "get",
@@ -403,11 +582,23 @@ impl Routes {
         auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
-    ) -> Result<
-        registration::list_form_field_datas::Response,
-        registration::list_form_field_datas::Error,
-    > {
-        self._list_registration_form_field_datas(auth, db, id).await
+        limit: Query<Option<i64>>,
+        cursor: Query<Option<String>>,
+        sort: Query<Option<String>>,
+        fields: Query<Option<String>>,
+    ) -> Result<registration::list_form_field_datas::Response, Error> {
+        self._list_registration_form_field_datas(
+            auth,
+            db,
+            id,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            fields.0,
+        )
+        .await
     }
 
     /// Get a form field data for a registration for a user
@@ -423,15 +614,15 @@ impl Routes {
         db: web::Data<&Database>,
         id: Path<String>,
         name: Path<String>,
-    ) -> Result<registration::get_form_field_data::Response, registration::get_form_field_data::Error>
-    {
-        self._get_registration_form_field_data(auth, db, id, name)
+        fields: Query<Option<String>>,
+    ) -> Result<registration::get_form_field_data::Response, Error> {
+        self._get_registration_form_field_data(auth, db, id, name, fields.0)
             .await
     }
 
     /// Update a form field data for a registration for a user
     #[oai(
-        path = "/registration/:id/form-field/:name",
+        path = "/registration/:id/form-field-data/:name",
         method = "patch",
         operation_id = "update-registration-form-field-data",
         tag = "Tag::Registration"
@@ -443,17 +634,14 @@ impl Routes {
         id: Path<String>,
         name: Path<String>,
         body: payload::Json<registration::update_form_field_data::Request>,
-    ) -> Result<
-        registration::update_form_field_data::Response,
-        registration::update_form_field_data::Error,
-    > {
+    ) -> Result<registration::update_form_field_data::Response, Error> {
         self._update_registration_form_field_data(auth, db, id, name, body)
             .await
     }
 
     /// Delete a form field for a registration for a user
     #[oai(
-        path = "/registration/:id/form-field/:name",
+        path = "/registration/:id/form-field-data/:name",
         method = "delete",
         operation_id = "delete-registration-form-field-data",
         tag = "Tag::Registration"
@@ -464,10 +652,7 @@ impl Routes {
         db: web::Data<&Database>,
         id: Path<String>,
         name: Path<String>,
-    ) -> Result<
-        registration::delete_form_field_data::Response,
-        registration::delete_form_field_data::Error,
-    > {
+    ) -> Result<registration::delete_form_field_data::Response, Error> {
         self._delete_registration_form_field_data(auth, db, id, name)
             .await
     }
@@ -475,6 +660,8 @@ impl Routes {
     /* Price */
 
     /// Create price
+    ///
+    /// Requires the `events:write` permission.
     #[oai(
         path = "/price",
         method = "post",
@@ -483,14 +670,18 @@ impl Routes {
     )]
     async fn create_price(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         body: payload::Json<price::create::Request>,
-    ) -> Result<price::create::Response, price::create::Error> {
-        self._create_price(db, body).await
+    ) -> Result<price::create::Response, Error> {
+        self._create_price(auth, db, body).await
     }
 
     /// List or search price
+    ///
+    /// Results are paged, pass the returned `next_cursor` as `cursor` to retrieve the next page.
+    /// Sortable on `created_at`, `updated_at`, `name` and `fee`, prefix with `-` for descending
+    /// order.
     #[oai(
         path = "/price",
         method = "get",
@@ -501,8 +692,27 @@ impl Routes {
         &self,
         _auth: BearerAuth,
         db: web::Data<&Database>,
-    ) -> Result<price::list::Response, price::list::Error> {
-        self._list_price(db).await
+        limit: Query<Option<i64>>,
+        cursor: Query<Option<String>>,
+        sort: Query<Option<String>>,
+        event_id: Query<Option<String>>,
+        currency_code: Query<Option<String>>,
+        fields: Query<Option<String>>,
+    ) -> Result<price::list::Response, Error> {
+        self._list_price(
+            db,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            price::list::Filter {
+                event_id: event_id.0,
+                currency_code: currency_code.0,
+            },
+            fields.0,
+        )
+        .await
     }
 
     /// Get a price
@@ -517,11 +727,14 @@ impl Routes {
         _auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
-    ) -> Result<price::get::Response, price::get::Error> {
-        self._get_price(db, id).await
+        fields: Query<Option<String>>,
+    ) -> Result<price::get::Response, Error> {
+        self._get_price(db, id, fields.0).await
     }
 
     /// Update a price
+    ///
+    /// Requires the `events:write` permission.
     #[oai(
         path = "/price/:id",
         method = "patch",
@@ -530,15 +743,17 @@ impl Routes {
     )]
     async fn update_price(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
         body: payload::Json<price::update::Request>,
-    ) -> Result<price::update::Response, price::update::Error> {
-        self._update_price(db, id, body).await
+    ) -> Result<price::update::Response, Error> {
+        self._update_price(auth, db, id, body).await
     }
 
     /// Delete a price
+    ///
+    /// Requires the `events:admin` permission.
     #[oai(
         path = "/price/:id",
         method = "delete",
@@ -547,16 +762,18 @@ impl Routes {
     )]
     async fn delete_price(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
-    ) -> Result<price::delete::Response, price::delete::Error> {
-        self._delete_price(db, id).await
+    ) -> Result<price::delete::Response, Error> {
+        self._delete_price(auth, db, id).await
     }
 
     /* Session */
 
     /// Create session
+    ///
+    /// Requires the `events:write` permission.
     #[oai(
         path = "/session",
         method = "post",
@@ -565,14 +782,18 @@ impl Routes {
     )]
     async fn create_session(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         body: payload::Json<session::create::Request>,
-    ) -> Result<session::create::Response, session::create::Error> {
-        self._create_session(db, body).await
+    ) -> Result<session::create::Response, Error> {
+        self._create_session(auth, db, body).await
     }
 
     /// List or search session
+    ///
+    /// Results are paged, pass the returned `next_cursor` as `cursor` to retrieve the next page.
+    /// Sortable on `start_at`, `end_at`, `created_at`, `updated_at` and `name`, prefix with `-` for
+    /// descending order.
     #[oai(
         path = "/session",
         method = "get",
@@ -583,8 +804,29 @@ impl Routes {
         &self,
         _auth: BearerAuth,
         db: web::Data<&Database>,
-    ) -> Result<session::list::Response, session::list::Error> {
-        self._list_session(db).await
+        limit: Query<Option<i64>>,
+        cursor: Query<Option<String>>,
+        sort: Query<Option<String>>,
+        event_id: Query<Option<String>>,
+        starts_after: Query<Option<chrono::DateTime<chrono::Utc>>>,
+        starts_before: Query<Option<chrono::DateTime<chrono::Utc>>>,
+        fields: Query<Option<String>>,
+    ) -> Result<session::list::Response, Error> {
+        self._list_session(
+            db,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            session::list::Filter {
+                event_id: event_id.0,
+                starts_after: starts_after.0,
+                starts_before: starts_before.0,
+            },
+            fields.0,
+        )
+        .await
     }
 
     /// Get a session
@@ -599,11 +841,14 @@ impl Routes {
         _auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
-    ) -> Result<session::get::Response, session::get::Error> {
-        self._get_session(db, id).await
+        fields: Query<Option<String>>,
+    ) -> Result<session::get::Response, Error> {
+        self._get_session(db, id, fields.0).await
     }
 
     /// Update a session
+    ///
+    /// Requires the `events:write` permission.
     #[oai(
         path = "/session/:id",
         method = "patch",
@@ -612,15 +857,17 @@ impl Routes {
     )]
     async fn update_session(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
         body: payload::Json<session::update::Request>,
-    ) -> Result<session::update::Response, session::update::Error> {
-        self._update_session(db, id, body).await
+    ) -> Result<session::update::Response, Error> {
+        self._update_session(auth, db, id, body).await
     }
 
     /// Delete a session
+    ///
+    /// Requires the `events:admin` permission.
     #[oai(
         path = "/session/:id",
         method = "delete",
@@ -629,14 +876,17 @@ impl Routes {
     )]
     async fn delete_session(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
-    ) -> Result<session::delete::Response, session::delete::Error> {
-        self._delete_session(db, id).await
+    ) -> Result<session::delete::Response, Error> {
+        self._delete_session(auth, db, id).await
     }
 
     /// List a session's attendance
+    ///
+    /// Results are paged, pass the returned `next_cursor` as `cursor` to retrieve the next page.
+    /// Sortable on `created_at` and `updated_at`, prefix with `-` for descending order.
     #[oai(
         path = "/session/:id/attendance",
         method = "get",
@@ -648,8 +898,26 @@ impl Routes {
         _auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
-    ) -> Result<session::list_attendance::Response, session::list_attendance::Error> {
-        self._list_session_attendance(db, id).await
+        limit: Query<Option<i64>>,
+        cursor: Query<Option<String>>,
+        sort: Query<Option<String>>,
+        created_after: Query<Option<chrono::DateTime<chrono::Utc>>>,
+        fields: Query<Option<String>>,
+    ) -> Result<session::list_attendance::Response, Error> {
+        self._list_session_attendance(
+            db,
+            id,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            session::list_attendance::Filter {
+                created_after: created_after.0,
+            },
+            fields.0,
+        )
+        .await
     }
 
     /* Atendance */
@@ -657,6 +925,8 @@ impl Routes {
     /// Create a attendance
     ///
     /// Create a new attendance given its information.
+    ///
+    /// Requires the `attendance:write` permission.
     #[oai(
         path = "/attendance",
         method = "post",
@@ -665,16 +935,18 @@ impl Routes {
     )]
     async fn create_attendance(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         body: payload::Json<attendance::create::Request>,
-    ) -> Result<attendance::create::Response, attendance::create::Error> {
-        self._create_attendance(db, body).await
+    ) -> Result<attendance::create::Response, Error> {
+        self._create_attendance(auth, db, body).await
     }
 
     /// List or search attendance
     ///
-    /// Retrieve a list of attendances or search for attendances given a query.
+    /// Retrieve a list of attendances or search for attendances given a query. Results are paged,
+    /// pass the returned `next_cursor` as `cursor` to retrieve the next page. Sortable on
+    /// `created_at` and `updated_at`, prefix with `-` for descending order.
     #[oai(
         path = "/attendance",
         method = "get",
@@ -685,8 +957,29 @@ impl Routes {
         &self,
         _auth: BearerAuth,
         db: web::Data<&Database>,
-    ) -> Result<attendance::list::Response, attendance::list::Error> {
-        self._list_attendance(db).await
+        limit: Query<Option<i64>>,
+        cursor: Query<Option<String>>,
+        sort: Query<Option<String>>,
+        session_id: Query<Option<String>>,
+        user_id: Query<Option<String>>,
+        created_after: Query<Option<chrono::DateTime<chrono::Utc>>>,
+        fields: Query<Option<String>>,
+    ) -> Result<attendance::list::Response, Error> {
+        self._list_attendance(
+            db,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            attendance::list::Filter {
+                session_id: session_id.0,
+                user_id: user_id.0,
+                created_after: created_after.0,
+            },
+            fields.0,
+        )
+        .await
     }
 
     /// Get a attendance
@@ -703,13 +996,16 @@ impl Routes {
         _auth: BearerAuth,
         db: web::Data<&Database>,
         body: payload::Json<attendance::get::Request>,
-    ) -> Result<attendance::get::Response, attendance::get::Error> {
-        self._get_attendance(db, body).await
+        fields: Query<Option<String>>,
+    ) -> Result<attendance::get::Response, Error> {
+        self._get_attendance(db, body, fields.0).await
     }
 
     /// Delete a attendance
     ///
     /// Delete a attendance given its session_id and user_id.
+    ///
+    /// Requires the `attendance:write` permission.
     #[oai(
         path = "/attendance",
         method = "delete",
@@ -718,11 +1014,11 @@ impl Routes {
     )]
     async fn delete_attendance(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         body: payload::Json<attendance::delete::Request>,
-    ) -> Result<attendance::delete::Response, attendance::delete::Error> {
-        self._delete_attendance(db, body).await
+    ) -> Result<attendance::delete::Response, Error> {
+        self._delete_attendance(auth, db, body).await
     }
 
     /* Currency */
@@ -730,6 +1026,8 @@ impl Routes {
     /// Create a currency
     ///
     /// Create a new currency given its information.
+    ///
+    /// Requires the `events:admin` permission.
     #[oai(
         path = "/currency",
         method = "post",
@@ -738,16 +1036,18 @@ impl Routes {
     )]
     async fn create_currency(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         body: payload::Json<currency::create::Request>,
-    ) -> Result<currency::create::Response, currency::create::Error> {
-        self._create_currency(db, body).await
+    ) -> Result<currency::create::Response, Error> {
+        self._create_currency(auth, db, body).await
     }
 
     /// List or search currency
     ///
-    /// Retrieve a list of currency or search for currency given a query.
+    /// Retrieve a list of currency or search for currency given a query. Results are paged, pass
+    /// the returned `next_cursor` as `cursor` to retrieve the next page. Sortable on `code`, `name`
+    /// and `num`, prefix with `-` for descending order.
     #[oai(
         path = "/currency",
         method = "get",
@@ -758,8 +1058,23 @@ impl Routes {
         &self,
         _auth: BearerAuth,
         db: web::Data<&Database>,
-    ) -> Result<currency::list::Response, currency::list::Error> {
-        self._list_currency(db).await
+        limit: Query<Option<i64>>,
+        cursor: Query<Option<String>>,
+        sort: Query<Option<String>>,
+        country: Query<Option<String>>,
+        fields: Query<Option<String>>,
+    ) -> Result<currency::list::Response, Error> {
+        self._list_currency(
+            db,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            currency::list::Filter { country: country.0 },
+            fields.0,
+        )
+        .await
     }
 
     /// Get a currency
@@ -776,13 +1091,16 @@ impl Routes {
         _auth: BearerAuth,
         db: web::Data<&Database>,
         code: Path<String>,
-    ) -> Result<currency::get::Response, currency::get::Error> {
-        self._get_currency(db, code).await
+        fields: Query<Option<String>>,
+    ) -> Result<currency::get::Response, Error> {
+        self._get_currency(db, code, fields.0).await
     }
 
     /// Update a currency
     ///
     /// Update a currency's details given its code and the corresponding fields to update.
+    ///
+    /// Requires the `events:admin` permission.
     #[oai(
         path = "/currency/:code",
         method = "patch",
@@ -791,17 +1109,19 @@ impl Routes {
     )]
     async fn update_currency(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         code: Path<String>,
         body: payload::Json<currency::update::Request>,
-    ) -> Result<currency::update::Response, currency::update::Error> {
-        self._update_currency(db, code, body).await
+    ) -> Result<currency::update::Response, Error> {
+        self._update_currency(auth, db, code, body).await
     }
 
     /// Delete a currency
     ///
     /// Delete a currency given its code.
+    ///
+    /// Requires the `events:admin` permission.
     #[oai(
         path = "/currency/:code",
         method = "delete",
@@ -810,11 +1130,11 @@ impl Routes {
     )]
     async fn delete_currency(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         code: Path<String>,
-    ) -> Result<currency::delete::Response, currency::delete::Error> {
-        self._delete_currency(db, code).await
+    ) -> Result<currency::delete::Response, Error> {
+        self._delete_currency(auth, db, code).await
     }
 
     /* Event Type */
@@ -822,6 +1142,8 @@ impl Routes {
     /// Create a event type
     ///
     /// Create a new event type given its information.
+    ///
+    /// Requires the `events:admin` permission.
     #[oai(
         path = "/event-type",
         method = "post",
@@ -830,16 +1152,18 @@ impl Routes {
     )]
     async fn create_event_type(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         body: payload::Json<event_type::create::Request>,
-    ) -> Result<event_type::create::Response, event_type::create::Error> {
-        self._create_event_type(db, body).await
+    ) -> Result<event_type::create::Response, Error> {
+        self._create_event_type(auth, db, body).await
     }
 
     /// List or search event_type
     ///
-    /// Retrieve a list of event_type or search for event_type given a query.
+    /// Retrieve a list of event_type or search for event_type given a query. Results are paged,
+    /// pass the returned `next_cursor` as `cursor` to retrieve the next page. Sortable on
+    /// `created_at`, `updated_at` and `name`, prefix with `-` for descending order.
     #[oai(
         path = "/event-type",
         method = "get",
@@ -850,8 +1174,21 @@ impl Routes {
         &self,
         _auth: BearerAuth,
         db: web::Data<&Database>,
-    ) -> Result<event_type::list::Response, event_type::list::Error> {
-        self._list_event_type(db).await
+        limit: Query<Option<i64>>,
+        cursor: Query<Option<String>>,
+        sort: Query<Option<String>>,
+        fields: Query<Option<String>>,
+    ) -> Result<event_type::list::Response, Error> {
+        self._list_event_type(
+            db,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            fields.0,
+        )
+        .await
     }
 
     /// Get a event_type
@@ -868,13 +1205,16 @@ impl Routes {
         _auth: BearerAuth,
         db: web::Data<&Database>,
         name: Path<String>,
-    ) -> Result<event_type::get::Response, event_type::get::Error> {
-        self._get_event_type(db, name).await
+        fields: Query<Option<String>>,
+    ) -> Result<event_type::get::Response, Error> {
+        self._get_event_type(db, name, fields.0).await
     }
 
     /// Update a event_type
     ///
     /// Update a event_type's details given its name and the corresponding fields to update.
+    ///
+    /// Requires the `events:admin` permission.
     #[oai(
         path = "/event-type/:name",
         method = "patch",
@@ -883,17 +1223,19 @@ impl Routes {
     )]
     async fn update_event_type(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         name: Path<String>,
         body: payload::Json<event_type::update::Request>,
-    ) -> Result<event_type::update::Response, event_type::update::Error> {
-        self._update_event_type(db, name, body).await
+    ) -> Result<event_type::update::Response, Error> {
+        self._update_event_type(auth, db, name, body).await
     }
 
     /// Delete a event_type
     ///
     /// Delete a event_type given its name.
+    ///
+    /// Requires the `events:admin` permission.
     #[oai(
         path = "/event-type/:name",
         method = "delete",
@@ -902,11 +1244,11 @@ impl Routes {
     )]
     async fn delete_event_type(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         name: Path<String>,
-    ) -> Result<event_type::delete::Response, event_type::delete::Error> {
-        self._delete_event_type(db, name).await
+    ) -> Result<event_type::delete::Response, Error> {
+        self._delete_event_type(auth, db, name).await
     }
 
     /* Form Field Type */
@@ -914,6 +1256,8 @@ impl Routes {
     /// Create a new form field type
     ///
     /// Create a form field type given its information.
+    ///
+    /// Requires the `events:admin` permission.
     #[oai(
         path = "/form-field-type",
         method = "post",
@@ -922,16 +1266,18 @@ impl Routes {
     )]
     async fn create_form_field_type(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         body: payload::Json<form_field_type::create::Request>,
-    ) -> Result<form_field_type::create::Response, form_field_type::create::Error> {
-        self._create_form_field_type(db, body).await
+    ) -> Result<form_field_type::create::Response, Error> {
+        self._create_form_field_type(auth, db, body).await
     }
 
     /// List or search form field type
     ///
-    /// Retrieve a list of form field type.
+    /// Retrieve a list of form field type. Results are paged, pass the returned `next_cursor` as
+    /// `cursor` to retrieve the next page. Sortable on `created_at`, `updated_at` and `type`,
+    /// prefix with `-` for descending order.
     #[oai(
         path = "/form-field-type",
         method = "get",
@@ -942,8 +1288,21 @@ impl Routes {
         &self,
         _auth: BearerAuth,
         db: web::Data<&Database>,
-    ) -> Result<form_field_type::list::Response, form_field_type::list::Error> {
-        self._list_form_field_type(db).await
+        limit: Query<Option<i64>>,
+        cursor: Query<Option<String>>,
+        sort: Query<Option<String>>,
+        fields: Query<Option<String>>,
+    ) -> Result<form_field_type::list::Response, Error> {
+        self._list_form_field_type(
+            db,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            fields.0,
+        )
+        .await
     }
 
     /// Get a form field type
@@ -960,13 +1319,16 @@ impl Routes {
         _auth: BearerAuth,
         db: web::Data<&Database>,
         r#type: Path<String>,
-    ) -> Result<form_field_type::get::Response, form_field_type::get::Error> {
-        self._get_form_field_type(db, r#type).await
+        fields: Query<Option<String>>,
+    ) -> Result<form_field_type::get::Response, Error> {
+        self._get_form_field_type(db, r#type, fields.0).await
     }
 
     /// Update a form field type
     ///
     /// Update a form field type's details given its name and the corresponding fields to update.
+    ///
+    /// Requires the `events:admin` permission.
     #[oai(
         path = "/form-field-type/:type",
         method = "patch",
@@ -975,17 +1337,19 @@ impl Routes {
     )]
     async fn update_form_field_type(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         r#type: Path<String>,
         body: payload::Json<form_field_type::update::Request>,
-    ) -> Result<form_field_type::update::Response, form_field_type::update::Error> {
-        self._update_form_field_type(db, r#type, body).await
+    ) -> Result<form_field_type::update::Response, Error> {
+        self._update_form_field_type(auth, db, r#type, body).await
     }
 
     /// Delete a form field type
     ///
     /// Delete a form field type given its name.
+    ///
+    /// Requires the `events:admin` permission.
     #[oai(
         path = "/form-field-type/:type",
         method = "delete",
@@ -994,10 +1358,10 @@ impl Routes {
     )]
     async fn delete_form_field_type(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         r#type: Path<String>,
-    ) -> Result<form_field_type::delete::Response, form_field_type::delete::Error> {
-        self._delete_form_field_type(db, r#type).await
+    ) -> Result<form_field_type::delete::Response, Error> {
+        self._delete_form_field_type(auth, db, r#type).await
     }
 }
@@ -2,7 +2,9 @@ use poem::web;
 use poem_openapi::{payload, Object};
 use serde::{Deserialize, Serialize};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{audit, auth::BearerAuth, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities};
 
 #[derive(Debug, Clone, Deserialize, Serialize, Object)]
 #[oai(rename = "CreatePriceRequest")]
@@ -20,24 +22,17 @@ pub enum Response {
     Ok(payload::Json<entities::Price>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
-}
-
 impl crate::routes::Routes {
     pub async fn _create_price(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         body: payload::Json<Request>,
     ) -> Result<Response, Error> {
+        auth.require(&db, Permission::EventsWrite).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
         let price = sqlx::query_as!(
             entities::Price,
             r#"
@@ -62,43 +57,10 @@ impl crate::routes::Routes {
             &body.fee,
             &body.currency_code,
         )
-        .fetch_one(&db.db)
-        .await
-        .map_err(|e| match e {
-            sqlx::Error::Database(e)
-                if e.is_unique_violation()
-                    && e.constraint()
-                        .is_some_and(|constraint| constraint == "price_pkey") =>
-            {
-                Error::BadRequest(payload::Json(ErrorResponse {
-                    message: format!(
-                        "Price with event_id '{}' and name '{}' already exists",
-                        body.event_id, body.name
-                    ),
-                }))
-            }
-            sqlx::Error::Database(e)
-                if e.is_foreign_key_violation()
-                    && e.constraint()
-                        .is_some_and(|constraint| constraint == "price_event_id_fkey") =>
-            {
-                Error::BadRequest(payload::Json(ErrorResponse {
-                    message: format!("Event with id '{}' does not exists", body.event_id),
-                }))
-            }
-            sqlx::Error::Database(e)
-                if e.is_foreign_key_violation()
-                    && e.constraint()
-                        .is_some_and(|constraint| constraint == "price_currency_code_fkey") =>
-            {
-                Error::BadRequest(payload::Json(ErrorResponse {
-                    message: format!("Currency code '{}' does not exists", body.currency_code),
-                }))
-            }
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
 
         Ok(Response::Ok(payload::Json(price)))
     }
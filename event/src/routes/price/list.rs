@@ -1,39 +1,78 @@
 use poem::web;
 use poem_openapi::payload;
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{
+    database::Database,
+    error::Error,
+    pagination::{self, Listing, Page, Sortable},
+    projection::{Fields, Projected},
+};
+
+use crate::entities;
+
+const LISTING: Listing = Listing {
+    select: "*",
+    from: "price",
+    key: "id",
+    sortable: &[
+        Sortable {
+            name: "created_at",
+            expr: "created_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "updated_at",
+            expr: "updated_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "name",
+            expr: "name",
+            ty: "TEXT",
+        },
+        Sortable {
+            name: "fee",
+            expr: "fee",
+            ty: "INTEGER",
+        },
+    ],
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub event_id: Option<String>,
+    pub currency_code: Option<String>,
+}
 
 #[derive(poem_openapi::ApiResponse)]
 pub enum Response {
     #[oai(status = 200)]
-    Ok(payload::Json<Vec<entities::Price>>),
+    Ok(payload::Json<Page<Projected<entities::Price>>>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
+impl crate::routes::Routes {
+    pub async fn _list_price(
+        &self,
+        db: web::Data<&Database>,
+        params: pagination::Params,
+        filter: Filter,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::Price>(fields)?;
 
-    #[oai(status = 500)]
-    InternalServerError(payload::Json<ErrorResponse>),
-}
+        let prices = LISTING
+            .fetch(&db.db, &params, |query| {
+                if let Some(event_id) = &filter.event_id {
+                    query.push(" AND event_id = ").push_bind(event_id.clone());
+                }
+                if let Some(currency_code) = &filter.currency_code {
+                    query
+                        .push(" AND currency_code = ")
+                        .push_bind(currency_code.clone());
+                }
+            })
+            .await?;
 
-impl crate::routes::Routes {
-    pub async fn _list_price(&self, db: web::Data<&Database>) -> Result<Response, Error> {
-        let price = sqlx::query_as!(
-            entities::Price,
-            r#"
-            SELECT * from price
-            "#,
-        )
-        .fetch_all(&db.db)
-        .await
-        .map_err(|e| match e {
-            _ => Error::InternalServerError(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
-
-        Ok(Response::Ok(payload::Json(price)))
+        Ok(Response::Ok(payload::Json(fields.project_page(prices))))
     }
 }
@@ -1,13 +1,15 @@
 use poem::web;
 use poem_openapi::{param::Path, payload, Object};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{audit, auth::BearerAuth, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Deserialize, Serialize, Object)]
 #[oai(rename = "UpdatePriceRequest")]
 pub struct Request {
-    name: Option<i32>,
+    name: Option<String>,
     fee: Option<i32>,
     currency_code: Option<String>,
 }
@@ -18,25 +20,18 @@ pub enum Response {
     Ok(payload::Json<entities::Price>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
-}
-
 impl crate::routes::Routes {
     pub async fn _update_price(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
         body: payload::Json<Request>,
     ) -> Result<Response, Error> {
+        auth.require(&db, Permission::EventsWrite).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
         let price = sqlx::query_as_unchecked!(
             entities::Price,
             r#"
@@ -53,17 +48,18 @@ impl crate::routes::Routes {
             &body.currency_code,
             &*id,
         )
-        .fetch_one(&db.db)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| match e {
-            sqlx::error::Error::RowNotFound => Error::NotFound(payload::Json(ErrorResponse {
-                message: format!("Price with id '{}' not found", &*id),
-            })),
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
+            sqlx::Error::RowNotFound => Error::not_found(
+                "PRICE_NOT_FOUND",
+                format!("Price with id '{}' not found", &*id),
+            ),
+            _ => Error::from(e),
         })?;
 
+        tx.commit().await?;
+
         Ok(Response::Ok(payload::Json(price)))
     }
 }
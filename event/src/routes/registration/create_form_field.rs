@@ -2,7 +2,9 @@ use poem::web;
 use poem_openapi::{param::Path, payload, Object};
 use serde::{Deserialize, Serialize};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{audit, auth::BearerAuth, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities};
 
 #[derive(Debug, Clone, Deserialize, Serialize, Object)]
 #[oai(rename = "CreateRegistrationFormFieldRequest")]
@@ -20,25 +22,18 @@ pub enum Response {
     Ok(payload::Json<entities::RegistrationFormField>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
-}
-
 impl crate::routes::Routes {
     pub async fn _create_registration_form_field(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         registration_id: Path<String>,
         body: payload::Json<Request>,
     ) -> Result<Response, Error> {
+        auth.require(&db, Permission::EventsWrite).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
         let registration_form_field = sqlx::query_as_unchecked!(
             entities::RegistrationFormField,
             r#"
@@ -66,61 +61,10 @@ impl crate::routes::Routes {
             &body.r#type,
             &body.weight,
         )
-        .fetch_one(&db.db)
-        .await
-        .map_err(|e| match e {
-            sqlx::Error::Database(e)
-                if e.is_unique_violation()
-                    && e.constraint()
-                        .is_some_and(|constraint| constraint == "registration_form_field_pkey") =>
-            {
-                Error::BadRequest(payload::Json(ErrorResponse {
-                    message: format!(
-                        "Registration with id '{}' and name '{}' already exists",
-                        &*registration_id, body.name
-                    ),
-                }))
-            }
-            sqlx::Error::Database(e)
-                if e.is_unique_violation()
-                    && e.constraint().is_some_and(|constraint| {
-                        constraint == "registration_form_field_registration_id_weight_key"
-                    }) =>
-            {
-                Error::BadRequest(payload::Json(ErrorResponse {
-                    message: format!(
-                        "Registration with id '{}' and weight '{}' already exists",
-                        &*registration_id, body.weight
-                    ),
-                }))
-            }
-            sqlx::Error::Database(e)
-                if e.is_foreign_key_violation()
-                    && e.constraint().is_some_and(|constraint| {
-                        constraint == "registration_form_field_registration_id_fkey"
-                    }) =>
-            {
-                Error::BadRequest(payload::Json(ErrorResponse {
-                    message: format!(
-                        "Registration with id '{}' does not exists",
-                        &*registration_id
-                    ),
-                }))
-            }
-            sqlx::Error::Database(e)
-                if e.is_foreign_key_violation()
-                    && e.constraint().is_some_and(|constraint| {
-                        constraint == "registration_form_field_type_fkey"
-                    }) =>
-            {
-                Error::BadRequest(payload::Json(ErrorResponse {
-                    message: format!("Form field type '{}' does not exists", &*body.r#type),
-                }))
-            }
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
 
         Ok(Response::Ok(payload::Json(registration_form_field)))
     }
@@ -2,7 +2,9 @@ use poem::web;
 use poem_openapi::{param::Path, payload, Object};
 use serde::{Deserialize, Serialize};
 
-use crate::{database::Database, entities, error::ErrorResponse, auth::BearerAuth};
+use common::{audit, auth::BearerAuth, database::Database, error::Error, outbox};
+
+use crate::entities;
 
 #[derive(Debug, Clone, Deserialize, Serialize, Object)]
 #[oai(rename = "CreateRegistrationFormFieldDataRequest")]
@@ -17,18 +19,6 @@ pub enum Response {
     Ok(payload::Json<entities::RegistrationFormFieldData>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
-}
-
 impl crate::routes::Routes {
     pub async fn _create_registration_form_field_data(
         &self,
@@ -37,6 +27,8 @@ impl crate::routes::Routes {
         registration_id: Path<String>,
         body: payload::Json<Request>,
     ) -> Result<Response, Error> {
+        let mut tx = audit::begin(&db, &auth).await?;
+
         let registration_form_field = sqlx::query_as_unchecked!(
             entities::RegistrationFormFieldData,
             r#"
@@ -58,39 +50,12 @@ impl crate::routes::Routes {
             &auth.0.id,
             &body.data,
         )
-        .fetch_one(&db.db)
-        .await
-        .map_err(|e| match e {
-            sqlx::Error::Database(e)
-                if e.is_unique_violation()
-                    && e.constraint().is_some_and(|constraint| {
-                        constraint == "registration_form_field_data_pkey"
-                    }) =>
-            {
-                Error::BadRequest(payload::Json(ErrorResponse {
-                    message: format!(
-                        "Registration with id '{}', name '{}' and user id '{}' already exists",
-                        &*registration_id, body.name, &auth.0.id
-                    ),
-                }))
-            }
-            sqlx::Error::Database(e)
-                if e.is_foreign_key_violation()
-                    && e.constraint().is_some_and(|constraint| {
-                        constraint == "registration_form_field_data_registration_id_name_fkey"
-                    }) =>
-            {
-                Error::BadRequest(payload::Json(ErrorResponse {
-                    message: format!(
-                        "Registration with id '{}' and name '{}' does not exists",
-                        &*registration_id, body.name
-                    ),
-                }))
-            }
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
+        .fetch_one(&mut *tx)
+        .await?;
+
+        outbox::publish(&mut tx, "registration.submitted", &registration_form_field).await?;
+
+        tx.commit().await?;
 
         Ok(Response::Ok(payload::Json(registration_form_field)))
     }
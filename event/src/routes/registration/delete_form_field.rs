@@ -1,7 +1,9 @@
 use poem::web;
 use poem_openapi::{param::Path, payload};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{audit, auth::BearerAuth, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities};
 
 #[derive(poem_openapi::ApiResponse)]
 pub enum Response {
@@ -9,25 +11,18 @@ pub enum Response {
     Ok(payload::Json<entities::RegistrationFormField>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
-}
-
 impl crate::routes::Routes {
     pub async fn _delete_registration_form_field(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
         name: Path<String>,
     ) -> Result<Response, Error> {
+        auth.require(&db, Permission::EventsAdmin).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
         let registration_form_field = sqlx::query_as!(
             entities::RegistrationFormField,
             r#"
@@ -38,20 +33,21 @@ impl crate::routes::Routes {
             &*id,
             &*name
         )
-        .fetch_one(&db.db)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| match e {
-            sqlx::error::Error::RowNotFound => Error::NotFound(payload::Json(ErrorResponse {
-                message: format!(
+            sqlx::Error::RowNotFound => Error::not_found(
+                "REGISTRATION_FORM_FIELD_NOT_FOUND",
+                format!(
                     "Registration with id '{}' and name '{}' not found",
                     &*id, &*name
                 ),
-            })),
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
+            ),
+            _ => Error::from(e),
         })?;
 
+        tx.commit().await?;
+
         Ok(Response::Ok(payload::Json(registration_form_field)))
     }
 }
@@ -1,24 +1,19 @@
 use poem::web;
 use poem_openapi::{param::Path, payload};
 
-use crate::{database::Database, entities, error::ErrorResponse, auth::BearerAuth};
+use common::{
+    auth::BearerAuth,
+    database::Database,
+    error::Error,
+    projection::{Fields, Projected},
+};
+
+use crate::entities;
 
 #[derive(poem_openapi::ApiResponse)]
 pub enum Response {
     #[oai(status = 200)]
-    Ok(payload::Json<entities::RegistrationFormFieldData>),
-}
-
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
+    Ok(payload::Json<Projected<entities::RegistrationFormFieldData>>),
 }
 
 impl crate::routes::Routes {
@@ -28,7 +23,10 @@ impl crate::routes::Routes {
         db: web::Data<&Database>,
         registration_id: Path<String>,
         name: Path<String>,
+        fields: Option<String>,
     ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::RegistrationFormFieldData>(fields)?;
+
         let registration = sqlx::query_as!(
             entities::RegistrationFormFieldData,
             r#"
@@ -43,17 +41,16 @@ impl crate::routes::Routes {
         .fetch_one(&db.db)
         .await
         .map_err(|e| match e {
-            sqlx::error::Error::RowNotFound => Error::NotFound(payload::Json(ErrorResponse {
-                message: format!(
+            sqlx::Error::RowNotFound => Error::not_found(
+                "REGISTRATION_FORM_FIELD_DATA_NOT_FOUND",
+                format!(
                     "Registration with id '{}', name '{}' and user id '{}' not found",
                     &*registration_id, &*name, &auth.0.id
                 ),
-            })),
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
+            ),
+            _ => Error::from(e),
         })?;
 
-        Ok(Response::Ok(payload::Json(registration)))
+        Ok(Response::Ok(payload::Json(fields.project(registration))))
     }
 }
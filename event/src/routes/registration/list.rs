@@ -1,39 +1,82 @@
 use poem::web;
 use poem_openapi::payload;
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{
+    database::Database,
+    error::Error,
+    pagination::{self, Listing, Page, Sortable},
+    projection::{Fields, Projected},
+};
+
+use crate::entities;
+
+const LISTING: Listing = Listing {
+    select: "*",
+    from: "registration",
+    key: "id",
+    sortable: &[
+        Sortable {
+            name: "created_at",
+            expr: "created_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "updated_at",
+            expr: "updated_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "close_at",
+            expr: "close_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "name",
+            expr: "name",
+            ty: "TEXT",
+        },
+    ],
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub event_id: Option<String>,
+    pub closes_after: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_after: Option<chrono::DateTime<chrono::Utc>>,
+}
 
 #[derive(poem_openapi::ApiResponse)]
 pub enum Response {
     #[oai(status = 200)]
-    Ok(payload::Json<Vec<entities::Registration>>),
+    Ok(payload::Json<Page<Projected<entities::Registration>>>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
+impl crate::routes::Routes {
+    pub async fn _list_registrations(
+        &self,
+        db: web::Data<&Database>,
+        params: pagination::Params,
+        filter: Filter,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::Registration>(fields)?;
 
-    #[oai(status = 500)]
-    InternalServerError(payload::Json<ErrorResponse>),
-}
+        let registrations = LISTING
+            .fetch(&db.db, &params, |query| {
+                if let Some(event_id) = &filter.event_id {
+                    query.push(" AND event_id = ").push_bind(event_id.clone());
+                }
+                if let Some(closes_after) = filter.closes_after {
+                    query.push(" AND close_at > ").push_bind(closes_after);
+                }
+                if let Some(created_after) = filter.created_after {
+                    query.push(" AND created_at > ").push_bind(created_after);
+                }
+            })
+            .await?;
 
-impl crate::routes::Routes {
-    pub async fn _list_registrations(&self, db: web::Data<&Database>) -> Result<Response, Error> {
-        let registrations = sqlx::query_as!(
-            entities::Registration,
-            r#"
-            SELECT * from registration
-            "#,
-        )
-        .fetch_all(&db.db)
-        .await
-        .map_err(|e| match e {
-            _ => Error::InternalServerError(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
-
-        Ok(Response::Ok(payload::Json(registrations)))
+        Ok(Response::Ok(payload::Json(
+            fields.project_page(registrations),
+        )))
     }
 }
@@ -1,21 +1,43 @@
 use poem::web;
 use poem_openapi::{param::Path, payload};
 
-use crate::{database::Database, entities, error::ErrorResponse, auth::BearerAuth};
+use common::{
+    auth::BearerAuth,
+    database::Database,
+    error::Error,
+    pagination::{self, Listing, Page, Sortable},
+    projection::{Fields, Projected},
+};
+
+use crate::entities;
+
+const LISTING: Listing = Listing {
+    select: "*",
+    from: "registration_form_field_data",
+    key: "name",
+    sortable: &[
+        Sortable {
+            name: "name",
+            expr: "name",
+            ty: "TEXT",
+        },
+        Sortable {
+            name: "created_at",
+            expr: "created_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "updated_at",
+            expr: "updated_at",
+            ty: "TIMESTAMPTZ",
+        },
+    ],
+};
 
 #[derive(poem_openapi::ApiResponse)]
 pub enum Response {
     #[oai(status = 200)]
-    Ok(payload::Json<Vec<entities::RegistrationFormFieldData>>),
-}
-
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServerError(payload::Json<ErrorResponse>),
+    Ok(payload::Json<Page<Projected<entities::RegistrationFormFieldData>>>),
 }
 
 impl crate::routes::Routes {
@@ -24,25 +46,22 @@ impl crate::routes::Routes {
         auth: BearerAuth,
         db: web::Data<&Database>,
         registration_id: Path<String>,
+        params: pagination::Params,
+        fields: Option<String>,
     ) -> Result<Response, Error> {
-        let registration_form_fields = sqlx::query_as!(
-            entities::RegistrationFormFieldData,
-            r#"
-            SELECT * 
-            FROM registration_form_field_data
-            WHERE registration_id = $1 AND user_id = $2
-            "#,
-            &*registration_id,
-            &auth.0.id,
-        )
-        .fetch_all(&db.db)
-        .await
-        .map_err(|e| match e {
-            _ => Error::InternalServerError(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
+        let fields = Fields::parse::<entities::RegistrationFormFieldData>(fields)?;
+
+        let registration_form_fields = LISTING
+            .fetch(&db.db, &params, |query| {
+                query
+                    .push(" AND registration_id = ")
+                    .push_bind(registration_id.0.clone());
+                query.push(" AND user_id = ").push_bind(auth.0.id.clone());
+            })
+            .await?;
 
-        Ok(Response::Ok(payload::Json(registration_form_fields)))
+        Ok(Response::Ok(payload::Json(
+            fields.project_page(registration_form_fields),
+        )))
     }
 }
@@ -1,7 +1,9 @@
 use poem::web;
 use poem_openapi::{param::Path, payload, Object};
 
-use crate::{database::Database, entities, error::ErrorResponse, auth::BearerAuth};
+use common::{audit, auth::BearerAuth, database::Database, error::Error};
+
+use crate::entities;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Deserialize, Serialize, Object)]
@@ -16,18 +18,6 @@ pub enum Response {
     Ok(payload::Json<entities::RegistrationFormFieldData>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
-}
-
 impl crate::routes::Routes {
     pub async fn _update_registration_form_field_data(
         &self,
@@ -37,6 +27,8 @@ impl crate::routes::Routes {
         name: Path<String>,
         body: payload::Json<Request>,
     ) -> Result<Response, Error> {
+        let mut tx = audit::begin(&db, &auth).await?;
+
         let registration = sqlx::query_as_unchecked!(
             entities::RegistrationFormFieldData,
             r#"
@@ -51,20 +43,21 @@ impl crate::routes::Routes {
             &*name,
             &auth.0.id,
         )
-        .fetch_one(&db.db)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| match e {
-            sqlx::error::Error::RowNotFound => Error::NotFound(payload::Json(ErrorResponse {
-                message: format!(
+            sqlx::Error::RowNotFound => Error::not_found(
+                "REGISTRATION_FORM_FIELD_DATA_NOT_FOUND",
+                format!(
                     "Registration with id '{}', name '{}' and user id '{}' not found",
                     &*registration_id, &*name, &auth.0.id
                 ),
-            })),
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
+            ),
+            _ => Error::from(e),
         })?;
 
+        tx.commit().await?;
+
         Ok(Response::Ok(payload::Json(registration)))
     }
 }
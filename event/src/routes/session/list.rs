@@ -1,39 +1,85 @@
 use poem::web;
 use poem_openapi::payload;
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{
+    database::Database,
+    error::Error,
+    pagination::{self, Listing, Page, Sortable},
+    projection::{Fields, Projected},
+};
+
+use crate::entities;
+
+const LISTING: Listing = Listing {
+    select: "*",
+    from: r#""session""#,
+    key: "id",
+    sortable: &[
+        Sortable {
+            name: "start_at",
+            expr: "start_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "end_at",
+            expr: "end_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "created_at",
+            expr: "created_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "updated_at",
+            expr: "updated_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "name",
+            expr: "name",
+            ty: "TEXT",
+        },
+    ],
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub event_id: Option<String>,
+    pub starts_after: Option<chrono::DateTime<chrono::Utc>>,
+    pub starts_before: Option<chrono::DateTime<chrono::Utc>>,
+}
 
 #[derive(poem_openapi::ApiResponse)]
 pub enum Response {
     #[oai(status = 200)]
-    Ok(payload::Json<Vec<entities::Session>>),
+    Ok(payload::Json<Page<Projected<entities::Session>>>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
+impl crate::routes::Routes {
+    pub async fn _list_session(
+        &self,
+        db: web::Data<&Database>,
+        params: pagination::Params,
+        filter: Filter,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::Session>(fields)?;
 
-    #[oai(status = 500)]
-    InternalServerError(payload::Json<ErrorResponse>),
-}
+        let sessions = LISTING
+            .fetch(&db.db, &params, |query| {
+                if let Some(event_id) = &filter.event_id {
+                    query.push(" AND event_id = ").push_bind(event_id.clone());
+                }
+                if let Some(starts_after) = filter.starts_after {
+                    query.push(" AND start_at > ").push_bind(starts_after);
+                }
+                if let Some(starts_before) = filter.starts_before {
+                    query.push(" AND start_at < ").push_bind(starts_before);
+                }
+            })
+            .await?;
 
-impl crate::routes::Routes {
-    pub async fn _list_session(&self, db: web::Data<&Database>) -> Result<Response, Error> {
-        let session = sqlx::query_as!(
-            entities::Session,
-            r#"
-            SELECT * from session
-            "#,
-        )
-        .fetch_all(&db.db)
-        .await
-        .map_err(|e| match e {
-            _ => Error::InternalServerError(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
-
-        Ok(Response::Ok(payload::Json(session)))
+        Ok(Response::Ok(payload::Json(fields.project_page(sessions))))
     }
 }
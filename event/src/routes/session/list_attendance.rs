@@ -1,24 +1,42 @@
 use poem::web;
 use poem_openapi::{param::Path, payload};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{
+    database::Database,
+    error::Error,
+    pagination::{self, Listing, Page, Sortable},
+    projection::{Fields, Projected},
+};
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Response {
-    #[oai(status = 200)]
-    Ok(payload::Json<Vec<entities::Attendance>>),
-}
+use crate::entities;
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
+const LISTING: Listing = Listing {
+    select: "a.*",
+    from: "attendance a",
+    key: "a.session_id || '/' || a.user_id",
+    sortable: &[
+        Sortable {
+            name: "created_at",
+            expr: "a.created_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "updated_at",
+            expr: "a.updated_at",
+            ty: "TIMESTAMPTZ",
+        },
+    ],
+};
 
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub created_after: Option<chrono::DateTime<chrono::Utc>>,
+}
 
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Page<Projected<entities::Attendance>>>),
 }
 
 impl crate::routes::Routes {
@@ -26,22 +44,23 @@ impl crate::routes::Routes {
         &self,
         db: web::Data<&Database>,
         id: Path<String>,
+        params: pagination::Params,
+        filter: Filter,
+        fields: Option<String>,
     ) -> Result<Response, Error> {
-        let attendances = sqlx::query_as!(
-            entities::Attendance,
-            r#"
-            SELECT * FROM attendance WHERE session_id = $1::TEXT
-            "#,
-            &*id
-        )
-        .fetch_all(&db.db)
-        .await
-        .map_err(|e| match e {
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
-
-        Ok(Response::Ok(payload::Json(attendances)))
+        let fields = Fields::parse::<entities::Attendance>(fields)?;
+
+        let attendances = LISTING
+            .fetch(&db.db, &params, |query| {
+                query.push(" AND a.session_id = ").push_bind(id.0.clone());
+                if let Some(created_after) = filter.created_after {
+                    query.push(" AND a.created_at > ").push_bind(created_after);
+                }
+            })
+            .await?;
+
+        Ok(Response::Ok(payload::Json(
+            fields.project_page(attendances),
+        )))
     }
 }
@@ -0,0 +1,74 @@
+use poem::http::StatusCode;
+use serde_json::json;
+
+use crate::{admin, app, create_event, create_session, fields};
+
+#[tokio::test]
+async fn record_attendance() {
+    let app = app().await;
+    let event = create_event(&app, "Youth camp").await;
+    let session = create_session(&app, event["id"].as_str().unwrap(), "Opening").await;
+    let attendance = json!({ "session_id": session["id"], "user_id": "alice" });
+
+    app.post("/attendance")
+        .bearer(&app.token("alice"))
+        .json(&attendance)
+        .send()
+        .await
+        .assert_error(StatusCode::FORBIDDEN, "MISSING_PERMISSION");
+
+    let recorded = app
+        .post("/attendance")
+        .bearer(&app.token("usher").permissions(["attendance:write"]))
+        .json(&attendance)
+        .send()
+        .await
+        .ok();
+    assert_eq!(recorded["user_id"], "alice");
+
+    app.post("/attendance")
+        .bearer(&admin(&app))
+        .json(&attendance)
+        .send()
+        .await
+        .assert_error(StatusCode::CONFLICT, "ATTENDANCE_ALREADY_RECORDED");
+
+    app.post("/attendance")
+        .bearer(&admin(&app))
+        .json(json!({ "session_id": "session_missing", "user_id": "alice" }))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "SESSION_NOT_FOUND");
+
+    let page = app
+        .get("/attendance?user_id=alice")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(
+        fields(&page, "session_id"),
+        [session["id"].as_str().unwrap()]
+    );
+
+    app.get("/attendance/get")
+        .bearer(&app.token("alice"))
+        .json(&attendance)
+        .send()
+        .await
+        .ok();
+
+    app.delete("/attendance")
+        .bearer(&admin(&app))
+        .json(&attendance)
+        .send()
+        .await
+        .ok();
+
+    app.get("/attendance/get")
+        .bearer(&app.token("alice"))
+        .json(&attendance)
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "ATTENDANCE_NOT_FOUND");
+}
@@ -0,0 +1,75 @@
+use poem::http::StatusCode;
+use serde_json::json;
+
+use crate::{admin, app, create_currency, fields};
+
+#[tokio::test]
+async fn manage_currencies() {
+    let app = app().await;
+
+    app.post("/currency")
+        .bearer(&app.token("alice").permissions(["events:write"]))
+        .json(json!({
+            "code": "MYR",
+            "num": 458,
+            "denominator": 100,
+            "name": "Malaysian ringgit",
+            "countries": ["MY"],
+        }))
+        .send()
+        .await
+        .assert_error(StatusCode::FORBIDDEN, "MISSING_PERMISSION");
+
+    create_currency(&app, "MYR").await;
+    create_currency(&app, "SGD").await;
+
+    app.post("/currency")
+        .bearer(&admin(&app))
+        .json(json!({
+            "code": "MYR",
+            "num": 458,
+            "denominator": 100,
+            "name": "Malaysian ringgit",
+            "countries": ["MY"],
+        }))
+        .send()
+        .await
+        .assert_error(StatusCode::CONFLICT, "CURRENCY_CODE_TAKEN");
+
+    let page = app
+        .get("/currency?sort=-code")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(fields(&page, "code"), ["SGD", "MYR"]);
+
+    let currency = app
+        .get("/currency/MYR")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(currency["countries"], json!(["MY"]));
+
+    let currency = app
+        .patch("/currency/MYR")
+        .bearer(&admin(&app))
+        .json(json!({ "name": "Malaysian ringgit" }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(currency["name"], "Malaysian ringgit");
+
+    app.delete("/currency/MYR")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+
+    app.get("/currency/MYR")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "CURRENCY_NOT_FOUND");
+}
@@ -0,0 +1,167 @@
+use poem::http::StatusCode;
+use serde_json::json;
+
+use crate::{
+    admin, app, create_currency, create_event, create_event_type, create_registration,
+    create_session, fields,
+};
+
+#[tokio::test]
+async fn create_event_requires_permission_and_event_type() {
+    let app = app().await;
+
+    app.post("/event")
+        .bearer(&app.token("alice"))
+        .json(json!({ "name": "Camp", "description": "Camp", "event_type": "camp" }))
+        .send()
+        .await
+        .assert_error(StatusCode::FORBIDDEN, "MISSING_PERMISSION");
+
+    app.post("/event")
+        .bearer(&app.token("alice").permissions(["events:write"]))
+        .json(json!({ "name": "Camp", "description": "Camp", "event_type": "camp" }))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "EVENT_TYPE_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn manage_events() {
+    let app = app().await;
+    let event = create_event(&app, "Youth camp").await;
+    let id = event["id"].as_str().unwrap();
+    assert!(id.starts_with("event_"));
+
+    let page = app
+        .get("/event")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(fields(&page, "id"), [id]);
+
+    let fetched = app
+        .get(format!("/event/{}", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(fetched["type"], "Youth camp type");
+
+    create_event_type(&app, "retreat").await;
+    let updated = app
+        .patch(format!("/event/{}", id))
+        .bearer(&admin(&app))
+        .json(json!({ "event_type": "retreat" }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(updated["type"], "retreat");
+
+    // Deleting requires `events:admin`, `events:write` is not enough.
+    app.delete(format!("/event/{}", id))
+        .bearer(&app.token("alice").permissions(["events:write"]))
+        .send()
+        .await
+        .assert_error(StatusCode::FORBIDDEN, "MISSING_PERMISSION");
+
+    app.delete(format!("/event/{}", id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+
+    app.get(format!("/event/{}", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "EVENT_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn list_event_resources() {
+    let app = app().await;
+    let event = create_event(&app, "Youth camp").await;
+    let id = event["id"].as_str().unwrap();
+    create_currency(&app, "MYR").await;
+
+    let registration = create_registration(&app, id, "Early bird").await;
+    let session = create_session(&app, id, "Opening").await;
+    let price = app
+        .post("/price")
+        .bearer(&admin(&app))
+        .json(json!({ "event_id": id, "name": "Standard", "fee": 5000, "currency_code": "MYR" }))
+        .send()
+        .await
+        .ok();
+    app.post("/attendance")
+        .bearer(&admin(&app))
+        .json(json!({ "session_id": session["id"], "user_id": "alice" }))
+        .send()
+        .await
+        .ok();
+
+    let page = app
+        .get(format!("/event/{}/registration", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(fields(&page, "id"), [registration["id"].as_str().unwrap()]);
+
+    let page = app
+        .get(format!("/event/{}/session", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(fields(&page, "id"), [session["id"].as_str().unwrap()]);
+
+    let page = app
+        .get(format!("/event/{}/price", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(fields(&page, "id"), [price["id"].as_str().unwrap()]);
+
+    let page = app
+        .get(format!("/event/{}/attendance", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(fields(&page, "user_id"), ["alice"]);
+
+    app.delete(format!("/event/{}", id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .assert_error(StatusCode::CONFLICT, "RESOURCE_IN_USE");
+}
+
+#[tokio::test]
+async fn projects_requested_fields() {
+    let app = app().await;
+    let event = create_event(&app, "Youth camp").await;
+
+    let page = app
+        .get("/event?fields=id,name")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(
+        page["items"],
+        json!([{ "id": event["id"], "name": "Youth camp" }])
+    );
+
+    app.get(format!(
+        "/event/{}?fields=type,venue",
+        event["id"].as_str().unwrap()
+    ))
+    .bearer(&app.token("alice"))
+    .send()
+    .await
+    .assert_error(StatusCode::BAD_REQUEST, "UNKNOWN_FIELD");
+}
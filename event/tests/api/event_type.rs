@@ -0,0 +1,65 @@
+use poem::http::StatusCode;
+use serde_json::json;
+
+use crate::{admin, app, create_event, create_event_type, fields};
+
+#[tokio::test]
+async fn manage_event_types() {
+    let app = app().await;
+    create_event_type(&app, "camp").await;
+
+    app.post("/event-type")
+        .bearer(&admin(&app))
+        .json(json!({ "name": "camp" }))
+        .send()
+        .await
+        .assert_error(StatusCode::CONFLICT, "EVENT_TYPE_NAME_TAKEN");
+
+    let page = app
+        .get("/event-type")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(fields(&page, "name"), ["camp"]);
+
+    app.get("/event-type/camp")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+
+    let event_type = app
+        .patch("/event-type/camp")
+        .bearer(&admin(&app))
+        .json(json!({ "name": "retreat" }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(event_type["name"], "retreat");
+
+    app.delete("/event-type/retreat")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+
+    app.get("/event-type/retreat")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "EVENT_TYPE_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn delete_event_type_in_use() {
+    let app = app().await;
+    let event = create_event(&app, "Youth camp").await;
+
+    let name = event["type"].as_str().unwrap().replace(' ', "%20");
+    app.delete(format!("/event-type/{}", name))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .assert_error(StatusCode::CONFLICT, "RESOURCE_IN_USE");
+}
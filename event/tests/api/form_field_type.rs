@@ -0,0 +1,52 @@
+use poem::http::StatusCode;
+use serde_json::json;
+
+use crate::{admin, app, create_form_field_type, fields};
+
+#[tokio::test]
+async fn manage_form_field_types() {
+    let app = app().await;
+    create_form_field_type(&app, "text").await;
+
+    app.post("/form-field-type")
+        .bearer(&app.token("alice"))
+        .json(json!({ "type": "date", "description": "date" }))
+        .send()
+        .await
+        .assert_error(StatusCode::FORBIDDEN, "MISSING_PERMISSION");
+
+    let page = app
+        .get("/form-field-type")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(fields(&page, "type"), ["text"]);
+
+    app.get("/form-field-type/text")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+
+    let form_field_type = app
+        .patch("/form-field-type/text")
+        .bearer(&admin(&app))
+        .json(json!({ "description": "A single line of text" }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(form_field_type["description"], "A single line of text");
+
+    app.delete("/form-field-type/text")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+
+    app.get("/form-field-type/text")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "FORM_FIELD_TYPE_NOT_FOUND");
+}
@@ -0,0 +1,111 @@
+//! End-to-end tests of the event endpoints, served by `common::testing::TestApp` against a
+//! throwaway Postgres and a mock of the Auth0 management API.
+
+use common::testing::{TestApp, Token};
+use serde_json::{json, Value};
+
+mod attendance;
+mod currency;
+mod event;
+mod event_type;
+mod form_field_type;
+mod price;
+mod registration;
+mod session;
+
+pub async fn app() -> TestApp {
+    TestApp::new(::event::routes::Routes::new).await
+}
+
+/// A token holding every permission of the service.
+pub fn admin(app: &TestApp) -> Token {
+    app.token("admin")
+        .permissions(["events:admin", "attendance:write"])
+}
+
+pub async fn create_currency(app: &TestApp, code: &str) -> Value {
+    app.post("/currency")
+        .bearer(&admin(app))
+        .json(json!({
+            "code": code,
+            "num": 458,
+            "denominator": 100,
+            "name": code,
+            "countries": ["MY"],
+        }))
+        .send()
+        .await
+        .ok()
+}
+
+pub async fn create_event_type(app: &TestApp, name: &str) -> Value {
+    app.post("/event-type")
+        .bearer(&admin(app))
+        .json(json!({ "name": name }))
+        .send()
+        .await
+        .ok()
+}
+
+pub async fn create_form_field_type(app: &TestApp, r#type: &str) -> Value {
+    app.post("/form-field-type")
+        .bearer(&admin(app))
+        .json(json!({ "type": r#type, "description": r#type }))
+        .send()
+        .await
+        .ok()
+}
+
+/// Create an event along with its own event type.
+pub async fn create_event(app: &TestApp, name: &str) -> Value {
+    create_event_type(app, &format!("{} type", name)).await;
+
+    app.post("/event")
+        .bearer(&admin(app))
+        .json(json!({
+            "name": name,
+            "description": name,
+            "event_type": format!("{} type", name),
+        }))
+        .send()
+        .await
+        .ok()
+}
+
+pub async fn create_registration(app: &TestApp, event_id: &str, name: &str) -> Value {
+    app.post("/registration")
+        .bearer(&admin(app))
+        .json(json!({
+            "event_id": event_id,
+            "name": name,
+            "close_at": "2030-01-01T00:00:00Z",
+        }))
+        .send()
+        .await
+        .ok()
+}
+
+pub async fn create_session(app: &TestApp, event_id: &str, name: &str) -> Value {
+    app.post("/session")
+        .bearer(&admin(app))
+        .json(json!({
+            "event_id": event_id,
+            "name": name,
+            "expected_attendees": 100,
+            "start_at": "2030-01-01T09:00:00Z",
+            "end_at": "2030-01-01T12:00:00Z",
+        }))
+        .send()
+        .await
+        .ok()
+}
+
+/// The values of `field` of the items of a page returned by a list endpoint.
+pub fn fields<'a>(page: &'a Value, field: &str) -> Vec<&'a str> {
+    page["items"]
+        .as_array()
+        .expect("response is a page")
+        .iter()
+        .map(|item| item[field].as_str().expect("item has the field"))
+        .collect()
+}
@@ -0,0 +1,89 @@
+use poem::http::StatusCode;
+use serde_json::json;
+
+use crate::{admin, app, create_currency, create_event, fields};
+
+#[tokio::test]
+async fn manage_prices() {
+    let app = app().await;
+    let event = create_event(&app, "Youth camp").await;
+    create_currency(&app, "MYR").await;
+    create_currency(&app, "SGD").await;
+
+    app.post("/price")
+        .bearer(&admin(&app))
+        .json(json!({
+            "event_id": event["id"],
+            "name": "Standard",
+            "fee": 5000,
+            "currency_code": "USD",
+        }))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "CURRENCY_NOT_FOUND");
+
+    let price = app
+        .post("/price")
+        .bearer(&admin(&app))
+        .json(json!({
+            "event_id": event["id"],
+            "name": "Standard",
+            "fee": 5000,
+            "currency_code": "MYR",
+        }))
+        .send()
+        .await
+        .ok();
+    let id = price["id"].as_str().unwrap();
+
+    app.post("/price")
+        .bearer(&admin(&app))
+        .json(json!({
+            "event_id": event["id"],
+            "name": "Standard",
+            "fee": 4000,
+            "currency_code": "MYR",
+        }))
+        .send()
+        .await
+        .assert_error(StatusCode::CONFLICT, "PRICE_NAME_TAKEN");
+
+    let page = app
+        .get("/price")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(fields(&page, "id"), [id]);
+
+    let fetched = app
+        .get(format!("/price/{}", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(fetched["fee"], 5000);
+
+    let updated = app
+        .patch(format!("/price/{}", id))
+        .bearer(&admin(&app))
+        .json(json!({ "name": "Early bird", "fee": 4000, "currency_code": "SGD" }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(updated["name"], "Early bird");
+    assert_eq!(updated["fee"], 4000);
+    assert_eq!(updated["currency_code"], "SGD");
+
+    app.delete(format!("/price/{}", id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+
+    app.get(format!("/price/{}", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "PRICE_NOT_FOUND");
+}
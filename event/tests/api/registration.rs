@@ -0,0 +1,179 @@
+use poem::http::StatusCode;
+use serde_json::json;
+
+use crate::{admin, app, create_event, create_form_field_type, create_registration, fields};
+
+#[tokio::test]
+async fn manage_registrations() {
+    let app = app().await;
+    let event = create_event(&app, "Youth camp").await;
+    let event_id = event["id"].as_str().unwrap();
+
+    let registration = create_registration(&app, event_id, "Early bird").await;
+    let id = registration["id"].as_str().unwrap();
+    assert_eq!(registration["close_at"], "2030-01-01T00:00:00+00:00");
+
+    app.post("/registration")
+        .bearer(&admin(&app))
+        .json(json!({
+            "event_id": event_id,
+            "name": "Early bird",
+            "close_at": "2030-01-01T00:00:00Z",
+        }))
+        .send()
+        .await
+        .assert_error(StatusCode::CONFLICT, "REGISTRATION_NAME_TAKEN");
+
+    let page = app
+        .get(format!("/registration?event_id={}", event_id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(fields(&page, "id"), [id]);
+
+    app.get(format!("/registration/{}", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+
+    let updated = app
+        .patch(format!("/registration/{}", id))
+        .bearer(&admin(&app))
+        .json(json!({ "name": "Standard" }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(updated["name"], "Standard");
+
+    app.delete(format!("/registration/{}", id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+
+    app.get(format!("/registration/{}", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "REGISTRATION_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn manage_registration_form() {
+    let app = app().await;
+    let event = create_event(&app, "Youth camp").await;
+    let registration = create_registration(&app, event["id"].as_str().unwrap(), "Standard").await;
+    let id = registration["id"].as_str().unwrap();
+    create_form_field_type(&app, "text").await;
+
+    app.post(format!("/registration/{}/form-field", id))
+        .bearer(&admin(&app))
+        .json(json!({ "name": "diet", "label": "Diet", "type": "essay", "weight": 1 }))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "FORM_FIELD_TYPE_NOT_FOUND");
+
+    let field = app
+        .post(format!("/registration/{}/form-field", id))
+        .bearer(&admin(&app))
+        .json(json!({ "name": "diet", "label": "Diet", "type": "text", "weight": 1 }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(field["label"], "Diet");
+
+    let page = app
+        .get(format!("/registration/{}/form-field", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(fields(&page, "name"), ["diet"]);
+
+    app.get(format!("/registration/{}/form-field/diet", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+
+    let field = app
+        .patch(format!("/registration/{}/form-field/diet", id))
+        .bearer(&admin(&app))
+        .json(json!({ "description": "Any dietary restrictions" }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(field["description"], "Any dietary restrictions");
+
+    // Every user fills in their own answers, no permission is needed.
+    let alice = app.token("alice");
+    let data = app
+        .post(format!("/registration/{}/form-field-data", id))
+        .bearer(&alice)
+        .json(json!({ "name": "diet", "data": "Vegetarian" }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(data["user_id"], "alice");
+
+    app.post(format!("/registration/{}/form-field-data", id))
+        .bearer(&alice)
+        .json(json!({ "name": "allergies", "data": "None" }))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "REGISTRATION_FORM_FIELD_NOT_FOUND");
+
+    let page = app
+        .get(format!("/registration/{}/form-field-data", id))
+        .bearer(&alice)
+        .send()
+        .await
+        .ok();
+    assert_eq!(fields(&page, "data"), ["Vegetarian"]);
+
+    let data = app
+        .patch(format!("/registration/{}/form-field-data/diet", id))
+        .bearer(&alice)
+        .json(json!({ "data": "Vegan" }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(data["data"], "Vegan");
+
+    let data = app
+        .get(format!("/registration/{}/form-field-data/diet", id))
+        .bearer(&alice)
+        .send()
+        .await
+        .ok();
+    assert_eq!(data["data"], "Vegan");
+
+    app.get(format!("/registration/{}/form-field-data/diet", id))
+        .bearer(&app.token("bob"))
+        .send()
+        .await
+        .assert_error(
+            StatusCode::NOT_FOUND,
+            "REGISTRATION_FORM_FIELD_DATA_NOT_FOUND",
+        );
+
+    app.delete(format!("/registration/{}/form-field-data/diet", id))
+        .bearer(&alice)
+        .send()
+        .await
+        .ok();
+
+    app.delete(format!("/registration/{}/form-field/diet", id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+
+    app.get(format!("/registration/{}/form-field/diet", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "REGISTRATION_FORM_FIELD_NOT_FOUND");
+}
@@ -0,0 +1,92 @@
+use poem::http::StatusCode;
+use serde_json::json;
+
+use crate::{admin, app, create_event, create_session, fields};
+
+#[tokio::test]
+async fn manage_sessions() {
+    let app = app().await;
+    let event = create_event(&app, "Youth camp").await;
+    let event_id = event["id"].as_str().unwrap();
+
+    app.post("/session")
+        .bearer(&admin(&app))
+        .json(json!({
+            "event_id": "event_missing",
+            "name": "Opening",
+            "expected_attendees": 100,
+            "start_at": "2030-01-01T09:00:00Z",
+            "end_at": "2030-01-01T12:00:00Z",
+        }))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "EVENT_NOT_FOUND");
+
+    let session = create_session(&app, event_id, "Opening").await;
+    let id = session["id"].as_str().unwrap();
+
+    let page = app
+        .get(format!("/session?event_id={}", event_id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(fields(&page, "id"), [id]);
+
+    let fetched = app
+        .get(format!("/session/{}", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(fetched["expected_attendees"], 100);
+
+    let updated = app
+        .patch(format!("/session/{}", id))
+        .bearer(&admin(&app))
+        .json(json!({ "actual_start_at": "2030-01-01T09:15:00Z" }))
+        .send()
+        .await
+        .ok();
+    assert!(updated["actual_start_at"].is_string());
+
+    app.post("/attendance")
+        .bearer(&admin(&app))
+        .json(json!({ "session_id": id, "user_id": "alice" }))
+        .send()
+        .await
+        .ok();
+
+    let page = app
+        .get(format!("/session/{}/attendance", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(fields(&page, "user_id"), ["alice"]);
+
+    app.delete(format!("/session/{}", id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .assert_error(StatusCode::CONFLICT, "RESOURCE_IN_USE");
+
+    app.delete("/attendance")
+        .bearer(&admin(&app))
+        .json(json!({ "session_id": id, "user_id": "alice" }))
+        .send()
+        .await
+        .ok();
+
+    app.delete(format!("/session/{}", id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+
+    app.get(format!("/session/{}", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "SESSION_NOT_FOUND");
+}
@@ -0,0 +1,256 @@
+use std::{collections::HashSet, time::Duration};
+
+use auth0::management::Api;
+use common::{
+    crypto::Keyring,
+    database::Database,
+    directory::{self, Profile, ProfileUpdate, Users},
+};
+use reqwest::{RequestBuilder, StatusCode};
+
+use crate::{entities::User, pii};
+
+/// How many Auth0 profiles [`reconcile`] pulls at once.
+const PAGE_SIZE: u32 = 100;
+
+/// A user whose Auth0 profile differs from their details here.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub user_id: String,
+
+    /// The fields that differ, named after the fields of the user (e.g. `avatar_url`).
+    pub fields: Vec<&'static str>,
+}
+
+/// What [`reconcile`] found, and fixed when asked to.
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    /// The number of Auth0 profiles compared.
+    pub checked: u64,
+
+    /// The users whose Auth0 profile differs from their details here.
+    pub mismatches: Vec<Mismatch>,
+
+    /// The Auth0 users without a user here, who signed up but never created their user.
+    pub missing: Vec<String>,
+}
+
+/// The details of `user` kept in sync with their Auth0 profile. The email is left out, Auth0
+/// treats any email given as a new one to verify. So is the phone number, which is only kept
+/// encrypted here and would be stored as is by Auth0.
+fn profile_of(user: &User) -> ProfileUpdate {
+    ProfileUpdate {
+        name: Some(user.name.clone()),
+        given_name: user.given_name.clone(),
+        family_name: user.family_name.clone(),
+        nickname: user.nickname.clone(),
+        picture: user.avatar_url.clone(),
+        ..Default::default()
+    }
+}
+
+/// Send a request about a single Auth0 user, returning `false` when there is no such user.
+async fn send(request: RequestBuilder) -> Result<bool, directory::Error> {
+    let response = request.send().await?;
+    if response.status() == StatusCode::NOT_FOUND {
+        return Ok(false);
+    }
+    response.error_for_status()?;
+    Ok(true)
+}
+
+/// Push the details of `user` to their Auth0 profile, along with their email when
+/// `email_changed`, which Auth0 then asks them to verify again.
+///
+/// Users without an Auth0 account, such as those added before they signed up, are skipped.
+pub async fn push(
+    management: &Api,
+    user: &User,
+    email_changed: bool,
+) -> Result<(), directory::Error> {
+    let mut update = profile_of(user);
+    if email_changed {
+        update.email = Some(user.email.clone());
+        update.email_verified = Some(false);
+        update.verify_email = Some(true);
+    }
+
+    if !send(management.update_user(&user.id, &update)).await? {
+        tracing::debug!("User '{}' has no Auth0 account, not synced", user.id);
+    }
+    Ok(())
+}
+
+/// Block the Auth0 account of the user `user_id` as they are deleted, or unblock it as they are
+/// restored.
+pub async fn block(management: &Api, user_id: &str, blocked: bool) -> Result<(), directory::Error> {
+    let update = ProfileUpdate {
+        blocked: Some(blocked),
+        ..Default::default()
+    };
+    if !send(management.update_user(user_id, &update)).await? {
+        tracing::debug!("User '{}' has no Auth0 account, not synced", user_id);
+    }
+    Ok(())
+}
+
+/// Delete the Auth0 account of the user `user_id` as they are purged.
+pub async fn remove(management: &Api, user_id: &str) -> Result<(), directory::Error> {
+    if !send(management.delete_user(user_id)).await? {
+        tracing::debug!("User '{}' has no Auth0 account, not synced", user_id);
+    }
+    Ok(())
+}
+
+/// The fields of `user` that their Auth0 `profile` differs on. The optional details are only
+/// compared when set here, since clearing them is not synced.
+fn compare(user: &User, profile: &Profile) -> Vec<&'static str> {
+    let differs =
+        |local: &Option<String>, remote: &Option<String>| local.is_some() && local != remote;
+
+    let mut fields = Vec::new();
+    if profile.email.as_deref() != Some(user.email.as_str()) {
+        fields.push("email");
+    } else if profile.email_verified != user.email_verified {
+        fields.push("email_verified");
+    }
+    if profile.name.as_deref() != Some(user.name.as_str()) {
+        fields.push("name");
+    }
+    if differs(&user.given_name, &profile.given_name) {
+        fields.push("given_name");
+    }
+    if differs(&user.family_name, &profile.family_name) {
+        fields.push("family_name");
+    }
+    if differs(&user.nickname, &profile.nickname) {
+        fields.push("nickname");
+    }
+    if differs(&user.avatar_url, &profile.picture) {
+        fields.push("avatar_url");
+    }
+    if profile.blocked != user.deleted {
+        fields.push("deleted");
+    }
+    fields
+}
+
+/// Pull the profiles of every Auth0 user and compare them with the users here, see [`Report`].
+///
+/// With `fix`, the details here win: they are pushed to the profiles that differ, asking users
+/// whose email differs to verify it, and the accounts of deleted users are blocked. Whether an
+/// email is verified is only known to Auth0 though, so it is pulled instead. The Auth0 users
+/// missing here are only reported, they create their user themselves, possibly by claiming an
+/// existing profile.
+pub async fn reconcile(
+    database: &Database,
+    keyring: &Keyring,
+    management: &Api,
+    fix: bool,
+) -> Result<Report, anyhow::Error> {
+    let mut report = Report::default();
+
+    // Every page starts from the signup time of the last profile listed, listing the profiles
+    // of that time again.
+    let mut created_from = None;
+    let mut listed = HashSet::new();
+    loop {
+        let response = management
+            .list_users(created_from.as_deref(), PAGE_SIZE)
+            .send()
+            .await?
+            .error_for_status()?;
+        let profiles: Vec<Profile> = serde_json::from_slice(&response.bytes().await?)?;
+        let last_page = profiles.len() < PAGE_SIZE as usize;
+
+        let mut listed_any = false;
+        for profile in &profiles {
+            if !listed.insert(profile.user_id.clone()) {
+                continue;
+            }
+            listed_any = true;
+            created_from = profile.created_at.clone();
+            report.checked += 1;
+
+            let row = sqlx::query_as::<_, pii::UserRow>(r#"SELECT * FROM "user" WHERE id = $1"#)
+                .bind(&profile.user_id)
+                .fetch_optional(&database.db)
+                .await?;
+            let Some(row) = row else {
+                report.missing.push(profile.user_id.clone());
+                continue;
+            };
+
+            let user = row.open(keyring)?;
+            let fields = compare(&user, profile);
+            if fields.is_empty() {
+                continue;
+            }
+
+            if fix {
+                if fields.contains(&"email_verified") {
+                    sqlx::query(r#"UPDATE "user" SET email_verified = $2 WHERE id = $1"#)
+                        .bind(&user.id)
+                        .bind(profile.email_verified)
+                        .execute(&database.db)
+                        .await?;
+                }
+                if fields.contains(&"deleted") {
+                    block(management, &user.id, user.deleted).await?;
+                }
+                if fields
+                    .iter()
+                    .any(|field| !matches!(*field, "email_verified" | "deleted"))
+                {
+                    push(management, &user, fields.contains(&"email")).await?;
+                }
+            }
+            report.mismatches.push(Mismatch {
+                user_id: user.id,
+                fields,
+            });
+        }
+
+        // More users than a page signing up at the same time cannot be paged through.
+        if last_page || !listed_any {
+            break;
+        }
+    }
+
+    Ok(report)
+}
+
+/// Reconcile the users with their Auth0 profiles every `interval`, meant to be spawned as a
+/// background job. Mismatches are logged, and fixed as well with `fix`.
+pub async fn run(
+    database: Database,
+    keyring: Keyring,
+    management: Api,
+    fix: bool,
+    interval: Duration,
+) {
+    let mut interval = tokio::time::interval(interval);
+    loop {
+        interval.tick().await;
+
+        match reconcile(&database, &keyring, &management, fix).await {
+            Ok(report) => {
+                for mismatch in &report.mismatches {
+                    tracing::warn!(
+                        user_id = mismatch.user_id,
+                        fields = ?mismatch.fields,
+                        fixed = fix,
+                        "Auth0 profile out of sync"
+                    );
+                }
+                tracing::info!(
+                    "Auth0 reconciliation checked {} profiles, {} out of sync, {} without a user",
+                    report.checked,
+                    report.mismatches.len(),
+                    report.missing.len()
+                );
+            }
+            Err(e) => tracing::error!("Auth0 reconciliation failed: {}", e),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use common::authorization;
+
+/// The permissions required by the endpoints of this service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    UsersRead,
+    UsersWrite,
+    UsersAdmin,
+    SatellitesWrite,
+    ConnectGroupsWrite,
+    MinistriesWrite,
+    RolesWrite,
+    AuditRead,
+    WebhooksWrite,
+    /// Reading the sensitive details of other users unmasked, only ever granted by tokens.
+    PiiRead,
+}
+
+impl authorization::Permission for Permission {
+    // Users granted through a role are further restricted to their members, see `crate::scope`.
+    const PASTORAL_ROLE_GRANTS: &'static [(i32, &'static [Self])] = &[
+        (4, &[Permission::UsersWrite]),
+        (3, &[Permission::ConnectGroupsWrite]),
+        (
+            2,
+            &[
+                Permission::UsersAdmin,
+                Permission::SatellitesWrite,
+                Permission::MinistriesWrite,
+                Permission::RolesWrite,
+            ],
+        ),
+    ];
+
+    const MINISTRY_ROLE_GRANTS: &'static [(i32, &'static [Self])] =
+        &[(2, &[Permission::MinistriesWrite])];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Permission::UsersRead => "users:read",
+            Permission::UsersWrite => "users:write",
+            Permission::UsersAdmin => "users:admin",
+            Permission::SatellitesWrite => "satellites:write",
+            Permission::ConnectGroupsWrite => "connect-groups:write",
+            Permission::MinistriesWrite => "ministries:write",
+            Permission::RolesWrite => "roles:write",
+            Permission::AuditRead => "audit:read",
+            Permission::WebhooksWrite => "webhooks:write",
+            Permission::PiiRead => "pii:read",
+        }
+    }
+}
+
+pub type Error = authorization::Error<Permission>;
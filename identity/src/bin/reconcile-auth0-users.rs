@@ -0,0 +1,52 @@
+use clap::Parser;
+use common::{config::Config, crypto::Keyring, database::Database};
+use identity::auth0_sync;
+
+/// Compare the profile of every Auth0 user with their user here and report the mismatches, the
+/// same way the reconciliation job of the server does.
+#[derive(clap::Parser, Debug)]
+struct Args {
+    #[clap(flatten)]
+    config: Config,
+
+    /// Also fix the mismatches: push the details of the users to Auth0 and pull whether their
+    /// email is verified. The Auth0 users missing here are only reported.
+    #[clap(long)]
+    fix: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+    dotenvy::dotenv().ok();
+    let args = Args::parse();
+
+    let keyring = Keyring::from_config(&args.config)?;
+    let management = auth0::management::Api::init(
+        reqwest::Url::parse(&args.config.auth0_domain)?,
+        args.config.auth0_client_id.clone(),
+        args.config.auth0_client_secret.clone(),
+    )
+    .await?;
+    let database = Database::new(&args.config.database_url).await?;
+
+    let report = auth0_sync::reconcile(&database, &keyring, &management, args.fix).await?;
+    for mismatch in &report.mismatches {
+        println!(
+            "User '{}' differs on {}",
+            mismatch.user_id,
+            mismatch.fields.join(", ")
+        );
+    }
+    for user_id in &report.missing {
+        println!("Auth0 user '{}' has no user", user_id);
+    }
+    println!(
+        "Checked {} profiles, {} out of sync, {} without a user{}",
+        report.checked,
+        report.mismatches.len(),
+        report.missing.len(),
+        if args.fix { ", mismatches fixed" } else { "" }
+    );
+
+    Ok(())
+}
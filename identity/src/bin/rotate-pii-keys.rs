@@ -0,0 +1,40 @@
+use clap::Parser;
+use common::{config::Config, crypto::Keyring, database::Database};
+use identity::pii;
+
+/// Re-encrypt the sensitive details of every user with the first key of `PII_KEYS`, encrypting
+/// those still stored in plaintext along the way.
+///
+/// To rotate a key, put the new key first in `PII_KEYS` while keeping the old one, deploy, run
+/// this command and only then remove the old key.
+#[derive(clap::Parser, Debug)]
+struct Args {
+    #[clap(flatten)]
+    config: Config,
+
+    /// Also compute the blind indexes again, after changing `PII_INDEX_KEY`. This decrypts every
+    /// detail, so the old index key is not needed.
+    #[clap(long)]
+    reindex: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+    dotenvy::dotenv().ok();
+    let args = Args::parse();
+
+    let keyring = Keyring::from_config(&args.config)?;
+    if keyring.is_empty() {
+        anyhow::bail!("No key to encrypt with, set `PII_KEYS`");
+    }
+    let database = Database::new(&args.config.database_url).await?;
+
+    let rotated = pii::rotate(&database, &keyring, args.reindex).await?;
+    println!(
+        "Re-encrypted {} users with key '{}'",
+        rotated,
+        keyring.primary_key_id().unwrap_or_default()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,184 @@
+use common::{crypto::Keyring, error::Error, masking};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use sqlx::PgConnection;
+
+use crate::{
+    entities::{ClaimCandidate, ClaimChannel, ClaimDestination},
+    pii,
+};
+
+/// How long a one-time code may be used for, in minutes.
+pub const CODE_TTL_MINUTES: i64 = 10;
+
+/// The number of wrong codes after which a claim can no longer be verified.
+pub const MAX_ATTEMPTS: i32 = 5;
+
+/// The number of claims a user may start per hour, each sends a message.
+pub const MAX_CLAIMS_PER_HOUR: i64 = 5;
+
+/// A profile that may be claimed, with its personal details unmasked.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Candidate {
+    pub shadow_user_no: Option<i32>,
+    pub user_id: Option<String>,
+    pub name: String,
+    pub email: Option<String>,
+    pub phone_number: Option<String>,
+}
+
+/// A candidate as selected, the phone number of users being encrypted.
+#[derive(sqlx::FromRow)]
+struct CandidateRow {
+    #[sqlx(flatten)]
+    candidate: Candidate,
+    phone_number_encrypted: Option<Vec<u8>>,
+}
+
+impl Candidate {
+    /// The email or phone number a one-time code is sent to through `channel`, if the profile has
+    /// one.
+    pub fn destination(&self, channel: ClaimChannel) -> Option<&str> {
+        match channel {
+            ClaimChannel::Email => self.email.as_deref(),
+            ClaimChannel::Phone => self.phone_number.as_deref(),
+        }
+        .filter(|destination| !destination.trim().is_empty())
+    }
+
+    pub fn to_masked(&self) -> ClaimCandidate {
+        ClaimCandidate {
+            shadow_user_no: self.shadow_user_no,
+            user_id: self.user_id.clone(),
+            name: mask_name(&self.name),
+            destinations: [ClaimChannel::Email, ClaimChannel::Phone]
+                .into_iter()
+                .filter_map(|channel| {
+                    self.destination(channel)
+                        .map(|destination| ClaimDestination {
+                            channel,
+                            destination: mask(channel, destination),
+                        })
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Find the profiles that the user identified by `user_id` may claim: the unclaimed shadow users
+/// and the soft-deleted users sharing their email or phone number, leaving out merged duplicates.
+pub async fn find_candidates(
+    conn: &mut PgConnection,
+    keyring: &Keyring,
+    user_id: &str,
+    email: Option<&str>,
+    phone_number: Option<&str>,
+) -> Result<Vec<Candidate>, Error> {
+    let phone_number_index = match phone_number {
+        Some(phone_number) => pii::phone_number_index(keyring, phone_number)?,
+        None => None,
+    };
+
+    let rows = sqlx::query_as::<_, CandidateRow>(
+        r#"
+        SELECT
+            s.no AS shadow_user_no,
+            NULL::TEXT AS user_id,
+            s.name,
+            s.email,
+            s.phone_number,
+            NULL::BYTEA AS phone_number_encrypted
+        FROM shadow_user s
+        WHERE
+            s.claimed_by IS NULL
+            AND s.deleted IS NOT TRUE
+            AND (
+                lower(s.email) = lower($1)
+                OR normalise_phone_number(s.phone_number) = normalise_phone_number($2)
+            )
+        UNION ALL
+        SELECT
+            NULL AS shadow_user_no,
+            u.id AS user_id,
+            u.name,
+            u.email,
+            u.phone_number,
+            u.phone_number_encrypted
+        FROM "user" u
+        WHERE
+            u.deleted IS TRUE
+            AND u.id != $3
+            AND NOT EXISTS (SELECT 1 FROM user_merge WHERE merged_user_id = u.id)
+            AND (lower(u.email) = lower($1) OR u.phone_number_index = $4)
+        "#,
+    )
+    .bind(email)
+    .bind(phone_number)
+    .bind(user_id)
+    .bind(phone_number_index)
+    .fetch_all(conn)
+    .await?;
+
+    let mut candidates = Vec::with_capacity(rows.len());
+    for row in rows {
+        let mut candidate = row.candidate;
+        candidate.phone_number = pii::open_phone_number(
+            keyring,
+            row.phone_number_encrypted.as_deref(),
+            candidate.phone_number,
+        )?;
+        candidates.push(candidate);
+    }
+
+    Ok(candidates)
+}
+
+/// Generate a random six-digit one-time code.
+pub fn generate_code() -> String {
+    format!("{:06}", rand::thread_rng().gen_range(0..1_000_000))
+}
+
+/// Hash a one-time code along with the id of its claim, so that equal codes of different claims
+/// hash differently.
+pub fn hash_code(claim_id: &str, code: &str) -> String {
+    format!(
+        "{:x}",
+        Sha256::new()
+            .chain_update(claim_id)
+            .chain_update(":")
+            .chain_update(code.trim())
+            .finalize()
+    )
+}
+
+pub fn mask(channel: ClaimChannel, destination: &str) -> String {
+    match channel {
+        ClaimChannel::Email => mask_email(destination),
+        ClaimChannel::Phone => mask_phone_number(destination),
+    }
+}
+
+/// Keep the first character of the local part and the domain (e.g. `a****@example.com`).
+pub fn mask_email(email: &str) -> String {
+    match email.split_once('@') {
+        Some((local, domain)) => format!("{}@{}", mask_word(local), domain),
+        None => mask_word(email),
+    }
+}
+
+/// Keep the last three digits (e.g. `*******789`).
+pub fn mask_phone_number(phone_number: &str) -> String {
+    masking::last(phone_number, 3)
+}
+
+/// Keep the first character of every word (e.g. `A**** T**`).
+pub fn mask_name(name: &str) -> String {
+    masking::initials(name)
+}
+
+fn mask_word(word: &str) -> String {
+    word.chars()
+        .enumerate()
+        .map(|(i, c)| if i == 0 { c } else { '*' })
+        .collect()
+}
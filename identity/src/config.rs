@@ -1,44 +1,40 @@
-/// The configuration parameters for the application.
+/// The configuration parameters of the identity service: the ones every service shares, along
+/// with those of its notifier and background jobs.
 ///
-/// These can either be passed on the command line, or pulled from environment variables.
-/// The latter is preferred as environment variables are one of the recommended ways to
-/// get configuration from Kubernetes Secrets in deployment.
-///
-/// For development convenience, these can also be read from a `.env` file in the working
-/// directory where the application is started.
-///
-/// See `.env.example` in the repository root for details.
+/// See `.env.example` in the `identity` directory for details.
 #[derive(clap::Parser, Debug, Clone)]
 pub struct Config {
-    /// The port number that the application should listen on.
-    #[clap(long, env, default_value = "0.0.0.0")]
-    pub address: String,
+    #[clap(flatten)]
+    pub common: common::config::Config,
 
-    /// The port number that the application should listen on.
-    #[clap(long, env, default_value_t = 8000)]
-    pub port: u16,
+    /// The URL of the webhook delivering the messages sent to members, such as one-time codes.
+    #[clap(long, env)]
+    pub notifier_webhook_url: Option<String>,
 
-    /// The OpenAPI URL Address.
-    #[clap(long, env, default_value = "http://0.0.0.0:8000")]
-    pub oai_address: String,
+    /// How often potential duplicate users are detected, `0` disables it.
+    #[clap(long, env, default_value_t = 24 * 60)]
+    pub duplicate_detection_interval_minutes: u64,
 
-    /// The connection URL for the Postgres database this application should use.
-    #[clap(long, env)]
-    pub database_url: String,
+    /// How often the users whose erasure is due are erased, `0` disables it.
+    #[clap(long, env, default_value_t = 60)]
+    pub erasure_interval_minutes: u64,
 
-    /// The domain for Auth0.
-    #[clap(long, env)]
-    pub auth0_domain: String,
+    /// How often the users are reconciled with their Auth0 profiles, `0` disables it.
+    #[clap(long, env, default_value_t = 24 * 60)]
+    pub auth0_reconciliation_interval_minutes: u64,
 
-    /// The client id for Auth0 app.
+    /// Also fix the mismatches found by the Auth0 reconciliation, which are only reported
+    /// otherwise.
     #[clap(long, env)]
-    pub auth0_client_id: String,
+    pub auth0_reconciliation_fix: bool,
 
-    /// The client secret for Auth0 app.
-    #[clap(long, env)]
-    pub auth0_client_secret: String,
+    /// How often the events of the outbox are delivered to the webhooks, `0` disables it.
+    #[clap(long, env, default_value_t = 10)]
+    pub webhook_dispatch_interval_seconds: u64,
+}
 
-    /// The signing public key for Auth0 app in PEM format.
-    #[clap(long, env)]
-    pub auth0_public_key: String,
+impl AsRef<common::config::Config> for Config {
+    fn as_ref(&self) -> &common::config::Config {
+        &self.common
+    }
 }
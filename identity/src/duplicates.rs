@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use common::database::Database;
+use sqlx::PgConnection;
+
+use crate::reconcile::{
+    EMAIL_WEIGHT, IC_NUMBER_WEIGHT, NAME_SIMILARITY_THRESHOLD, NAME_WEIGHT, PHONE_NUMBER_WEIGHT,
+};
+
+/// Pairs scoring below this are not reported, so that users merely sharing a similar name are
+/// left out unless the names are nearly identical.
+pub const MIN_SCORE: f64 = 0.5;
+
+/// Find the pairs of users that are likely the same person, comparing the blind indexes of their
+/// phone and IC numbers, normalised emails, and the similarity of their names. They are scored
+/// the same way as the matches of a shadow user.
+///
+/// New pairs are recorded as pending and known ones have their score refreshed, keeping their
+/// review. Pending pairs that are no longer found are removed. Returns the number of pairs
+/// found.
+pub async fn detect(conn: &mut PgConnection) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        WITH u AS (
+            SELECT
+                id,
+                name,
+                phone_number_index AS phone_number,
+                ic_number_index AS ic_number,
+                normalise_email(email) AS email
+            FROM "user"
+            WHERE deleted IS FALSE
+        ),
+        pair AS (
+            SELECT a.id AS user_id, b.id AS duplicate_user_id
+            FROM u a JOIN u b ON a.phone_number = b.phone_number AND a.id < b.id
+            UNION
+            SELECT a.id, b.id
+            FROM u a JOIN u b ON a.ic_number = b.ic_number AND a.id < b.id
+            UNION
+            SELECT a.id, b.id
+            FROM u a JOIN u b ON a.email = b.email AND a.id < b.id
+            UNION
+            -- Joined on the table itself so that `user_name_trgm_idx` can be used.
+            SELECT a.id, b.id
+            FROM "user" a JOIN "user" b ON a.name % b.name AND a.id < b.id
+            WHERE a.deleted IS FALSE AND b.deleted IS FALSE
+        ),
+        evidence AS (
+            SELECT
+                p.user_id,
+                p.duplicate_user_id,
+                COALESCE(a.ic_number = b.ic_number, FALSE) AS ic_number_matched,
+                COALESCE(a.email = b.email, FALSE) AS email_matched,
+                COALESCE(a.phone_number = b.phone_number, FALSE) AS phone_number_matched,
+                CASE
+                    WHEN similarity(a.name, b.name) >= $5 THEN similarity(a.name, b.name)
+                    ELSE 0
+                END AS name_similarity
+            FROM pair p
+            JOIN u a ON a.id = p.user_id
+            JOIN u b ON b.id = p.duplicate_user_id
+        ),
+        scored AS (
+            SELECT
+                user_id,
+                duplicate_user_id,
+                1 - (1 - $1 * ic_number_matched::INTEGER)
+                  * (1 - $2 * email_matched::INTEGER)
+                  * (1 - $3 * phone_number_matched::INTEGER)
+                  * (1 - $4 * name_similarity) AS score,
+                array_remove(ARRAY[
+                    CASE WHEN ic_number_matched THEN 'ic_number'::matched_attribute END,
+                    CASE WHEN email_matched THEN 'email'::matched_attribute END,
+                    CASE WHEN phone_number_matched THEN 'phone_number'::matched_attribute END,
+                    CASE WHEN name_similarity > 0 THEN 'name'::matched_attribute END
+                ], NULL) AS matched_on
+            FROM evidence
+        ),
+        stale AS (
+            DELETE FROM user_duplicate d
+            WHERE d.status = 'pending' AND NOT EXISTS (
+                SELECT 1 FROM scored s
+                WHERE
+                    s.user_id = d.user_id
+                    AND s.duplicate_user_id = d.duplicate_user_id
+                    AND s.score >= $6
+            )
+        )
+        INSERT INTO user_duplicate (user_id, duplicate_user_id, score, matched_on)
+        SELECT user_id, duplicate_user_id, score, matched_on FROM scored WHERE score >= $6
+        ON CONFLICT (user_id, duplicate_user_id) DO UPDATE SET
+            score       = EXCLUDED.score,
+            matched_on  = EXCLUDED.matched_on,
+            detected_at = NOW()
+        "#,
+    )
+    .bind(IC_NUMBER_WEIGHT)
+    .bind(EMAIL_WEIGHT)
+    .bind(PHONE_NUMBER_WEIGHT)
+    .bind(NAME_WEIGHT)
+    .bind(NAME_SIMILARITY_THRESHOLD)
+    .bind(MIN_SCORE)
+    .execute(conn)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Run the duplicate detection every `interval`, meant to be spawned as a background job.
+pub async fn run(database: Database, interval: Duration) {
+    let mut interval = tokio::time::interval(interval);
+    loop {
+        interval.tick().await;
+
+        let result = match database.db.acquire().await {
+            Ok(mut conn) => detect(&mut conn).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(found) => tracing::info!("Duplicate detection found {} pairs of users", found),
+            Err(e) => tracing::error!("Duplicate detection failed: {}", e),
+        }
+    }
+}
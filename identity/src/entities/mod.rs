@@ -30,10 +30,61 @@ pub struct UserRelationship {
     pub source_user_id: String,
     pub destination_user_id: String,
     pub relationship: String,
+    /// Whether the source user may pick up the destination user, e.g. from the kids ministry.
+    pub can_pick_up: bool,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// A relationship users may have, stored along with its inverse, e.g. `parent` and `child`.
+#[derive(Debug, Clone, Deserialize, Serialize, Object, sqlx::FromRow)]
+pub struct RelationshipType {
+    pub name: String,
+    /// The relationship the destination user holds back, itself when symmetric.
+    pub inverse: String,
+    pub description: String,
+}
+
+/// Users living together, sharing an address.
+#[derive(Debug, Clone, Deserialize, Serialize, Object, sqlx::FromRow)]
+pub struct Household {
+    pub id: String,
+    pub name: String,
+    pub address: Option<Address>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A member of a household.
+#[derive(Debug, Clone, Deserialize, Serialize, Object, sqlx::FromRow)]
+pub struct HouseholdMember {
+    pub user_id: String,
+    pub name: String,
+    /// When the user joined the household.
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A user reached by following relationships.
+#[derive(Debug, Clone, Deserialize, Serialize, Object, sqlx::FromRow)]
+pub struct FamilyMember {
+    pub user_id: String,
+    pub name: String,
+    /// How many relationships away the user is, 0 for the user the family is retrieved for.
+    pub depth: i32,
+    /// The households the user belongs to.
+    pub household_ids: Vec<String>,
+}
+
+/// A user allowed to pick up a child, along with how to reach them.
+#[derive(Debug, Clone, Deserialize, Serialize, Object, sqlx::FromRow)]
+pub struct PickUp {
+    pub user_id: String,
+    pub name: String,
+    pub phone_number: Option<String>,
+    /// Relationship of the user to the child, e.g. `parent`.
+    pub relationship: String,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, Object, sqlx::FromRow)]
 pub struct Ministry {
     pub id: String,
@@ -110,6 +161,329 @@ pub struct MinistryDepartment {
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// A leader overseen by a more senior leader, e.g. a CGL by their coach.
+#[derive(Debug, Clone, Deserialize, Serialize, Object, sqlx::FromRow)]
+pub struct UserOversight {
+    pub user_id: String,
+    pub overseer_id: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A user reached by following the lines of pastoral care, from members to the leaders of their
+/// connect groups and from leaders to their overseers, or the other way around.
+#[derive(Debug, Clone, Deserialize, Serialize, Object, sqlx::FromRow)]
+pub struct PastoralLink {
+    pub user_id: String,
+    pub name: String,
+    /// The most senior pastoral role held, absent for users in no connect group.
+    pub role_id: Option<String>,
+    pub role_name: Option<String>,
+    /// The connect group the last line of care runs through, absent for oversight.
+    pub connect_group_id: Option<String>,
+    /// How many lines of care away the user is, 1 for direct leaders or members.
+    pub depth: i32,
+}
+
+/// A period a user was a member of a connect group with the same role.
+#[derive(Debug, Clone, Deserialize, Serialize, Object, sqlx::FromRow)]
+pub struct ConnectGroupMembership {
+    pub id: i64,
+    pub user_id: String,
+    pub user_name: String,
+    pub connect_group_id: String,
+    pub connect_group_name: Option<String>,
+    /// The id of the pastoral role held.
+    pub user_role: String,
+    /// Absent once the pastoral role is deleted.
+    pub user_role_name: Option<String>,
+    /// Absent for memberships that predate the history.
+    pub joined_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Absent while ongoing. A role change ends the period at the time the next one starts.
+    pub left_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub left_reason: Option<String>,
+}
+
+/// A period a user was a member of a ministry with the same role.
+#[derive(Debug, Clone, Deserialize, Serialize, Object, sqlx::FromRow)]
+pub struct MinistryMembership {
+    pub id: i64,
+    pub user_id: String,
+    pub user_name: String,
+    pub ministry_id: String,
+    pub ministry_name: String,
+    /// The id of the ministry role held.
+    pub user_role: String,
+    /// Absent once the ministry role is deleted.
+    pub user_role_name: Option<String>,
+    /// Absent for memberships that predate the history.
+    pub joined_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Absent while ongoing. A role change ends the period at the time the next one starts.
+    pub left_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub left_reason: Option<String>,
+}
+
+/// A member imported from the legacy spreadsheets, pending to be merged into a user.
+#[derive(Debug, Clone, Deserialize, Serialize, Object, sqlx::FromRow)]
+pub struct ShadowUser {
+    pub no: i32,
+    pub name: String,
+    pub email: Option<String>,
+    pub username: Option<String>,
+    pub given_name: Option<String>,
+    pub family_name: Option<String>,
+    pub gender: Option<Gender>,
+    pub ic_number: Option<String>,
+    pub phone_number: String,
+    pub nickname: Option<String>,
+    pub avatar_url: Option<String>,
+    pub address: Option<Address>,
+    pub date_of_birth: Option<chrono::DateTime<chrono::Utc>>,
+    /// Free-text connect group (e.g. `12A` or the name of the connect group).
+    pub cg: Option<String>,
+    /// Free-text pastoral role (e.g. `CGL`).
+    pub pastoral_status: Option<String>,
+    /// Free-text ministries, separated by `,`, `;` or `/`.
+    pub ministry: Option<String>,
+    pub deleted: Option<bool>,
+    pub claimed_by: Option<String>,
+    pub claimed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A user that a shadow user likely refers to.
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+pub struct ShadowUserMatch {
+    pub user: User,
+    /// Confidence that both refer to the same person, from 0 to 1.
+    pub score: f64,
+    pub matched_on: Vec<MatchedAttribute>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize, Enum, sqlx::Type)]
+#[sqlx(type_name = "matched_attribute", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[oai(rename_all = "snake_case")]
+pub enum MatchedAttribute {
+    PhoneNumber,
+    Email,
+    IcNumber,
+    Name,
+}
+
+impl ::sqlx::postgres::PgHasArrayType for MatchedAttribute {
+    fn array_type_info() -> ::sqlx::postgres::PgTypeInfo {
+        ::sqlx::postgres::PgTypeInfo::with_name("_matched_attribute")
+    }
+}
+
+/// What the free-text columns of a shadow user resolve to.
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+pub struct ShadowUserResolution {
+    pub connect_group: Option<ConnectGroup>,
+    /// The role named by `pastoral_status`, or the most junior pastoral role when it is empty.
+    pub pastoral_role: Option<PastoralRole>,
+    pub ministries: Vec<Ministry>,
+    /// The most junior ministry role, which the ministries are joined with.
+    pub ministry_role: Option<MinistryRole>,
+    /// The values that matched nothing, or more than one thing.
+    pub unresolved: Vec<UnresolvedValue>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+pub struct UnresolvedValue {
+    /// The column of the shadow user the value comes from (e.g. `cg`).
+    pub field: String,
+    pub value: String,
+}
+
+/// A profile that a new user may claim instead of starting from a blank one, either a shadow
+/// user or a soft-deleted user. Personal details are masked until the claim is verified.
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+pub struct ClaimCandidate {
+    pub shadow_user_no: Option<i32>,
+    pub user_id: Option<String>,
+    /// The masked name of the profile (e.g. `A**** T**`).
+    pub name: String,
+    /// Where a one-time code can be sent to prove the profile belongs to the user.
+    pub destinations: Vec<ClaimDestination>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+pub struct ClaimDestination {
+    pub channel: ClaimChannel,
+    /// The masked email or phone number (e.g. `a****@example.com`).
+    pub destination: String,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize, Enum, sqlx::Type)]
+#[sqlx(type_name = "claim_channel", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[oai(rename_all = "lowercase")]
+pub enum ClaimChannel {
+    Email,
+    Phone,
+}
+
+/// A pending or verified claim of a profile, the one-time code is never returned.
+#[derive(Debug, Clone, Deserialize, Serialize, Object, sqlx::FromRow)]
+pub struct UserClaim {
+    pub id: String,
+    pub user_id: String,
+    pub shadow_user_no: Option<i32>,
+    pub claimed_user_id: Option<String>,
+    pub channel: ClaimChannel,
+    /// The masked email or phone number the one-time code was sent to.
+    pub destination: String,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub verified_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A duplicate user merged into another one. Rows are moved to the kept user unless it already
+/// had an equivalent one, in which case they are dropped.
+#[derive(Debug, Clone, Deserialize, Serialize, Object, sqlx::FromRow)]
+pub struct UserMerge {
+    pub id: String,
+    /// The user that was kept.
+    pub user_id: String,
+    /// The user that was merged and soft-deleted.
+    pub merged_user_id: String,
+    pub merged_by: String,
+    pub connect_groups_moved: i32,
+    /// Dropped because the kept user was already in the connect group, the most senior of the
+    /// two roles is kept.
+    pub connect_groups_dropped: i32,
+    pub ministries_moved: i32,
+    /// Dropped because the kept user was already in the ministry, the most senior of the two
+    /// roles is kept.
+    pub ministries_dropped: i32,
+    pub relationships_moved: i32,
+    pub relationships_dropped: i32,
+    pub attendance_moved: i32,
+    pub attendance_dropped: i32,
+    pub form_data_moved: i32,
+    pub form_data_dropped: i32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A request to erase the personal data of a user, kept once carried out as a record of the
+/// erasure.
+#[derive(Debug, Clone, Deserialize, Serialize, Object, sqlx::FromRow)]
+pub struct UserErasure {
+    pub user_id: String,
+    pub requested_by: String,
+    pub reason: Option<String>,
+    /// The erasure may be cancelled until then.
+    pub scheduled_at: chrono::DateTime<chrono::Utc>,
+    /// Unset while the erasure is pending.
+    pub erased_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Two users that are likely the same person, awaiting review unless dismissed or merged.
+#[derive(Debug, Clone, Deserialize, Serialize, Object, sqlx::FromRow)]
+pub struct UserDuplicate {
+    pub user_id: String,
+    pub user_name: String,
+    pub duplicate_user_id: String,
+    pub duplicate_user_name: String,
+    /// Confidence that both users are the same person, from 0 to 1.
+    pub score: f64,
+    pub matched_on: Vec<MatchedAttribute>,
+    pub status: UserDuplicateStatus,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// When the pair was last found by the duplicate detection.
+    pub detected_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize, Enum, sqlx::Type)]
+#[sqlx(type_name = "user_duplicate_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[oai(rename_all = "lowercase")]
+pub enum UserDuplicateStatus {
+    Pending,
+    Dismissed,
+    /// Set when the users are merged through `/users/:id/merge`.
+    Merged,
+}
+
+/// A change made to a row of an audited table, recorded in the same transaction as the change.
+#[derive(Debug, Clone, Deserialize, Serialize, Object, sqlx::FromRow)]
+pub struct AuditLog {
+    pub id: i64,
+    /// Subject of the access token that made the change, absent for changes made outside of
+    /// the API.
+    pub actor: Option<String>,
+    pub request_id: Option<String>,
+    /// Name of the changed table (e.g. `user`, `user_connect_group`).
+    pub resource: String,
+    /// Primary key of the changed row, the columns of a composite key are joined by `/`.
+    pub resource_id: String,
+    pub action: AuditAction,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    /// The columns changed by an update with their new values.
+    pub changes: Option<serde_json::Value>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize, Enum, sqlx::Type)]
+#[sqlx(type_name = "audit_action", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[oai(rename_all = "lowercase")]
+pub enum AuditAction {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// An endpoint subscribed to domain events, its secret is only returned when it is created.
+#[derive(Debug, Clone, Deserialize, Serialize, Object, sqlx::FromRow)]
+pub struct Webhook {
+    pub id: String,
+    pub url: String,
+    /// Types of the events delivered (e.g. `connect_group.users_joined`), every type when empty.
+    pub event_types: Vec<String>,
+    pub description: Option<String>,
+    pub active: bool,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// The delivery of a domain event to a webhook.
+#[derive(Debug, Clone, Deserialize, Serialize, Object, sqlx::FromRow)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub webhook_id: String,
+    pub event_id: i64,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i32,
+    /// When the delivery is attempted next while pending.
+    pub next_attempt_at: chrono::DateTime<chrono::Utc>,
+    pub last_attempt_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Status of the last response, absent when the webhook could not be reached.
+    pub last_response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize, Enum, sqlx::Type)]
+#[sqlx(type_name = "webhook_delivery_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[oai(rename_all = "lowercase")]
+pub enum WebhookDeliveryStatus {
+    Pending,
+    Delivered,
+    /// Failed every attempt, only retried on request.
+    Dead,
+}
+
 #[derive(Debug, Copy, Clone, Deserialize, Serialize, Enum, sqlx::Type)]
 #[sqlx(type_name = "gender", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
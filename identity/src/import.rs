@@ -0,0 +1,119 @@
+use std::io::Cursor;
+
+use calamine::{DataType, Reader, Xlsx};
+use chrono::{Datelike, Duration, NaiveDate};
+
+/// The rows of an imported spreadsheet, every cell read as text.
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// The index of the column named `header`, ignoring case and surrounding whitespace.
+    pub fn column(&self, header: &str) -> Option<usize> {
+        self.headers
+            .iter()
+            .position(|h| h.trim().eq_ignore_ascii_case(header.trim()))
+    }
+}
+
+/// Read the first worksheet of an XLSX file, or a CSV file delimited by commas, semicolons or
+/// tabs, whichever the header line has most of.
+pub fn read_table(content: &[u8]) -> Result<Table, String> {
+    // XLSX files are ZIP archives.
+    if content.starts_with(b"PK\x03\x04") {
+        read_xlsx(content)
+    } else {
+        read_csv(content)
+    }
+}
+
+fn read_csv(content: &[u8]) -> Result<Table, String> {
+    let header_line = content.split(|b| *b == b'\n').next().unwrap_or_default();
+    let delimiter = [b',', b';', b'\t']
+        .into_iter()
+        .max_by_key(|d| header_line.iter().filter(|b| *b == d).count())
+        .unwrap_or(b',');
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_reader(content);
+
+    let headers = reader
+        .headers()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|h| h.trim_start_matches('\u{feff}').to_string())
+        .collect();
+    let rows = reader
+        .records()
+        .map(|record| {
+            record
+                .map(|record| record.iter().map(str::to_string).collect())
+                .map_err(|e| e.to_string())
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Table { headers, rows })
+}
+
+fn read_xlsx(content: &[u8]) -> Result<Table, String> {
+    let mut workbook = Xlsx::new(Cursor::new(content)).map_err(|e| e.to_string())?;
+    let range = workbook
+        .worksheet_range_at(0)
+        .ok_or("The workbook has no worksheet")?
+        .map_err(|e| e.to_string())?;
+
+    let mut rows = range.rows().map(|row| row.iter().map(cell).collect());
+    Ok(Table {
+        headers: rows.next().unwrap_or_default(),
+        rows: rows.collect(),
+    })
+}
+
+fn cell(cell: &DataType) -> String {
+    match cell {
+        DataType::Empty => String::new(),
+        DataType::String(s) | DataType::DateTimeIso(s) => s.clone(),
+        DataType::Float(f) if f.fract() == 0.0 => format!("{}", *f as i64),
+        // Excel counts days from 1899-12-30.
+        DataType::DateTime(days) => (NaiveDate::from_ymd_opt(1899, 12, 30).unwrap()
+            + Duration::days(*days as i64))
+        .to_string(),
+        _ => cell.to_string(),
+    }
+}
+
+/// Parse a date written day first (e.g. `11.6.1989` or `11/06/1989`), or in ISO 8601.
+pub fn parse_date(value: &str) -> Option<NaiveDate> {
+    let value = value.trim();
+    ["%Y-%m-%d", "%d.%m.%Y", "%d/%m/%Y", "%d-%m-%Y"]
+        .into_iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+        // Two-digit years would otherwise be read as the first century.
+        .filter(|date| date.year() >= 1900)
+}
+
+/// Write a phone number in E.164, numbers without a country code are taken as Malaysian
+/// (e.g. `012-345 6789` becomes `+60123456789`).
+pub fn to_e164(value: &str) -> Option<String> {
+    let digits = value
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect::<String>();
+
+    let number = if value.trim_start().starts_with('+') || digits.starts_with("60") {
+        digits
+    } else if let Some(national) = digits.strip_prefix('0') {
+        format!("60{}", national)
+    } else {
+        format!("60{}", digits)
+    };
+
+    (10..=15)
+        .contains(&number.len())
+        .then(|| format!("+{}", number))
+}
@@ -0,0 +1,18 @@
+//! The identity service, built as a library so that its routes can be served by the end-to-end
+//! tests as well as by the binary.
+
+pub mod auth0_sync;
+pub mod authorization;
+pub mod config;
+pub mod duplicates;
+pub mod entities;
+pub mod notifier;
+pub mod pii;
+pub mod privacy;
+pub mod routes;
+mod claim;
+mod import;
+mod membership;
+mod pastoral_care;
+mod reconcile;
+mod scope;
@@ -1,99 +1,65 @@
-use std::sync::Arc;
+use std::time::Duration;
 
-use clap::Parser;
-use database::Database;
-use poem::{
-    middleware::{Cors, Tracing},
-    EndpointExt, Result, Route,
+use common::{crypto::Keyring, outbox, server::Server};
+use identity::{
+    auth0_sync, config::Config, duplicates, notifier::WebhookNotifier, privacy, routes,
 };
-use poem_openapi::{payload, LicenseObject, OpenApiService};
-
-use crate::config::Config;
-
-mod auth;
-mod config;
-mod database;
-mod entities;
-mod error;
-mod routes;
 
 #[tokio::main]
 async fn main() -> Result<(), anyhow::Error> {
-    // This returns an error if the `.env` file doesn't exist, but that's not what we want
-    // since we're not going to use a `.env` file if we deploy this application.
-    dotenvy::dotenv().ok();
-
-    // Set up tracing
-    if std::env::var_os("RUST_LOG").is_none() {
-        #[cfg(debug_assertions)]
-        std::env::set_var("RUST_LOG", "poem=debug,identity=debug,auth0=info,sqlx=info");
-        #[cfg(not(debug_assertions))]
-        std::env::set_var("RUST_LOG", "poem=info,identity=info,auth0=info,sqlx=info");
-    }
-    tracing_subscriber::fmt::init();
-
-    // Parse configuration
-    let config = Config::parse();
-
-    // Make a connection to the database
-    let database = Database::new(&config.database_url).await?;
+    Server::<Config>::with_config("FGACYC Identity", "0.0.1")
+        .log_target("identity")
+        .job(|database, config| async move {
+            let minutes = config.duplicate_detection_interval_minutes;
+            if minutes > 0 {
+                duplicates::run(database, Duration::from_secs(minutes * 60)).await;
+            }
+        })
+        .job(|database, config| async move {
+            let minutes = config.erasure_interval_minutes;
+            if minutes > 0 {
+                privacy::run(database, Duration::from_secs(minutes * 60)).await;
+            }
+        })
+        .job(|database, config| async move {
+            let minutes = config.auth0_reconciliation_interval_minutes;
+            if minutes > 0 {
+                // Already checked by the server before spawning its jobs.
+                let keyring = Keyring::from_config(&config.common).expect("PII keys are valid");
+                let management = auth0::management::Api::init(
+                    reqwest::Url::parse(&config.common.auth0_domain)
+                        .expect("Auth0 domain is valid"),
+                    config.common.auth0_client_id,
+                    config.common.auth0_client_secret,
+                )
+                .await
+                .expect("failed to initialise the Auth0 management client");
+                let interval = Duration::from_secs(minutes * 60);
+                let fix = config.auth0_reconciliation_fix;
+                auth0_sync::run(database, keyring, management, fix, interval).await;
+            }
+        })
+        // The event service publishes to the same outbox, its events are delivered from here.
+        .job(|database, config| async move {
+            let seconds = config.webhook_dispatch_interval_seconds;
+            if seconds > 0 {
+                outbox::run(database, Duration::from_secs(seconds)).await;
+            }
+        })
+        .run(|management, config| {
+            // Every user carries personal details, which could be neither stored nor read.
+            Keyring::from_config(&config.common)?.ensure_keys()?;
 
-    // Make the api clients for Auth0
-    let auth = auth0::authentication::Api::init(
-        reqwest::Url::parse(&config.auth0_domain)?,
-        auth0::authentication::AuthenticationMethod::ClientIDClientSecret(
-            config.auth0_client_id.clone(),
-            config.auth0_client_secret.clone(),
-        ),
-    );
-    let management = auth0::management::Api::init(
-        reqwest::Url::parse(&config.auth0_domain)?,
-        config.auth0_client_id.clone(),
-        config.auth0_client_secret.clone(),
-    )
-    .await?;
+            // The one-time codes sent to members would be lost otherwise, see `LogNotifier`.
+            let notifier_url = config
+                .notifier_webhook_url
+                .clone()
+                .filter(|url| !url.is_empty())
+                .ok_or_else(|| {
+                    anyhow::anyhow!("`NOTIFIER_WEBHOOK_URL` must be set to deliver messages")
+                })?;
 
-    let service = OpenApiService::new(routes::Routes::new(management), "FGACYC Identity", "0.0.1")
-        .server(&config.oai_address)
-        .license(LicenseObject::new("MIT").url(format!("{}/license", config.oai_address)));
-    let rapidoc = service.rapidoc();
-    let specs = service.spec();
-
-    let routes = Route::new()
-        .nest("/", service)
-        .nest("/docs", rapidoc)
-        .at(
-            "/ping",
-            poem::endpoint::make_sync(move |_| payload::PlainText("PONG")),
-        )
-        .at(
-            "/specs",
-            poem::endpoint::make_sync(move |_| {
-                payload::Json(serde_json::from_str::<serde_json::Value>(&specs.clone()).unwrap())
-            }),
-        )
-        .at(
-            "/license",
-            poem::endpoint::make_sync(move |_| payload::PlainText(include_str!("../../LICENSE"))),
-        )
-        .with(Tracing)
-        .with(Cors::new())
-        .data(database)
-        .data(Arc::new(auth))
-        .data(config.clone());
-
-    #[cfg(not(feature = "lambda"))]
-    poem::Server::new(poem::listener::TcpListener::bind(&format!(
-        "{}:{}",
-        config.address, config.port
-    )))
-    .run(routes)
-    .await?;
-
-    #[cfg(feature = "lambda")]
-    poem_lambda::run(routes)
+            Ok(routes::Routes::new(management).with_notifier(WebhookNotifier::new(notifier_url)))
+        })
         .await
-        .map_err(|e| anyhow::anyhow!("Failed running on lambda: {:?}", e))?;
-
-    Ok(())
 }
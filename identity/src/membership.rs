@@ -0,0 +1,16 @@
+use sqlx::PgConnection;
+
+/// Record why the memberships removed in the rest of the transaction are left, the history of
+/// connect group and ministry memberships is kept by triggers reading it from the
+/// transaction-local `membership.left_reason` setting.
+pub async fn set_left_reason(
+    conn: &mut PgConnection,
+    reason: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("SELECT set_config('membership.left_reason', $1, TRUE)")
+        .bind(reason.unwrap_or_default())
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use serde::Serialize;
+
+/// Where a message is delivered to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "channel", content = "to", rename_all = "lowercase")]
+pub enum Recipient {
+    Email(String),
+    Phone(String),
+}
+
+/// A message sent to a member outside of the API, such as a one-time code.
+#[derive(Debug, Clone, Serialize)]
+pub struct Message {
+    #[serde(flatten)]
+    pub recipient: Recipient,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Delivers messages to members, by email, SMS or whatever a deployment has available.
+///
+/// The routes only log messages through [`LogNotifier`] unless another notifier is configured with
+/// [`crate::routes::Routes::with_notifier`], which the service requires to start.
+#[async_trait::async_trait]
+pub trait Notifier: Send + Sync {
+    async fn send(&self, message: &Message) -> Result<(), anyhow::Error>;
+}
+
+/// Logs that messages were sent instead of delivering them. Their body is left out, it holds the
+/// one-time codes that anyone reading the logs could otherwise use.
+pub struct LogNotifier;
+
+#[async_trait::async_trait]
+impl Notifier for LogNotifier {
+    async fn send(&self, message: &Message) -> Result<(), anyhow::Error> {
+        tracing::warn!(
+            recipient = ?message.recipient,
+            subject = message.subject,
+            "No notifier configured, message not delivered"
+        );
+        Ok(())
+    }
+}
+
+/// Posts messages as JSON to a webhook, which is in charge of delivering them, e.g.
+/// `{"channel": "email", "to": "alice@example.com", "subject": "...", "body": "..."}`.
+pub struct WebhookNotifier {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Notifier for WebhookNotifier {
+    async fn send(&self, message: &Message) -> Result<(), anyhow::Error> {
+        self.client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(message)?)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
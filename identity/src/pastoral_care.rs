@@ -0,0 +1,99 @@
+use sqlx::{Postgres, QueryBuilder};
+
+use crate::scope::CONNECT_GROUP_LEADER_WEIGHT;
+
+/// The farthest a chain of pastoral care is followed.
+pub const MAX_DEPTH: i32 = 10;
+
+/// The direct lines of pastoral care, as a `care (leader_id, user_id, connect_group_id)` common
+/// table expression. The leaders of an open connect group care for its members holding a more
+/// junior role, and overseers (e.g. coaches) for the leaders they oversee, in which case the
+/// connect group is NULL.
+fn care() -> String {
+    format!(
+        r#"
+        care (leader_id, user_id, connect_group_id) AS (
+            SELECT o.overseer_id, o.user_id, NULL::TEXT
+            FROM user_oversight o
+            UNION ALL
+            SELECT l.user_id, m.user_id, l.connect_group_id
+            FROM
+                user_connect_group l
+                    INNER JOIN pastoral_role lr ON lr.id = l.user_role
+                    INNER JOIN connect_group cg ON cg.id = l.connect_group_id
+                    INNER JOIN user_connect_group m ON m.connect_group_id = l.connect_group_id
+                    INNER JOIN pastoral_role mr ON mr.id = m.user_role
+            WHERE cg.active AND lr.weight <= {CONNECT_GROUP_LEADER_WEIGHT} AND mr.weight > lr.weight
+        )
+        "#
+    )
+}
+
+/// Push a query selecting the users reached from `user_id` by following the lines of pastoral
+/// care up to `max_depth` deep, towards the leaders when `upwards` or towards the members
+/// otherwise. Every user is reached once, through the shortest chain.
+///
+/// The users are selected as `u` and the chains as `r`, the query ends with a `WHERE` clause so
+/// that further conditions such as the scope can be pushed prefixed with `AND`.
+pub fn push_chain(
+    query: &mut QueryBuilder<'static, Postgres>,
+    user_id: &str,
+    max_depth: i32,
+    upwards: bool,
+) {
+    let (from, to) = if upwards {
+        ("user_id", "leader_id")
+    } else {
+        ("leader_id", "user_id")
+    };
+
+    query
+        .push(format!(
+            r#"
+            WITH RECURSIVE {care},
+            chain (user_id, connect_group_id, depth, path) AS (
+                SELECT c.{to}, c.connect_group_id, 1, ARRAY[c.{from}, c.{to}]
+                FROM care c
+                WHERE c.{from} = "#,
+            care = care(),
+        ))
+        .push_bind(user_id.to_string())
+        .push(format!(
+            r#"
+                UNION ALL
+                SELECT c.{to}, c.connect_group_id, chain.depth + 1, chain.path || c.{to}
+                FROM chain INNER JOIN care c ON c.{from} = chain.user_id
+                WHERE NOT c.{to} = ANY(chain.path) AND chain.depth < "#
+        ))
+        .push_bind(max_depth.clamp(1, MAX_DEPTH))
+        .push(
+            r#"
+            ),
+            reached AS (
+                SELECT DISTINCT ON (user_id) *
+                FROM chain
+                ORDER BY user_id, depth, connect_group_id NULLS LAST
+            )
+            SELECT
+                u.id AS user_id,
+                u.name,
+                pr.id AS role_id,
+                pr.name AS role_name,
+                r.connect_group_id,
+                r.depth
+            FROM
+                reached r
+                    INNER JOIN "user" u ON u.id = r.user_id
+                    LEFT JOIN LATERAL (
+                        SELECT pr.id, pr.name, pr.weight
+                        FROM
+                            user_connect_group ucg
+                                INNER JOIN pastoral_role pr ON pr.id = ucg.user_role
+                        WHERE ucg.user_id = u.id
+                        ORDER BY pr.weight
+                        LIMIT 1
+                    ) pr ON TRUE
+            WHERE u.deleted IS FALSE
+            "#,
+        );
+}
@@ -0,0 +1,445 @@
+use chrono::{DateTime, Datelike, Utc};
+use common::{
+    auth::BearerAuth,
+    crypto::{self, Keyring},
+    database::Database,
+    masking::{Mask, Policy, Rule},
+};
+use sqlx::PgConnection;
+
+use crate::{
+    authorization::Permission,
+    entities::{Address, ShadowUser, User},
+};
+
+/// How many users [`rotate`] re-encrypts per transaction.
+const BATCH_SIZE: i64 = 100;
+
+/// The columns holding the sensitive details of users in plaintext, from before they were
+/// encrypted.
+pub const PLAINTEXT_COLUMNS: &[&str] = &["ic_number", "phone_number", "address", "date_of_birth"];
+
+/// The columns holding the sensitive details of users encrypted, along with what is derived
+/// from them to look users up and filter them by.
+pub const ENCRYPTED_COLUMNS: &[&str] = &[
+    "ic_number_encrypted",
+    "ic_number_index",
+    "phone_number_encrypted",
+    "phone_number_index",
+    "address_encrypted",
+    "date_of_birth_encrypted",
+    "year_of_birth",
+];
+
+// The contexts the details are encrypted and indexed for, named after their column.
+const IC_NUMBER: &str = "user.ic_number";
+const PHONE_NUMBER: &str = "user.phone_number";
+const ADDRESS: &str = "user.address";
+const DATE_OF_BIRTH: &str = "user.date_of_birth";
+
+/// A user as stored, with its sensitive details still encrypted. Details stored before they were
+/// encrypted are read from their plaintext columns until [`rotate`] encrypts them.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct UserRow {
+    #[sqlx(flatten)]
+    user: User,
+    ic_number_encrypted: Option<Vec<u8>>,
+    ic_number_index: Option<Vec<u8>>,
+    phone_number_encrypted: Option<Vec<u8>>,
+    phone_number_index: Option<Vec<u8>>,
+    address_encrypted: Option<Vec<u8>>,
+    date_of_birth_encrypted: Option<Vec<u8>>,
+    year_of_birth: Option<i32>,
+}
+
+/// The sensitive details of a user: their IC number, phone number, address and date of birth.
+#[derive(Debug, Clone, Default)]
+pub struct Details {
+    pub ic_number: Option<String>,
+    pub phone_number: Option<String>,
+    pub address: Option<Address>,
+    pub date_of_birth: Option<DateTime<Utc>>,
+}
+
+/// The sensitive details of a user encrypted to be stored in the columns of the same name
+/// suffixed with `_encrypted`, along with what they are looked up and filtered by.
+#[derive(Debug, Clone, Default)]
+pub struct Sealed {
+    pub ic_number: Option<Vec<u8>>,
+    pub ic_number_index: Option<Vec<u8>>,
+    pub phone_number: Option<Vec<u8>>,
+    pub phone_number_index: Option<Vec<u8>>,
+    pub address: Option<Vec<u8>>,
+    pub date_of_birth: Option<Vec<u8>>,
+    pub year_of_birth: Option<i32>,
+}
+
+impl UserRow {
+    pub fn id(&self) -> &str {
+        &self.user.id
+    }
+
+    /// Decrypt the sensitive details of the user.
+    pub fn open(self, keyring: &Keyring) -> Result<User, crypto::Error> {
+        let mut user = self.user;
+        if let Some(value) = &self.ic_number_encrypted {
+            user.ic_number = Some(decrypt_string(keyring, IC_NUMBER, value)?);
+        }
+        if let Some(value) = &self.phone_number_encrypted {
+            user.phone_number = Some(decrypt_string(keyring, PHONE_NUMBER, value)?);
+        }
+        if let Some(value) = &self.address_encrypted {
+            user.address = Some(
+                serde_json::from_slice(&keyring.decrypt(ADDRESS, value)?)
+                    .map_err(|_| crypto::Error::Malformed)?,
+            );
+        }
+        if let Some(value) = &self.date_of_birth_encrypted {
+            user.date_of_birth = Some(
+                DateTime::parse_from_rfc3339(&decrypt_string(keyring, DATE_OF_BIRTH, value)?)
+                    .map_err(|_| crypto::Error::Malformed)?
+                    .with_timezone(&Utc),
+            );
+        }
+        Ok(user)
+    }
+
+    /// Decrypt the sensitive details of the user, masked unless `auth` may read them.
+    pub fn reveal(self, keyring: &Keyring, auth: &BearerAuth) -> Result<User, crypto::Error> {
+        Ok(mask_for(auth, self.open(keyring)?))
+    }
+
+    fn has_plaintext(&self) -> bool {
+        self.user.ic_number.is_some()
+            || self.user.phone_number.is_some()
+            || self.user.address.is_some()
+            || self.user.date_of_birth.is_some()
+    }
+
+    /// The details of the user encrypted the way `keyring` would encrypt them now, `None` when
+    /// they already are. Data keys wrapped with another key than the first one of the keyring
+    /// are re-wrapped, without decrypting the details, unless the details are to be decrypted
+    /// anyway to be indexed again or because they are stored in plaintext.
+    fn rotate(&self, keyring: &Keyring, reindex: bool) -> Result<Option<Sealed>, crypto::Error> {
+        if reindex || self.has_plaintext() {
+            return Details::of(&self.clone().open(keyring)?)
+                .seal(keyring)
+                .map(Some);
+        }
+
+        let mut rewrapped = false;
+        let mut rewrap = |value: &Option<Vec<u8>>| -> Result<Option<Vec<u8>>, crypto::Error> {
+            let Some(value) = value else {
+                return Ok(None);
+            };
+            Ok(Some(match keyring.rewrap(value)? {
+                Some(value) => {
+                    rewrapped = true;
+                    value
+                }
+                None => value.clone(),
+            }))
+        };
+        let sealed = Sealed {
+            ic_number: rewrap(&self.ic_number_encrypted)?,
+            ic_number_index: self.ic_number_index.clone(),
+            phone_number: rewrap(&self.phone_number_encrypted)?,
+            phone_number_index: self.phone_number_index.clone(),
+            address: rewrap(&self.address_encrypted)?,
+            date_of_birth: rewrap(&self.date_of_birth_encrypted)?,
+            year_of_birth: self.year_of_birth,
+        };
+
+        Ok(rewrapped.then_some(sealed))
+    }
+}
+
+impl Details {
+    pub fn of(user: &User) -> Self {
+        Self {
+            ic_number: user.ic_number.clone(),
+            phone_number: user.phone_number.clone(),
+            address: user.address.clone(),
+            date_of_birth: user.date_of_birth,
+        }
+    }
+
+    pub fn of_shadow_user(shadow_user: &ShadowUser) -> Self {
+        Self {
+            ic_number: shadow_user.ic_number.clone(),
+            phone_number: Some(shadow_user.phone_number.clone()),
+            address: shadow_user.address.clone(),
+            date_of_birth: shadow_user.date_of_birth,
+        }
+    }
+
+    /// Fill in the details missing from these with those of `other`.
+    pub fn or(self, other: Self) -> Self {
+        Self {
+            ic_number: self.ic_number.or(other.ic_number),
+            phone_number: self.phone_number.or(other.phone_number),
+            address: self.address.or(other.address),
+            date_of_birth: self.date_of_birth.or(other.date_of_birth),
+        }
+    }
+
+    /// Encrypt the details to be stored, each with a data key of its own.
+    pub fn seal(&self, keyring: &Keyring) -> Result<Sealed, crypto::Error> {
+        let encrypt = |context, value: Option<&[u8]>| {
+            value
+                .map(|value| keyring.encrypt(context, value))
+                .transpose()
+        };
+
+        Ok(Sealed {
+            ic_number: encrypt(IC_NUMBER, self.ic_number.as_ref().map(String::as_bytes))?,
+            ic_number_index: match &self.ic_number {
+                Some(ic_number) => ic_number_index(keyring, ic_number)?,
+                None => None,
+            },
+            phone_number: encrypt(
+                PHONE_NUMBER,
+                self.phone_number.as_ref().map(String::as_bytes),
+            )?,
+            phone_number_index: match &self.phone_number {
+                Some(phone_number) => phone_number_index(keyring, phone_number)?,
+                None => None,
+            },
+            address: encrypt(
+                ADDRESS,
+                self.address
+                    .as_ref()
+                    .map(|address| serde_json::to_vec(address).expect("addresses serialise"))
+                    .as_deref(),
+            )?,
+            date_of_birth: encrypt(
+                DATE_OF_BIRTH,
+                self.date_of_birth
+                    .map(|date_of_birth| date_of_birth.to_rfc3339())
+                    .as_ref()
+                    .map(String::as_bytes),
+            )?,
+            year_of_birth: self.date_of_birth.map(|date_of_birth| date_of_birth.year()),
+        })
+    }
+}
+
+fn decrypt_string(keyring: &Keyring, context: &str, value: &[u8]) -> Result<String, crypto::Error> {
+    String::from_utf8(keyring.decrypt(context, value)?).map_err(|_| crypto::Error::Malformed)
+}
+
+/// Decrypt a phone number selected without the rest of its user, falling back to `plaintext`,
+/// the phone number stored before it was encrypted.
+pub fn open_phone_number(
+    keyring: &Keyring,
+    encrypted: Option<&[u8]>,
+    plaintext: Option<String>,
+) -> Result<Option<String>, crypto::Error> {
+    match encrypted {
+        Some(value) => decrypt_string(keyring, PHONE_NUMBER, value).map(Some),
+        None => Ok(plaintext),
+    }
+}
+
+/// Keep the digits of a phone number without its country code or trunk prefix, like
+/// `normalise_phone_number` in the database (e.g. `+60 12-345 6789` becomes `123456789`).
+pub fn normalise_phone_number(phone_number: &str) -> Option<String> {
+    let digits = phone_number
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect::<String>();
+
+    let mut national = digits.as_str();
+    while let Some(rest) = national
+        .strip_prefix("60")
+        .or_else(|| national.strip_prefix('0'))
+    {
+        national = rest;
+    }
+
+    (!national.is_empty()).then(|| national.to_string())
+}
+
+/// Keep the letters and digits of an IC number in upper case, like `normalise_ic_number` in the
+/// database (e.g. `900101-14-5678` becomes `900101145678`).
+pub fn normalise_ic_number(ic_number: &str) -> Option<String> {
+    let normalised = ic_number
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_uppercase)
+        .collect::<String>();
+
+    (!normalised.is_empty()).then_some(normalised)
+}
+
+/// The blind index users are looked up by phone number with, `None` when it has no digits.
+pub fn phone_number_index(
+    keyring: &Keyring,
+    phone_number: &str,
+) -> Result<Option<Vec<u8>>, crypto::Error> {
+    normalise_phone_number(phone_number)
+        .map(|normalised| keyring.blind_index(PHONE_NUMBER, &normalised))
+        .transpose()
+}
+
+/// The blind index users are looked up by IC number with, `None` when it is blank.
+pub fn ic_number_index(
+    keyring: &Keyring,
+    ic_number: &str,
+) -> Result<Option<Vec<u8>>, crypto::Error> {
+    normalise_ic_number(ic_number)
+        .map(|normalised| keyring.blind_index(IC_NUMBER, &normalised))
+        .transpose()
+}
+
+/// Whether `auth` may read the sensitive details of the user `user_id` unmasked, which only the
+/// user themselves and callers granted `pii:read` by their access token may.
+pub fn can_read(auth: &BearerAuth, user_id: &str) -> bool {
+    auth.0.id == user_id || auth.is_granted_by_token(Permission::PiiRead)
+}
+
+/// How the sensitive details of users are masked from the callers that may not read them,
+/// leaving just enough to tell users apart: the last digits of the IC and phone numbers and the
+/// city, state and country of the address. The date of birth is left out.
+pub const USER_MASKING: Policy<Permission> = Policy(&[
+    Rule {
+        field: "ic_number",
+        permission: Permission::PiiRead,
+        mask: Mask::Last(4),
+    },
+    Rule {
+        field: "phone_number",
+        permission: Permission::PiiRead,
+        mask: Mask::Last(3),
+    },
+    Rule {
+        field: "address",
+        permission: Permission::PiiRead,
+        mask: Mask::Fields(&[
+            ("line_one", Mask::Initials),
+            ("line_two", Mask::Initials),
+            ("postal_code", Mask::Initials),
+        ]),
+    },
+    Rule {
+        field: "date_of_birth",
+        permission: Permission::PiiRead,
+        mask: Mask::Null,
+    },
+]);
+
+/// Mask the sensitive details of `user` unless `auth` may read them, see [`can_read`].
+pub fn mask_for(auth: &BearerAuth, user: User) -> User {
+    if auth.0.id == user.id {
+        user
+    } else {
+        USER_MASKING.apply_for(auth, user)
+    }
+}
+
+/// Mask the sensitive details of `user`, see [`USER_MASKING`].
+pub fn mask(user: User) -> User {
+    USER_MASKING.apply_all(user)
+}
+
+/// Store the sensitive details `sealed` of the user `user_id`, clearing the plaintext columns.
+async fn store(conn: &mut PgConnection, user_id: &str, sealed: &Sealed) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE "user" SET
+            ic_number_encrypted     = $2,
+            ic_number_index         = $3,
+            phone_number_encrypted  = $4,
+            phone_number_index      = $5,
+            address_encrypted       = $6,
+            date_of_birth_encrypted = $7,
+            year_of_birth           = $8,
+            ic_number               = NULL,
+            phone_number            = NULL,
+            address                 = NULL,
+            date_of_birth           = NULL
+        WHERE id = $1
+        "#,
+    )
+    .bind(user_id)
+    .bind(&sealed.ic_number)
+    .bind(&sealed.ic_number_index)
+    .bind(&sealed.phone_number)
+    .bind(&sealed.phone_number_index)
+    .bind(&sealed.address)
+    .bind(&sealed.date_of_birth)
+    .bind(sealed.year_of_birth)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Encrypt the sensitive details of every user the way `keyring` would encrypt them now: the
+/// data keys wrapped with another key than the first one of the keyring are re-wrapped with it,
+/// and the details stored in plaintext are encrypted, redacting them from the `audit_log` as
+/// well. With `reindex`, every detail is decrypted to compute its blind index again, after
+/// changing `PII_INDEX_KEY`.
+///
+/// Users are re-encrypted in batches, each in a transaction of its own, so that it may be
+/// stopped and run again. Returns the number of users re-encrypted.
+pub async fn rotate(
+    database: &Database,
+    keyring: &Keyring,
+    reindex: bool,
+) -> Result<u64, anyhow::Error> {
+    let mut rotated = 0;
+    let mut after = String::new();
+    loop {
+        let mut tx = database.db.begin().await?;
+
+        let rows = sqlx::query_as::<_, UserRow>(
+            r#"SELECT * FROM "user" WHERE id > $1 ORDER BY id LIMIT $2 FOR UPDATE"#,
+        )
+        .bind(&after)
+        .bind(BATCH_SIZE)
+        .fetch_all(&mut *tx)
+        .await?;
+        let Some(last) = rows.last() else {
+            return Ok(rotated);
+        };
+        after = last.id().to_string();
+
+        let mut encrypted = Vec::new();
+        for row in &rows {
+            let Some(sealed) = row.rotate(keyring, reindex)? else {
+                continue;
+            };
+            if row.has_plaintext() {
+                encrypted.push(row.id().to_string());
+            }
+            store(&mut tx, row.id(), &sealed).await?;
+            rotated += 1;
+        }
+
+        // Redacted last, so that the rows recorded by the encryption itself are redacted too.
+        if !encrypted.is_empty() {
+            sqlx::query("SELECT set_config('audit.redact', 'on', TRUE)")
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query(
+                r#"
+                UPDATE audit_log SET
+                    before = before - $2::TEXT[],
+                    after = after - $2::TEXT[],
+                    changes = changes - $2::TEXT[]
+                WHERE resource = 'user' AND resource_id = ANY($1)
+                "#,
+            )
+            .bind(&encrypted)
+            .bind(PLAINTEXT_COLUMNS)
+            .execute(&mut *tx)
+            .await?;
+            sqlx::query("SELECT set_config('audit.redact', '', TRUE)")
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+    }
+}
@@ -0,0 +1,413 @@
+use std::io::{Cursor, Write};
+use std::time::Duration;
+
+use common::{crypto::Keyring, database::Database, error::Error, outbox};
+use serde_json::{json, Value};
+use sqlx::PgConnection;
+
+use crate::{membership, pii};
+
+/// How long an erasure may be cancelled for by default, in days.
+pub const GRACE_PERIOD_DAYS: i64 = 30;
+
+/// The columns holding personal data, removed from the rows recorded in the `audit_log` and
+/// from the payloads of the events about an erased user. `data` is the answer to a registration
+/// form field and `destination` where a one-time code was sent to.
+const PERSONAL_DATA: &[&str] = &[
+    "name",
+    "email",
+    "username",
+    "given_name",
+    "family_name",
+    "gender",
+    "ic_number",
+    "ic_number_encrypted",
+    "ic_number_index",
+    "phone_number",
+    "phone_number_encrypted",
+    "phone_number_index",
+    "nickname",
+    "avatar_url",
+    "address",
+    "address_encrypted",
+    "date_of_birth",
+    "date_of_birth_encrypted",
+    "year_of_birth",
+    "data",
+    "destination",
+];
+
+/// Everything held about a user, each section selecting the rows of a table with the user bound
+/// as `$1`. The event service shares the database, its tables are exported along.
+const SECTIONS: &[(&str, &str)] = &[
+    ("user", r#"SELECT * FROM "user" WHERE id = $1"#),
+    (
+        "connect_groups",
+        r#"
+        SELECT m.*, c.name AS connect_group_name
+        FROM user_connect_group m INNER JOIN connect_group c ON c.id = m.connect_group_id
+        WHERE m.user_id = $1
+        "#,
+    ),
+    (
+        "connect_group_history",
+        "SELECT * FROM user_connect_group_history WHERE user_id = $1 ORDER BY joined_at",
+    ),
+    (
+        "ministries",
+        r#"
+        SELECT m.*, n.name AS ministry_name
+        FROM user_ministry m INNER JOIN ministry n ON n.id = m.ministry_id
+        WHERE m.user_id = $1
+        "#,
+    ),
+    (
+        "ministry_history",
+        "SELECT * FROM user_ministry_history WHERE user_id = $1 ORDER BY joined_at",
+    ),
+    (
+        "relationships",
+        "SELECT * FROM user_relationship WHERE source_user_id = $1 OR destination_user_id = $1",
+    ),
+    (
+        "oversight",
+        "SELECT * FROM user_oversight WHERE user_id = $1 OR overseer_id = $1",
+    ),
+    (
+        "households",
+        r#"
+        SELECT h.*, m.created_at AS joined_at
+        FROM household h INNER JOIN user_household m ON m.household_id = h.id
+        WHERE m.user_id = $1
+        "#,
+    ),
+    (
+        "shadow_users",
+        "SELECT * FROM shadow_user WHERE claimed_by = $1",
+    ),
+    (
+        "claims",
+        r#"
+        SELECT id, user_id, email, shadow_user_no, claimed_user_id, channel, destination,
+            expires_at, verified_at, created_at
+        FROM user_claim
+        WHERE user_id = $1 OR claimed_user_id = $1
+        ORDER BY created_at
+        "#,
+    ),
+    (
+        "merges",
+        "SELECT * FROM user_merge WHERE user_id = $1 OR merged_user_id = $1 ORDER BY created_at",
+    ),
+    (
+        "duplicates",
+        "SELECT * FROM user_duplicate WHERE user_id = $1 OR duplicate_user_id = $1",
+    ),
+    ("erasure", "SELECT * FROM user_erasure WHERE user_id = $1"),
+    (
+        "attendance",
+        r#"
+        SELECT
+            a.*,
+            s.name AS session_name,
+            e.id AS event_id,
+            e.name AS event_name
+        FROM attendance a
+        INNER JOIN "session" s ON s.id = a.session_id
+        INNER JOIN event e ON e.id = s.event_id
+        WHERE a.user_id = $1
+        ORDER BY s.start_at
+        "#,
+    ),
+    (
+        "registration_form_data",
+        r#"
+        SELECT d.*, r.event_id, r.name AS registration_name
+        FROM registration_form_field_data d
+        INNER JOIN registration r ON r.id = d.registration_id
+        WHERE d.user_id = $1
+        ORDER BY d.created_at
+        "#,
+    ),
+    (
+        "audit_log",
+        r#"
+        SELECT * FROM audit_log
+        WHERE (resource = 'user' AND resource_id = $1) OR actor = $1
+        ORDER BY id
+        "#,
+    ),
+];
+
+/// Export everything held about a user, as sections named after what they hold (e.g.
+/// `connect_groups`) each listing its rows. The sensitive details of the user are decrypted,
+/// and masked with `mask`. Returns `None` when the user does not exist.
+pub async fn export(
+    conn: &mut PgConnection,
+    keyring: &Keyring,
+    user_id: &str,
+    mask: bool,
+) -> Result<Option<Vec<(&'static str, Value)>>, Error> {
+    let mut sections = Vec::with_capacity(SECTIONS.len());
+    for (name, sql) in SECTIONS {
+        // The search vector of a user only repeats the other columns.
+        let mut rows = sqlx::query_scalar::<_, Value>(&format!(
+            "SELECT COALESCE(jsonb_agg(to_jsonb(t) - 'search_vector'), '[]') FROM ({}) t",
+            sql
+        ))
+        .bind(user_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        if *name == "user" {
+            let Some(Value::Object(row)) = rows.get_mut(0) else {
+                return Ok(None);
+            };
+
+            let user = sqlx::query_as::<_, pii::UserRow>(r#"SELECT * FROM "user" WHERE id = $1"#)
+                .bind(user_id)
+                .fetch_one(&mut *conn)
+                .await?
+                .open(keyring)?;
+            let user = serde_json::to_value(if mask { pii::mask(user) } else { user })
+                .map_err(Error::internal)?;
+
+            // The sensitive details are exported decrypted, in place of their legacy columns.
+            for column in pii::ENCRYPTED_COLUMNS {
+                row.remove(*column);
+            }
+            for column in pii::PLAINTEXT_COLUMNS {
+                row.insert(column.to_string(), user[column].clone());
+            }
+        }
+        sections.push((*name, rows));
+    }
+
+    Ok(Some(sections))
+}
+
+/// Bundle the sections of an export in a ZIP archive, one JSON file per section along with a
+/// `manifest.json` describing the export.
+pub fn zip(
+    user_id: &str,
+    exported_at: chrono::DateTime<chrono::Utc>,
+    sections: &[(&str, Value)],
+) -> Result<Vec<u8>, anyhow::Error> {
+    let mut archive = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = zip::write::FileOptions::default();
+
+    let files = sections
+        .iter()
+        .map(|(name, rows)| {
+            json!({
+                "file": format!("{}.json", name),
+                "rows": rows.as_array().map_or(0, Vec::len),
+            })
+        })
+        .collect::<Vec<_>>();
+    archive.start_file("manifest.json", options)?;
+    archive.write_all(&serde_json::to_vec_pretty(&json!({
+        "user_id": user_id,
+        "exported_at": exported_at,
+        "files": files,
+    }))?)?;
+
+    for (name, rows) in sections {
+        archive.start_file(format!("{}.json", name), options)?;
+        archive.write_all(&serde_json::to_vec_pretty(rows)?)?;
+    }
+
+    Ok(archive.finish()?.into_inner())
+}
+
+/// Erase the personal data of a user, meant to be called within a transaction.
+///
+/// The user is kept, soft-deleted and anonymised, so that the attendance and the membership
+/// history it is counted in stay as they were. Everything else tying the user to a person is
+/// removed: their memberships, relationships, households, claims, the shadow users they claimed
+/// and their registration form data. The personal data recorded in the `audit_log` and in the
+/// events about the user is redacted. Returns `false` when the user does not exist.
+pub async fn erase(conn: &mut PgConnection, user_id: &str) -> Result<bool, sqlx::Error> {
+    membership::set_left_reason(conn, Some("Erased")).await?;
+
+    let result = sqlx::query(
+        r#"
+        UPDATE "user" SET
+            email = id || '@erased.invalid',
+            email_verified = FALSE,
+            name = 'Erased user',
+            username = NULL,
+            given_name = NULL,
+            family_name = NULL,
+            gender = NULL,
+            ic_number = NULL,
+            ic_number_encrypted = NULL,
+            ic_number_index = NULL,
+            phone_number = NULL,
+            phone_number_encrypted = NULL,
+            phone_number_index = NULL,
+            phone_number_verified = FALSE,
+            nickname = NULL,
+            avatar_url = NULL,
+            address = NULL,
+            address_encrypted = NULL,
+            date_of_birth = NULL,
+            date_of_birth_encrypted = NULL,
+            year_of_birth = NULL,
+            deleted = TRUE,
+            updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(user_id)
+    .execute(&mut *conn)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Ok(false);
+    }
+
+    for sql in [
+        "DELETE FROM user_connect_group WHERE user_id = $1",
+        "DELETE FROM user_ministry WHERE user_id = $1",
+        "DELETE FROM user_relationship WHERE source_user_id = $1 OR destination_user_id = $1",
+        "DELETE FROM user_oversight WHERE user_id = $1 OR overseer_id = $1",
+        "DELETE FROM user_claim WHERE user_id = $1 OR claimed_user_id = $1",
+        "DELETE FROM user_duplicate WHERE user_id = $1 OR duplicate_user_id = $1",
+        "DELETE FROM registration_form_field_data WHERE user_id = $1",
+        // Households left without members only hold the address of the user.
+        r#"
+        WITH removed AS (
+            DELETE FROM user_household WHERE user_id = $1 RETURNING household_id
+        )
+        DELETE FROM household h
+        USING removed r
+        WHERE h.id = r.household_id
+            AND NOT EXISTS (
+                SELECT 1 FROM user_household m
+                WHERE m.household_id = h.id AND m.user_id != $1
+            )
+        "#,
+        "UPDATE user_erasure SET erased_at = NOW() WHERE user_id = $1",
+    ] {
+        sqlx::query(sql).bind(user_id).execute(&mut *conn).await?;
+    }
+
+    // A shadow user is identified by its name and phone number, which is personal data too.
+    let shadow_users = sqlx::query_scalar::<_, String>(
+        "DELETE FROM shadow_user WHERE claimed_by = $1 RETURNING concat_ws('/', name, phone_number)",
+    )
+    .bind(user_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    // Redacted last, so that the rows recorded by the erasure itself are redacted as well.
+    redact_audit_log(conn, user_id, &shadow_users).await?;
+
+    sqlx::query(
+        r#"
+        UPDATE outbox_event SET payload = payload - $2::TEXT[]
+        WHERE type LIKE 'user.%' AND payload ->> 'id' = $1
+        "#,
+    )
+    .bind(user_id)
+    .bind(PERSONAL_DATA)
+    .execute(&mut *conn)
+    .await?;
+
+    outbox::publish(conn, "user.erased", json!({ "id": user_id })).await?;
+
+    Ok(true)
+}
+
+/// Redact the personal data from the rows recorded in the `audit_log` about the user `user_id`,
+/// their claims and their registration form data, as well as about the `shadow_users` they
+/// claimed, identified by their name and phone number, which are removed.
+pub async fn redact_audit_log(
+    conn: &mut PgConnection,
+    user_id: &str,
+    shadow_users: &[String],
+) -> Result<(), sqlx::Error> {
+    sqlx::query("SELECT set_config('audit.redact', 'on', TRUE)")
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query(
+        r#"
+        UPDATE audit_log SET
+            before = before - $2::TEXT[],
+            after = after - $2::TEXT[],
+            changes = changes - $2::TEXT[]
+        WHERE
+            (resource = 'user' AND resource_id = $1)
+            OR (
+                resource IN ('user_claim', 'registration_form_field_data')
+                AND COALESCE(after, before) ->> 'user_id' = $1
+            )
+        "#,
+    )
+    .bind(user_id)
+    .bind(PERSONAL_DATA)
+    .execute(&mut *conn)
+    .await?;
+    sqlx::query(
+        r#"
+        UPDATE audit_log SET
+            resource_id = 'erased',
+            before = before - $2::TEXT[],
+            after = after - $2::TEXT[],
+            changes = changes - $2::TEXT[]
+        WHERE resource = 'shadow_user' AND resource_id = ANY($1)
+        "#,
+    )
+    .bind(shadow_users)
+    .bind(PERSONAL_DATA)
+    .execute(&mut *conn)
+    .await?;
+    sqlx::query("SELECT set_config('audit.redact', '', TRUE)")
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Carry out the erasures whose grace period is over, each in a transaction of its own.
+/// Returns the number of users erased.
+pub async fn erase_due(database: &Database) -> Result<u64, sqlx::Error> {
+    let mut erased = 0;
+    loop {
+        let mut tx = database.db.begin().await?;
+
+        let user_id = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT user_id FROM user_erasure
+            WHERE erased_at IS NULL AND scheduled_at <= NOW()
+            ORDER BY scheduled_at
+            LIMIT 1
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(user_id) = user_id else {
+            return Ok(erased);
+        };
+
+        erase(&mut tx, &user_id).await?;
+        tx.commit().await?;
+        erased += 1;
+    }
+}
+
+/// Carry out the erasures that are due every `interval`, meant to be spawned as a background
+/// job.
+pub async fn run(database: Database, interval: Duration) {
+    let mut interval = tokio::time::interval(interval);
+    loop {
+        interval.tick().await;
+
+        match erase_due(&database).await {
+            Ok(erased) if erased > 0 => tracing::info!("Erased {} users", erased),
+            Ok(_) => {}
+            Err(e) => tracing::error!("Erasure failed: {}", e),
+        }
+    }
+}
@@ -0,0 +1,334 @@
+use common::{crypto::Keyring, error::Error};
+use sqlx::PgConnection;
+
+use crate::{
+    entities::{
+        ConnectGroup, MatchedAttribute, Ministry, MinistryRole, PastoralRole, ShadowUser,
+        ShadowUserMatch, ShadowUserResolution, UnresolvedValue,
+    },
+    pii,
+};
+
+/// The memberships a user is given when claiming a shadow user.
+#[derive(Debug, Clone, Default)]
+pub struct Memberships {
+    /// The connect group to join along with the pastoral role to join it with.
+    pub connect_group: Option<(String, String)>,
+    pub ministry_ids: Vec<String>,
+    /// The role to join the ministries with, they are not joined without one.
+    pub ministry_role_id: Option<String>,
+}
+
+impl Memberships {
+    /// The memberships that were resolved, leaving out the unresolved values.
+    pub fn resolved(resolution: &ShadowUserResolution) -> Self {
+        Self {
+            connect_group: resolution
+                .connect_group
+                .as_ref()
+                .zip(resolution.pastoral_role.as_ref())
+                .map(|(cg, role)| (cg.id.clone(), role.id.clone())),
+            ministry_ids: resolution
+                .ministries
+                .iter()
+                .map(|ministry| ministry.id.clone())
+                .collect(),
+            ministry_role_id: resolution
+                .ministry_role
+                .as_ref()
+                .map(|role| role.id.clone()),
+        }
+    }
+}
+
+/// How much each matching attribute contributes to the score of a match. Attributes are treated
+/// as independent evidence, so a match on both the phone number and the name scores higher than
+/// on either alone.
+pub const IC_NUMBER_WEIGHT: f64 = 0.95;
+pub const EMAIL_WEIGHT: f64 = 0.9;
+pub const PHONE_NUMBER_WEIGHT: f64 = 0.8;
+pub const NAME_WEIGHT: f64 = 0.6;
+
+/// Names are only considered a match above this `pg_trgm` similarity.
+pub const NAME_SIMILARITY_THRESHOLD: f32 = 0.4;
+
+#[derive(sqlx::FromRow)]
+struct Candidate {
+    #[sqlx(flatten)]
+    user: pii::UserRow,
+    phone_number_matched: bool,
+    email_matched: bool,
+    ic_number_matched: bool,
+    name_similarity: f32,
+}
+
+impl Candidate {
+    fn into_match(self, keyring: &Keyring) -> Result<ShadowUserMatch, Error> {
+        let mut matched_on = Vec::new();
+        let mut mismatch = 1.0;
+        for (matched, attribute, weight) in [
+            (
+                self.ic_number_matched,
+                MatchedAttribute::IcNumber,
+                IC_NUMBER_WEIGHT,
+            ),
+            (self.email_matched, MatchedAttribute::Email, EMAIL_WEIGHT),
+            (
+                self.phone_number_matched,
+                MatchedAttribute::PhoneNumber,
+                PHONE_NUMBER_WEIGHT,
+            ),
+        ] {
+            if matched {
+                matched_on.push(attribute);
+                mismatch *= 1.0 - weight;
+            }
+        }
+        if self.name_similarity >= NAME_SIMILARITY_THRESHOLD {
+            matched_on.push(MatchedAttribute::Name);
+            mismatch *= 1.0 - NAME_WEIGHT * f64::from(self.name_similarity);
+        }
+
+        Ok(ShadowUserMatch {
+            user: self.user.open(keyring)?,
+            score: 1.0 - mismatch,
+            matched_on,
+        })
+    }
+}
+
+/// Find the users that `shadow_user` likely refers to, by phone number, email, IC number and
+/// name, best matches first.
+pub async fn find_matches(
+    conn: &mut PgConnection,
+    keyring: &Keyring,
+    shadow_user: &ShadowUser,
+    limit: i64,
+) -> Result<Vec<ShadowUserMatch>, Error> {
+    let phone_number_index = pii::phone_number_index(keyring, &shadow_user.phone_number)?;
+    let ic_number_index = match &shadow_user.ic_number {
+        Some(ic_number) => pii::ic_number_index(keyring, ic_number)?,
+        None => None,
+    };
+
+    let candidates = sqlx::query_as::<_, Candidate>(
+        r#"
+        SELECT
+            u.*,
+            COALESCE(u.phone_number_index = $1, FALSE) AS phone_number_matched,
+            COALESCE(lower(u.email) = lower($2), FALSE) AS email_matched,
+            COALESCE(u.ic_number_index = $3, FALSE) AS ic_number_matched,
+            similarity(u.name, $4) AS name_similarity
+        FROM "user" u
+        WHERE u.deleted IS FALSE AND (
+            u.phone_number_index = $1
+            OR lower(u.email) = lower($2)
+            OR u.ic_number_index = $3
+            OR similarity(u.name, $4) >= $5
+        )
+        "#,
+    )
+    .bind(phone_number_index)
+    .bind(&shadow_user.email)
+    .bind(ic_number_index)
+    .bind(&shadow_user.name)
+    .bind(NAME_SIMILARITY_THRESHOLD)
+    .fetch_all(conn)
+    .await?;
+
+    let mut matches = candidates
+        .into_iter()
+        .map(|candidate| candidate.into_match(keyring))
+        .collect::<Result<Vec<_>, _>>()?;
+    matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+    matches.truncate(limit.max(0) as usize);
+
+    Ok(matches)
+}
+
+/// Resolve the free-text `cg`, `pastoral_status` and `ministry` columns of `shadow_user` to
+/// actual connect groups, roles and ministries.
+///
+/// A connect group is matched by name or by number and variant (e.g. `12A`), ignoring case and
+/// punctuation. Ministries are matched by name, preferring those of the connect group's satellite
+/// when the name is ambiguous. Values matching nothing, or more than one thing, are reported in
+/// `unresolved` for an admin to pick from.
+pub async fn resolve(
+    conn: &mut PgConnection,
+    shadow_user: &ShadowUser,
+) -> Result<ShadowUserResolution, sqlx::Error> {
+    let mut unresolved = Vec::new();
+
+    let mut connect_group = None;
+    if let Some(cg) = non_empty(&shadow_user.cg) {
+        let mut connect_groups = sqlx::query_as::<_, ConnectGroup>(
+            r#"
+            SELECT *
+            FROM connect_group
+            WHERE
+                upper(regexp_replace(COALESCE(name, ''), '[^[:alnum:]]', '', 'g')) = $1
+                OR no::TEXT || upper(TRIM(COALESCE(variant, ''))) = $1
+            "#,
+        )
+        .bind(
+            cg.chars()
+                .filter(char::is_ascii_alphanumeric)
+                .collect::<String>()
+                .to_uppercase(),
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        if connect_groups.len() == 1 {
+            connect_group = connect_groups.pop();
+        } else {
+            unresolved.push(unresolved_value("cg", cg));
+        }
+    }
+
+    let pastoral_role = match non_empty(&shadow_user.pastoral_status) {
+        Some(pastoral_status) => {
+            let role = sqlx::query_as::<_, PastoralRole>(
+                "SELECT * FROM pastoral_role WHERE lower(name) = lower($1)",
+            )
+            .bind(pastoral_status)
+            .fetch_optional(&mut *conn)
+            .await?;
+            if role.is_none() {
+                unresolved.push(unresolved_value("pastoral_status", pastoral_status));
+            }
+            role
+        }
+        None => {
+            sqlx::query_as::<_, PastoralRole>(
+                "SELECT * FROM pastoral_role ORDER BY weight DESC, name LIMIT 1",
+            )
+            .fetch_optional(&mut *conn)
+            .await?
+        }
+    };
+
+    let mut ministries = Vec::new();
+    let names = non_empty(&shadow_user.ministry)
+        .map(|ministry| {
+            ministry
+                .split([',', ';', '/'])
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    for name in names {
+        let mut candidates =
+            sqlx::query_as::<_, Ministry>("SELECT * FROM ministry WHERE lower(name) = lower($1)")
+                .bind(name)
+                .fetch_all(&mut *conn)
+                .await?;
+
+        if candidates.len() > 1 {
+            if let Some(connect_group) = &connect_group {
+                candidates.retain(|ministry| ministry.satellite_id == connect_group.satellite_id);
+            }
+        }
+
+        match candidates.pop() {
+            Some(ministry) if candidates.is_empty() => ministries.push(ministry),
+            _ => unresolved.push(unresolved_value("ministry", name)),
+        }
+    }
+
+    let ministry_role = sqlx::query_as::<_, MinistryRole>(
+        "SELECT * FROM ministry_role ORDER BY weight DESC, name LIMIT 1",
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    Ok(ShadowUserResolution {
+        connect_group,
+        pastoral_role,
+        ministries,
+        ministry_role,
+        unresolved,
+    })
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+fn unresolved_value(field: &str, value: &str) -> UnresolvedValue {
+    UnresolvedValue {
+        field: field.to_string(),
+        value: value.to_string(),
+    }
+}
+
+/// Give the user identified by `user_id` the `memberships` of the shadow user numbered
+/// `shadow_user_no`, and mark the shadow user as claimed by them.
+pub async fn claim(
+    conn: &mut PgConnection,
+    shadow_user_no: i32,
+    user_id: &str,
+    memberships: &Memberships,
+) -> Result<(), sqlx::Error> {
+    if let Some((connect_group_id, pastoral_role_id)) = &memberships.connect_group {
+        sqlx::query(
+            r#"
+            INSERT INTO user_connect_group (
+                user_id,
+                connect_group_id,
+                user_role
+            ) VALUES (
+                $1,
+                $2,
+                $3
+            )
+            ON CONFLICT (user_id, connect_group_id) DO NOTHING
+            "#,
+        )
+        .bind(user_id)
+        .bind(connect_group_id)
+        .bind(pastoral_role_id)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    if let Some(ministry_role_id) = &memberships.ministry_role_id {
+        sqlx::query(
+            r#"
+            INSERT INTO user_ministry (
+                user_id,
+                ministry_id,
+                user_role
+            )
+            SELECT $1, ministry_id, $3
+            FROM UNNEST($2::TEXT[]) AS ministry_id
+            ON CONFLICT (user_id, ministry_id) DO NOTHING
+            "#,
+        )
+        .bind(user_id)
+        .bind(&memberships.ministry_ids)
+        .bind(ministry_role_id)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE shadow_user SET
+            claimed_by = $1,
+            claimed_at = NOW(),
+            updated_at = NOW()
+        WHERE no = $2
+        "#,
+    )
+    .bind(user_id)
+    .bind(shadow_user_no)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use poem::web;
+use poem_openapi::payload;
+
+use common::{
+    auth::BearerAuth,
+    database::Database,
+    error::Error,
+    pagination::{self, Listing, Page, Sortable},
+    projection::{Fields, Projected},
+};
+
+use crate::{authorization::Permission, entities};
+
+const LISTING: Listing = Listing {
+    select: "a.*",
+    from: "audit_log a",
+    // Padded so that the ids, which are compared as text, keep their order.
+    key: "lpad(a.id::TEXT, 19, '0')",
+    sortable: &[Sortable {
+        name: "created_at",
+        expr: "a.created_at",
+        ty: "TIMESTAMPTZ",
+    }],
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub actor: Option<String>,
+    pub resource: Option<String>,
+    pub resource_id: Option<String>,
+    pub request_id: Option<String>,
+    pub created_after: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_before: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Page<Projected<entities::AuditLog>>>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _list_audit_logs(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        params: pagination::Params,
+        filter: Filter,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::AuditLog>(fields)?;
+
+        auth.require(&db, Permission::AuditRead).await?;
+
+        let logs = LISTING
+            .fetch(&db.db, &params, |query| {
+                if let Some(actor) = &filter.actor {
+                    query.push(" AND a.actor = ").push_bind(actor.clone());
+                }
+                if let Some(resource) = &filter.resource {
+                    query.push(" AND a.resource = ").push_bind(resource.clone());
+                }
+                if let Some(resource_id) = &filter.resource_id {
+                    query
+                        .push(" AND a.resource_id = ")
+                        .push_bind(resource_id.clone());
+                }
+                if let Some(request_id) = &filter.request_id {
+                    query
+                        .push(" AND a.request_id = ")
+                        .push_bind(request_id.clone());
+                }
+                if let Some(created_after) = filter.created_after {
+                    query.push(" AND a.created_at > ").push_bind(created_after);
+                }
+                if let Some(created_before) = filter.created_before {
+                    query.push(" AND a.created_at < ").push_bind(created_before);
+                }
+            })
+            .await?;
+
+        Ok(Response::Ok(payload::Json(fields.project_page(logs))))
+    }
+}
@@ -0,0 +1,141 @@
+use poem::web;
+use poem_openapi::{param::Path, payload, Object};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use common::{audit, auth::BearerAuth, database::Database, error::Error, outbox};
+
+use crate::{authorization::Permission, entities, membership};
+
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "CloseConnectGroupRequest")]
+pub struct Request {
+    /// Move the members to this connect group along with their roles, members already in it
+    /// keep their role there.
+    reassign_to: Option<String>,
+    /// Remove the members from the connect group.
+    release: Option<bool>,
+    /// Why the members are leaving, kept in their membership history.
+    reason: Option<String>,
+}
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<entities::ConnectGroup>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _close_connect_group(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: Path<String>,
+        body: payload::Json<Request>,
+    ) -> Result<Response, Error> {
+        auth.require(&db, Permission::ConnectGroupsWrite).await?;
+
+        let release = body.release.unwrap_or(false);
+        if release && body.reassign_to.is_some() {
+            return Err(Error::bad_request(
+                "CONNECT_GROUP_CLOSE_AMBIGUOUS",
+                "The members are either reassigned or released, not both",
+            ));
+        }
+        if body.reassign_to.as_deref() == Some(&*id) {
+            return Err(Error::bad_request(
+                "CONNECT_GROUP_REASSIGN_TO_SELF",
+                "The members cannot be reassigned to the connect group being closed",
+            ));
+        }
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        super::lock_open(&mut tx, &id).await?;
+
+        if let Some(reassign_to) = &body.reassign_to {
+            super::lock_open(&mut tx, reassign_to).await?;
+
+            let reason = format!("Connect group closed, moved to '{}'", reassign_to);
+            membership::set_left_reason(&mut tx, Some(body.reason.as_deref().unwrap_or(&reason)))
+                .await?;
+            sqlx::query(
+                r#"
+                INSERT INTO user_connect_group (user_id, connect_group_id, user_role)
+                SELECT user_id, $1, user_role FROM user_connect_group WHERE connect_group_id = $2
+                ON CONFLICT (user_id, connect_group_id) DO NOTHING
+                "#,
+            )
+            .bind(reassign_to)
+            .bind(&*id)
+            .execute(&mut *tx)
+            .await?;
+        } else {
+            membership::set_left_reason(
+                &mut tx,
+                Some(body.reason.as_deref().unwrap_or("Connect group closed")),
+            )
+            .await?;
+        }
+
+        let members = if release || body.reassign_to.is_some() {
+            sqlx::query_scalar::<_, String>(
+                r#"
+                DELETE FROM user_connect_group WHERE connect_group_id = $1 RETURNING user_id
+                "#,
+            )
+            .bind(&*id)
+            .fetch_all(&mut *tx)
+            .await?
+        } else {
+            let members = sqlx::query_scalar::<_, i64>(
+                r#"
+                SELECT COUNT(*) FROM user_connect_group WHERE connect_group_id = $1
+                "#,
+            )
+            .bind(&*id)
+            .fetch_one(&mut *tx)
+            .await?;
+            if members > 0 {
+                return Err(Error::conflict(
+                    "CONNECT_GROUP_HAS_MEMBERS",
+                    format!(
+                        "Connect group with id '{}' still has {} members, reassign or release them",
+                        &*id, members
+                    ),
+                ));
+            }
+            Vec::new()
+        };
+
+        let cg = sqlx::query_as::<_, entities::ConnectGroup>(
+            r#"
+            UPDATE connect_group SET
+                active     = FALSE,
+                closed_at  = NOW(),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(&*id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        outbox::publish(
+            &mut tx,
+            "connect_group.closed",
+            json!({
+                "connect_group_id": &*id,
+                "reassigned_to": &body.reassign_to,
+                "user_ids": members,
+                "reason": &body.reason,
+            }),
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Response::Ok(payload::Json(cg)))
+    }
+}
@@ -2,7 +2,9 @@ use poem::web;
 use poem_openapi::{payload, Object};
 use serde::{Deserialize, Serialize};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{audit, auth::BearerAuth, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities};
 
 #[derive(Debug, Clone, Deserialize, Serialize, Object)]
 #[oai(rename = "CreateConnectGroupRequest")]
@@ -21,24 +23,17 @@ pub enum Response {
     Ok(payload::Json<entities::ConnectGroup>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
-}
-
 impl crate::routes::Routes {
     pub async fn _create_connect_group(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         body: payload::Json<Request>,
     ) -> Result<Response, Error> {
+        auth.require(&db, Permission::ConnectGroupsWrite).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
         let cg = sqlx::query_as!(
             entities::ConnectGroup,
             r#"
@@ -66,36 +61,10 @@ impl crate::routes::Routes {
             &body.satellite_id,
             &body.category_id,
         )
-        .fetch_one(&db.db)
-        .await
-        .map_err(|e| match e {
-            sqlx::Error::Database(e)
-                if e.is_unique_violation()
-                    && e.constraint().is_some_and(|constraint| {
-                        constraint == "connect_group_satellite_id_no_variant_key"
-                    }) =>
-            {
-                Error::BadRequest(payload::Json(ErrorResponse {
-                    message: format!(
-                        "CG with satellite_id '{}', no '{}' and variant '{}' already exists",
-                        body.satellite_id, body.no, body.variant
-                    ),
-                }))
-            }
-            sqlx::Error::Database(e)
-                if e.is_foreign_key_violation()
-                    && e.constraint().is_some_and(|constraint| {
-                        constraint == "connect_group_satellite_id_fkey"
-                    }) =>
-            {
-                Error::BadRequest(payload::Json(ErrorResponse {
-                    message: format!("Satellite with id '{}' does not exists", body.satellite_id),
-                }))
-            }
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
 
         Ok(Response::Ok(payload::Json(cg)))
     }
@@ -1,7 +1,9 @@
 use poem::web;
 use poem_openapi::{param::Path, payload};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{audit, auth::BearerAuth, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities};
 
 #[derive(poem_openapi::ApiResponse)]
 pub enum Response {
@@ -9,44 +11,87 @@ pub enum Response {
     Ok(payload::Json<entities::ConnectGroup>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
+#[derive(Debug, sqlx::FromRow)]
+struct Usage {
+    members: i64,
+    attendances: i64,
 }
 
 impl crate::routes::Routes {
     pub async fn _delete_connect_group(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
+        force: bool,
     ) -> Result<Response, Error> {
-        let cg = sqlx::query_as!(
-            entities::ConnectGroup,
+        auth.require(&db, Permission::ConnectGroupsWrite).await?;
+        if force {
+            auth.require(&db, Permission::UsersAdmin).await?;
+        }
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        // Deleting the connect group deletes its membership history, and with it which
+        // attendance was taken while the attendees were its members.
+        let usage = sqlx::query_as::<_, Usage>(
             r#"
-            DELETE FROM connect_group 
-            WHERE id = $1::TEXT 
+            SELECT
+                (SELECT COUNT(*) FROM user_connect_group WHERE connect_group_id = $1) AS members,
+                (
+                    SELECT COUNT(*)
+                    FROM attendance a
+                    JOIN user_connect_group_history h ON h.user_id = a.user_id
+                    WHERE
+                        h.connect_group_id = $1
+                        AND a.created_at >= COALESCE(h.joined_at, '-infinity')
+                        AND a.created_at < COALESCE(h.left_at, 'infinity')
+                ) AS attendances
+            "#,
+        )
+        .bind(&*id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if !force && (usage.members > 0 || usage.attendances > 0) {
+            return Err(Error::conflict(
+                "CONNECT_GROUP_IN_USE",
+                format!(
+                    "Connect group with id '{}' has {} members and {} attendances, close it \
+                     instead",
+                    &*id, usage.members, usage.attendances
+                ),
+            ));
+        }
+
+        sqlx::query(
+            r#"
+            DELETE FROM user_connect_group WHERE connect_group_id = $1
+            "#,
+        )
+        .bind(&*id)
+        .execute(&mut *tx)
+        .await?;
+
+        let cg = sqlx::query_as::<_, entities::ConnectGroup>(
+            r#"
+            DELETE FROM connect_group
+            WHERE id = $1
             RETURNING *
             "#,
-            &*id
         )
-        .fetch_one(&db.db)
+        .bind(&*id)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| match e {
-            sqlx::error::Error::RowNotFound => Error::NotFound(payload::Json(ErrorResponse {
-                message: format!("Connect group with id '{}' not found", &*id),
-            })),
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
+            sqlx::Error::RowNotFound => Error::not_found(
+                "CONNECT_GROUP_NOT_FOUND",
+                format!("Connect group with id '{}' not found", &*id),
+            ),
+            _ => Error::from(e),
         })?;
 
+        tx.commit().await?;
+
         Ok(Response::Ok(payload::Json(cg)))
     }
 }
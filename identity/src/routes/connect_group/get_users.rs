@@ -1,55 +1,96 @@
 use poem::web;
 use poem_openapi::{param::Path, payload};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{
+    auth::BearerAuth,
+    crypto::Keyring,
+    database::Database,
+    error::Error,
+    pagination::{self, Listing, Page, Sortable},
+    projection::{Fields, Projected},
+};
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Response {
-    #[oai(status = 200)]
-    Ok(payload::Json<Vec<entities::User>>),
-}
+use crate::{authorization::Permission, entities, pii, scope::Scope};
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
+const LISTING: Listing = Listing {
+    select: "u.*",
+    from: r#"user_connect_group ucg INNER JOIN "user" u ON ucg.user_id = u.id"#,
+    key: "u.id",
+    sortable: &[
+        Sortable {
+            name: "created_at",
+            expr: "u.created_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "updated_at",
+            expr: "u.updated_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "name",
+            expr: "u.name",
+            ty: "TEXT",
+        },
+        Sortable {
+            name: "email",
+            expr: "u.email",
+            ty: "TEXT",
+        },
+        Sortable {
+            name: "no",
+            expr: "u.no",
+            ty: "INTEGER",
+        },
+    ],
+};
 
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub role_id: Option<String>,
+}
 
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Page<Projected<entities::User>>>),
 }
 
 impl crate::routes::Routes {
     pub async fn _get_connect_group_users(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
+        keyring: web::Data<&Keyring>,
         id: Path<String>,
+        params: pagination::Params,
+        filter: Filter,
+        fields: Option<String>,
     ) -> Result<Response, Error> {
-        let users = sqlx::query_as_unchecked!(
-            entities::User,
-            r#"
-            SELECT 
-                u.* 
-            FROM 
-                user_connect_group ucg
-                    INNER JOIN "user" u ON ucg.user_id = u.id
-            WHERE ucg.connect_group_id = $1::TEXT
-            "#,
-            &*id
-        )
-        .fetch_all(&db.db)
-        .await
-        .map_err(|e| match e {
-            sqlx::error::Error::RowNotFound => Error::NotFound(payload::Json(ErrorResponse {
-                message: format!("Connect group with id '{}' not found", &*id),
-            })),
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
-
-        Ok(Response::Ok(payload::Json(users)))
+        let fields = Fields::parse::<entities::User>(fields)?;
+
+        let scope = Scope::resolve(&auth, &db, Permission::UsersRead).await?;
+
+        let users = LISTING
+            .fetch_map(
+                &db.db,
+                &params,
+                |query| {
+                    scope.push_filter(query);
+                    query.push(" AND u.deleted IS FALSE");
+                    query
+                        .push(" AND ucg.connect_group_id = ")
+                        .push_bind(id.0.clone());
+                    if let Some(role_id) = &filter.role_id {
+                        query
+                            .push(" AND ucg.user_role = ")
+                            .push_bind(role_id.clone());
+                    }
+                },
+                |row: pii::UserRow| row.reveal(&keyring, &auth),
+            )
+            .await?;
+
+        Ok(Response::Ok(payload::Json(fields.project_page(users))))
     }
 }
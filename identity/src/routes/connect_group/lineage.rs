@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use poem::web;
+use poem_openapi::{param::Path, payload, Object};
+use serde::{Deserialize, Serialize};
+
+use common::{
+    database::Database,
+    error::Error,
+    projection::{Fields, Projected},
+};
+
+use crate::entities;
+
+/// A connect group along with the connect groups multiplied out of it, recursively.
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "ConnectGroupLineageNode")]
+pub struct Node {
+    #[oai(flatten)]
+    #[serde(flatten)]
+    pub connect_group: entities::ConnectGroup,
+    /// Time the connect group was multiplied out of its parent, NULL if it has none.
+    pub multiplied_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub children: Vec<Node>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "ConnectGroupLineage")]
+pub struct Lineage {
+    /// The connect groups the connect group was multiplied out of, from its parent up.
+    pub ancestors: Vec<entities::ConnectGroup>,
+    /// The connect group and its descendants.
+    pub tree: Node,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct Row {
+    #[sqlx(flatten)]
+    connect_group: entities::ConnectGroup,
+    parent_id: Option<String>,
+    multiplied_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Projected<Lineage>>),
+}
+
+/// Build the node of `row` from the descendants grouped by their parent.
+fn build(row: Row, descendants: &mut HashMap<String, Vec<Row>>) -> Node {
+    let children = descendants
+        .remove(&row.connect_group.id)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|child| build(child, descendants))
+        .collect();
+
+    Node {
+        connect_group: row.connect_group,
+        multiplied_at: row.multiplied_at,
+        children,
+    }
+}
+
+impl crate::routes::Routes {
+    pub async fn _get_connect_group_lineage(
+        &self,
+        db: web::Data<&Database>,
+        id: Path<String>,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<Lineage>(fields)?;
+
+        let root = sqlx::query_as::<_, Row>(
+            r#"
+            SELECT cg.*, l.parent_id, l.multiplied_at
+            FROM connect_group cg
+            LEFT JOIN connect_group_lineage l ON l.child_id = cg.id
+            WHERE cg.id = $1
+            "#,
+        )
+        .bind(&*id)
+        .fetch_one(&db.db)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::not_found(
+                "CONNECT_GROUP_NOT_FOUND",
+                format!("Connect group with id '{}' not found", &*id),
+            ),
+            _ => Error::from(e),
+        })?;
+
+        let ancestors = sqlx::query_as::<_, entities::ConnectGroup>(
+            r#"
+            WITH RECURSIVE ancestor AS (
+                SELECT l.parent_id AS id, 1 AS depth
+                FROM connect_group_lineage l
+                WHERE l.child_id = $1
+                UNION ALL
+                SELECT l.parent_id, a.depth + 1
+                FROM connect_group_lineage l
+                JOIN ancestor a ON l.child_id = a.id
+            )
+            SELECT cg.*
+            FROM ancestor a
+            JOIN connect_group cg ON cg.id = a.id
+            ORDER BY a.depth
+            "#,
+        )
+        .bind(&*id)
+        .fetch_all(&db.db)
+        .await?;
+
+        let descendants = sqlx::query_as::<_, Row>(
+            r#"
+            WITH RECURSIVE descendant AS (
+                SELECT l.child_id AS id, l.parent_id, l.multiplied_at
+                FROM connect_group_lineage l
+                WHERE l.parent_id = $1
+                UNION ALL
+                SELECT l.child_id, l.parent_id, l.multiplied_at
+                FROM connect_group_lineage l
+                JOIN descendant d ON l.parent_id = d.id
+            )
+            SELECT cg.*, d.parent_id, d.multiplied_at
+            FROM descendant d
+            JOIN connect_group cg ON cg.id = d.id
+            ORDER BY d.multiplied_at, cg.no, cg.variant
+            "#,
+        )
+        .bind(&*id)
+        .fetch_all(&db.db)
+        .await?;
+
+        let mut children = HashMap::<String, Vec<Row>>::new();
+        for row in descendants {
+            let parent_id = row.parent_id.clone().unwrap_or_default();
+            children.entry(parent_id).or_default().push(row);
+        }
+
+        Ok(Response::Ok(payload::Json(fields.project(Lineage {
+            ancestors,
+            tree: build(root, &mut children),
+        }))))
+    }
+}
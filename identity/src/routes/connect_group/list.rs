@@ -1,39 +1,84 @@
 use poem::web;
 use poem_openapi::payload;
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{
+    database::Database,
+    error::Error,
+    pagination::{self, Listing, Page, Sortable},
+    projection::{Fields, Projected},
+};
+
+use crate::entities;
+
+const LISTING: Listing = Listing {
+    select: "*",
+    from: "connect_group",
+    key: "id",
+    sortable: &[
+        Sortable {
+            name: "created_at",
+            expr: "created_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "updated_at",
+            expr: "updated_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "no",
+            expr: "no",
+            ty: "INTEGER",
+        },
+    ],
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub satellite_id: Option<String>,
+    pub category_id: Option<String>,
+    pub active: Option<bool>,
+    pub created_after: Option<chrono::DateTime<chrono::Utc>>,
+}
 
 #[derive(poem_openapi::ApiResponse)]
 pub enum Response {
     #[oai(status = 200)]
-    Ok(payload::Json<Vec<entities::ConnectGroup>>),
+    Ok(payload::Json<Page<Projected<entities::ConnectGroup>>>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
+impl crate::routes::Routes {
+    pub async fn _list_connect_groups(
+        &self,
+        db: web::Data<&Database>,
+        params: pagination::Params,
+        filter: Filter,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::ConnectGroup>(fields)?;
 
-    #[oai(status = 500)]
-    InternalServerError(payload::Json<ErrorResponse>),
-}
+        let cgs = LISTING
+            .fetch(&db.db, &params, |query| {
+                if let Some(satellite_id) = &filter.satellite_id {
+                    query
+                        .push(" AND satellite_id = ")
+                        .push_bind(satellite_id.clone());
+                }
+                if let Some(category_id) = &filter.category_id {
+                    query
+                        .push(" AND category_id = ")
+                        .push_bind(category_id.clone());
+                }
+                // Closed connect groups are only listed on request.
+                query
+                    .push(" AND active = ")
+                    .push_bind(filter.active.unwrap_or(true));
+                if let Some(created_after) = filter.created_after {
+                    query.push(" AND created_at > ").push_bind(created_after);
+                }
+            })
+            .await?;
 
-impl crate::routes::Routes {
-    pub async fn _list_connect_groups(&self, db: web::Data<&Database>) -> Result<Response, Error> {
-        let cgs = sqlx::query_as!(
-            entities::ConnectGroup,
-            r#"
-            SELECT * from connect_group
-            "#,
-        )
-        .fetch_all(&db.db)
-        .await
-        .map_err(|e| match e {
-            _ => Error::InternalServerError(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
-
-        Ok(Response::Ok(payload::Json(cgs)))
+        Ok(Response::Ok(payload::Json(fields.project_page(cgs))))
     }
 }
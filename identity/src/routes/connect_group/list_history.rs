@@ -0,0 +1,93 @@
+use poem::web;
+use poem_openapi::{param::Path, payload};
+
+use common::{
+    auth::BearerAuth,
+    database::Database,
+    error::Error,
+    pagination::{self, Listing, Page, Sortable},
+    projection::{Fields, Projected},
+};
+
+use crate::{authorization::Permission, entities, scope::Scope};
+
+pub(crate) const SELECT: &str =
+    "h.*, u.name AS user_name, cg.name AS connect_group_name, pr.name AS user_role_name";
+
+pub(crate) const FROM: &str = r#"
+    user_connect_group_history h
+    JOIN "user" u ON u.id = h.user_id
+    JOIN connect_group cg ON cg.id = h.connect_group_id
+    LEFT JOIN pastoral_role pr ON pr.id = h.user_role
+"#;
+
+const LISTING: Listing = Listing {
+    select: SELECT,
+    from: FROM,
+    // Padded so that the ids, which are compared as text, keep their order.
+    key: "lpad(h.id::TEXT, 19, '0')",
+    sortable: &[
+        Sortable {
+            name: "joined_at",
+            expr: "COALESCE(h.joined_at, '-infinity')",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "name",
+            expr: "u.name",
+            ty: "TEXT",
+        },
+    ],
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub at: Option<chrono::DateTime<chrono::Utc>>,
+    pub role_id: Option<String>,
+}
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Page<Projected<entities::ConnectGroupMembership>>>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _list_connect_group_history(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: Path<String>,
+        params: pagination::Params,
+        filter: Filter,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::ConnectGroupMembership>(fields)?;
+
+        let scope = Scope::resolve(&auth, &db, Permission::UsersRead).await?;
+
+        let memberships = LISTING
+            .fetch(&db.db, &params, |query| {
+                scope.push_filter(query);
+                query.push(" AND u.deleted IS FALSE");
+                query
+                    .push(" AND h.connect_group_id = ")
+                    .push_bind(id.0.clone());
+                if let Some(at) = filter.at {
+                    query
+                        .push(" AND COALESCE(h.joined_at, '-infinity') <= ")
+                        .push_bind(at)
+                        .push(" AND COALESCE(h.left_at, 'infinity') > ")
+                        .push_bind(at);
+                }
+                if let Some(role_id) = &filter.role_id {
+                    query.push(" AND h.user_role = ").push_bind(role_id.clone());
+                }
+            })
+            .await?;
+
+        Ok(Response::Ok(payload::Json(
+            fields.project_page(memberships),
+        )))
+    }
+}
@@ -1,8 +1,48 @@
 pub mod associate_users;
+pub mod close;
 pub mod create;
 pub mod delete;
 pub mod get;
 pub mod get_users;
+pub mod lineage;
 pub mod list;
+pub mod list_history;
+pub mod multiply;
 pub mod remove_users;
+pub mod reopen;
 pub mod update;
+
+use sqlx::PgConnection;
+
+use common::error::Error;
+
+use crate::entities;
+
+/// Lock a connect group for the rest of the transaction, refusing closed connect groups since
+/// nobody can join them.
+async fn lock_open(conn: &mut PgConnection, id: &str) -> Result<entities::ConnectGroup, Error> {
+    let cg = sqlx::query_as::<_, entities::ConnectGroup>(
+        r#"
+        SELECT * FROM connect_group WHERE id = $1 FOR UPDATE
+        "#,
+    )
+    .bind(id)
+    .fetch_one(conn)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::RowNotFound => Error::not_found(
+            "CONNECT_GROUP_NOT_FOUND",
+            format!("Connect group with id '{}' not found", id),
+        ),
+        _ => Error::from(e),
+    })?;
+
+    if !cg.active {
+        return Err(Error::conflict(
+            "CONNECT_GROUP_CLOSED",
+            format!("Connect group with id '{}' is closed", id),
+        ));
+    }
+
+    Ok(cg)
+}
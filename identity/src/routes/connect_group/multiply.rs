@@ -0,0 +1,176 @@
+use poem::web;
+use poem_openapi::{param::Path, payload, Object};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use common::{audit, auth::BearerAuth, database::Database, error::Error, outbox};
+
+use crate::{authorization::Permission, entities, membership};
+
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "MultiplyConnectGroupChild")]
+pub struct Child {
+    /// Name of the new connect group, the parent's name when omitted.
+    name: Option<String>,
+    #[oai(validator(max_length = 2))]
+    variant: String,
+    /// Members of the parent moving to the new connect group, they keep their roles.
+    #[oai(default)]
+    user_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "MultiplyConnectGroupRequest")]
+pub struct Request {
+    /// The connect groups to create, under the same number, satellite and category as the
+    /// parent.
+    #[oai(validator(min_items = 1))]
+    children: Vec<Child>,
+    /// Close the parent once multiplied, its remaining members stay in it.
+    close_parent: Option<bool>,
+}
+
+/// A multiplied connect group along with the connect groups created out of it.
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "ConnectGroupMultiplication")]
+pub struct Multiplication {
+    pub parent: entities::ConnectGroup,
+    pub children: Vec<entities::ConnectGroup>,
+}
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Multiplication>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _multiply_connect_group(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: Path<String>,
+        body: payload::Json<Request>,
+    ) -> Result<Response, Error> {
+        auth.require(&db, Permission::ConnectGroupsWrite).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        let mut parent = super::lock_open(&mut tx, &id).await?;
+
+        let mut children = Vec::with_capacity(body.children.len());
+        for child in &body.children {
+            let cg = sqlx::query_as::<_, entities::ConnectGroup>(
+                r#"
+                INSERT INTO connect_group (
+                    id,
+                    name,
+                    no,
+                    variant,
+                    satellite_id,
+                    category_id
+                ) VALUES (
+                    $1,
+                    $2,
+                    $3,
+                    $4,
+                    $5,
+                    $6
+                )
+                RETURNING *
+                "#,
+            )
+            .bind(format!("connect_group_{}", ulid::Ulid::new()))
+            .bind(child.name.as_ref().or(parent.name.as_ref()))
+            .bind(parent.no)
+            .bind(&child.variant)
+            .bind(&parent.satellite_id)
+            .bind(&parent.category_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO connect_group_lineage (child_id, parent_id) VALUES ($1, $2)
+                "#,
+            )
+            .bind(&cg.id)
+            .bind(&parent.id)
+            .execute(&mut *tx)
+            .await?;
+
+            // Moving the memberships rather than recreating them keeps the roles, and ends the
+            // period in the parent in the members' history.
+            membership::set_left_reason(
+                &mut tx,
+                Some(&format!("Multiplied into connect group '{}'", cg.id)),
+            )
+            .await?;
+            let moved = sqlx::query(
+                r#"
+                UPDATE user_connect_group SET connect_group_id = $1
+                WHERE connect_group_id = $2 AND user_id = ANY($3)
+                "#,
+            )
+            .bind(&cg.id)
+            .bind(&parent.id)
+            .bind(&child.user_ids)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+            if moved != child.user_ids.len() as u64 {
+                return Err(Error::bad_request(
+                    "USER_NOT_IN_CONNECT_GROUP",
+                    format!(
+                        "Every user moved to a new connect group must be a member of connect \
+                         group '{}', and moved only once",
+                        &*id
+                    ),
+                ));
+            }
+
+            children.push(cg);
+        }
+
+        if body.close_parent.unwrap_or(false) {
+            parent = sqlx::query_as::<_, entities::ConnectGroup>(
+                r#"
+                UPDATE connect_group SET
+                    active     = FALSE,
+                    closed_at  = NOW(),
+                    updated_at = NOW()
+                WHERE id = $1
+                RETURNING *
+                "#,
+            )
+            .bind(&parent.id)
+            .fetch_one(&mut *tx)
+            .await?;
+        }
+
+        outbox::publish(
+            &mut tx,
+            "connect_group.multiplied",
+            json!({
+                "connect_group_id": &parent.id,
+                "children": children
+                    .iter()
+                    .zip(&body.children)
+                    .map(|(cg, child)| json!({
+                        "connect_group_id": &cg.id,
+                        "user_ids": &child.user_ids,
+                    }))
+                    .collect::<Vec<_>>(),
+                "parent_closed": !parent.active,
+            }),
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Response::Ok(payload::Json(Multiplication {
+            parent,
+            children,
+        })))
+    }
+}
@@ -1,14 +1,19 @@
 use poem::web;
 use poem_openapi::{param::Path, payload, Object};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 
-use crate::{database::Database, error::ErrorResponse};
+use common::{audit, auth::BearerAuth, database::Database, error::Error, outbox};
+
+use crate::{authorization::Permission, membership};
 
 #[derive(Debug, Clone, Deserialize, Serialize, Object)]
 #[oai(rename = "RemoveUsersFromConnectGroupRequest")]
 pub struct Request {
     #[oai(validator(min_items = 1, unique_items = true))]
     users: Vec<String>,
+    /// Why the users are leaving, kept in their membership history.
+    reason: Option<String>,
 }
 
 #[derive(poem_openapi::ApiResponse)]
@@ -17,25 +22,20 @@ pub enum Response {
     Ok(payload::Json<String>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
-}
-
 impl crate::routes::Routes {
     pub async fn _remove_users_from_connect_group(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
         body: payload::Json<Request>,
     ) -> Result<Response, Error> {
+        auth.require(&db, Permission::ConnectGroupsWrite).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        membership::set_left_reason(&mut tx, body.reason.as_deref()).await?;
+
         sqlx::query!(
             r#"
             DELETE FROM 
@@ -47,13 +47,17 @@ impl crate::routes::Routes {
             &*id,
             &body.users
         )
-        .execute(&db.db)
-        .await
-        .map_err(|e| match e {
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
+        .execute(&mut *tx)
+        .await?;
+
+        outbox::publish(
+            &mut tx,
+            "connect_group.users_left",
+            json!({ "connect_group_id": &*id, "users": &body.users, "reason": &body.reason }),
+        )
+        .await?;
+
+        tx.commit().await?;
 
         Ok(Response::Ok(payload::Json("success".to_string())))
     }
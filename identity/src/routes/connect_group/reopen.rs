@@ -0,0 +1,73 @@
+use poem::web;
+use poem_openapi::{param::Path, payload};
+use serde_json::json;
+
+use common::{audit, auth::BearerAuth, database::Database, error::Error, outbox};
+
+use crate::{authorization::Permission, entities};
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<entities::ConnectGroup>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _reopen_connect_group(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: Path<String>,
+    ) -> Result<Response, Error> {
+        auth.require(&db, Permission::ConnectGroupsWrite).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        let active = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT active FROM connect_group WHERE id = $1 FOR UPDATE
+            "#,
+        )
+        .bind(&*id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::not_found(
+                "CONNECT_GROUP_NOT_FOUND",
+                format!("Connect group with id '{}' not found", &*id),
+            ),
+            _ => Error::from(e),
+        })?;
+        if active {
+            return Err(Error::conflict(
+                "CONNECT_GROUP_NOT_CLOSED",
+                format!("Connect group with id '{}' is not closed", &*id),
+            ));
+        }
+
+        let cg = sqlx::query_as::<_, entities::ConnectGroup>(
+            r#"
+            UPDATE connect_group SET
+                active     = TRUE,
+                closed_at  = NULL,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(&*id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        outbox::publish(
+            &mut tx,
+            "connect_group.reopened",
+            json!({ "connect_group_id": &*id }),
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Response::Ok(payload::Json(cg)))
+    }
+}
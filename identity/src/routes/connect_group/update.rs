@@ -1,7 +1,9 @@
 use poem::web;
 use poem_openapi::{param::Path, payload, Object};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{audit, auth::BearerAuth, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Deserialize, Serialize, Object)]
@@ -13,8 +15,6 @@ pub struct Request {
     variant: Option<String>,
     satellite_id: Option<String>,
     category_id: Option<String>,
-    active: Option<bool>,
-    closed_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 #[derive(poem_openapi::ApiResponse)]
@@ -23,27 +23,19 @@ pub enum Response {
     Ok(payload::Json<entities::ConnectGroup>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
-}
-
 impl crate::routes::Routes {
     pub async fn _update_connect_group(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
         body: payload::Json<Request>,
     ) -> Result<Response, Error> {
-        let cg = sqlx::query_as_unchecked!(
-            entities::ConnectGroup,
+        auth.require(&db, Permission::ConnectGroupsWrite).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        let cg = sqlx::query_as::<_, entities::ConnectGroup>(
             r#"
             UPDATE connect_group SET
                 no           = COALESCE($1, no),
@@ -51,32 +43,29 @@ impl crate::routes::Routes {
                 variant      = COALESCE($3, variant),
                 satellite_id = COALESCE($4, satellite_id),
                 category_id  = COALESCE($5, category_id),
-                active       = COALESCE($6, active),
-                closed_at    = COALESCE($7, closed_at),
                 updated_at   = NOW()
-            WHERE id = $8
+            WHERE id = $6
             RETURNING *
             "#,
-            &body.no,
-            &body.name,
-            &body.variant,
-            &body.satellite_id,
-            &body.category_id,
-            &body.active,
-            &body.closed_at,
-            &*id,
         )
-        .fetch_one(&db.db)
+        .bind(body.no)
+        .bind(&body.name)
+        .bind(&body.variant)
+        .bind(&body.satellite_id)
+        .bind(&body.category_id)
+        .bind(&*id)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| match e {
-            sqlx::error::Error::RowNotFound => Error::NotFound(payload::Json(ErrorResponse {
-                message: format!("Connect group with id '{}' not found", &*id),
-            })),
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
+            sqlx::Error::RowNotFound => Error::not_found(
+                "CONNECT_GROUP_NOT_FOUND",
+                format!("Connect group with id '{}' not found", &*id),
+            ),
+            _ => Error::from(e),
         })?;
 
+        tx.commit().await?;
+
         Ok(Response::Ok(payload::Json(cg)))
     }
 }
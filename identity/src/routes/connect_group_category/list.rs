@@ -1,42 +1,43 @@
 use poem::web;
 use poem_openapi::payload;
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{
+    database::Database,
+    error::Error,
+    pagination::{self, Listing, Page, Sortable},
+    projection::{Fields, Projected},
+};
+
+use crate::entities;
+
+const LISTING: Listing = Listing {
+    select: "*",
+    from: "connect_group_category",
+    key: "id",
+    sortable: &[Sortable {
+        name: "name",
+        expr: "name",
+        ty: "TEXT",
+    }],
+};
 
 #[derive(poem_openapi::ApiResponse)]
 pub enum Response {
     #[oai(status = 200)]
-    Ok(payload::Json<Vec<entities::ConnectGroupCategory>>),
-}
-
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServerError(payload::Json<ErrorResponse>),
+    Ok(payload::Json<Page<Projected<entities::ConnectGroupCategory>>>),
 }
 
 impl crate::routes::Routes {
     pub async fn _list_connect_group_categories(
         &self,
         db: web::Data<&Database>,
+        params: pagination::Params,
+        fields: Option<String>,
     ) -> Result<Response, Error> {
-        let cgs = sqlx::query_as!(
-            entities::ConnectGroupCategory,
-            r#"
-            SELECT * from connect_group_category
-            "#,
-        )
-        .fetch_all(&db.db)
-        .await
-        .map_err(|e| match e {
-            _ => Error::InternalServerError(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
+        let fields = Fields::parse::<entities::ConnectGroupCategory>(fields)?;
+
+        let cgs = LISTING.fetch(&db.db, &params, |_| {}).await?;
 
-        Ok(Response::Ok(payload::Json(cgs)))
+        Ok(Response::Ok(payload::Json(fields.project_page(cgs))))
     }
 }
@@ -0,0 +1,66 @@
+use poem::web;
+use poem_openapi::{payload, Object};
+use serde::{Deserialize, Serialize};
+
+use common::{audit, auth::BearerAuth, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities};
+
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "CreateHouseholdRequest")]
+pub struct Request {
+    name: String,
+    address: Option<entities::Address>,
+    /// The members of the household.
+    #[oai(default, validator(unique_items = true))]
+    user_ids: Vec<String>,
+}
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<super::Detail>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _create_household(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        body: payload::Json<Request>,
+    ) -> Result<Response, Error> {
+        auth.require(&db, Permission::UsersWrite).await?;
+        super::require_users_accessible(&auth, &db, &body.user_ids).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        let id = format!("household_{}", ulid::Ulid::new());
+        sqlx::query(
+            r#"
+            INSERT INTO household (id, name, address) VALUES ($1, $2, $3)
+            "#,
+        )
+        .bind(&id)
+        .bind(&body.name)
+        .bind(&body.address)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO user_household (household_id, user_id)
+            SELECT $1, user_id FROM UNNEST($2::TEXT[]) AS user_id
+            "#,
+        )
+        .bind(&id)
+        .bind(&body.user_ids)
+        .execute(&mut *tx)
+        .await?;
+
+        let household = super::fetch(&mut tx, &id).await?;
+
+        tx.commit().await?;
+
+        Ok(Response::Ok(payload::Json(household)))
+    }
+}
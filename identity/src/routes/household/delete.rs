@@ -0,0 +1,43 @@
+use poem::web;
+use poem_openapi::{param::Path, payload};
+
+use common::{audit, auth::BearerAuth, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities};
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<entities::Household>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _delete_household(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: Path<String>,
+    ) -> Result<Response, Error> {
+        auth.require(&db, Permission::UsersWrite).await?;
+        super::require_access(&auth, &db, &id, Permission::UsersWrite).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        let household = sqlx::query_as::<_, entities::Household>(
+            r#"
+            DELETE FROM household WHERE id = $1 RETURNING *
+            "#,
+        )
+        .bind(&*id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => super::not_found(&id),
+            _ => Error::from(e),
+        })?;
+
+        tx.commit().await?;
+
+        Ok(Response::Ok(payload::Json(household)))
+    }
+}
@@ -0,0 +1,36 @@
+use poem::web;
+use poem_openapi::{param::Path, payload};
+
+use common::{
+    auth::BearerAuth,
+    database::Database,
+    error::Error,
+    projection::{Fields, Projected},
+};
+
+use crate::authorization::Permission;
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Projected<super::Detail>>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _get_household(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: Path<String>,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<super::Detail>(fields)?;
+
+        super::require_access(&auth, &db, &id, Permission::UsersRead).await?;
+
+        let mut conn = db.db.acquire().await?;
+        let household = super::fetch(&mut conn, &id).await?;
+
+        Ok(Response::Ok(payload::Json(fields.project(household))))
+    }
+}
@@ -0,0 +1,117 @@
+pub mod add_users;
+pub mod create;
+pub mod delete;
+pub mod get;
+pub mod remove_users;
+pub mod update;
+
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgConnection, QueryBuilder};
+
+use common::{auth::BearerAuth, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities, scope::Scope};
+
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "HouseholdDetail")]
+pub struct Detail {
+    #[oai(flatten)]
+    #[serde(flatten)]
+    pub household: entities::Household,
+    /// Deleted users are left out.
+    pub members: Vec<entities::HouseholdMember>,
+}
+
+fn not_found(id: &str) -> Error {
+    Error::not_found(
+        "HOUSEHOLD_NOT_FOUND",
+        format!("Household with id '{}' not found", id),
+    )
+}
+
+/// Fetch a household along with its members.
+async fn fetch(conn: &mut PgConnection, id: &str) -> Result<Detail, Error> {
+    let household = sqlx::query_as::<_, entities::Household>(
+        r#"
+        SELECT * FROM household WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::RowNotFound => not_found(id),
+        _ => Error::from(e),
+    })?;
+
+    let members = sqlx::query_as::<_, entities::HouseholdMember>(
+        r#"
+        SELECT u.id AS user_id, u.name, uh.created_at
+        FROM
+            user_household uh
+                INNER JOIN "user" u ON u.id = uh.user_id
+        WHERE uh.household_id = $1 AND u.deleted IS FALSE
+        ORDER BY uh.created_at, u.name
+        "#,
+    )
+    .bind(id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(Detail { household, members })
+}
+
+/// Ensure the household identified by `id` is within the scope resolved for `permission`, that is
+/// one of its members is unless the scope is every user.
+async fn require_access(
+    auth: &BearerAuth,
+    db: &Database,
+    id: &str,
+    permission: Permission,
+) -> Result<(), Error> {
+    let scope = Scope::resolve(auth, db, permission).await?;
+    if let Scope::All = scope {
+        return Ok(());
+    }
+
+    let mut query = QueryBuilder::new(
+        r#"
+        SELECT EXISTS (
+            SELECT 1
+            FROM
+                user_household uh
+                    INNER JOIN "user" u ON u.id = uh.user_id
+            WHERE uh.household_id = "#,
+    );
+    query.push_bind(id.to_string());
+    query.push(" AND u.deleted IS FALSE");
+    scope.push_filter(&mut query);
+    query.push(")");
+
+    let accessible = query.build_query_scalar::<bool>().fetch_one(&db.db).await?;
+    if !accessible {
+        return Err(not_found(id));
+    }
+
+    Ok(())
+}
+
+/// Ensure the users identified by `user_ids` are within the scope of the authenticated user.
+async fn require_users_accessible(
+    auth: &BearerAuth,
+    db: &Database,
+    user_ids: &[String],
+) -> Result<(), Error> {
+    let scope = Scope::resolve(auth, db, Permission::UsersWrite).await?;
+    for user_id in user_ids {
+        if !scope.contains(db, user_id).await? {
+            return Err(Error::not_found(
+                "USER_NOT_FOUND",
+                format!("User with id '{}' not found", user_id),
+            ));
+        }
+    }
+
+    Ok(())
+}
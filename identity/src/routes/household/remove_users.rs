@@ -0,0 +1,51 @@
+use poem::web;
+use poem_openapi::{param::Path, payload, Object};
+use serde::{Deserialize, Serialize};
+
+use common::{audit, auth::BearerAuth, database::Database, error::Error};
+
+use crate::authorization::Permission;
+
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "RemoveUsersFromHouseholdRequest")]
+pub struct Request {
+    #[oai(validator(min_items = 1, unique_items = true))]
+    user_ids: Vec<String>,
+}
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<super::Detail>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _remove_users_from_household(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: Path<String>,
+        body: payload::Json<Request>,
+    ) -> Result<Response, Error> {
+        auth.require(&db, Permission::UsersWrite).await?;
+        super::require_access(&auth, &db, &id, Permission::UsersWrite).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM user_household WHERE household_id = $1 AND user_id = ANY($2)
+            "#,
+        )
+        .bind(&*id)
+        .bind(&body.user_ids)
+        .execute(&mut *tx)
+        .await?;
+
+        let household = super::fetch(&mut tx, &id).await?;
+
+        tx.commit().await?;
+
+        Ok(Response::Ok(payload::Json(household)))
+    }
+}
@@ -0,0 +1,61 @@
+use poem::web;
+use poem_openapi::{param::Path, payload, Object};
+use serde::{Deserialize, Serialize};
+
+use common::{audit, auth::BearerAuth, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities};
+
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "UpdateHouseholdRequest")]
+pub struct Request {
+    name: Option<String>,
+    address: Option<entities::Address>,
+}
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<super::Detail>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _update_household(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: Path<String>,
+        body: payload::Json<Request>,
+    ) -> Result<Response, Error> {
+        auth.require(&db, Permission::UsersWrite).await?;
+        super::require_access(&auth, &db, &id, Permission::UsersWrite).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        sqlx::query_as::<_, entities::Household>(
+            r#"
+            UPDATE household SET
+                name       = COALESCE($1, name),
+                address    = COALESCE($2, address),
+                updated_at = NOW()
+            WHERE id = $3
+            RETURNING *
+            "#,
+        )
+        .bind(&body.name)
+        .bind(&body.address)
+        .bind(&*id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => super::not_found(&id),
+            _ => Error::from(e),
+        })?;
+
+        let household = super::fetch(&mut tx, &id).await?;
+
+        tx.commit().await?;
+
+        Ok(Response::Ok(payload::Json(household)))
+    }
+}
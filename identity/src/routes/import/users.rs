@@ -0,0 +1,589 @@
+use std::collections::HashMap;
+
+use chrono::{Datelike, TimeZone};
+use poem::web;
+use poem_openapi::{
+    payload,
+    types::multipart::{JsonField, Upload},
+    Multipart, Object,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use common::{audit, auth::BearerAuth, crypto::Keyring, database::Database, error::Error, outbox};
+
+use crate::{authorization::Permission, entities, import, pii};
+
+/// Users imported without an email are given a placeholder one on this reserved domain, since
+/// every user has an email.
+const PLACEHOLDER_EMAIL_DOMAIN: &str = "import.invalid";
+
+/// Which columns of the file hold which fields, by their header. Only the name is required.
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "ImportUsersMapping")]
+pub struct Mapping {
+    name: String,
+    email: Option<String>,
+    phone_number: Option<String>,
+    ic_number: Option<String>,
+    date_of_birth: Option<String>,
+    satellite: Option<String>,
+    /// The connect group, either its name or its number and variant (e.g. `01J`).
+    connect_group: Option<String>,
+    /// The pastoral role the user joins the connect group with.
+    pastoral_role: Option<String>,
+    /// Values of the satellite column standing for a satellite's name.
+    #[oai(default)]
+    satellite_aliases: HashMap<String, String>,
+    /// Values of the pastoral role column standing for a pastoral role's name (e.g. `CGL` for
+    /// `Connect Group Leader`).
+    #[oai(default)]
+    pastoral_role_aliases: HashMap<String, String>,
+    /// The address of the satellites the import creates, without it satellites that do not
+    /// exist are reported as errors.
+    satellite_address: Option<entities::Address>,
+}
+
+#[derive(Debug, Multipart)]
+pub struct Request {
+    /// A CSV or XLSX file starting with a header row.
+    file: Upload,
+    mapping: JsonField<Mapping>,
+    /// Validate the file and report what the import would change without changing anything.
+    dry_run: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "UserImportError")]
+pub struct RowError {
+    /// The line of the file, the header being line 1.
+    pub row: i32,
+    /// The header of the column at fault.
+    pub column: Option<String>,
+    pub code: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, Object)]
+#[oai(rename = "UserImport")]
+pub struct Report {
+    pub dry_run: bool,
+    /// Whether the import was committed, which it only is without any error.
+    pub committed: bool,
+    /// The rows read, leaving out blank ones.
+    pub rows: i32,
+    pub users_created: i32,
+    /// Rows matching an existing user by email, phone number, IC number, or name and date of
+    /// birth.
+    pub users_matched: i32,
+    pub satellites_created: i32,
+    pub memberships_created: i32,
+    pub errors: Vec<RowError>,
+}
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Report>),
+}
+
+/// The index of each mapped column.
+struct Columns {
+    name: usize,
+    email: Option<usize>,
+    phone_number: Option<usize>,
+    ic_number: Option<usize>,
+    date_of_birth: Option<usize>,
+    satellite: Option<usize>,
+    connect_group: Option<usize>,
+    pastoral_role: Option<usize>,
+}
+
+impl Columns {
+    fn resolve(table: &import::Table, mapping: &Mapping) -> Result<Self, Error> {
+        let column = |header: &str| {
+            table.column(header).ok_or_else(|| {
+                Error::bad_request(
+                    "IMPORT_COLUMN_NOT_FOUND",
+                    format!("Column '{}' not found in the file", header),
+                )
+            })
+        };
+        let optional = |header: &Option<String>| header.as_deref().map(column).transpose();
+
+        Ok(Self {
+            name: column(&mapping.name)?,
+            email: optional(&mapping.email)?,
+            phone_number: optional(&mapping.phone_number)?,
+            ic_number: optional(&mapping.ic_number)?,
+            date_of_birth: optional(&mapping.date_of_birth)?,
+            satellite: optional(&mapping.satellite)?,
+            connect_group: optional(&mapping.connect_group)?,
+            pastoral_role: optional(&mapping.pastoral_role)?,
+        })
+    }
+}
+
+/// Look `value` up in `aliases`, ignoring case.
+fn unalias<'a>(aliases: &'a HashMap<String, String>, value: &'a str) -> &'a str {
+    aliases
+        .iter()
+        .find(|(alias, _)| alias.trim().eq_ignore_ascii_case(value))
+        .map_or(value, |(_, name)| name.as_str())
+}
+
+/// Split a connect group written as its number and variant, e.g. `01J` into `1` and `J`.
+fn split_connect_group(value: &str) -> Option<(i32, String)> {
+    let digits = value.chars().take_while(char::is_ascii_digit).count();
+    let (no, variant) = value.split_at(digits);
+    let variant = variant.trim();
+    if variant.len() > 2 || !variant.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    Some((no.parse().ok()?, variant.to_ascii_uppercase()))
+}
+
+/// A user that a row of the file may refer to, `identified` when it shares their email, phone
+/// number or IC number rather than just their name.
+#[derive(sqlx::FromRow)]
+struct Candidate {
+    #[sqlx(flatten)]
+    user: pii::UserRow,
+    identified: bool,
+}
+
+impl crate::routes::Routes {
+    pub async fn _import_users(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        keyring: web::Data<&Keyring>,
+        body: Request,
+    ) -> Result<Response, Error> {
+        auth.require(&db, Permission::UsersAdmin).await?;
+
+        let mapping = body.mapping.0;
+        if mapping.satellite_address.is_some() {
+            auth.require(&db, Permission::SatellitesWrite).await?;
+        }
+
+        let content = body.file.into_vec().await.map_err(|e| {
+            Error::bad_request("IMPORT_FILE_INVALID", format!("Unreadable file: {}", e))
+        })?;
+        let table = import::read_table(&content).map_err(|e| {
+            Error::bad_request("IMPORT_FILE_INVALID", format!("Unreadable file: {}", e))
+        })?;
+        let columns = Columns::resolve(&table, &mapping)?;
+
+        let mut report = Report {
+            dry_run: body.dry_run.unwrap_or(false),
+            ..Default::default()
+        };
+
+        // Rows are imported in a single transaction, so that a dry run makes the same changes
+        // and lookups as the import itself before rolling them back.
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        let mut satellites = sqlx::query_as::<_, (String, String)>(
+            r#"
+            SELECT lower(name), id FROM satellite
+            "#,
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+        let pastoral_roles = sqlx::query_as::<_, (String, String)>(
+            r#"
+            SELECT lower(name), id FROM pastoral_role
+            "#,
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+        let mut connect_groups = HashMap::<(String, String), Option<String>>::new();
+        let mut joined = HashMap::<String, Vec<serde_json::Value>>::new();
+
+        for (i, cells) in table.rows.iter().enumerate() {
+            let row = i as i32 + 2;
+            let cell = |column: Option<usize>| {
+                column
+                    .and_then(|column| cells.get(column))
+                    .map(|value| value.trim())
+                    .filter(|value| !value.is_empty())
+            };
+            if cells.iter().all(|value| value.trim().is_empty()) {
+                continue;
+            }
+            report.rows += 1;
+
+            let mut errors = Vec::new();
+            let mut error = |header: Option<&String>, code: &str, message: String| {
+                errors.push(RowError {
+                    row,
+                    column: header.cloned(),
+                    code: code.to_string(),
+                    message,
+                })
+            };
+
+            let name = cell(Some(columns.name));
+            if name.is_none() {
+                error(
+                    Some(&mapping.name),
+                    "NAME_REQUIRED",
+                    "The name is missing".to_string(),
+                );
+            }
+
+            let email = cell(columns.email);
+            if let Some(email) = email.filter(|email| !email.contains('@')) {
+                error(
+                    mapping.email.as_ref(),
+                    "INVALID_EMAIL",
+                    format!("'{}' is not an email", email),
+                );
+            }
+
+            let phone_number = cell(columns.phone_number).and_then(|value| {
+                let e164 = import::to_e164(value);
+                if e164.is_none() {
+                    error(
+                        mapping.phone_number.as_ref(),
+                        "INVALID_PHONE_NUMBER",
+                        format!("'{}' is not a phone number", value),
+                    );
+                }
+                e164
+            });
+
+            let date_of_birth = cell(columns.date_of_birth).and_then(|value| {
+                let date = import::parse_date(value);
+                if date.is_none() {
+                    error(
+                        mapping.date_of_birth.as_ref(),
+                        "INVALID_DATE",
+                        format!("'{}' is not a date, such as 11.6.1989 or 1989-06-11", value),
+                    );
+                }
+                date
+            });
+
+            let mut satellite_id = None;
+            if let Some(value) = cell(columns.satellite) {
+                let satellite = unalias(&mapping.satellite_aliases, value);
+                satellite_id = satellites.get(&satellite.to_lowercase()).cloned();
+                if satellite_id.is_none() {
+                    match &mapping.satellite_address {
+                        Some(address) => {
+                            let id = format!("satellite_{}", ulid::Ulid::new());
+                            sqlx::query(
+                                r#"
+                                INSERT INTO satellite (id, name, address) VALUES ($1, $2, $3)
+                                "#,
+                            )
+                            .bind(&id)
+                            .bind(satellite)
+                            .bind(address)
+                            .execute(&mut *tx)
+                            .await?;
+
+                            satellites.insert(satellite.to_lowercase(), id.clone());
+                            report.satellites_created += 1;
+                            satellite_id = Some(id);
+                        }
+                        None => error(
+                            mapping.satellite.as_ref(),
+                            "SATELLITE_NOT_FOUND",
+                            format!("Satellite '{}' not found", satellite),
+                        ),
+                    }
+                }
+            }
+
+            let mut pastoral_role_id = None;
+            if let Some(value) = cell(columns.pastoral_role) {
+                let pastoral_role = unalias(&mapping.pastoral_role_aliases, value);
+                pastoral_role_id = pastoral_roles.get(&pastoral_role.to_lowercase()).cloned();
+                if pastoral_role_id.is_none() {
+                    error(
+                        mapping.pastoral_role.as_ref(),
+                        "PASTORAL_ROLE_NOT_FOUND",
+                        format!("Pastoral role '{}' not found", pastoral_role),
+                    );
+                }
+            }
+
+            let mut connect_group_id = None;
+            if let Some(value) = cell(columns.connect_group) {
+                match &satellite_id {
+                    Some(satellite_id) => {
+                        let key = (satellite_id.clone(), value.to_lowercase());
+                        if !connect_groups.contains_key(&key) {
+                            let (no, variant) = split_connect_group(value).unzip();
+                            let id = sqlx::query_scalar::<_, String>(
+                                r#"
+                                SELECT id FROM connect_group
+                                WHERE
+                                    satellite_id = $1
+                                    AND active
+                                    AND (
+                                        lower(name) = lower($2)
+                                        OR (no = $3 AND COALESCE(trim(variant), '') = $4)
+                                    )
+                                ORDER BY lower(name) = lower($2) IS TRUE DESC
+                                LIMIT 1
+                                "#,
+                            )
+                            .bind(satellite_id)
+                            .bind(value)
+                            .bind(no)
+                            .bind(variant)
+                            .fetch_optional(&mut *tx)
+                            .await?;
+                            connect_groups.insert(key.clone(), id);
+                        }
+
+                        connect_group_id = connect_groups[&key].clone();
+                        if connect_group_id.is_none() {
+                            error(
+                                mapping.connect_group.as_ref(),
+                                "CONNECT_GROUP_NOT_FOUND",
+                                format!("No open connect group '{}' in the satellite", value),
+                            );
+                        } else if pastoral_role_id.is_none()
+                            && cell(columns.pastoral_role).is_none()
+                        {
+                            error(
+                                mapping.pastoral_role.as_ref(),
+                                "PASTORAL_ROLE_REQUIRED",
+                                "A pastoral role is required to join the connect group".to_string(),
+                            );
+                        }
+                    }
+                    None if cell(columns.satellite).is_none() => error(
+                        mapping.satellite.as_ref(),
+                        "SATELLITE_REQUIRED",
+                        "The connect group is looked up in the satellite, which is missing"
+                            .to_string(),
+                    ),
+                    None => {}
+                }
+            }
+
+            let Some(name) = name.filter(|_| errors.is_empty()) else {
+                report.errors.append(&mut errors);
+                continue;
+            };
+
+            // Importing the same file again matches the users it created. Dates of birth are
+            // encrypted, users of the same name are narrowed down by year before comparing them.
+            // Deleted users are looked up too, their email and IC number are still taken.
+            let candidates = sqlx::query_as::<_, Candidate>(
+                r#"
+                SELECT
+                    *,
+                    COALESCE(
+                        normalise_email(email) = normalise_email($1)
+                            OR phone_number_index = $2
+                            OR ic_number_index = $3,
+                        FALSE
+                    ) AS identified
+                FROM "user"
+                WHERE
+                    normalise_email(email) = normalise_email($1)
+                    OR phone_number_index = $2
+                    OR ic_number_index = $3
+                    OR (
+                        deleted IS FALSE
+                        AND lower(name) = lower($4)
+                        AND ($5::INTEGER IS NULL OR year_of_birth IS NULL OR year_of_birth = $5)
+                    )
+                "#,
+            )
+            .bind(email)
+            .bind(match &phone_number {
+                Some(phone_number) => pii::phone_number_index(&keyring, phone_number)?,
+                None => None,
+            })
+            .bind(match cell(columns.ic_number) {
+                Some(ic_number) => pii::ic_number_index(&keyring, ic_number)?,
+                None => None,
+            })
+            .bind(name)
+            .bind(date_of_birth.map(|date| date.year()))
+            .fetch_all(&mut *tx)
+            .await?;
+
+            // Users only sharing the name are the same person when they share the date of birth
+            // too, and cannot be told apart from a namesake when either date is missing.
+            let mut identified = Vec::new();
+            let mut namesakes = Vec::new();
+            let mut unsure = false;
+            for candidate in candidates {
+                let user = candidate.user.open(&keyring)?;
+                if candidate.identified {
+                    identified.push(user);
+                    continue;
+                }
+                match (
+                    user.date_of_birth.map(|date| date.date_naive()),
+                    date_of_birth,
+                ) {
+                    (Some(theirs), Some(ours)) if theirs == ours => namesakes.push(user),
+                    (Some(_), Some(_)) => {}
+                    _ => unsure = true,
+                }
+            }
+            let matches = if identified.is_empty() {
+                namesakes
+            } else {
+                identified
+            };
+
+            let user_id = match matches.as_slice() {
+                [user] if user.deleted => {
+                    report.errors.push(RowError {
+                        row,
+                        column: None,
+                        code: "USER_DELETED".to_string(),
+                        message: format!(
+                            "'{}' matches the deleted user '{}', restore them first",
+                            name, user.id
+                        ),
+                    });
+                    continue;
+                }
+                [user] => {
+                    report.users_matched += 1;
+                    user.id.clone()
+                }
+                [] if unsure => {
+                    report.errors.push(RowError {
+                        row,
+                        column: None,
+                        code: "USER_AMBIGUOUS".to_string(),
+                        message: format!(
+                            "'{}' shares the name of an existing user, add their email, phone \
+                             number, IC number or date of birth to tell them apart",
+                            name
+                        ),
+                    });
+                    continue;
+                }
+                [] => {
+                    let id = format!("user_{}", ulid::Ulid::new());
+                    let sealed = pii::Details {
+                        ic_number: cell(columns.ic_number).map(str::to_string),
+                        phone_number,
+                        address: None,
+                        date_of_birth: date_of_birth.map(|date| {
+                            chrono::Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+                        }),
+                    }
+                    .seal(&keyring)?;
+
+                    let user = sqlx::query_as::<_, pii::UserRow>(
+                        r#"
+                        INSERT INTO "user" (
+                            id,
+                            name,
+                            email,
+                            phone_number_encrypted,
+                            phone_number_index,
+                            ic_number_encrypted,
+                            ic_number_index,
+                            date_of_birth_encrypted,
+                            year_of_birth
+                        ) VALUES (
+                            $1,
+                            $2,
+                            COALESCE($3, $1 || '@' || $4),
+                            $5,
+                            $6,
+                            $7,
+                            $8,
+                            $9,
+                            $10
+                        )
+                        RETURNING *
+                        "#,
+                    )
+                    .bind(&id)
+                    .bind(name)
+                    .bind(email)
+                    .bind(PLACEHOLDER_EMAIL_DOMAIN)
+                    .bind(&sealed.phone_number)
+                    .bind(&sealed.phone_number_index)
+                    .bind(&sealed.ic_number)
+                    .bind(&sealed.ic_number_index)
+                    .bind(&sealed.date_of_birth)
+                    .bind(sealed.year_of_birth)
+                    .fetch_one(&mut *tx)
+                    .await?
+                    .open(&keyring)?;
+
+                    outbox::publish(&mut tx, "user.created", &pii::mask(user)).await?;
+
+                    report.users_created += 1;
+                    id
+                }
+                _ => {
+                    report.errors.push(RowError {
+                        row,
+                        column: None,
+                        code: "USER_AMBIGUOUS".to_string(),
+                        message: format!("'{}' matches several existing users", name),
+                    });
+                    continue;
+                }
+            };
+
+            if let Some((connect_group_id, pastoral_role_id)) =
+                connect_group_id.zip(pastoral_role_id)
+            {
+                let joined_now = sqlx::query(
+                    r#"
+                    INSERT INTO user_connect_group (user_id, connect_group_id, user_role)
+                    VALUES ($1, $2, $3)
+                    ON CONFLICT (user_id, connect_group_id) DO NOTHING
+                    "#,
+                )
+                .bind(&user_id)
+                .bind(&connect_group_id)
+                .bind(&pastoral_role_id)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected()
+                    > 0;
+
+                if joined_now {
+                    report.memberships_created += 1;
+                    joined
+                        .entry(connect_group_id)
+                        .or_default()
+                        .push(json!({ "user_id": user_id, "role_id": pastoral_role_id }));
+                }
+            }
+        }
+
+        for (connect_group_id, users) in joined {
+            outbox::publish(
+                &mut tx,
+                "connect_group.users_joined",
+                json!({ "connect_group_id": connect_group_id, "users": users }),
+            )
+            .await?;
+        }
+
+        if report.dry_run || !report.errors.is_empty() {
+            tx.rollback().await?;
+        } else {
+            tx.commit().await?;
+            report.committed = true;
+        }
+
+        Ok(Response::Ok(payload::Json(report)))
+    }
+}
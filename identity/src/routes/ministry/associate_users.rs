@@ -1,8 +1,11 @@
 use poem::web;
 use poem_openapi::{param::Path, payload, Object};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 
-use crate::{database::Database, error::ErrorResponse};
+use common::{audit, auth::BearerAuth, database::Database, error::Error, outbox};
+
+use crate::authorization::Permission;
 
 #[derive(Debug, Clone, Deserialize, Serialize, Object, PartialEq, Eq, Hash)]
 #[oai(rename = "AssociateUsersWithMinistryRequestUser")]
@@ -24,25 +27,25 @@ pub enum Response {
     Ok(payload::Json<String>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
-}
-
 impl crate::routes::Routes {
     pub async fn _associate_users_with_ministry(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
         body: payload::Json<Request>,
     ) -> Result<Response, Error> {
+        auth.require(&db, Permission::MinistriesWrite).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        let user_ids = body
+            .users
+            .iter()
+            .map(|user| user.user_id.as_str())
+            .collect::<Vec<_>>();
+        crate::routes::users::require_active(&mut *tx, &user_ids).await?;
+
         sqlx::QueryBuilder::new(
             r#"INSERT INTO user_ministry (
                 user_id, 
@@ -60,53 +63,17 @@ impl crate::routes::Routes {
 						SET user_role = EXCLUDED.user_role",
         )
         .build()
-        .execute(&db.db)
-        .await
-        .map_err(|e| match e {
-            sqlx::Error::Database(e)
-                if e.is_unique_violation()
-                    && e.constraint()
-                        .is_some_and(|constraint| constraint == "user_ministry_pkey") =>
-            {
-                Error::BadRequest(payload::Json(ErrorResponse {
-                    message: format!(
-                        "One of the user in the list is already associated with the ministry '{}'",
-                        &*id
-                    ),
-                }))
-            }
-            sqlx::Error::Database(e)
-                if e.is_foreign_key_violation()
-                    && e.constraint().is_some_and(|constraint| {
-                        constraint == "user_ministry_ministry_id_fkey"
-                    }) =>
-            {
-                Error::BadRequest(payload::Json(ErrorResponse {
-                    message: format!("Ministry with id '{}' does not exists", &*id),
-                }))
-            }
-            sqlx::Error::Database(e)
-                if e.is_foreign_key_violation()
-                    && e.constraint()
-                        .is_some_and(|constraint| constraint == "user_ministry_user_id_fkey") =>
-            {
-                Error::BadRequest(payload::Json(ErrorResponse {
-                    message: format!("One of the user in the list does not exists"),
-                }))
-            }
-            sqlx::Error::Database(e)
-                if e.is_foreign_key_violation()
-                    && e.constraint()
-                        .is_some_and(|constraint| constraint == "user_ministry_user_role_fkey") =>
-            {
-                Error::BadRequest(payload::Json(ErrorResponse {
-                    message: format!("One of the role in the list does not exists"),
-                }))
-            }
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
+        .execute(&mut *tx)
+        .await?;
+
+        outbox::publish(
+            &mut tx,
+            "ministry.users_joined",
+            json!({ "ministry_id": &*id, "users": &body.users }),
+        )
+        .await?;
+
+        tx.commit().await?;
 
         Ok(Response::Ok(payload::Json("success".to_string())))
     }
@@ -1,39 +1,83 @@
 use poem::web;
 use poem_openapi::payload;
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{
+    database::Database,
+    error::Error,
+    pagination::{self, Listing, Page, Sortable},
+    projection::{Fields, Projected},
+};
+
+use crate::entities;
+
+const LISTING: Listing = Listing {
+    select: "*",
+    from: "ministry",
+    key: "id",
+    sortable: &[
+        Sortable {
+            name: "created_at",
+            expr: "created_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "updated_at",
+            expr: "updated_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "name",
+            expr: "name",
+            ty: "TEXT",
+        },
+    ],
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub satellite_id: Option<String>,
+    pub department_id: Option<String>,
+    pub team_id: Option<String>,
+    pub created_after: Option<chrono::DateTime<chrono::Utc>>,
+}
 
 #[derive(poem_openapi::ApiResponse)]
 pub enum Response {
     #[oai(status = 200)]
-    Ok(payload::Json<Vec<entities::Ministry>>),
+    Ok(payload::Json<Page<Projected<entities::Ministry>>>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
+impl crate::routes::Routes {
+    pub async fn _list_ministries(
+        &self,
+        db: web::Data<&Database>,
+        params: pagination::Params,
+        filter: Filter,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::Ministry>(fields)?;
 
-    #[oai(status = 500)]
-    InternalServerError(payload::Json<ErrorResponse>),
-}
+        let ministries = LISTING
+            .fetch(&db.db, &params, |query| {
+                if let Some(satellite_id) = &filter.satellite_id {
+                    query
+                        .push(" AND satellite_id = ")
+                        .push_bind(satellite_id.clone());
+                }
+                if let Some(department_id) = &filter.department_id {
+                    query
+                        .push(" AND department_id = ")
+                        .push_bind(department_id.clone());
+                }
+                if let Some(team_id) = &filter.team_id {
+                    query.push(" AND team_id = ").push_bind(team_id.clone());
+                }
+                if let Some(created_after) = filter.created_after {
+                    query.push(" AND created_at > ").push_bind(created_after);
+                }
+            })
+            .await?;
 
-impl crate::routes::Routes {
-    pub async fn _list_ministries(&self, db: web::Data<&Database>) -> Result<Response, Error> {
-        let ministries = sqlx::query_as!(
-            entities::Ministry,
-            r#"
-            SELECT * from ministry
-            "#,
-        )
-        .fetch_all(&db.db)
-        .await
-        .map_err(|e| match e {
-            _ => Error::InternalServerError(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
-
-        Ok(Response::Ok(payload::Json(ministries)))
+        Ok(Response::Ok(payload::Json(fields.project_page(ministries))))
     }
 }
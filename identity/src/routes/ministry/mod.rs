@@ -4,5 +4,6 @@ pub mod delete;
 pub mod get;
 pub mod get_users;
 pub mod list;
+pub mod list_history;
 pub mod remove_users;
 pub mod update;
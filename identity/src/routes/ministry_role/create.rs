@@ -3,7 +3,9 @@ use poem::web;
 use poem_openapi::{payload, Object};
 use serde::{Deserialize, Serialize};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{audit, auth::BearerAuth, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities};
 
 #[derive(Debug, Clone, Deserialize, Serialize, Object)]
 #[oai(rename = "CreateMinistryRoleRequest")]
@@ -19,29 +21,16 @@ pub enum Response {
     Ok(payload::Json<entities::MinistryRole>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
-}
-
 impl crate::routes::Routes {
     pub async fn _create_ministry_role(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         body: payload::Json<Request>,
     ) -> Result<Response, Error> {
-        let mut tx = db.db.begin().await.map_err(|e| {
-            Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Send + Sync),
-            )))
-        })?;
+        auth.require(&db, Permission::RolesWrite).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
 
         let role_id = self
             .management
@@ -51,34 +40,16 @@ impl crate::routes::Routes {
             })
             .send()
             .await
-            .map_err(|e| {
-                Error::InternalServer(payload::Json(ErrorResponse::from(
-                    &e as &(dyn std::error::Error + Send + Send + Sync),
-                )))
-            })?
+            .map_err(Error::internal)?
             .error_for_status()
-            .map_err(|e| {
-                tracing::error!("Failed to create role: {}", e);
-
-                Error::InternalServer(payload::Json(ErrorResponse::from(
-                    &e as &(dyn std::error::Error + Send + Send + Sync),
-                )))
-            })?
+            .map_err(Error::internal)?
             .json::<serde_json::Value>()
             .await
-            .map_err(|e| {
-                Error::InternalServer(payload::Json(ErrorResponse::from(
-                    &e as &(dyn std::error::Error + Send + Send + Sync),
-                )))
-            })?
+            .map_err(Error::internal)?
             .get("id")
-            .ok_or(Error::BadRequest(payload::Json(ErrorResponse {
-                message: format!("Failed to create a role on Auth0"),
-            })))?
+            .ok_or_else(|| Error::internal("Failed to create a role on Auth0"))?
             .as_str()
-            .ok_or(Error::InternalServer(payload::Json(ErrorResponse {
-                message: format!("ID returned from Auth0 is not a string"),
-            })))?
+            .ok_or_else(|| Error::internal("ID returned from Auth0 is not a string"))?
             .to_string();
 
         let ministry_role = sqlx::query_as!(
@@ -103,27 +74,9 @@ impl crate::routes::Routes {
             &body.weight,
         )
         .fetch_one(&mut *tx)
-        .await
-        .map_err(|e| match e {
-            sqlx::Error::Database(e)
-                if e.is_unique_violation()
-                    && e.constraint()
-                        .is_some_and(|constraint: &str| constraint == "ministry_role_name_key") =>
-            {
-                Error::BadRequest(payload::Json(ErrorResponse {
-                    message: format!("Ministry role with name '{}' already exists", body.name),
-                }))
-            }
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Send + Sync),
-            ))),
-        })?;
+        .await?;
 
-        tx.commit().await.map_err(|e| {
-            Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Send + Sync),
-            )))
-        })?;
+        tx.commit().await?;
 
         Ok(Response::Ok(payload::Json(ministry_role)))
     }
@@ -1,39 +1,52 @@
 use poem::web;
 use poem_openapi::payload;
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{
+    database::Database,
+    error::Error,
+    pagination::{self, Listing, Page, Sortable},
+    projection::{Fields, Projected},
+};
+
+use crate::entities;
+
+const LISTING: Listing = Listing {
+    select: "*",
+    from: "ministry_role",
+    key: "id",
+    sortable: &[
+        Sortable {
+            name: "weight",
+            expr: "weight",
+            ty: "INTEGER",
+        },
+        Sortable {
+            name: "name",
+            expr: "name",
+            ty: "TEXT",
+        },
+    ],
+};
 
 #[derive(poem_openapi::ApiResponse)]
 pub enum Response {
     #[oai(status = 200)]
-    Ok(payload::Json<Vec<entities::MinistryRole>>),
-}
-
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServerError(payload::Json<ErrorResponse>),
+    Ok(payload::Json<Page<Projected<entities::MinistryRole>>>),
 }
 
 impl crate::routes::Routes {
-    pub async fn _list_ministry_roles(&self, db: web::Data<&Database>) -> Result<Response, Error> {
-        let ministry_roles = sqlx::query_as!(
-            entities::MinistryRole,
-            r#"
-            SELECT * from ministry_role
-            "#,
-        )
-        .fetch_all(&db.db)
-        .await
-        .map_err(|e| match e {
-            _ => Error::InternalServerError(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
+    pub async fn _list_ministry_roles(
+        &self,
+        db: web::Data<&Database>,
+        params: pagination::Params,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::MinistryRole>(fields)?;
+
+        let ministry_roles = LISTING.fetch(&db.db, &params, |_| {}).await?;
 
-        Ok(Response::Ok(payload::Json(ministry_roles)))
+        Ok(Response::Ok(payload::Json(
+            fields.project_page(ministry_roles),
+        )))
     }
 }
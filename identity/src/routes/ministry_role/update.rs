@@ -3,7 +3,9 @@ use poem::web;
 use poem_openapi::{param::Path, payload, Object};
 use reqwest::StatusCode;
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{audit, auth::BearerAuth, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Deserialize, Serialize, Object)]
@@ -20,30 +22,17 @@ pub enum Response {
     Ok(payload::Json<entities::MinistryRole>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
-}
-
 impl crate::routes::Routes {
     pub async fn _update_ministry_role(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
         body: payload::Json<Request>,
     ) -> Result<Response, Error> {
-        let mut tx = db.db.begin().await.map_err(|e| {
-            Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Send + Sync),
-            )))
-        })?;
+        auth.require(&db, Permission::RolesWrite).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
 
         match self
             .management
@@ -56,18 +45,15 @@ impl crate::routes::Routes {
             )
             .send()
             .await
-            .map_err(|e| {
-                Error::InternalServer(payload::Json(ErrorResponse::from(
-                    &e as &(dyn std::error::Error + Send + Send + Sync),
-                )))
-            })?
+            .map_err(Error::internal)?
             .status()
         {
             StatusCode::OK => {}
             _ => {
-                return Err(Error::InternalServer(payload::Json(ErrorResponse {
-                    message: format!("Failed to update ministry role with id '{}' on Auth0", &*id),
-                })))
+                return Err(Error::internal(format!(
+                    "Failed to update ministry role with id '{}' on Auth0",
+                    &*id
+                )))
             }
         };
 
@@ -89,19 +75,14 @@ impl crate::routes::Routes {
         .fetch_one(&mut *tx)
         .await
         .map_err(|e| match e {
-            sqlx::error::Error::RowNotFound => Error::NotFound(payload::Json(ErrorResponse {
-                message: format!("Ministry role with id '{}' not found", &*id),
-            })),
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
+            sqlx::Error::RowNotFound => Error::not_found(
+                "MINISTRY_ROLE_NOT_FOUND",
+                format!("Ministry role with id '{}' not found", &*id),
+            ),
+            _ => Error::from(e),
         })?;
 
-        tx.commit().await.map_err(|e| {
-            Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Send + Sync),
-            )))
-        })?;
+        tx.commit().await?;
 
         Ok(Response::Ok(payload::Json(ministry_role)))
     }
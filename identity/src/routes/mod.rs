@@ -1,17 +1,29 @@
+use std::sync::Arc;
+
 use poem::web;
 use poem_openapi::{param, payload, OpenApi, Tags};
 
-use crate::{auth::BearerAuth, database::Database};
+use common::{auth::BearerAuth, crypto::Keyring, database::Database, error::Error, pagination};
+
+use crate::notifier::{LogNotifier, Notifier};
 
+mod audit;
 mod connect_group;
 mod connect_group_category;
+mod household;
+mod import;
 mod ministry;
 mod ministry_department;
 mod ministry_role;
 mod ministry_team;
+mod org_tree;
 mod pastoral_role;
+mod relationship_type;
 mod satellite;
+mod shadow_user;
+mod user_duplicate;
 mod users;
+mod webhook;
 
 #[derive(Tags)]
 enum Tag {
@@ -41,15 +53,46 @@ enum Tag {
 
     /// Ministry role related endpoints
     MinistryRole,
+
+    /// Shadow user related endpoints
+    ShadowUser,
+
+    /// Duplicate user related endpoints
+    UserDuplicate,
+
+    /// Audit log related endpoints
+    Audit,
+
+    /// Webhook related endpoints
+    Webhook,
+
+    /// Organisation tree related endpoints
+    OrgTree,
+
+    /// Household related endpoints
+    Household,
+
+    /// Import related endpoints
+    Import,
 }
 
 pub struct Routes {
     management: auth0::management::Api,
+    notifier: Arc<dyn Notifier>,
 }
 
 impl Routes {
     pub fn new(management: auth0::management::Api) -> Self {
-        Self { management }
+        Self {
+            management,
+            notifier: Arc::new(LogNotifier),
+        }
+    }
+
+    /// Deliver the messages sent to members, such as one-time codes, through `notifier`.
+    pub fn with_notifier(mut self, notifier: impl Notifier + 'static) -> Self {
+        self.notifier = Arc::new(notifier);
+        self
     }
 }
 
@@ -61,6 +104,18 @@ impl Routes {
     ///
     /// Create a new user given its information. This endpoint ideally should be called after a
     /// user is created in Auth0 such as after signup or logging in through social providers.
+    ///
+    /// When the email or phone number matches a shadow user or a soft-deleted user, a
+    /// `USER_PROFILE_CLAIMABLE` error is returned so that the user may claim that profile instead
+    /// of starting from a blank one, pass `skip_claim` to create the user regardless. Users
+    /// registering themselves are only matched on the email of their access token.
+    ///
+    /// The Auth0 profile of the user, when they have one, is updated with their details.
+    /// `email_verified` and `phone_number_verified` are ignored unless the caller holds the
+    /// `users:admin` permission.
+    ///
+    /// Requires the `users:write` permission granted by the access token, unless the user is
+    /// acting on themselves.
     #[oai(
         path = "/users",
         method = "post",
@@ -69,16 +124,32 @@ impl Routes {
     )]
     async fn create_user(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
+        keyring: web::Data<&Keyring>,
         body: payload::Json<users::create::Request>,
-    ) -> Result<users::create::Response, users::create::Error> {
-        self._create_user(db, body).await
+    ) -> Result<users::create::Response, Error> {
+        self._create_user(auth, db, keyring, body).await
     }
 
     /// List or search users
     ///
-    /// Retrieve a list of users or search for users given a query. For now it only supports
-    /// retrieving a list of users.
+    /// Retrieve a list of users, optionally filtered by satellite, connect group, ministry,
+    /// gender, age or creation time, where ages are those users turn in the current year. A
+    /// `search` matches whole words of the names, username and email, supporting quotes, `or`
+    /// and `-` as in web search engines, as well as partial and misspelt names, phone numbers
+    /// and IC numbers. Results are paged, pass the returned `next_cursor` as `cursor` to
+    /// retrieve the next page. Sortable on `created_at`,
+    /// `updated_at`, `name`, `email` and `no`, or on `relevance` when searching, which is then
+    /// the default. Prefix with `-` for descending order.
+    ///
+    /// Deleted users are left out unless `include_deleted` is set, which requires the
+    /// `users:admin` permission.
+    ///
+    /// Without the `users:read` permission only the authenticated user and the members of the
+    /// connect groups they lead, or of the satellites they oversee as a coach or pastor, are
+    /// returned. The IC numbers, phone numbers and addresses of other users are masked and their
+    /// dates of birth left out, unless the access token grants the `pii:read` permission.
     #[oai(
         path = "/users",
         method = "get",
@@ -87,16 +158,58 @@ impl Routes {
     )]
     async fn list_users(
         &self,
-        _auth: BearerAuth,
-        search: param::Query<Option<String>>,
+        auth: BearerAuth,
         db: web::Data<&Database>,
-    ) -> Result<users::list::Response, users::list::Error> {
-        self._list_users(search, db).await
+        keyring: web::Data<&Keyring>,
+        limit: param::Query<Option<i64>>,
+        cursor: param::Query<Option<String>>,
+        sort: param::Query<Option<String>>,
+        search: param::Query<Option<String>>,
+        satellite_id: param::Query<Option<String>>,
+        connect_group_id: param::Query<Option<String>>,
+        ministry_id: param::Query<Option<String>>,
+        gender: param::Query<Option<crate::entities::Gender>>,
+        min_age: param::Query<Option<i32>>,
+        max_age: param::Query<Option<i32>>,
+        created_after: param::Query<Option<chrono::DateTime<chrono::Utc>>>,
+        include_deleted: param::Query<Option<bool>>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<users::list::Response, Error> {
+        self._list_users(
+            auth,
+            db,
+            keyring,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            users::list::Filter {
+                search: search.0,
+                satellite_id: satellite_id.0,
+                connect_group_id: connect_group_id.0,
+                ministry_id: ministry_id.0,
+                gender: gender.0,
+                min_age: min_age.0,
+                max_age: max_age.0,
+                created_after: created_after.0,
+                include_deleted: include_deleted.0.unwrap_or_default(),
+            },
+            fields.0,
+        )
+        .await
     }
 
     /// Get a user
     ///
     /// Retrieve a user's details given its id.
+    ///
+    /// Without the `users:read` permission only the authenticated user and the members of the
+    /// connect groups they lead, or of the satellites they oversee as a coach or pastor, can
+    /// be retrieved.
+    ///
+    /// The IC number, phone number and address of another user are masked and their date of
+    /// birth left out, unless the access token grants the `pii:read` permission.
     #[oai(
         path = "/users/:id",
         method = "get",
@@ -105,16 +218,25 @@ impl Routes {
     )]
     async fn get_user(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
+        keyring: web::Data<&Keyring>,
         id: param::Path<String>,
-    ) -> Result<users::get::Response, users::get::Error> {
-        self._get_user(db, id).await
+        fields: param::Query<Option<String>>,
+    ) -> Result<users::get::Response, Error> {
+        self._get_user(auth, db, keyring, id, fields.0).await
     }
 
     /// Update a user
     ///
-    /// Update a user's details given its id and the corresponding fields to update.
+    /// Update a user's details given its id and the corresponding fields to update. Their names,
+    /// nickname and avatar are synced to their Auth0 profile once saved, when they have one.
+    /// Changing their email marks it unverified, Auth0 then sends them an email to verify it.
+    /// `email_verified` and `phone_number_verified` are ignored unless the caller holds the
+    /// `users:admin` permission.
+    ///
+    /// Requires the `users:write` permission, unless the user is acting on themselves. Leaders
+    /// granted it through their pastoral role may only update the members they oversee.
     #[oai(
         path = "/users/:id",
         method = "patch",
@@ -123,17 +245,22 @@ impl Routes {
     )]
     async fn update_user(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
+        keyring: web::Data<&Keyring>,
         id: param::Path<String>,
         body: payload::Json<users::update::Request>,
-    ) -> Result<users::update::Response, users::update::Error> {
-        self._update_user(db, id, body).await
+    ) -> Result<users::update::Response, Error> {
+        self._update_user(auth, db, keyring, id, body).await
     }
 
     /// Delete a user
     ///
-    /// Deletes a user based on the id from the database.
+    /// Deletes a user based on the id from the database. The user is soft-deleted, it is left out
+    /// of every endpoint until restored and may be purged for good. Their Auth0 account is
+    /// blocked meanwhile.
+    ///
+    /// Requires the `users:admin` permission.
     #[oai(
         path = "/users/:id",
         method = "delete",
@@ -142,11 +269,61 @@ impl Routes {
     )]
     async fn delete_user(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        keyring: web::Data<&Keyring>,
+        id: param::Path<String>,
+    ) -> Result<users::delete::Response, Error> {
+        self._delete_user(auth, db, keyring, id).await
+    }
+
+    /// Restore a user
+    ///
+    /// Undo the deletion of a user and unblock their Auth0 account. Users that have been erased
+    /// or merged into another user cannot be restored.
+    ///
+    /// Requires the `users:admin` permission.
+    #[oai(
+        path = "/users/:id/restore",
+        method = "post",
+        operation_id = "restore-user",
+        tag = "Tag::User"
+    )]
+    async fn restore_user(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        keyring: web::Data<&Keyring>,
+        id: param::Path<String>,
+    ) -> Result<users::restore::Response, Error> {
+        self._restore_user(auth, db, keyring, id).await
+    }
+
+    /// Purge a user
+    ///
+    /// Permanently remove a deleted user along with everything referencing it: its memberships
+    /// and their history, relationships, households, claims, the record of its merge into
+    /// another user, attendance and registration form data. The shadow users it claimed may be
+    /// claimed again. Only deleted users can be purged, the audit log keeps the record of the
+    /// purge with the personal data of the user redacted. Their Auth0 account is deleted as well.
+    /// Users that others were merged into are kept for the record of those merges, they can only
+    /// be erased.
+    ///
+    /// Requires the `users:admin` permission.
+    #[oai(
+        path = "/users/:id/purge",
+        method = "post",
+        operation_id = "purge-user",
+        tag = "Tag::User"
+    )]
+    async fn purge_user(
+        &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
+        keyring: web::Data<&Keyring>,
         id: param::Path<String>,
-    ) -> Result<users::delete::Response, users::delete::Error> {
-        self._delete_user(db, id).await
+    ) -> Result<users::purge::Response, Error> {
+        self._purge_user(auth, db, keyring, id).await
     }
 
     /// Get a user's pastoral roles
@@ -163,8 +340,9 @@ impl Routes {
         _auth: BearerAuth,
         db: web::Data<&Database>,
         id: param::Path<String>,
-    ) -> Result<users::get_pastoral_roles::Response, users::get_pastoral_roles::Error> {
-        self._get_user_pastoral_roles(db, id).await
+        fields: param::Query<Option<String>>,
+    ) -> Result<users::get_pastoral_roles::Response, Error> {
+        self._get_user_pastoral_roles(db, id, fields.0).await
     }
 
     /// Get a user's ministry roles
@@ -181,8 +359,9 @@ impl Routes {
         _auth: BearerAuth,
         db: web::Data<&Database>,
         id: param::Path<String>,
-    ) -> Result<users::get_ministry_roles::Response, users::get_ministry_roles::Error> {
-        self._get_user_ministry_roles(db, id).await
+        fields: param::Query<Option<String>>,
+    ) -> Result<users::get_ministry_roles::Response, Error> {
+        self._get_user_ministry_roles(db, id, fields.0).await
     }
 
     /// Get a user's connect groups
@@ -199,8 +378,9 @@ impl Routes {
         _auth: BearerAuth,
         db: web::Data<&Database>,
         id: param::Path<String>,
-    ) -> Result<users::get_connect_groups::Response, users::get_connect_groups::Error> {
-        self._get_user_connect_groups(db, id).await
+        fields: param::Query<Option<String>>,
+    ) -> Result<users::get_connect_groups::Response, Error> {
+        self._get_user_connect_groups(db, id, fields.0).await
     }
 
     /// Get a user's ministries
@@ -217,13 +397,162 @@ impl Routes {
         _auth: BearerAuth,
         db: web::Data<&Database>,
         id: param::Path<String>,
-    ) -> Result<users::get_ministries::Response, users::get_ministries::Error> {
-        self._get_ministries(db, id).await
+        fields: param::Query<Option<String>>,
+    ) -> Result<users::get_ministries::Response, Error> {
+        self._get_ministries(db, id, fields.0).await
+    }
+
+    /// Get a user's connect group history
+    ///
+    /// List the periods a user was a member of any connect group, oldest first, with the role
+    /// held and why they left. A role change ends a period at the time the next one starts.
+    ///
+    /// Without the `users:read` permission only the authenticated user and the members of the
+    /// connect groups they lead, or of the satellites they oversee as a coach or pastor, can
+    /// be retrieved.
+    #[oai(
+        path = "/users/:id/connect-group-history",
+        method = "get",
+        operation_id = "get-user-connect-group-history",
+        tag = "Tag::User"
+    )]
+    async fn get_user_connect_group_history(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<users::get_connect_group_history::Response, Error> {
+        self._get_user_connect_group_history(auth, db, id, fields.0).await
+    }
+
+    /// Get a user's ministry history
+    ///
+    /// List the periods a user was a member of any ministry, oldest first, with the role held
+    /// and why they left. A role change ends a period at the time the next one starts.
+    ///
+    /// Without the `users:read` permission only the authenticated user and the members of the
+    /// connect groups they lead, or of the satellites they oversee as a coach or pastor, can
+    /// be retrieved.
+    #[oai(
+        path = "/users/:id/ministry-history",
+        method = "get",
+        operation_id = "get-user-ministry-history",
+        tag = "Tag::User"
+    )]
+    async fn get_user_ministry_history(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<users::get_ministry_history::Response, Error> {
+        self._get_user_ministry_history(auth, db, id, fields.0).await
+    }
+
+    /// Get a user's leaders
+    ///
+    /// Follow the lines of pastoral care up from a user, nearest first: the leaders of their
+    /// connect groups holding a more senior role, then the overseers of those leaders, e.g.
+    /// their coach and pastor. Chains are followed up to `max_depth` lines, 10 at most.
+    ///
+    /// Without the `users:read` permission only the authenticated user and the members of the
+    /// connect groups they lead, or of the satellites they oversee as a coach or pastor, can
+    /// be retrieved.
+    #[oai(
+        path = "/users/:id/leaders",
+        method = "get",
+        operation_id = "get-user-leaders",
+        tag = "Tag::User"
+    )]
+    async fn get_user_leaders(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        max_depth: param::Query<Option<i32>>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<users::get_leaders::Response, Error> {
+        self._get_user_leaders(auth, db, id, max_depth.0, fields.0).await
+    }
+
+    /// Get a user's flock
+    ///
+    /// Follow the lines of pastoral care down from a leader, nearest first: the members of the
+    /// connect groups they lead holding a more junior role, and the leaders they oversee.
+    /// Chains are followed down to `max_depth` lines, 1 by default and 10 at most.
+    ///
+    /// Without the `users:read` permission only the authenticated user and the members of the
+    /// connect groups they lead, or of the satellites they oversee as a coach or pastor, can
+    /// be retrieved. The flock is limited to the same users.
+    #[oai(
+        path = "/users/:id/flock",
+        method = "get",
+        operation_id = "get-user-flock",
+        tag = "Tag::User"
+    )]
+    async fn get_user_flock(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        max_depth: param::Query<Option<i32>>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<users::get_flock::Response, Error> {
+        self._get_user_flock(auth, db, id, max_depth.0, fields.0).await
+    }
+
+    /// Set a user's overseer
+    ///
+    /// Set the more senior leader overseeing a leader, e.g. the coach of a CGL or the pastor
+    /// of a coach, replacing their current overseer.
+    ///
+    /// Requires the `users:admin` permission.
+    #[oai(
+        path = "/users/:id/overseer",
+        method = "put",
+        operation_id = "set-user-overseer",
+        tag = "Tag::User"
+    )]
+    async fn set_user_overseer(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        body: payload::Json<users::set_overseer::Request>,
+    ) -> Result<users::set_overseer::Response, Error> {
+        self._set_user_overseer(auth, db, id, body).await
+    }
+
+    /// Remove a user's overseer
+    ///
+    /// Remove the leader overseeing a user given the user's id.
+    ///
+    /// Requires the `users:admin` permission.
+    #[oai(
+        path = "/users/:id/overseer",
+        method = "delete",
+        operation_id = "remove-user-overseer",
+        tag = "Tag::User"
+    )]
+    async fn remove_user_overseer(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+    ) -> Result<users::remove_overseer::Response, Error> {
+        self._remove_user_overseer(auth, db, id).await
     }
 
     /// Create a user relationship
     ///
-    /// Associate a user to another by creating a user relationship.
+    /// Associate a user to another by creating a user relationship, one of the relationship
+    /// types. The destination user holds the inverse relationship back, e.g. creating `parent`
+    /// creates `child` the other way around.
+    ///
+    /// Requires the `users:write` permission, unless the user is acting on themselves. Leaders
+    /// granted it through their pastoral role may only act on the members they oversee. Allowing
+    /// the user to pick up the destination user always requires it.
     #[oai(
         path = "/users/:id/relationships",
         method = "post",
@@ -232,11 +561,12 @@ impl Routes {
     )]
     async fn create_user_relationship(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: param::Path<String>,
         body: payload::Json<users::create_relationship::Request>,
-    ) -> Result<users::create_relationship::Response, users::create_relationship::Error> {
-        self._create_user_relationship(db, id, body).await
+    ) -> Result<users::create_relationship::Response, Error> {
+        self._create_user_relationship(auth, db, id, body).await
     }
 
     /// List or search relationships for a particular user
@@ -254,13 +584,19 @@ impl Routes {
         _auth: BearerAuth,
         db: web::Data<&Database>,
         id: param::Path<String>,
-    ) -> Result<users::list_relationships::Response, users::list_relationships::Error> {
-        self._list_user_relationships(db, id).await
+        fields: param::Query<Option<String>>,
+    ) -> Result<users::list_relationships::Response, Error> {
+        self._list_user_relationships(db, id, fields.0).await
     }
 
     /// Update a user relationship
     ///
-    /// Update a user relationship given the source and the destination user.
+    /// Update a user relationship given the source and the destination user, along with its
+    /// inverse.
+    ///
+    /// Requires the `users:write` permission, unless the user is acting on themselves. Leaders
+    /// granted it through their pastoral role may only act on the members they oversee. Changing
+    /// whether the user may pick up the destination user always requires it.
     #[oai(
         path = "/users/:id/relationships",
         method = "patch",
@@ -269,17 +605,20 @@ impl Routes {
     )]
     async fn update_user_relationship(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: param::Path<String>,
         body: payload::Json<users::update_relationship::Request>,
-    ) -> Result<users::update_relationship::Response, users::update_relationship::Error> {
-        self._update_user_relationship(db, id, body).await
+    ) -> Result<users::update_relationship::Response, Error> {
+        self._update_user_relationship(auth, db, id, body).await
     }
 
     /// Delete a user relationship
     ///
-    /// Deletes a user relationship.
+    /// Deletes a user relationship along with its inverse.
+    ///
+    /// Requires the `users:write` permission, unless the user is acting on themselves. Leaders
+    /// granted it through their pastoral role may only act on the members they oversee.
     #[oai(
         path = "/users/:id/relationships",
         method = "delete",
@@ -288,862 +627,2305 @@ impl Routes {
     )]
     async fn delete_user_relationship(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: param::Path<String>,
         body: payload::Json<users::delete_relationship::Request>,
-    ) -> Result<users::delete_relationship::Response, users::delete_relationship::Error> {
-        self._delete_user_relationship(db, id, body).await
+    ) -> Result<users::delete_relationship::Response, Error> {
+        self._delete_user_relationship(auth, db, id, body).await
     }
 
-    /* Satellite */
-
-    /// Create a satellite
+    /// List the relationship types
     ///
-    /// Create a new satellite given its information.
+    /// Retrieve the relationships users may have, along with their inverse.
     #[oai(
-        path = "/satellites",
-        method = "post",
-        operation_id = "create-satellite",
-        tag = "Tag::Satellite"
+        path = "/relationship-types",
+        method = "get",
+        operation_id = "list-relationship-types",
+        tag = "Tag::User"
     )]
-    async fn create_satellite(
+    async fn list_relationship_types(
         &self,
         _auth: BearerAuth,
         db: web::Data<&Database>,
-        body: payload::Json<satellite::create::Request>,
-    ) -> Result<satellite::create::Response, satellite::create::Error> {
-        self._create_satellite(db, body).await
+        fields: param::Query<Option<String>>,
+    ) -> Result<relationship_type::list::Response, Error> {
+        self._list_relationship_types(db, fields.0).await
     }
 
-    /// List or search satellites
+    /// Get a user's family
+    ///
+    /// Follow the relationships of a user up to `depth` relationships away, 1 by default and 5
+    /// at most. Retrieve the relatives reached nearest first, the relationships between them
+    /// and the households they belong to.
     ///
-    /// Retrieve a list of satellites or search for satellites given a query.
+    /// Without the `users:read` permission only the authenticated user and the members of the
+    /// connect groups they lead, or of the satellites they oversee as a coach or pastor, can
+    /// be retrieved.
     #[oai(
-        path = "/satellites",
+        path = "/users/:id/family",
         method = "get",
-        operation_id = "list-satellites",
-        tag = "Tag::Satellite"
+        operation_id = "get-user-family",
+        tag = "Tag::User"
     )]
-    async fn list_satellites(
+    async fn get_user_family(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
-    ) -> Result<satellite::list::Response, satellite::list::Error> {
-        self._list_satellites(db).await
+        id: param::Path<String>,
+        depth: param::Query<Option<i32>>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<users::get_family::Response, Error> {
+        self._get_user_family(auth, db, id, depth.0, fields.0).await
     }
 
-    /// Get a satellite
+    /// Get who may pick up a user
     ///
-    /// Retrieve a satellite's details given its id.
+    /// Retrieve the users allowed to pick up a user, e.g. a child from the kids ministry, along
+    /// with their phone number, masked unless the access token grants the `pii:read` permission.
+    ///
+    /// Without the `users:read` permission only the authenticated user and the members of the
+    /// connect groups they lead, or of the satellites they oversee as a coach or pastor, can
+    /// be retrieved.
     #[oai(
-        path = "/satellites/:id",
+        path = "/users/:id/pick-ups",
         method = "get",
-        operation_id = "get-satellite",
-        tag = "Tag::Satellite"
+        operation_id = "get-user-pick-ups",
+        tag = "Tag::User"
     )]
-    async fn get_satellite(
+    async fn get_user_pick_ups(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
+        keyring: web::Data<&Keyring>,
         id: param::Path<String>,
-    ) -> Result<satellite::get::Response, satellite::get::Error> {
-        self._get_satellite(db, id).await
+        fields: param::Query<Option<String>>,
+    ) -> Result<users::get_pick_ups::Response, Error> {
+        self._get_user_pick_ups(auth, db, keyring, id, fields.0).await
     }
 
-    /// Update a satellite
+    /// Get a user's households
     ///
-    /// Update a satellite's details given its id and the corresponding fields to update.
+    /// Retrieve the households a user belongs to.
+    ///
+    /// Without the `users:read` permission only the authenticated user and the members of the
+    /// connect groups they lead, or of the satellites they oversee as a coach or pastor, can
+    /// be retrieved.
     #[oai(
-        path = "/satellites/:id",
-        method = "patch",
-        operation_id = "update-satellite",
-        tag = "Tag::Satellite"
+        path = "/users/:id/households",
+        method = "get",
+        operation_id = "get-user-households",
+        tag = "Tag::User"
     )]
-    async fn update_satellite(
+    async fn get_user_households(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: param::Path<String>,
-        body: payload::Json<satellite::update::Request>,
-    ) -> Result<satellite::update::Response, satellite::update::Error> {
-        self._update_satellite(db, id, body).await
+        fields: param::Query<Option<String>>,
+    ) -> Result<users::get_households::Response, Error> {
+        self._get_user_households(auth, db, id, fields.0).await
     }
 
-    /// Delete a satellite
+    /// List the profiles a user may claim
     ///
-    /// Delete a satellite given its id.
+    /// Retrieve the shadow users and soft-deleted users sharing the given email or phone number,
+    /// which a new user may claim instead of starting from a blank profile. Users acting on
+    /// themselves are only matched on the email of their access token, the `email` and
+    /// `phone_number` given are ignored. Personal details are masked until the claim is verified.
+    ///
+    /// Requires the `users:write` permission granted by the access token, unless the user is
+    /// acting on themselves.
     #[oai(
-        path = "/satellites/:id",
-        method = "delete",
-        operation_id = "delete-satellite",
-        tag = "Tag::Satellite"
+        path = "/users/:id/claims/candidates",
+        method = "get",
+        operation_id = "list-user-claim-candidates",
+        tag = "Tag::User"
     )]
-    async fn delete_satellite(
+    async fn list_user_claim_candidates(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
+        keyring: web::Data<&Keyring>,
         id: param::Path<String>,
-    ) -> Result<satellite::delete::Response, satellite::delete::Error> {
-        self._delete_satellite(db, id).await
+        email: param::Query<Option<String>>,
+        phone_number: param::Query<Option<String>>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<users::list_claim_candidates::Response, Error> {
+        self._list_user_claim_candidates(auth, db, keyring, id, email.0, phone_number.0, fields.0)
+            .await
     }
 
-    /* Connect Group */
-
-    /// Create a connect group
+    /// Claim a profile
     ///
-    /// Create a new connect group given its information.
+    /// Start claiming one of the profiles listed by `/users/:id/claims/candidates` for a user
+    /// that does not exist yet. A one-time code is sent to the email or phone number of the
+    /// claimed profile, which must be submitted to `/users/:id/claims/:claim_id/verify` within
+    /// 10 minutes. Users acting on themselves claim with the email of their access token, the
+    /// `email` and `phone_number` given are ignored.
+    ///
+    /// Requires the `users:write` permission granted by the access token, unless the user is
+    /// acting on themselves.
     #[oai(
-        path = "/connect-groups",
+        path = "/users/:id/claims",
         method = "post",
-        operation_id = "create-connect-group",
-        tag = "Tag::ConnectGroup"
+        operation_id = "create-user-claim",
+        tag = "Tag::User"
     )]
-    async fn create_connect_group(
+    async fn create_user_claim(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
-        body: payload::Json<connect_group::create::Request>,
-    ) -> Result<connect_group::create::Response, connect_group::create::Error> {
-        self._create_connect_group(db, body).await
+        keyring: web::Data<&Keyring>,
+        id: param::Path<String>,
+        body: payload::Json<users::create_claim::Request>,
+    ) -> Result<users::create_claim::Response, Error> {
+        self._create_user_claim(auth, db, keyring, id, body).await
     }
 
-    /// List or search connect groups
+    /// Verify a profile claim
+    ///
+    /// Submit the one-time code of a claim to create the user from the claimed profile, along
+    /// with its connect group and ministry memberships. A claim can no longer be verified after
+    /// 5 wrong codes.
     ///
-    /// Retrieve a list of connect groups or search for connect groups given a query.
+    /// Requires the `users:write` permission granted by the access token, unless the user is
+    /// acting on themselves.
     #[oai(
-        path = "/connect-groups",
-        method = "get",
-        operation_id = "list-connect-groups",
-        tag = "Tag::ConnectGroup"
+        path = "/users/:id/claims/:claim_id/verify",
+        method = "post",
+        operation_id = "verify-user-claim",
+        tag = "Tag::User"
     )]
-    async fn list_connect_groups(
+    async fn verify_user_claim(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
-    ) -> Result<connect_group::list::Response, connect_group::list::Error> {
-        self._list_connect_groups(db).await
+        keyring: web::Data<&Keyring>,
+        id: param::Path<String>,
+        claim_id: param::Path<String>,
+        body: payload::Json<users::verify_claim::Request>,
+    ) -> Result<users::verify_claim::Response, Error> {
+        self._verify_user_claim(auth, db, keyring, id, claim_id, body).await
     }
 
-    /// Get a connect group
+    /// Merge a duplicate user
     ///
-    /// Retrieve a connect group's details given its id.
+    /// Merge a duplicate user into this one in a single transaction, moving its connect group
+    /// and ministry memberships, relationships, households, attendance and registration form
+    /// data, and filling in the fields this user is missing. Rows this user already has an equivalent of
+    /// are dropped, keeping the most senior role. The duplicate user is soft-deleted and the
+    /// merge recorded. A dry run returns the same counts without changing anything.
+    ///
+    /// Requires the `users:admin` permission.
     #[oai(
-        path = "/connect-groups/:id",
-        method = "get",
-        operation_id = "get-connect-group",
-        tag = "Tag::ConnectGroup"
+        path = "/users/:id/merge",
+        method = "post",
+        operation_id = "merge-user",
+        tag = "Tag::User"
     )]
-    async fn get_connect_group(
+    async fn merge_user(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: param::Path<String>,
-    ) -> Result<connect_group::get::Response, connect_group::get::Error> {
-        self._get_connect_group(db, id).await
+        body: payload::Json<users::merge::Request>,
+    ) -> Result<users::merge::Response, Error> {
+        self._merge_user(auth, db, id, body).await
     }
 
-    /// Update a connect group
+    /// List a user's merges
     ///
-    /// Update a connect group's details given its id and the corresponding fields to update.
+    /// Retrieve the merges a user took part in, either as the kept user or as the merged one,
+    /// most recent first.
+    ///
+    /// Requires the `users:admin` permission.
     #[oai(
-        path = "/connect-groups/:id",
-        method = "patch",
-        operation_id = "update-connect-group",
-        tag = "Tag::ConnectGroup"
+        path = "/users/:id/merges",
+        method = "get",
+        operation_id = "list-user-merges",
+        tag = "Tag::User"
     )]
-    async fn update_connect_group(
+    async fn list_user_merges(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: param::Path<String>,
-        body: payload::Json<connect_group::update::Request>,
-    ) -> Result<connect_group::update::Response, connect_group::update::Error> {
-        self._update_connect_group(db, id, body).await
+        fields: param::Query<Option<String>>,
+    ) -> Result<users::list_merges::Response, Error> {
+        self._list_user_merges(auth, db, id, fields.0).await
     }
 
-    /// Delete a connect group
+    /// Export a user's data
+    ///
+    /// Export everything held about a user across the identity and event services: their
+    /// details, memberships and their history, relationships, households, claims, merges,
+    /// attendance, registration form data and the audit log of their changes. The export is a
+    /// single JSON document by default, or a ZIP archive of one JSON file per section along with
+    /// a manifest.
     ///
-    /// Delete a connect group given its id.
+    /// Requires the `users:admin` permission, unless the user is acting on themselves. The IC
+    /// number, phone number and address of the user are then masked, and their date of birth
+    /// left out, unless the access token grants the `pii:read` permission.
     #[oai(
-        path = "/connect-groups/:id",
-        method = "delete",
-        operation_id = "delete-connect-group",
-        tag = "Tag::ConnectGroup"
+        path = "/users/:id/export",
+        method = "get",
+        operation_id = "export-user",
+        tag = "Tag::User"
     )]
-    async fn delete_connect_group(
+    async fn export_user(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
+        keyring: web::Data<&Keyring>,
         id: param::Path<String>,
-    ) -> Result<connect_group::delete::Response, connect_group::delete::Error> {
-        self._delete_connect_group(db, id).await
+        format: param::Query<Option<users::export::Format>>,
+    ) -> Result<users::export::Response, Error> {
+        self._export_user(auth, db, keyring, id, format.0).await
     }
 
-    /// Associate users with a connect group
+    /// Erase a user
     ///
-    /// Associate users with a connect group given the connect group's id and the users' ids.
+    /// Request the erasure of a user's personal data, carried out once the grace period is over
+    /// unless cancelled, or right away when it is `0`. The user is kept anonymised and
+    /// soft-deleted so that the attendance and membership history it is counted in stay as they
+    /// were, while their memberships, relationships, households, claims and registration form
+    /// data are removed. The personal data recorded in the audit log is redacted, the request is
+    /// kept as a record of the erasure.
+    ///
+    /// Requires the `users:admin` permission.
     #[oai(
-        path = "/connect-groups/:id/users",
+        path = "/users/:id/erase",
         method = "post",
-        operation_id = "associate-users-with-connect-group",
-        tag = "Tag::ConnectGroup"
+        operation_id = "erase-user",
+        tag = "Tag::User"
     )]
-    async fn associate_users_with_connect_group(
+    async fn erase_user(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: param::Path<String>,
-        body: payload::Json<connect_group::associate_users::Request>,
-    ) -> Result<connect_group::associate_users::Response, connect_group::associate_users::Error>
-    {
-        self._associate_users_with_connect_group(db, id, body).await
+        body: payload::Json<users::erase::Request>,
+    ) -> Result<users::erase::Response, Error> {
+        self._erase_user(auth, db, id, body).await
     }
 
-    /// Get connect group users
+    /// Cancel a user's erasure
     ///
-    /// Retrieve a list of users associated with a connect group given the connect group's id.
+    /// Cancel the pending erasure of a user's personal data, before its grace period is over.
+    ///
+    /// Requires the `users:admin` permission.
     #[oai(
-        path = "/connect-groups/:id/users",
-        method = "get",
-        operation_id = "get-connect-group-users",
-        tag = "Tag::ConnectGroup"
+        path = "/users/:id/erase",
+        method = "delete",
+        operation_id = "cancel-user-erasure",
+        tag = "Tag::User"
     )]
-    async fn get_connect_group_users(
+    async fn cancel_user_erasure(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: param::Path<String>,
-    ) -> Result<connect_group::get_users::Response, connect_group::get_users::Error> {
-        self._get_connect_group_users(db, id).await
+    ) -> Result<users::cancel_erasure::Response, Error> {
+        self._cancel_user_erasure(auth, db, id).await
     }
 
-    /// Remove users from a connect group
+    /* Satellite */
+
+    /// Create a satellite
+    ///
+    /// Create a new satellite given its information.
     ///
-    /// Remove users from a connect group given the connect group's id and the users' ids.
+    /// Requires the `satellites:write` permission.
     #[oai(
-        path = "/connect-groups/:id/users",
+        path = "/satellites",
+        method = "post",
+        operation_id = "create-satellite",
+        tag = "Tag::Satellite"
+    )]
+    async fn create_satellite(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        body: payload::Json<satellite::create::Request>,
+    ) -> Result<satellite::create::Response, Error> {
+        self._create_satellite(auth, db, body).await
+    }
+
+    /// List or search satellites
+    ///
+    /// Retrieve a list of satellites or search for satellites given a query. Results are paged,
+    /// pass the returned `next_cursor` as `cursor` to retrieve the next page. Sortable on
+    /// `created_at`, `updated_at`, `name` and `no`, prefix with `-` for descending order.
+    #[oai(
+        path = "/satellites",
+        method = "get",
+        operation_id = "list-satellites",
+        tag = "Tag::Satellite"
+    )]
+    async fn list_satellites(
+        &self,
+        _auth: BearerAuth,
+        db: web::Data<&Database>,
+        limit: param::Query<Option<i64>>,
+        cursor: param::Query<Option<String>>,
+        sort: param::Query<Option<String>>,
+        created_after: param::Query<Option<chrono::DateTime<chrono::Utc>>>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<satellite::list::Response, Error> {
+        self._list_satellites(
+            db,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            satellite::list::Filter {
+                created_after: created_after.0,
+            },
+            fields.0,
+        )
+        .await
+    }
+
+    /// Get a satellite
+    ///
+    /// Retrieve a satellite's details given its id.
+    #[oai(
+        path = "/satellites/:id",
+        method = "get",
+        operation_id = "get-satellite",
+        tag = "Tag::Satellite"
+    )]
+    async fn get_satellite(
+        &self,
+        _auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<satellite::get::Response, Error> {
+        self._get_satellite(db, id, fields.0).await
+    }
+
+    /// Update a satellite
+    ///
+    /// Update a satellite's details given its id and the corresponding fields to update.
+    ///
+    /// Requires the `satellites:write` permission.
+    #[oai(
+        path = "/satellites/:id",
+        method = "patch",
+        operation_id = "update-satellite",
+        tag = "Tag::Satellite"
+    )]
+    async fn update_satellite(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        body: payload::Json<satellite::update::Request>,
+    ) -> Result<satellite::update::Response, Error> {
+        self._update_satellite(auth, db, id, body).await
+    }
+
+    /// Delete a satellite
+    ///
+    /// Delete a satellite given its id.
+    ///
+    /// Requires the `satellites:write` permission.
+    #[oai(
+        path = "/satellites/:id",
         method = "delete",
-        operation_id = "remove-users-from-connect-group",
+        operation_id = "delete-satellite",
+        tag = "Tag::Satellite"
+    )]
+    async fn delete_satellite(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+    ) -> Result<satellite::delete::Response, Error> {
+        self._delete_satellite(auth, db, id).await
+    }
+
+    /// Get a satellite's organisation tree
+    ///
+    /// Retrieve the organisation tree of a satellite given its id, see `get-org-tree`.
+    #[oai(
+        path = "/satellites/:id/org-tree",
+        method = "get",
+        operation_id = "get-satellite-org-tree",
+        tag = "Tag::Satellite"
+    )]
+    async fn get_satellite_org_tree(
+        &self,
+        _auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<satellite::get_org_tree::Response, Error> {
+        self._get_satellite_org_tree(db, id, fields.0).await
+    }
+
+    /* Connect Group */
+
+    /// Create a connect group
+    ///
+    /// Create a new connect group given its information.
+    ///
+    /// Requires the `connect-groups:write` permission.
+    #[oai(
+        path = "/connect-groups",
+        method = "post",
+        operation_id = "create-connect-group",
+        tag = "Tag::ConnectGroup"
+    )]
+    async fn create_connect_group(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        body: payload::Json<connect_group::create::Request>,
+    ) -> Result<connect_group::create::Response, Error> {
+        self._create_connect_group(auth, db, body).await
+    }
+
+    /// List or search connect groups
+    ///
+    /// Retrieve a list of connect groups or search for connect groups given a query. Results are
+    /// paged, pass the returned `next_cursor` as `cursor` to retrieve the next page. Sortable on
+    /// `created_at`, `updated_at` and `no`, prefix with `-` for descending order. Closed connect
+    /// groups are only listed when `active` is false.
+    #[oai(
+        path = "/connect-groups",
+        method = "get",
+        operation_id = "list-connect-groups",
+        tag = "Tag::ConnectGroup"
+    )]
+    async fn list_connect_groups(
+        &self,
+        _auth: BearerAuth,
+        db: web::Data<&Database>,
+        limit: param::Query<Option<i64>>,
+        cursor: param::Query<Option<String>>,
+        sort: param::Query<Option<String>>,
+        satellite_id: param::Query<Option<String>>,
+        category_id: param::Query<Option<String>>,
+        active: param::Query<Option<bool>>,
+        created_after: param::Query<Option<chrono::DateTime<chrono::Utc>>>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<connect_group::list::Response, Error> {
+        self._list_connect_groups(
+            db,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            connect_group::list::Filter {
+                satellite_id: satellite_id.0,
+                category_id: category_id.0,
+                active: active.0,
+                created_after: created_after.0,
+            },
+            fields.0,
+        )
+        .await
+    }
+
+    /// Get a connect group
+    ///
+    /// Retrieve a connect group's details given its id.
+    #[oai(
+        path = "/connect-groups/:id",
+        method = "get",
+        operation_id = "get-connect-group",
+        tag = "Tag::ConnectGroup"
+    )]
+    async fn get_connect_group(
+        &self,
+        _auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<connect_group::get::Response, Error> {
+        self._get_connect_group(db, id, fields.0).await
+    }
+
+    /// Update a connect group
+    ///
+    /// Update a connect group's details given its id and the corresponding fields to update.
+    ///
+    /// Requires the `connect-groups:write` permission.
+    #[oai(
+        path = "/connect-groups/:id",
+        method = "patch",
+        operation_id = "update-connect-group",
+        tag = "Tag::ConnectGroup"
+    )]
+    async fn update_connect_group(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        body: payload::Json<connect_group::update::Request>,
+    ) -> Result<connect_group::update::Response, Error> {
+        self._update_connect_group(auth, db, id, body).await
+    }
+
+    /// Delete a connect group
+    ///
+    /// Delete a connect group given its id. Connect groups with members or with attendance taken
+    /// while the attendees were members are kept, they should be closed instead, unless `force`
+    /// is set.
+    ///
+    /// Requires the `connect-groups:write` permission, and the `users:admin` permission to
+    /// `force` the deletion.
+    #[oai(
+        path = "/connect-groups/:id",
+        method = "delete",
+        operation_id = "delete-connect-group",
+        tag = "Tag::ConnectGroup"
+    )]
+    async fn delete_connect_group(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        force: param::Query<Option<bool>>,
+    ) -> Result<connect_group::delete::Response, Error> {
+        self._delete_connect_group(auth, db, id, force.0.unwrap_or(false)).await
+    }
+
+    /// Close a connect group
+    ///
+    /// Close a connect group given its id, hiding it from the connect group listings while
+    /// keeping its history. Its members are either reassigned to another connect group with
+    /// `reassign_to` or removed with `release`, closing a connect group with members is refused
+    /// otherwise.
+    ///
+    /// Requires the `connect-groups:write` permission.
+    #[oai(
+        path = "/connect-groups/:id/close",
+        method = "post",
+        operation_id = "close-connect-group",
+        tag = "Tag::ConnectGroup"
+    )]
+    async fn close_connect_group(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        body: payload::Json<connect_group::close::Request>,
+    ) -> Result<connect_group::close::Response, Error> {
+        self._close_connect_group(auth, db, id, body).await
+    }
+
+    /// Reopen a connect group
+    ///
+    /// Reopen a closed connect group given its id, its former members are not added back.
+    ///
+    /// Requires the `connect-groups:write` permission.
+    #[oai(
+        path = "/connect-groups/:id/reopen",
+        method = "post",
+        operation_id = "reopen-connect-group",
+        tag = "Tag::ConnectGroup"
+    )]
+    async fn reopen_connect_group(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+    ) -> Result<connect_group::reopen::Response, Error> {
+        self._reopen_connect_group(auth, db, id).await
+    }
+
+    /// Multiply a connect group
+    ///
+    /// Create connect groups out of a connect group given its id, under the same number,
+    /// satellite and category with new variants. The chosen members move to the new connect
+    /// groups along with their roles, and the parent is closed when `close_parent` is set.
+    ///
+    /// Requires the `connect-groups:write` permission.
+    #[oai(
+        path = "/connect-groups/:id/multiply",
+        method = "post",
+        operation_id = "multiply-connect-group",
+        tag = "Tag::ConnectGroup"
+    )]
+    async fn multiply_connect_group(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        body: payload::Json<connect_group::multiply::Request>,
+    ) -> Result<connect_group::multiply::Response, Error> {
+        self._multiply_connect_group(auth, db, id, body).await
+    }
+
+    /// Get a connect group's lineage
+    ///
+    /// Retrieve the connect groups a connect group was multiplied out of, and the tree of the
+    /// connect groups multiplied out of it, given its id.
+    #[oai(
+        path = "/connect-groups/:id/lineage",
+        method = "get",
+        operation_id = "get-connect-group-lineage",
+        tag = "Tag::ConnectGroup"
+    )]
+    async fn get_connect_group_lineage(
+        &self,
+        _auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<connect_group::lineage::Response, Error> {
+        self._get_connect_group_lineage(db, id, fields.0).await
+    }
+
+    /// Associate users with a connect group
+    ///
+    /// Associate users with a connect group given the connect group's id and the users' ids.
+    ///
+    /// Requires the `connect-groups:write` permission.
+    #[oai(
+        path = "/connect-groups/:id/users",
+        method = "post",
+        operation_id = "associate-users-with-connect-group",
+        tag = "Tag::ConnectGroup"
+    )]
+    async fn associate_users_with_connect_group(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        body: payload::Json<connect_group::associate_users::Request>,
+    ) -> Result<connect_group::associate_users::Response, Error> {
+        self._associate_users_with_connect_group(auth, db, id, body)
+            .await
+    }
+
+    /// Get connect group users
+    ///
+    /// Retrieve a list of users associated with a connect group given the connect group's id.
+    /// Results are paged, pass the returned `next_cursor` as `cursor` to retrieve the next page.
+    /// Sortable on `created_at`, `updated_at`, `name`, `email` and `no`, prefix with `-` for
+    /// descending order.
+    ///
+    /// Without the `users:read` permission only the authenticated user and the members of the
+    /// connect groups they lead, or of the satellites they oversee as a coach or pastor, are
+    /// returned.
+    #[oai(
+        path = "/connect-groups/:id/users",
+        method = "get",
+        operation_id = "get-connect-group-users",
+        tag = "Tag::ConnectGroup"
+    )]
+    async fn get_connect_group_users(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        keyring: web::Data<&Keyring>,
+        id: param::Path<String>,
+        limit: param::Query<Option<i64>>,
+        cursor: param::Query<Option<String>>,
+        sort: param::Query<Option<String>>,
+        role_id: param::Query<Option<String>>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<connect_group::get_users::Response, Error> {
+        self._get_connect_group_users(
+            auth,
+            db,
+            keyring,
+            id,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            connect_group::get_users::Filter { role_id: role_id.0 },
+            fields.0,
+        )
+        .await
+    }
+
+    /// Get connect group history
+    ///
+    /// Retrieve the periods users were members of a connect group, a period ending whenever a
+    /// user leaves or changes role, optionally only the ones ongoing `at` a point in time to
+    /// answer who was in the connect group then. Periods have no `joined_at` when they predate
+    /// the history. Results are paged, pass the returned `next_cursor` as `cursor` to retrieve
+    /// the next page. Sortable on `joined_at` and `name`, prefix with `-` for descending order.
+    ///
+    /// Without the `users:read` permission only the authenticated user and the members of the
+    /// connect groups they lead, or of the satellites they oversee as a coach or pastor, are
+    /// returned.
+    #[oai(
+        path = "/connect-groups/:id/history",
+        method = "get",
+        operation_id = "get-connect-group-history",
         tag = "Tag::ConnectGroup"
     )]
-    async fn remove_users_from_connect_group(
+    async fn get_connect_group_history(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        limit: param::Query<Option<i64>>,
+        cursor: param::Query<Option<String>>,
+        sort: param::Query<Option<String>>,
+        at: param::Query<Option<chrono::DateTime<chrono::Utc>>>,
+        role_id: param::Query<Option<String>>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<connect_group::list_history::Response, Error> {
+        self._list_connect_group_history(
+            auth,
+            db,
+            id,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            connect_group::list_history::Filter {
+                at: at.0,
+                role_id: role_id.0,
+            },
+            fields.0,
+        )
+        .await
+    }
+
+    /// Remove users from a connect group
+    ///
+    /// Remove users from a connect group given the connect group's id and the users' ids. The optional `reason`
+    /// is kept in their membership history.
+    ///
+    /// Requires the `connect-groups:write` permission.
+    #[oai(
+        path = "/connect-groups/:id/users",
+        method = "delete",
+        operation_id = "remove-users-from-connect-group",
+        tag = "Tag::ConnectGroup"
+    )]
+    async fn remove_users_from_connect_group(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        body: payload::Json<connect_group::remove_users::Request>,
+    ) -> Result<connect_group::remove_users::Response, Error> {
+        self._remove_users_from_connect_group(auth, db, id, body)
+            .await
+    }
+
+    /* Connect Group Category */
+
+    /// Create a connect group category
+    ///
+    /// Create a new connect group category given its information.
+    ///
+    /// Requires the `connect-groups:write` permission.
+    #[oai(
+        path = "/connect-group-categories",
+        method = "post",
+        operation_id = "create-connect-group-category",
+        tag = "Tag::ConnectGroupCategory"
+    )]
+    async fn create_connect_group_category(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        body: payload::Json<connect_group_category::create::Request>,
+    ) -> Result<connect_group_category::create::Response, Error> {
+        self._create_connect_group_category(auth, db, body).await
+    }
+
+    /// List or search connect group categories
+    ///
+    /// Retrieve a list of connect group categories or search for connect group categories given a
+    /// query. Results are paged, pass the returned `next_cursor` as `cursor` to retrieve the next
+    /// page. Sortable on `name`, prefix with `-` for descending order.
+    #[oai(
+        path = "/connect-group-categories",
+        method = "get",
+        operation_id = "list-connect-group-categories",
+        tag = "Tag::ConnectGroupCategory"
+    )]
+    async fn list_connect_group_categories(
+        &self,
+        _auth: BearerAuth,
+        db: web::Data<&Database>,
+        limit: param::Query<Option<i64>>,
+        cursor: param::Query<Option<String>>,
+        sort: param::Query<Option<String>>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<connect_group_category::list::Response, Error> {
+        self._list_connect_group_categories(
+            db,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            fields.0,
+        )
+        .await
+    }
+
+    /// Get a connect group category
+    ///
+    /// Retrieve a connect group category's details given its id.
+    #[oai(
+        path = "/connect-group-categories/:id",
+        method = "get",
+        operation_id = "get-connect-group-category",
+        tag = "Tag::ConnectGroupCategory"
+    )]
+    async fn get_connect_group_category(
+        &self,
+        _auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<connect_group_category::get::Response, Error> {
+        self._get_connect_group_category(db, id, fields.0).await
+    }
+
+    /// Update a connect group category
+    ///
+    /// Update a connect group category's details given its id and the corresponding fields to update.
+    ///
+    /// Requires the `connect-groups:write` permission.
+    #[oai(
+        path = "/connect-group-categories/:id",
+        method = "patch",
+        operation_id = "update-connect-group-category",
+        tag = "Tag::ConnectGroupCategory"
+    )]
+    async fn update_connect_group_category(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        body: payload::Json<connect_group_category::update::Request>,
+    ) -> Result<connect_group_category::update::Response, Error> {
+        self._update_connect_group_category(auth, db, id, body)
+            .await
+    }
+
+    /// Delete a connect group category
+    ///
+    /// Delete a connect group category given its id.
+    ///
+    /// Requires the `connect-groups:write` permission.
+    #[oai(
+        path = "/connect-group-categories/:id",
+        method = "delete",
+        operation_id = "delete-connect-group-category",
+        tag = "Tag::ConnectGroupCategory"
+    )]
+    async fn delete_connect_group_category(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+    ) -> Result<connect_group_category::delete::Response, Error> {
+        self._delete_connect_group_category(auth, db, id).await
+    }
+
+    /* Pastoral Roles */
+
+    /// Create a pastoral role
+    ///
+    /// Create a new pastoral role given its information.
+    ///
+    /// Requires the `roles:write` permission.
+    #[oai(
+        path = "/pastoral-roles",
+        method = "post",
+        operation_id = "create-pastoral-role",
+        tag = "Tag::PastoralRole"
+    )]
+    async fn create_pastoral_role(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        body: payload::Json<pastoral_role::create::Request>,
+    ) -> Result<pastoral_role::create::Response, Error> {
+        self._create_pastoral_role(auth, db, body).await
+    }
+
+    /// List or search pastoral roles
+    ///
+    /// Retrieve a list of pastoral roles or search for pastoral roles given a query. Results are
+    /// paged, pass the returned `next_cursor` as `cursor` to retrieve the next page. Sortable on
+    /// `weight` and `name`, prefix with `-` for descending order.
+    #[oai(
+        path = "/pastoral-roles",
+        method = "get",
+        operation_id = "list-pastoral-roles",
+        tag = "Tag::PastoralRole"
+    )]
+    async fn list_pastoral_roles(
+        &self,
+        _auth: BearerAuth,
+        db: web::Data<&Database>,
+        limit: param::Query<Option<i64>>,
+        cursor: param::Query<Option<String>>,
+        sort: param::Query<Option<String>>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<pastoral_role::list::Response, Error> {
+        self._list_pastoral_roles(
+            db,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            fields.0,
+        )
+        .await
+    }
+
+    /// Get a pastoral role
+    ///
+    /// Retrieve a pastoral role's details given its id.
+    #[oai(
+        path = "/pastoral-roles/:id",
+        method = "get",
+        operation_id = "get-pastoral-role",
+        tag = "Tag::PastoralRole"
+    )]
+    async fn get_pastoral_role(
+        &self,
+        _auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<pastoral_role::get::Response, Error> {
+        self._get_pastoral_role(db, id, fields.0).await
+    }
+
+    /// Update a pastoral role
+    ///
+    /// Update a pastoral role's details given its id and the corresponding fields to update.
+    ///
+    /// Requires the `roles:write` permission.
+    #[oai(
+        path = "/pastoral-roles/:id",
+        method = "patch",
+        operation_id = "update-pastoral-role",
+        tag = "Tag::PastoralRole"
+    )]
+    async fn update_pastoral_role(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        body: payload::Json<pastoral_role::update::Request>,
+    ) -> Result<pastoral_role::update::Response, Error> {
+        self._update_pastoral_role(auth, db, id, body).await
+    }
+
+    /// Delete a pastoral role
+    ///
+    /// Delete a pastoral role given its id.
+    ///
+    /// Requires the `roles:write` permission.
+    #[oai(
+        path = "/pastoral-roles/:id",
+        method = "delete",
+        operation_id = "delete-pastoral-role",
+        tag = "Tag::PastoralRole"
+    )]
+    async fn delete_pastoral_role(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+    ) -> Result<pastoral_role::delete::Response, Error> {
+        self._delete_pastoral_role(auth, db, id).await
+    }
+
+    /* Ministry Roles */
+
+    /// Create a ministry role
+    ///
+    /// Create a new ministry role given its information.
+    ///
+    /// Requires the `roles:write` permission.
+    #[oai(
+        path = "/ministry-roles",
+        method = "post",
+        operation_id = "create-ministry-role",
+        tag = "Tag::MinistryRole"
+    )]
+    async fn create_ministry_role(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        body: payload::Json<ministry_role::create::Request>,
+    ) -> Result<ministry_role::create::Response, Error> {
+        self._create_ministry_role(auth, db, body).await
+    }
+
+    /// List or search ministry roles
+    ///
+    /// Retrieve a list of ministry roles or search for ministry roles given a query. Results are
+    /// paged, pass the returned `next_cursor` as `cursor` to retrieve the next page. Sortable on
+    /// `weight` and `name`, prefix with `-` for descending order.
+    #[oai(
+        path = "/ministry-roles",
+        method = "get",
+        operation_id = "list-ministry-roles",
+        tag = "Tag::MinistryRole"
+    )]
+    async fn list_ministry_roles(
+        &self,
+        _auth: BearerAuth,
+        db: web::Data<&Database>,
+        limit: param::Query<Option<i64>>,
+        cursor: param::Query<Option<String>>,
+        sort: param::Query<Option<String>>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<ministry_role::list::Response, Error> {
+        self._list_ministry_roles(
+            db,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            fields.0,
+        )
+        .await
+    }
+
+    /// Get a ministry role
+    ///
+    /// Retrieve a ministry role's details given its id.
+    #[oai(
+        path = "/ministry-roles/:id",
+        method = "get",
+        operation_id = "get-ministry-role",
+        tag = "Tag::MinistryRole"
+    )]
+    async fn get_ministry_role(
+        &self,
+        _auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<ministry_role::get::Response, Error> {
+        self._get_ministry_role(db, id, fields.0).await
+    }
+
+    /// Update a ministry role
+    ///
+    /// Update a ministry role's details given its id and the corresponding fields to update.
+    ///
+    /// Requires the `roles:write` permission.
+    #[oai(
+        path = "/ministry-roles/:id",
+        method = "patch",
+        operation_id = "update-ministry-role",
+        tag = "Tag::MinistryRole"
+    )]
+    async fn update_ministry_role(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        body: payload::Json<ministry_role::update::Request>,
+    ) -> Result<ministry_role::update::Response, Error> {
+        self._update_ministry_role(auth, db, id, body).await
+    }
+
+    /// Delete a ministry role
+    ///
+    /// Delete a ministry role given its id.
+    ///
+    /// Requires the `roles:write` permission.
+    #[oai(
+        path = "/ministry-roles/:id",
+        method = "delete",
+        operation_id = "delete-ministry-role",
+        tag = "Tag::MinistryRole"
+    )]
+    async fn delete_ministry_role(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+    ) -> Result<ministry_role::delete::Response, Error> {
+        self._delete_ministry_role(auth, db, id).await
+    }
+
+    /* Ministry Team */
+
+    /// Create a ministry team
+    ///
+    /// Create a new ministry team given its information.
+    ///
+    /// Requires the `ministries:write` permission.
+    #[oai(
+        path = "/ministry-teams",
+        method = "post",
+        operation_id = "create-ministry-team",
+        tag = "Tag::MinistryTeam"
+    )]
+    async fn create_ministry_team(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        body: payload::Json<ministry_team::create::Request>,
+    ) -> Result<ministry_team::create::Response, Error> {
+        self._create_ministry_team(auth, db, body).await
+    }
+
+    /// List or search ministry teams
+    ///
+    /// Retrieve a list of ministry teams or search for ministry teams given a query. Results are
+    /// paged, pass the returned `next_cursor` as `cursor` to retrieve the next page. Sortable on
+    /// `created_at`, `updated_at` and `name`, prefix with `-` for descending order.
+    #[oai(
+        path = "/ministry-teams",
+        method = "get",
+        operation_id = "list-ministry-team",
+        tag = "Tag::MinistryTeam"
+    )]
+    async fn list_ministry_team(
+        &self,
+        _auth: BearerAuth,
+        db: web::Data<&Database>,
+        limit: param::Query<Option<i64>>,
+        cursor: param::Query<Option<String>>,
+        sort: param::Query<Option<String>>,
+        created_after: param::Query<Option<chrono::DateTime<chrono::Utc>>>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<ministry_team::list::Response, Error> {
+        self._list_ministry_team(
+            db,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            ministry_team::list::Filter {
+                created_after: created_after.0,
+            },
+            fields.0,
+        )
+        .await
+    }
+
+    /// Get a ministry team
+    ///
+    /// Retrieve a ministry team's details given its id.
+    #[oai(
+        path = "/ministry-teams/:id",
+        method = "get",
+        operation_id = "get-ministry-team",
+        tag = "Tag::MinistryTeam"
+    )]
+    async fn get_ministry_team(
         &self,
         _auth: BearerAuth,
         db: web::Data<&Database>,
         id: param::Path<String>,
-        body: payload::Json<connect_group::remove_users::Request>,
-    ) -> Result<connect_group::remove_users::Response, connect_group::remove_users::Error> {
-        self._remove_users_from_connect_group(db, id, body).await
+        fields: param::Query<Option<String>>,
+    ) -> Result<ministry_team::get::Response, Error> {
+        self._get_ministry_team(db, id, fields.0).await
     }
 
-    /* Connect Group Category */
+    /// Update a ministry team
+    ///
+    /// Update a ministry team's details given its id and the corresponding fields to update.
+    ///
+    /// Requires the `ministries:write` permission.
+    #[oai(
+        path = "/ministry-teams/:id",
+        method = "patch",
+        operation_id = "update-ministry-team",
+        tag = "Tag::MinistryTeam"
+    )]
+    async fn update_ministry_team(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        body: payload::Json<ministry_team::update::Request>,
+    ) -> Result<ministry_team::update::Response, Error> {
+        self._update_ministry_team(auth, db, id, body).await
+    }
 
-    /// Create a connect group category
+    /// Delete a ministry team
     ///
-    /// Create a new connect group category given its information.
+    /// Delete a ministry team given its id.
+    ///
+    /// Requires the `ministries:write` permission.
     #[oai(
-        path = "/connect-group-categories",
+        path = "/ministry-teams/:id",
+        method = "delete",
+        operation_id = "delete-ministry-team",
+        tag = "Tag::MinistryTeam"
+    )]
+    async fn delete_ministry_team(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+    ) -> Result<ministry_team::delete::Response, Error> {
+        self._delete_ministry_team(auth, db, id).await
+    }
+
+    /* Ministry Departments */
+
+    /// Create a ministry department
+    ///
+    /// Create a new ministry department given its information.
+    ///
+    /// Requires the `ministries:write` permission.
+    #[oai(
+        path = "/ministry-departments",
         method = "post",
-        operation_id = "create-connect-group-category",
-        tag = "Tag::ConnectGroupCategory"
+        operation_id = "create-ministry-department",
+        tag = "Tag::MinistryDepartment"
     )]
-    async fn create_connect_group_category(
+    async fn create_ministry_department(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
-        body: payload::Json<connect_group_category::create::Request>,
-    ) -> Result<connect_group_category::create::Response, connect_group_category::create::Error>
-    {
-        self._create_connect_group_category(db, body).await
+        body: payload::Json<ministry_department::create::Request>,
+    ) -> Result<ministry_department::create::Response, Error> {
+        self._create_ministry_department(auth, db, body).await
     }
 
-    /// List or search connect group categories
+    /// List or search ministry departments
     ///
-    /// Retrieve a list of connect group categories or search for connect group categories given a query.
+    /// Retrieve a list of ministry departments or search for ministry departments given a query.
+    /// Results are paged, pass the returned `next_cursor` as `cursor` to retrieve the next page.
+    /// Sortable on `created_at`, `updated_at` and `name`, prefix with `-` for descending order.
     #[oai(
-        path = "/connect-group-categories",
+        path = "/ministry-departments",
         method = "get",
-        operation_id = "list-connect-group-categories",
-        tag = "Tag::ConnectGroupCategory"
+        operation_id = "list-ministry-department",
+        tag = "Tag::MinistryDepartment"
     )]
-    async fn list_connect_group_categories(
+    async fn list_ministry_department(
         &self,
         _auth: BearerAuth,
         db: web::Data<&Database>,
-    ) -> Result<connect_group_category::list::Response, connect_group_category::list::Error> {
-        self._list_connect_group_categories(db).await
+        limit: param::Query<Option<i64>>,
+        cursor: param::Query<Option<String>>,
+        sort: param::Query<Option<String>>,
+        created_after: param::Query<Option<chrono::DateTime<chrono::Utc>>>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<ministry_department::list::Response, Error> {
+        self._list_ministry_department(
+            db,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            ministry_department::list::Filter {
+                created_after: created_after.0,
+            },
+            fields.0,
+        )
+        .await
     }
 
-    /// Get a connect group category
+    /// Get a ministry department
     ///
-    /// Retrieve a connect group category's details given its id.
+    /// Retrieve a ministry department's details given its id.
     #[oai(
-        path = "/connect-group-categories/:id",
+        path = "/ministry-departments/:id",
         method = "get",
-        operation_id = "get-connect-group-category",
-        tag = "Tag::ConnectGroupCategory"
+        operation_id = "get-ministry-department",
+        tag = "Tag::MinistryDepartment"
     )]
-    async fn get_connect_group_category(
+    async fn get_ministry_department(
         &self,
         _auth: BearerAuth,
         db: web::Data<&Database>,
         id: param::Path<String>,
-    ) -> Result<connect_group_category::get::Response, connect_group_category::get::Error> {
-        self._get_connect_group_category(db, id).await
+        fields: param::Query<Option<String>>,
+    ) -> Result<ministry_department::get::Response, Error> {
+        self._get_ministry_department(db, id, fields.0).await
     }
 
-    /// Update a connect group category
+    /// Update a ministry department
     ///
-    /// Update a connect group category's details given its id and the corresponding fields to update.
+    /// Update a ministry department's details given its id and the corresponding fields to update.
+    ///
+    /// Requires the `ministries:write` permission.
     #[oai(
-        path = "/connect-group-categories/:id",
+        path = "/ministry-departments/:id",
         method = "patch",
-        operation_id = "update-connect-group-category",
-        tag = "Tag::ConnectGroupCategory"
+        operation_id = "update-ministry-department",
+        tag = "Tag::MinistryDepartment"
     )]
-    async fn update_connect_group_category(
+    async fn update_ministry_department(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: param::Path<String>,
-        body: payload::Json<connect_group_category::update::Request>,
-    ) -> Result<connect_group_category::update::Response, connect_group_category::update::Error>
-    {
-        self._update_connect_group_category(db, id, body).await
+        body: payload::Json<ministry_department::update::Request>,
+    ) -> Result<ministry_department::update::Response, Error> {
+        self._update_ministry_department(auth, db, id, body).await
     }
-
-    /// Delete a connect group category
+    /// Delete a ministry department
     ///
-    /// Delete a connect group category given its id.
+    /// Delete a ministry department given its id.
+    ///
+    /// Requires the `ministries:write` permission.
     #[oai(
-        path = "/connect-group-categories/:id",
+        path = "/ministry-departments/:id",
         method = "delete",
-        operation_id = "delete-connect-group-category",
-        tag = "Tag::ConnectGroupCategory"
+        operation_id = "delete-ministry-department",
+        tag = "Tag::MinistryDepartment"
     )]
-    async fn delete_connect_group_category(
+    async fn delete_ministry_department(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: param::Path<String>,
-    ) -> Result<connect_group_category::delete::Response, connect_group_category::delete::Error>
-    {
-        self._delete_connect_group_category(db, id).await
+    ) -> Result<ministry_department::delete::Response, Error> {
+        self._delete_ministry_department(auth, db, id).await
     }
 
-    /* Pastoral Roles */
+    /* Ministry */
 
-    /// Create a pastoral role
+    /// Create a ministry
     ///
-    /// Create a new pastoral role given its information.
+    /// Create a new ministry given its information.
+    ///
+    /// Requires the `ministries:write` permission.
     #[oai(
-        path = "/pastoral-roles",
+        path = "/ministries",
         method = "post",
-        operation_id = "create-pastoral-role",
-        tag = "Tag::PastoralRole"
+        operation_id = "create-ministry",
+        tag = "Tag::Ministry"
     )]
-    async fn create_pastoral_role(
+    async fn create_ministry(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
-        body: payload::Json<pastoral_role::create::Request>,
-    ) -> Result<pastoral_role::create::Response, pastoral_role::create::Error> {
-        self._create_pastoral_role(db, body).await
+        body: payload::Json<ministry::create::Request>,
+    ) -> Result<ministry::create::Response, Error> {
+        self._create_minitry(auth, db, body).await
     }
 
-    /// List or search pastoral roles
+    /// List or search ministries
     ///
-    /// Retrieve a list of pastoral roles or search for pastoral roles given a query.
+    /// Retrieve a list of ministries or search for ministries given a query. Results are paged,
+    /// pass the returned `next_cursor` as `cursor` to retrieve the next page. Sortable on
+    /// `created_at`, `updated_at` and `name`, prefix with `-` for descending order.
     #[oai(
-        path = "/pastoral-roles",
+        path = "/ministries",
         method = "get",
-        operation_id = "list-pastoral-roles",
-        tag = "Tag::PastoralRole"
+        operation_id = "list-ministries",
+        tag = "Tag::Ministry"
     )]
-    async fn list_pastoral_roles(
+    async fn list_ministries(
         &self,
         _auth: BearerAuth,
         db: web::Data<&Database>,
-    ) -> Result<pastoral_role::list::Response, pastoral_role::list::Error> {
-        self._list_pastoral_roles(db).await
+        limit: param::Query<Option<i64>>,
+        cursor: param::Query<Option<String>>,
+        sort: param::Query<Option<String>>,
+        satellite_id: param::Query<Option<String>>,
+        department_id: param::Query<Option<String>>,
+        team_id: param::Query<Option<String>>,
+        created_after: param::Query<Option<chrono::DateTime<chrono::Utc>>>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<ministry::list::Response, Error> {
+        self._list_ministries(
+            db,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            ministry::list::Filter {
+                satellite_id: satellite_id.0,
+                department_id: department_id.0,
+                team_id: team_id.0,
+                created_after: created_after.0,
+            },
+            fields.0,
+        )
+        .await
     }
 
-    /// Get a pastoral role
+    /// Get a ministry
     ///
-    /// Retrieve a pastoral role's details given its id.
+    /// Retrieve a ministry's details given its id.
     #[oai(
-        path = "/pastoral-roles/:id",
+        path = "/ministries/:id",
         method = "get",
-        operation_id = "get-pastoral-role",
-        tag = "Tag::PastoralRole"
+        operation_id = "get-ministry",
+        tag = "Tag::Ministry"
     )]
-    async fn get_pastoral_role(
+    async fn get_ministry(
         &self,
         _auth: BearerAuth,
         db: web::Data<&Database>,
         id: param::Path<String>,
-    ) -> Result<pastoral_role::get::Response, pastoral_role::get::Error> {
-        self._get_pastoral_role(db, id).await
+        fields: param::Query<Option<String>>,
+    ) -> Result<ministry::get::Response, Error> {
+        self._get_ministry(db, id, fields.0).await
     }
 
-    /// Update a pastoral role
+    /// Update a ministry
     ///
-    /// Update a pastoral role's details given its id and the corresponding fields to update.
+    /// Update a ministry's details given its id and the corresponding fields to update.
+    ///
+    /// Requires the `ministries:write` permission.
     #[oai(
-        path = "/pastoral-roles/:id",
+        path = "/ministries/:id",
         method = "patch",
-        operation_id = "update-pastoral-role",
-        tag = "Tag::PastoralRole"
+        operation_id = "update-ministry",
+        tag = "Tag::Ministry"
+    )]
+    async fn update_ministry(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        body: payload::Json<ministry::update::Request>,
+    ) -> Result<ministry::update::Response, Error> {
+        self._update_ministry(auth, db, id, body).await
+    }
+
+    /// Delete a ministry
+    ///
+    /// Delete a ministry given its id.
+    ///
+    /// Requires the `ministries:write` permission.
+    #[oai(
+        path = "/ministries/:id",
+        method = "delete",
+        operation_id = "delete-ministry",
+        tag = "Tag::Ministry"
+    )]
+    async fn delete_ministry(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+    ) -> Result<ministry::delete::Response, Error> {
+        self._delete_ministry(auth, db, id).await
+    }
+
+    /// Associate users with a ministry
+    ///
+    /// Associate users with a ministry given the ministry's id and the users' ids.
+    ///
+    /// Requires the `ministries:write` permission.
+    #[oai(
+        path = "/ministries/:id/users",
+        method = "post",
+        operation_id = "associate-users-with-ministry",
+        tag = "Tag::Ministry"
+    )]
+    async fn associate_users_with_ministry(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        body: payload::Json<ministry::associate_users::Request>,
+    ) -> Result<ministry::associate_users::Response, Error> {
+        self._associate_users_with_ministry(auth, db, id, body)
+            .await
+    }
+
+    /// Get users associated with a ministry
+    ///
+    /// Retrieve a list of users associated with a ministry given the ministry's id. Results are
+    /// paged, pass the returned `next_cursor` as `cursor` to retrieve the next page. Sortable on
+    /// `created_at`, `updated_at`, `name`, `email` and `no`, prefix with `-` for descending order.
+    #[oai(
+        path = "/ministries/:id/users",
+        method = "get",
+        operation_id = "get-ministry-users",
+        tag = "Tag::Ministry"
+    )]
+    async fn get_ministry_users(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        keyring: web::Data<&Keyring>,
+        id: param::Path<String>,
+        limit: param::Query<Option<i64>>,
+        cursor: param::Query<Option<String>>,
+        sort: param::Query<Option<String>>,
+        role_id: param::Query<Option<String>>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<ministry::get_users::Response, Error> {
+        self._get_ministry_users(
+            auth,
+            db,
+            keyring,
+            id,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            ministry::get_users::Filter { role_id: role_id.0 },
+            fields.0,
+        )
+        .await
+    }
+
+    /// Get ministry history
+    ///
+    /// Retrieve the periods users were members of a ministry, a period ending whenever a user
+    /// leaves or changes role, optionally only the ones ongoing `at` a point in time to answer
+    /// who was in the ministry then. Periods have no `joined_at` when they predate the history.
+    /// Results are paged, pass the returned `next_cursor` as `cursor` to retrieve the next
+    /// page. Sortable on `joined_at` and `name`, prefix with `-` for descending order.
+    ///
+    /// Without the `users:read` permission only the authenticated user and the members of the
+    /// connect groups they lead, or of the satellites they oversee as a coach or pastor, are
+    /// returned.
+    #[oai(
+        path = "/ministries/:id/history",
+        method = "get",
+        operation_id = "get-ministry-history",
+        tag = "Tag::Ministry"
+    )]
+    async fn get_ministry_history(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        limit: param::Query<Option<i64>>,
+        cursor: param::Query<Option<String>>,
+        sort: param::Query<Option<String>>,
+        at: param::Query<Option<chrono::DateTime<chrono::Utc>>>,
+        role_id: param::Query<Option<String>>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<ministry::list_history::Response, Error> {
+        self._list_ministry_history(
+            auth,
+            db,
+            id,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            ministry::list_history::Filter {
+                at: at.0,
+                role_id: role_id.0,
+            },
+            fields.0,
+        )
+        .await
+    }
+
+    /// Remove users from a ministry
+    ///
+    /// Remove users from a ministry given the ministry's id and the users' ids. The optional `reason`
+    /// is kept in their membership history.
+    ///
+    /// Requires the `ministries:write` permission.
+    #[oai(
+        path = "/ministries/:id/users",
+        method = "delete",
+        operation_id = "remove-users-from-ministry",
+        tag = "Tag::Ministry"
     )]
-    async fn update_pastoral_role(
+    async fn remove_users_from_ministry(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: param::Path<String>,
-        body: payload::Json<pastoral_role::update::Request>,
-    ) -> Result<pastoral_role::update::Response, pastoral_role::update::Error> {
-        self._update_pastoral_role(db, id, body).await
+        body: payload::Json<ministry::remove_users::Request>,
+    ) -> Result<ministry::remove_users::Response, Error> {
+        self._remove_users_from_ministry(auth, db, id, body).await
     }
 
-    /// Delete a pastoral role
+    /* Org Tree */
+
+    /// Get the organisation tree
     ///
-    /// Delete a pastoral role given its id.
+    /// Retrieve every satellite along with its departments, teams and ministries, and its
+    /// connect group categories and open connect groups. Every node carries its member count,
+    /// and satellites, ministries and connect groups their leaders as resolved through the
+    /// weights of the pastoral and ministry roles.
     #[oai(
-        path = "/pastoral-roles/:id",
-        method = "delete",
-        operation_id = "delete-pastoral-role",
-        tag = "Tag::PastoralRole"
+        path = "/org-tree",
+        method = "get",
+        operation_id = "get-org-tree",
+        tag = "Tag::OrgTree"
     )]
-    async fn delete_pastoral_role(
+    async fn get_org_tree(
         &self,
         _auth: BearerAuth,
         db: web::Data<&Database>,
-        id: param::Path<String>,
-    ) -> Result<pastoral_role::delete::Response, pastoral_role::delete::Error> {
-        self._delete_pastoral_role(db, id).await
+        fields: param::Query<Option<String>>,
+    ) -> Result<org_tree::get::Response, Error> {
+        self._get_org_tree(db, fields.0).await
     }
 
-    /* Ministry Roles */
+    /* Household */
 
-    /// Create a ministry role
+    /// Create a household
     ///
-    /// Create a new ministry role given its information.
+    /// Group users living together under a shared address.
+    ///
+    /// Requires the `users:write` permission. Without it granted by the access token, only the
+    /// members of the connect groups the user leads, or of the satellites they oversee as a
+    /// coach or pastor, can be added.
     #[oai(
-        path = "/ministry-roles",
+        path = "/households",
         method = "post",
-        operation_id = "create-ministry-role",
-        tag = "Tag::MinistryRole"
+        operation_id = "create-household",
+        tag = "Tag::Household"
     )]
-    async fn create_ministry_role(
+    async fn create_household(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
-        body: payload::Json<ministry_role::create::Request>,
-    ) -> Result<ministry_role::create::Response, ministry_role::create::Error> {
-        self._create_ministry_role(db, body).await
+        body: payload::Json<household::create::Request>,
+    ) -> Result<household::create::Response, Error> {
+        self._create_household(auth, db, body).await
     }
 
-    /// List or search ministry roles
+    /// Get a household
+    ///
+    /// Retrieve a household along with its members.
     ///
-    /// Retrieve a list of ministry roles or search for ministry roles given a query.
+    /// Without the `users:read` permission only households with the authenticated user or a
+    /// member of the connect groups they lead, or of the satellites they oversee as a coach or
+    /// pastor, can be retrieved.
     #[oai(
-        path = "/ministry-roles",
+        path = "/households/:id",
         method = "get",
-        operation_id = "list-ministry-roles",
-        tag = "Tag::MinistryRole"
+        operation_id = "get-household",
+        tag = "Tag::Household"
     )]
-    async fn list_ministry_roles(
+    async fn get_household(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
-    ) -> Result<ministry_role::list::Response, ministry_role::list::Error> {
-        self._list_ministry_roles(db).await
+        id: param::Path<String>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<household::get::Response, Error> {
+        self._get_household(auth, db, id, fields.0).await
     }
 
-    /// Get a ministry role
+    /// Update a household
     ///
-    /// Retrieve a ministry role's details given its id.
+    /// Update the name or address of a household.
+    ///
+    /// Requires the `users:write` permission. Without it granted by the access token, only
+    /// households with a member in the connect groups the user leads, or in the satellites they
+    /// oversee as a coach or pastor, can be updated.
     #[oai(
-        path = "/ministry-roles/:id",
-        method = "get",
-        operation_id = "get-ministry-role",
-        tag = "Tag::MinistryRole"
+        path = "/households/:id",
+        method = "patch",
+        operation_id = "update-household",
+        tag = "Tag::Household"
     )]
-    async fn get_ministry_role(
+    async fn update_household(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: param::Path<String>,
-    ) -> Result<ministry_role::get::Response, ministry_role::get::Error> {
-        self._get_ministry_role(db, id).await
+        body: payload::Json<household::update::Request>,
+    ) -> Result<household::update::Response, Error> {
+        self._update_household(auth, db, id, body).await
     }
 
-    /// Update a ministry role
+    /// Delete a household
     ///
-    /// Update a ministry role's details given its id and the corresponding fields to update.
+    /// Delete a household, its members are left untouched.
+    ///
+    /// Requires the `users:write` permission. Without it granted by the access token, only
+    /// households with a member in the connect groups the user leads, or in the satellites they
+    /// oversee as a coach or pastor, can be deleted.
     #[oai(
-        path = "/ministry-roles/:id",
-        method = "patch",
-        operation_id = "update-ministry-role",
-        tag = "Tag::MinistryRole"
+        path = "/households/:id",
+        method = "delete",
+        operation_id = "delete-household",
+        tag = "Tag::Household"
     )]
-    async fn update_ministry_role(
+    async fn delete_household(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: param::Path<String>,
-        body: payload::Json<ministry_role::update::Request>,
-    ) -> Result<ministry_role::update::Response, ministry_role::update::Error> {
-        self._update_ministry_role(db, id, body).await
+    ) -> Result<household::delete::Response, Error> {
+        self._delete_household(auth, db, id).await
     }
 
-    /// Delete a ministry role
+    /// Add users to a household
     ///
-    /// Delete a ministry role given its id.
+    /// Add users to a household, a user may belong to several households.
+    ///
+    /// Requires the `users:write` permission. Without it granted by the access token, only
+    /// households with a member in the connect groups the user leads, or in the satellites they
+    /// oversee as a coach or pastor, can be changed, and only their members added.
     #[oai(
-        path = "/ministry-roles/:id",
+        path = "/households/:id/users",
+        method = "post",
+        operation_id = "add-users-to-household",
+        tag = "Tag::Household"
+    )]
+    async fn add_users_to_household(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: param::Path<String>,
+        body: payload::Json<household::add_users::Request>,
+    ) -> Result<household::add_users::Response, Error> {
+        self._add_users_to_household(auth, db, id, body).await
+    }
+
+    /// Remove users from a household
+    ///
+    /// Remove users from a household.
+    ///
+    /// Requires the `users:write` permission. Without it granted by the access token, only
+    /// households with a member in the connect groups the user leads, or in the satellites they
+    /// oversee as a coach or pastor, can be changed.
+    #[oai(
+        path = "/households/:id/users",
         method = "delete",
-        operation_id = "delete-ministry-role",
-        tag = "Tag::MinistryRole"
+        operation_id = "remove-users-from-household",
+        tag = "Tag::Household"
     )]
-    async fn delete_ministry_role(
+    async fn remove_users_from_household(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: param::Path<String>,
-    ) -> Result<ministry_role::delete::Response, ministry_role::delete::Error> {
-        self._delete_ministry_role(db, id).await
+        body: payload::Json<household::remove_users::Request>,
+    ) -> Result<household::remove_users::Response, Error> {
+        self._remove_users_from_household(auth, db, id, body).await
     }
 
-    /* Ministry Team */
+    /* Import */
 
-    /// Create a ministry team
+    /// Import users
     ///
-    /// Create a new ministry team given its information.
+    /// Import users from a CSV or XLSX file, replacing the legacy spreadsheets. The `mapping`
+    /// names the column holding each field, only the name is required. Dates are read day first
+    /// (e.g. `11.6.1989`) or in ISO 8601, and phone numbers normalised to E.164, taking numbers
+    /// without a country code as Malaysian. Satellites, connect groups and pastoral roles are
+    /// looked up by name, through the aliases given.
+    ///
+    /// Rows matching an existing user by email, phone number, IC number, or name and date of
+    /// birth are not imported again, so importing the same file twice changes nothing. Rows only
+    /// sharing the name of an existing user without both dates of birth to compare, or matching
+    /// a deleted user, are reported as errors instead. Users imported without an email are given
+    /// a placeholder one on the `import.invalid` domain. The connect group memberships are
+    /// created along with the users, and satellites that do not exist are created when
+    /// `satellite_address` is given.
+    ///
+    /// The file is imported in a single transaction, which is only committed when no row has an
+    /// error. A dry run reports the same counts and errors without changing anything.
+    ///
+    /// Requires the `users:admin` permission, and the `satellites:write` permission to create
+    /// satellites.
     #[oai(
-        path = "/ministry-teams",
+        path = "/imports/users",
         method = "post",
-        operation_id = "create-ministry-team",
-        tag = "Tag::MinistryTeam"
+        operation_id = "import-users",
+        tag = "Tag::Import"
     )]
-    async fn create_ministry_team(
+    async fn import_users(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
-        body: payload::Json<ministry_team::create::Request>,
-    ) -> Result<ministry_team::create::Response, ministry_team::create::Error> {
-        self._create_ministry_team(db, body).await
+        keyring: web::Data<&Keyring>,
+        body: import::users::Request,
+    ) -> Result<import::users::Response, Error> {
+        self._import_users(auth, db, keyring, body).await
     }
 
-    /// List or search ministry teams
+    /* Shadow User */
+
+    /// List shadow users
+    ///
+    /// Retrieve a list of the members imported from the legacy spreadsheets, optionally filtered
+    /// by a `search` over their name, email and phone number, or by whether they were already
+    /// merged into a user. Results are paged, pass the returned `next_cursor` as `cursor` to
+    /// retrieve the next page. Sortable on `no`, `name` and `created_at`, prefix with `-` for
+    /// descending order.
     ///
-    /// Retrieve a list of ministry teams or search for ministry teams given a query.
+    /// Requires the `users:admin` permission.
     #[oai(
-        path = "/ministry-teams",
+        path = "/shadow-users",
         method = "get",
-        operation_id = "list-ministry-team",
-        tag = "Tag::MinistryTeam"
+        operation_id = "list-shadow-users",
+        tag = "Tag::ShadowUser"
     )]
-    async fn list_ministry_team(
+    async fn list_shadow_users(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
-    ) -> Result<ministry_team::list::Response, ministry_team::list::Error> {
-        self._list_ministry_team(db).await
+        limit: param::Query<Option<i64>>,
+        cursor: param::Query<Option<String>>,
+        sort: param::Query<Option<String>>,
+        search: param::Query<Option<String>>,
+        claimed: param::Query<Option<bool>>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<shadow_user::list::Response, Error> {
+        self._list_shadow_users(
+            auth,
+            db,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            shadow_user::list::Filter {
+                search: search.0,
+                claimed: claimed.0,
+            },
+            fields.0,
+        )
+        .await
     }
 
-    /// Get a ministry team
+    /// Get a shadow user
     ///
-    /// Retrieve a ministry team's details given its id.
+    /// Retrieve a shadow user's details given its number.
+    ///
+    /// Requires the `users:admin` permission.
     #[oai(
-        path = "/ministry-teams/:id",
+        path = "/shadow-users/:no",
         method = "get",
-        operation_id = "get-ministry-team",
-        tag = "Tag::MinistryTeam"
+        operation_id = "get-shadow-user",
+        tag = "Tag::ShadowUser"
     )]
-    async fn get_ministry_team(
+    async fn get_shadow_user(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
-        id: param::Path<String>,
-    ) -> Result<ministry_team::get::Response, ministry_team::get::Error> {
-        self._get_ministry_team(db, id).await
+        no: param::Path<i32>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<shadow_user::get::Response, Error> {
+        self._get_shadow_user(auth, db, no, fields.0).await
     }
 
-    /// Update a ministry team
+    /// List the users matching a shadow user
     ///
-    /// Update a ministry team's details given its id and the corresponding fields to update.
+    /// Retrieve the users that a shadow user likely refers to, best matches first. Users are
+    /// matched on their phone number and IC number regardless of formatting, their email and the
+    /// similarity of their name, each match is scored from 0 to 1.
+    ///
+    /// Requires the `users:admin` permission.
     #[oai(
-        path = "/ministry-teams/:id",
-        method = "patch",
-        operation_id = "update-ministry-team",
-        tag = "Tag::MinistryTeam"
+        path = "/shadow-users/:no/matches",
+        method = "get",
+        operation_id = "list-shadow-user-matches",
+        tag = "Tag::ShadowUser"
     )]
-    async fn update_ministry_team(
+    async fn list_shadow_user_matches(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
-        id: param::Path<String>,
-        body: payload::Json<ministry_team::update::Request>,
-    ) -> Result<ministry_team::update::Response, ministry_team::update::Error> {
-        self._update_ministry_team(db, id, body).await
+        keyring: web::Data<&Keyring>,
+        no: param::Path<i32>,
+        limit: param::Query<Option<i64>>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<shadow_user::list_matches::Response, Error> {
+        self._list_shadow_user_matches(auth, db, keyring, no, limit.0, fields.0).await
     }
 
-    /// Delete a ministry team
+    /// Resolve a shadow user
     ///
-    /// Delete a ministry team given its id.
+    /// Retrieve the connect group, pastoral role and ministries that the free-text `cg`,
+    /// `pastoral_status` and `ministry` columns of a shadow user refer to, as they would be
+    /// linked when the shadow user is confirmed. Values that match nothing, or more than one
+    /// thing, are listed in `unresolved`.
+    ///
+    /// Requires the `users:admin` permission.
     #[oai(
-        path = "/ministry-teams/:id",
-        method = "delete",
-        operation_id = "delete-ministry-team",
-        tag = "Tag::MinistryTeam"
+        path = "/shadow-users/:no/resolution",
+        method = "get",
+        operation_id = "get-shadow-user-resolution",
+        tag = "Tag::ShadowUser"
     )]
-    async fn delete_ministry_team(
+    async fn get_shadow_user_resolution(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
-        id: param::Path<String>,
-    ) -> Result<ministry_team::delete::Response, ministry_team::delete::Error> {
-        self._delete_ministry_team(db, id).await
+        no: param::Path<i32>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<shadow_user::get_resolution::Response, Error> {
+        self._get_shadow_user_resolution(auth, db, no, fields.0).await
     }
 
-    /* Ministry Departments */
-
-    /// Create a ministry department
+    /// Confirm a shadow user
     ///
-    /// Create a new ministry department given its information.
+    /// Merge a shadow user into the given user. The user's missing fields are filled in from the
+    /// shadow user, the user joins the resolved connect group and ministries, and the shadow user
+    /// is marked as claimed. Unresolved values must be given explicitly, otherwise a
+    /// `SHADOW_USER_UNRESOLVED` error lists them.
+    ///
+    /// Requires the `users:admin` permission.
     #[oai(
-        path = "/ministry-departments",
+        path = "/shadow-users/:no/confirm",
         method = "post",
-        operation_id = "create-ministry-department",
-        tag = "Tag::MinistryDepartment"
+        operation_id = "confirm-shadow-user",
+        tag = "Tag::ShadowUser"
     )]
-    async fn create_ministry_department(
+    async fn confirm_shadow_user(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
-        body: payload::Json<ministry_department::create::Request>,
-    ) -> Result<ministry_department::create::Response, ministry_department::create::Error> {
-        self._create_ministry_department(db, body).await
+        keyring: web::Data<&Keyring>,
+        no: param::Path<i32>,
+        body: payload::Json<shadow_user::confirm::Request>,
+    ) -> Result<shadow_user::confirm::Response, Error> {
+        self._confirm_shadow_user(auth, db, keyring, no, body).await
     }
 
-    /// List or search ministry departments
+    /* User Duplicate */
+
+    /// List duplicate users
     ///
-    /// Retrieve a list of ministry departments or search for ministry departments given a query.
+    /// Retrieve the pairs of users that are likely the same person, as found by the duplicate
+    /// detection, optionally filtered by `status`, by a `user_id` on either side or by a
+    /// `min_score`. Results are paged, pass the returned `next_cursor` as `cursor` to retrieve
+    /// the next page. Sortable on `detected_at` and `score`, prefix with `-` for descending
+    /// order (e.g. `-score` for the most likely duplicates first).
+    ///
+    /// Requires the `users:admin` permission.
     #[oai(
-        path = "/ministry-departments",
+        path = "/user-duplicates",
         method = "get",
-        operation_id = "list-ministry-department",
-        tag = "Tag::MinistryDepartment"
+        operation_id = "list-user-duplicates",
+        tag = "Tag::UserDuplicate"
     )]
-    async fn list_ministry_department(
+    async fn list_user_duplicates(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
-    ) -> Result<ministry_department::list::Response, ministry_department::list::Error> {
-        self._list_ministry_department(db).await
+        limit: param::Query<Option<i64>>,
+        cursor: param::Query<Option<String>>,
+        sort: param::Query<Option<String>>,
+        status: param::Query<Option<crate::entities::UserDuplicateStatus>>,
+        user_id: param::Query<Option<String>>,
+        min_score: param::Query<Option<f64>>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<user_duplicate::list::Response, Error> {
+        self._list_user_duplicates(
+            auth,
+            db,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            user_duplicate::list::Filter {
+                status: status.0,
+                user_id: user_id.0,
+                min_score: min_score.0,
+            },
+            fields.0,
+        )
+        .await
     }
 
-    /// Get a ministry department
+    /// Detect duplicate users
     ///
-    /// Retrieve a ministry department's details given its id.
+    /// Run the duplicate detection now rather than waiting for the background job. Users are
+    /// compared on their normalised phone number, IC number and email, ignoring dots and
+    /// plus-aliases, and on the similarity of their names. Reviewed pairs keep their status.
+    ///
+    /// Requires the `users:admin` permission.
     #[oai(
-        path = "/ministry-departments/:id",
-        method = "get",
-        operation_id = "get-ministry-department",
-        tag = "Tag::MinistryDepartment"
+        path = "/user-duplicates/detect",
+        method = "post",
+        operation_id = "detect-user-duplicates",
+        tag = "Tag::UserDuplicate"
     )]
-    async fn get_ministry_department(
+    async fn detect_user_duplicates(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
-        id: param::Path<String>,
-    ) -> Result<ministry_department::get::Response, ministry_department::get::Error> {
-        self._get_ministry_department(db, id).await
+    ) -> Result<user_duplicate::detect::Response, Error> {
+        self._detect_user_duplicates(auth, db).await
     }
 
-    /// Update a ministry department
+    /// Review a duplicate user
     ///
-    /// Update a ministry department's details given its id and the corresponding fields to update.
+    /// Dismiss a pair of users as distinct people, or set it back to pending. Duplicates are
+    /// resolved by merging the users through `/users/:id/merge`, which marks the pair as merged.
+    ///
+    /// Requires the `users:admin` permission.
     #[oai(
-        path = "/ministry-departments/:id",
+        path = "/user-duplicates/:user_id/:duplicate_user_id",
         method = "patch",
-        operation_id = "update-ministry-department",
-        tag = "Tag::MinistryDepartment"
+        operation_id = "update-user-duplicate",
+        tag = "Tag::UserDuplicate"
     )]
-    async fn update_ministry_department(
+    async fn update_user_duplicate(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
-        id: param::Path<String>,
-        body: payload::Json<ministry_department::update::Request>,
-    ) -> Result<ministry_department::update::Response, ministry_department::update::Error> {
-        self._update_ministry_department(db, id, body).await
+        user_id: param::Path<String>,
+        duplicate_user_id: param::Path<String>,
+        body: payload::Json<user_duplicate::update::Request>,
+    ) -> Result<user_duplicate::update::Response, Error> {
+        self._update_user_duplicate(auth, db, user_id, duplicate_user_id, body)
+            .await
     }
-    /// Delete a ministry department
+
+    /* Audit */
+
+    /// List audit logs
     ///
-    /// Delete a ministry department given its id.
+    /// Retrieve the changes made to users, memberships, connect groups, ministries, roles,
+    /// satellites and events, each with the user that made it and the row before and after,
+    /// optionally filtered by `actor`, by `resource` (the table, e.g. `user_connect_group`) and
+    /// `resource_id`, by `request_id` or by time range. Results are paged, pass the returned
+    /// `next_cursor` as `cursor` to retrieve the next page. Sortable on `created_at`, prefix
+    /// with `-` for descending order.
+    ///
+    /// Requires the `audit:read` permission.
     #[oai(
-        path = "/ministry-departments/:id",
-        method = "delete",
-        operation_id = "delete-ministry-department",
-        tag = "Tag::MinistryDepartment"
+        path = "/audit",
+        method = "get",
+        operation_id = "list-audit-logs",
+        tag = "Tag::Audit"
     )]
-    async fn delete_ministry_department(
+    async fn list_audit_logs(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
-        id: param::Path<String>,
-    ) -> Result<ministry_department::delete::Response, ministry_department::delete::Error> {
-        self._delete_ministry_department(db, id).await
+        limit: param::Query<Option<i64>>,
+        cursor: param::Query<Option<String>>,
+        sort: param::Query<Option<String>>,
+        actor: param::Query<Option<String>>,
+        resource: param::Query<Option<String>>,
+        resource_id: param::Query<Option<String>>,
+        request_id: param::Query<Option<String>>,
+        created_after: param::Query<Option<chrono::DateTime<chrono::Utc>>>,
+        created_before: param::Query<Option<chrono::DateTime<chrono::Utc>>>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<audit::list::Response, Error> {
+        self._list_audit_logs(
+            auth,
+            db,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            audit::list::Filter {
+                actor: actor.0,
+                resource: resource.0,
+                resource_id: resource_id.0,
+                request_id: request_id.0,
+                created_after: created_after.0,
+                created_before: created_before.0,
+            },
+            fields.0,
+        )
+        .await
     }
 
-    /* Ministry */
+    /* Webhook */
 
-    /// Create a ministry
+    /// Create a webhook
     ///
-    /// Create a new ministry given its information.
+    /// Subscribe an endpoint to domain events, e.g. `user.created`, `user.updated`,
+    /// `user.deleted`, `user.merged`, `connect_group.users_joined`, `connect_group.users_left`,
+    /// `ministry.users_joined`, `ministry.users_left`, `registration.submitted`,
+    /// `attendance.recorded` and `attendance.deleted`, or to every event when `event_types` is
+    /// empty. Events are posted as JSON along with the `webhook-id`, `webhook-timestamp` and
+    /// `webhook-signature` headers, the signature being `v1=` followed by the hex encoded
+    /// HMAC-SHA256 of `{timestamp}.{body}` keyed with the returned `secret`, which is not
+    /// returned again. Failed deliveries are retried with an exponential backoff.
+    ///
+    /// Requires the `webhooks:write` permission.
     #[oai(
-        path = "/ministries",
+        path = "/webhooks",
         method = "post",
-        operation_id = "create-ministry",
-        tag = "Tag::Ministry"
+        operation_id = "create-webhook",
+        tag = "Tag::Webhook"
     )]
-    async fn create_ministry(
+    async fn create_webhook(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
-        body: payload::Json<ministry::create::Request>,
-    ) -> Result<ministry::create::Response, ministry::create::Error> {
-        self._create_minitry(db, body).await
+        body: payload::Json<webhook::create::Request>,
+    ) -> Result<webhook::create::Response, Error> {
+        self._create_webhook(auth, db, body).await
     }
 
-    /// List or search ministries
+    /// List webhooks
+    ///
+    /// Retrieve the webhooks subscribed to domain events. Results are paged, pass the returned
+    /// `next_cursor` as `cursor` to retrieve the next page. Sortable on `created_at` and `url`,
+    /// prefix with `-` for descending order.
     ///
-    /// Retrieve a list of ministries or search for ministries given a query.
+    /// Requires the `webhooks:write` permission.
     #[oai(
-        path = "/ministries",
+        path = "/webhooks",
         method = "get",
-        operation_id = "list-ministries",
-        tag = "Tag::Ministry"
+        operation_id = "list-webhooks",
+        tag = "Tag::Webhook"
     )]
-    async fn list_ministries(
+    async fn list_webhooks(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
-    ) -> Result<ministry::list::Response, ministry::list::Error> {
-        self._list_ministries(db).await
+        limit: param::Query<Option<i64>>,
+        cursor: param::Query<Option<String>>,
+        sort: param::Query<Option<String>>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<webhook::list::Response, Error> {
+        self._list_webhooks(
+            auth,
+            db,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            fields.0,
+        )
+        .await
     }
 
-    /// Get a ministry
+    /// Get a webhook
     ///
-    /// Retrieve a ministry's details given its id.
+    /// Retrieve a webhook given its id, without its secret.
+    ///
+    /// Requires the `webhooks:write` permission.
     #[oai(
-        path = "/ministries/:id",
+        path = "/webhooks/:id",
         method = "get",
-        operation_id = "get-ministry",
-        tag = "Tag::Ministry"
+        operation_id = "get-webhook",
+        tag = "Tag::Webhook"
     )]
-    async fn get_ministry(
+    async fn get_webhook(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: param::Path<String>,
-    ) -> Result<ministry::get::Response, ministry::get::Error> {
-        self._get_ministry(db, id).await
+        fields: param::Query<Option<String>>,
+    ) -> Result<webhook::get::Response, Error> {
+        self._get_webhook(auth, db, id, fields.0).await
     }
 
-    /// Update a ministry
+    /// Update a webhook
     ///
-    /// Update a ministry's details given its id and the corresponding fields to update.
+    /// Update the URL, event types or description of a webhook, or deactivate it. The
+    /// deliveries of an inactive webhook are kept until it is activated again.
+    ///
+    /// Requires the `webhooks:write` permission.
     #[oai(
-        path = "/ministries/:id",
+        path = "/webhooks/:id",
         method = "patch",
-        operation_id = "update-ministry",
-        tag = "Tag::Ministry"
+        operation_id = "update-webhook",
+        tag = "Tag::Webhook"
     )]
-    async fn update_ministry(
+    async fn update_webhook(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: param::Path<String>,
-        body: payload::Json<ministry::update::Request>,
-    ) -> Result<ministry::update::Response, ministry::update::Error> {
-        self._update_ministry(db, id, body).await
+        body: payload::Json<webhook::update::Request>,
+    ) -> Result<webhook::update::Response, Error> {
+        self._update_webhook(auth, db, id, body).await
     }
 
-    /// Delete a ministry
+    /// Delete a webhook
     ///
-    /// Delete a ministry given its id.
+    /// Delete a webhook along with its deliveries.
+    ///
+    /// Requires the `webhooks:write` permission.
     #[oai(
-        path = "/ministries/:id",
+        path = "/webhooks/:id",
         method = "delete",
-        operation_id = "delete-ministry",
-        tag = "Tag::Ministry"
+        operation_id = "delete-webhook",
+        tag = "Tag::Webhook"
     )]
-    async fn delete_ministry(
+    async fn delete_webhook(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: param::Path<String>,
-    ) -> Result<ministry::delete::Response, ministry::delete::Error> {
-        self._delete_ministry(db, id).await
+    ) -> Result<webhook::delete::Response, Error> {
+        self._delete_webhook(auth, db, id).await
     }
 
-    /// Associate users with a ministry
+    /// List webhook deliveries
     ///
-    /// Associate users with a ministry given the ministry's id and the users' ids.
-    #[oai(
-        path = "/ministries/:id/users",
-        method = "post",
-        operation_id = "associate-users-with-ministry",
-        tag = "Tag::Ministry"
-    )]
-    async fn associate_users_with_ministry(
-        &self,
-        _auth: BearerAuth,
-        db: web::Data<&Database>,
-        id: param::Path<String>,
-        body: payload::Json<ministry::associate_users::Request>,
-    ) -> Result<ministry::associate_users::Response, ministry::associate_users::Error> {
-        self._associate_users_with_ministry(db, id, body).await
-    }
-
-    /// Get users associated with a ministry
+    /// Retrieve the deliveries of domain events to the webhooks, optionally filtered by
+    /// `webhook_id`, `status` or `event_type`. Filter on the `dead` status for the deliveries
+    /// that failed every attempt. Results are paged, pass the returned `next_cursor` as `cursor`
+    /// to retrieve the next page. Sortable on `created_at` and `next_attempt_at`, prefix with
+    /// `-` for descending order.
     ///
-    /// Retrieve a list of users associated with a ministry given the ministry's id.
+    /// Requires the `webhooks:write` permission.
     #[oai(
-        path = "/ministries/:id/users",
+        path = "/webhook-deliveries",
         method = "get",
-        operation_id = "get-ministry-users",
-        tag = "Tag::Ministry"
+        operation_id = "list-webhook-deliveries",
+        tag = "Tag::Webhook"
     )]
-    async fn get_ministry_users(
+    async fn list_webhook_deliveries(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
-        id: param::Path<String>,
-    ) -> Result<ministry::get_users::Response, ministry::get_users::Error> {
-        self._get_ministry_users(db, id).await
+        limit: param::Query<Option<i64>>,
+        cursor: param::Query<Option<String>>,
+        sort: param::Query<Option<String>>,
+        webhook_id: param::Query<Option<String>>,
+        status: param::Query<Option<crate::entities::WebhookDeliveryStatus>>,
+        event_type: param::Query<Option<String>>,
+        fields: param::Query<Option<String>>,
+    ) -> Result<webhook::list_deliveries::Response, Error> {
+        self._list_webhook_deliveries(
+            auth,
+            db,
+            pagination::Params {
+                limit: limit.0,
+                cursor: cursor.0,
+                sort: sort.0,
+            },
+            webhook::list_deliveries::Filter {
+                webhook_id: webhook_id.0,
+                status: status.0,
+                event_type: event_type.0,
+            },
+            fields.0,
+        )
+        .await
     }
 
-    /// Remove users from a ministry
+    /// Retry a webhook delivery
     ///
-    /// Remove users from a ministry given the ministry's id and the users' ids.
+    /// Queue a pending or dead delivery to be attempted right away, with its attempts reset.
+    ///
+    /// Requires the `webhooks:write` permission.
     #[oai(
-        path = "/ministries/:id/users",
-        method = "delete",
-        operation_id = "remove-users-from-ministry",
-        tag = "Tag::Ministry"
+        path = "/webhook-deliveries/:id/retry",
+        method = "post",
+        operation_id = "retry-webhook-delivery",
+        tag = "Tag::Webhook"
     )]
-    async fn remove_users_from_ministry(
+    async fn retry_webhook_delivery(
         &self,
-        _auth: BearerAuth,
+        auth: BearerAuth,
         db: web::Data<&Database>,
-        id: param::Path<String>,
-        body: payload::Json<ministry::remove_users::Request>,
-    ) -> Result<ministry::remove_users::Response, ministry::remove_users::Error> {
-        self._remove_users_from_ministry(db, id, body).await
+        id: param::Path<i64>,
+    ) -> Result<webhook::retry_delivery::Response, Error> {
+        self._retry_webhook_delivery(auth, db, id).await
     }
 }
@@ -0,0 +1,28 @@
+use poem::web;
+use poem_openapi::payload;
+
+use common::{
+    database::Database,
+    error::Error,
+    projection::{Fields, Projected},
+};
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Vec<Projected<super::SatelliteNode>>>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _get_org_tree(
+        &self,
+        db: web::Data<&Database>,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<super::SatelliteNode>(fields)?;
+
+        let tree = super::build(&db, None).await?;
+
+        Ok(Response::Ok(payload::Json(fields.project_all(tree))))
+    }
+}
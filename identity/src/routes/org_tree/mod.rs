@@ -0,0 +1,375 @@
+pub mod get;
+
+use std::collections::{HashMap, HashSet};
+
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+
+use common::database::Database;
+
+use crate::{
+    entities,
+    scope::{CONNECT_GROUP_LEADER_WEIGHT, SATELLITE_LEADER_WEIGHT},
+};
+
+/// Ministry roles with a weight at or below this lead their ministries, the same ministry roles
+/// granted the `ministries:write` permission.
+const MINISTRY_LEADER_WEIGHT: i32 = 2;
+
+/// A member holding a leading role, most senior first.
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "OrgTreeLeader")]
+pub struct Leader {
+    pub user_id: String,
+    pub name: String,
+    pub role_id: String,
+    pub role_name: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "OrgTreeSatellite")]
+pub struct SatelliteNode {
+    #[oai(flatten)]
+    #[serde(flatten)]
+    pub satellite: entities::Satellite,
+    /// Members of its connect groups or ministries, counted once.
+    pub member_count: i64,
+    /// Members overseeing every connect group of the satellite, e.g. coaches and pastors.
+    pub leaders: Vec<Leader>,
+    /// The departments with ministries in the satellite.
+    pub departments: Vec<DepartmentNode>,
+    /// The categories with open connect groups in the satellite.
+    pub categories: Vec<CategoryNode>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "OrgTreeDepartment")]
+pub struct DepartmentNode {
+    #[oai(flatten)]
+    #[serde(flatten)]
+    pub department: entities::MinistryDepartment,
+    pub member_count: i64,
+    pub teams: Vec<TeamNode>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "OrgTreeTeam")]
+pub struct TeamNode {
+    #[oai(flatten)]
+    #[serde(flatten)]
+    pub team: entities::MinistryTeam,
+    pub member_count: i64,
+    pub ministries: Vec<MinistryNode>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "OrgTreeMinistry")]
+pub struct MinistryNode {
+    #[oai(flatten)]
+    #[serde(flatten)]
+    pub ministry: entities::Ministry,
+    pub member_count: i64,
+    pub leaders: Vec<Leader>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "OrgTreeCategory")]
+pub struct CategoryNode {
+    #[oai(flatten)]
+    #[serde(flatten)]
+    pub category: entities::ConnectGroupCategory,
+    pub member_count: i64,
+    pub connect_groups: Vec<ConnectGroupNode>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "OrgTreeConnectGroup")]
+pub struct ConnectGroupNode {
+    #[oai(flatten)]
+    #[serde(flatten)]
+    pub connect_group: entities::ConnectGroup,
+    pub member_count: i64,
+    pub leaders: Vec<Leader>,
+}
+
+/// A membership of a connect group or ministry, along with the role held.
+#[derive(Debug, sqlx::FromRow)]
+struct Membership {
+    group_id: String,
+    user_id: String,
+    user_name: String,
+    role_id: String,
+    role_name: String,
+    weight: i32,
+}
+
+type ByGroup<'a> = HashMap<&'a str, Vec<&'a Membership>>;
+
+fn by_group(memberships: &[Membership]) -> ByGroup<'_> {
+    let mut by_group = ByGroup::new();
+    for m in memberships {
+        by_group.entry(m.group_id.as_str()).or_default().push(m);
+    }
+    by_group
+}
+
+/// The memberships of the groups identified by `ids`, in the order they were fetched.
+fn members<'a>(
+    by_group: &'a ByGroup<'a>,
+    ids: impl IntoIterator<Item = &'a str>,
+) -> impl Iterator<Item = &'a Membership> {
+    ids.into_iter()
+        .flat_map(|id| by_group.get(id).into_iter().flatten().copied())
+}
+
+fn member_count<'a>(memberships: impl Iterator<Item = &'a Membership>) -> i64 {
+    memberships
+        .map(|m| m.user_id.as_str())
+        .collect::<HashSet<_>>()
+        .len() as i64
+}
+
+/// The members holding a role at or below `weight`, once each with their most senior role
+/// since the memberships are sorted by weight.
+fn leaders<'a>(memberships: impl Iterator<Item = &'a Membership>, weight: i32) -> Vec<Leader> {
+    let mut seen = HashSet::new();
+    memberships
+        .filter(|m| m.weight <= weight && seen.insert(m.user_id.as_str()))
+        .map(|m| Leader {
+            user_id: m.user_id.clone(),
+            name: m.user_name.clone(),
+            role_id: m.role_id.clone(),
+            role_name: m.role_name.clone(),
+        })
+        .collect()
+}
+
+/// Build the organisation tree of every satellite, or of the satellite identified by
+/// `satellite_id`. Closed connect groups and deleted users are left out.
+pub(crate) async fn build(
+    db: &Database,
+    satellite_id: Option<&str>,
+) -> Result<Vec<SatelliteNode>, sqlx::Error> {
+    let satellites = sqlx::query_as::<_, entities::Satellite>(
+        r#"
+        SELECT * FROM satellite WHERE $1::TEXT IS NULL OR id = $1 ORDER BY no
+        "#,
+    )
+    .bind(satellite_id)
+    .fetch_all(&db.db)
+    .await?;
+
+    let connect_groups = sqlx::query_as::<_, entities::ConnectGroup>(
+        r#"
+        SELECT * FROM connect_group
+        WHERE active AND ($1::TEXT IS NULL OR satellite_id = $1)
+        ORDER BY no, variant
+        "#,
+    )
+    .bind(satellite_id)
+    .fetch_all(&db.db)
+    .await?;
+
+    let categories = sqlx::query_as::<_, entities::ConnectGroupCategory>(
+        r#"
+        SELECT * FROM connect_group_category ORDER BY name
+        "#,
+    )
+    .fetch_all(&db.db)
+    .await?;
+
+    let ministries = sqlx::query_as::<_, entities::Ministry>(
+        r#"
+        SELECT * FROM ministry WHERE $1::TEXT IS NULL OR satellite_id = $1 ORDER BY name
+        "#,
+    )
+    .bind(satellite_id)
+    .fetch_all(&db.db)
+    .await?;
+
+    let departments = sqlx::query_as::<_, entities::MinistryDepartment>(
+        r#"
+        SELECT * FROM ministry_department ORDER BY name
+        "#,
+    )
+    .fetch_all(&db.db)
+    .await?;
+
+    let teams = sqlx::query_as::<_, entities::MinistryTeam>(
+        r#"
+        SELECT * FROM ministry_team ORDER BY name
+        "#,
+    )
+    .fetch_all(&db.db)
+    .await?;
+
+    let connect_group_memberships = sqlx::query_as::<_, Membership>(
+        r#"
+        SELECT
+            ucg.connect_group_id AS group_id,
+            u.id AS user_id,
+            u.name AS user_name,
+            pr.id AS role_id,
+            pr.name AS role_name,
+            pr.weight
+        FROM
+            user_connect_group ucg
+                INNER JOIN connect_group cg ON cg.id = ucg.connect_group_id
+                INNER JOIN "user" u ON u.id = ucg.user_id
+                INNER JOIN pastoral_role pr ON pr.id = ucg.user_role
+        WHERE
+            cg.active
+            AND u.deleted IS FALSE
+            AND ($1::TEXT IS NULL OR cg.satellite_id = $1)
+        ORDER BY pr.weight, u.name
+        "#,
+    )
+    .bind(satellite_id)
+    .fetch_all(&db.db)
+    .await?;
+
+    let ministry_memberships = sqlx::query_as::<_, Membership>(
+        r#"
+        SELECT
+            um.ministry_id AS group_id,
+            u.id AS user_id,
+            u.name AS user_name,
+            mr.id AS role_id,
+            mr.name AS role_name,
+            mr.weight
+        FROM
+            user_ministry um
+                INNER JOIN ministry m ON m.id = um.ministry_id
+                INNER JOIN "user" u ON u.id = um.user_id
+                INNER JOIN ministry_role mr ON mr.id = um.user_role
+        WHERE
+            u.deleted IS FALSE
+            AND ($1::TEXT IS NULL OR m.satellite_id = $1)
+        ORDER BY mr.weight, u.name
+        "#,
+    )
+    .bind(satellite_id)
+    .fetch_all(&db.db)
+    .await?;
+
+    let connect_group_members = by_group(&connect_group_memberships);
+    let ministry_members = by_group(&ministry_memberships);
+
+    let tree = satellites
+        .into_iter()
+        .map(|satellite| {
+            let connect_groups = connect_groups
+                .iter()
+                .filter(|cg| cg.satellite_id == satellite.id)
+                .collect::<Vec<_>>();
+            let ministries = ministries
+                .iter()
+                .filter(|m| m.satellite_id == satellite.id)
+                .collect::<Vec<_>>();
+
+            let categories = categories
+                .iter()
+                .filter_map(|category| {
+                    let connect_groups = connect_groups
+                        .iter()
+                        .filter(|cg| cg.category_id == category.id)
+                        .collect::<Vec<_>>();
+                    if connect_groups.is_empty() {
+                        return None;
+                    }
+
+                    let ids = connect_groups.iter().map(|cg| cg.id.as_str());
+                    Some(CategoryNode {
+                        category: category.clone(),
+                        member_count: member_count(members(&connect_group_members, ids)),
+                        connect_groups: connect_groups
+                            .iter()
+                            .map(|cg| ConnectGroupNode {
+                                connect_group: entities::ConnectGroup::clone(cg),
+                                member_count: member_count(members(
+                                    &connect_group_members,
+                                    [cg.id.as_str()],
+                                )),
+                                leaders: leaders(
+                                    members(&connect_group_members, [cg.id.as_str()]),
+                                    CONNECT_GROUP_LEADER_WEIGHT,
+                                ),
+                            })
+                            .collect(),
+                    })
+                })
+                .collect();
+
+            let departments = departments
+                .iter()
+                .filter_map(|department| {
+                    let in_department = ministries
+                        .iter()
+                        .filter(|m| m.department_id == department.id)
+                        .collect::<Vec<_>>();
+                    if in_department.is_empty() {
+                        return None;
+                    }
+
+                    let teams = teams
+                        .iter()
+                        .filter_map(|team| {
+                            let in_team = in_department
+                                .iter()
+                                .filter(|m| m.team_id == team.id)
+                                .collect::<Vec<_>>();
+                            if in_team.is_empty() {
+                                return None;
+                            }
+
+                            let ids = in_team.iter().map(|m| m.id.as_str());
+                            Some(TeamNode {
+                                team: team.clone(),
+                                member_count: member_count(members(&ministry_members, ids)),
+                                ministries: in_team
+                                    .iter()
+                                    .map(|m| MinistryNode {
+                                        ministry: entities::Ministry::clone(m),
+                                        member_count: member_count(members(
+                                            &ministry_members,
+                                            [m.id.as_str()],
+                                        )),
+                                        leaders: leaders(
+                                            members(&ministry_members, [m.id.as_str()]),
+                                            MINISTRY_LEADER_WEIGHT,
+                                        ),
+                                    })
+                                    .collect(),
+                            })
+                        })
+                        .collect();
+
+                    let ids = in_department.iter().map(|m| m.id.as_str());
+                    Some(DepartmentNode {
+                        department: department.clone(),
+                        member_count: member_count(members(&ministry_members, ids)),
+                        teams,
+                    })
+                })
+                .collect();
+
+            let connect_group_ids = connect_groups.iter().map(|cg| cg.id.as_str());
+            let ministry_ids = ministries.iter().map(|m| m.id.as_str());
+            SatelliteNode {
+                member_count: member_count(
+                    members(&connect_group_members, connect_group_ids.clone())
+                        .chain(members(&ministry_members, ministry_ids)),
+                ),
+                leaders: leaders(
+                    members(&connect_group_members, connect_group_ids),
+                    SATELLITE_LEADER_WEIGHT,
+                ),
+                satellite,
+                departments,
+                categories,
+            }
+        })
+        .collect();
+
+    Ok(tree)
+}
@@ -3,7 +3,9 @@ use poem::web;
 use poem_openapi::{param::Path, payload};
 use reqwest::StatusCode;
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{audit, auth::BearerAuth, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities};
 
 #[derive(poem_openapi::ApiResponse)]
 pub enum Response {
@@ -11,52 +13,37 @@ pub enum Response {
     Ok(payload::Json<entities::PastoralRole>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
-}
-
 impl crate::routes::Routes {
     pub async fn _delete_pastoral_role(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
     ) -> Result<Response, Error> {
-        let mut tx = db.db.begin().await.map_err(|e| {
-            Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Send + Sync),
-            )))
-        })?;
+        auth.require(&db, Permission::RolesWrite).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
 
         match self
             .management
             .delete_role(id.clone())
             .send()
             .await
-            .map_err(|e| {
-                Error::InternalServer(payload::Json(ErrorResponse::from(
-                    &e as &(dyn std::error::Error + Send + Send + Sync),
-                )))
-            })?
+            .map_err(Error::internal)?
             .status()
         {
             StatusCode::OK => {}
             StatusCode::NOT_FOUND => {
-                return Err(Error::NotFound(payload::Json(ErrorResponse {
-                    message: format!("Auth0 uole with id '{}' not found", &*id),
-                })))
+                return Err(Error::not_found(
+                    "PASTORAL_ROLE_NOT_FOUND",
+                    format!("Auth0 role with id '{}' not found", &*id),
+                ))
             }
             _ => {
-                return Err(Error::InternalServer(payload::Json(ErrorResponse {
-                    message: format!("Failed to delete auth0 role with id '{}'", &*id),
-                })))
+                return Err(Error::internal(format!(
+                    "Failed to delete auth0 role with id '{}'",
+                    &*id
+                )))
             }
         };
 
@@ -72,19 +59,14 @@ impl crate::routes::Routes {
         .fetch_one(&mut *tx)
         .await
         .map_err(|e| match e {
-            sqlx::error::Error::RowNotFound => Error::NotFound(payload::Json(ErrorResponse {
-                message: format!("Pastoral role with id '{}' not found", &*id),
-            })),
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
+            sqlx::Error::RowNotFound => Error::not_found(
+                "PASTORAL_ROLE_NOT_FOUND",
+                format!("Pastoral role with id '{}' not found", &*id),
+            ),
+            _ => Error::from(e),
         })?;
 
-        tx.commit().await.map_err(|e| {
-            Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Send + Sync),
-            )))
-        })?;
+        tx.commit().await?;
 
         Ok(Response::Ok(payload::Json(pr)))
     }
@@ -3,7 +3,9 @@ use poem::web;
 use poem_openapi::{param::Path, payload, Object};
 use reqwest::StatusCode;
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{audit, auth::BearerAuth, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Deserialize, Serialize, Object)]
@@ -20,30 +22,17 @@ pub enum Response {
     Ok(payload::Json<entities::PastoralRole>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
-}
-
 impl crate::routes::Routes {
     pub async fn _update_pastoral_role(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
         body: payload::Json<Request>,
     ) -> Result<Response, Error> {
-        let mut tx = db.db.begin().await.map_err(|e| {
-            Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Send + Sync),
-            )))
-        })?;
+        auth.require(&db, Permission::RolesWrite).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
 
         match self
             .management
@@ -56,18 +45,15 @@ impl crate::routes::Routes {
             )
             .send()
             .await
-            .map_err(|e| {
-                Error::InternalServer(payload::Json(ErrorResponse::from(
-                    &e as &(dyn std::error::Error + Send + Send + Sync),
-                )))
-            })?
+            .map_err(Error::internal)?
             .status()
         {
             StatusCode::OK => {}
             _ => {
-                return Err(Error::InternalServer(payload::Json(ErrorResponse {
-                    message: format!("Failed to update auth0 role with id '{}' on Auth0", &*id),
-                })))
+                return Err(Error::internal(format!(
+                    "Failed to update auth0 role with id '{}' on Auth0",
+                    &*id
+                )))
             }
         };
 
@@ -89,29 +75,14 @@ impl crate::routes::Routes {
         .fetch_one(&mut *tx)
         .await
         .map_err(|e| match e {
-            sqlx::error::Error::RowNotFound => Error::NotFound(payload::Json(ErrorResponse {
-                message: format!("Pastoral role with id '{}' not found", &*id),
-            })),
-            sqlx::Error::Database(e)
-                if e.is_unique_violation()
-                    && e.constraint()
-                        .is_some_and(|constraint| constraint == "pastoral_role_name_key") =>
-            {
-                Error::BadRequest(payload::Json(ErrorResponse {
-                    //message: format!("Pastoral role name of '{}' already exists", body.name), //TODO : Fix this
-                    message: format!("Pastoral role name already exists"),
-                }))
-            }
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
+            sqlx::Error::RowNotFound => Error::not_found(
+                "PASTORAL_ROLE_NOT_FOUND",
+                format!("Pastoral role with id '{}' not found", &*id),
+            ),
+            _ => Error::from(e),
         })?;
 
-        tx.commit().await.map_err(|e| {
-            Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Send + Sync),
-            )))
-        })?;
+        tx.commit().await?;
 
         Ok(Response::Ok(payload::Json(pr)))
     }
@@ -0,0 +1,38 @@
+use poem::web;
+use poem_openapi::payload;
+
+use common::{
+    database::Database,
+    error::Error,
+    projection::{Fields, Projected},
+};
+
+use crate::entities;
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Vec<Projected<entities::RelationshipType>>>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _list_relationship_types(
+        &self,
+        db: web::Data<&Database>,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::RelationshipType>(fields)?;
+
+        let relationship_types = sqlx::query_as::<_, entities::RelationshipType>(
+            r#"
+            SELECT * FROM relationship_type ORDER BY name
+            "#,
+        )
+        .fetch_all(&db.db)
+        .await?;
+
+        Ok(Response::Ok(payload::Json(
+            fields.project_all(relationship_types),
+        )))
+    }
+}
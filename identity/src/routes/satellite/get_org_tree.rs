@@ -0,0 +1,39 @@
+use poem::web;
+use poem_openapi::{param::Path, payload};
+
+use common::{
+    database::Database,
+    error::Error,
+    projection::{Fields, Projected},
+};
+
+use crate::routes::org_tree;
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Projected<org_tree::SatelliteNode>>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _get_satellite_org_tree(
+        &self,
+        db: web::Data<&Database>,
+        id: Path<String>,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<org_tree::SatelliteNode>(fields)?;
+
+        let satellite = org_tree::build(&db, Some(&id))
+            .await?
+            .pop()
+            .ok_or_else(|| {
+                Error::not_found(
+                    "SATELLITE_NOT_FOUND",
+                    format!("Satellite with id '{}' not found", &*id),
+                )
+            })?;
+
+        Ok(Response::Ok(payload::Json(fields.project(satellite))))
+    }
+}
@@ -1,5 +1,6 @@
 pub mod create;
 pub mod delete;
 pub mod get;
+pub mod get_org_tree;
 pub mod list;
 pub mod update;
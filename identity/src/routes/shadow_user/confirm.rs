@@ -0,0 +1,238 @@
+use poem::web;
+use poem_openapi::{param::Path, payload, Object};
+use serde::{Deserialize, Serialize};
+
+use common::{audit, auth::BearerAuth, crypto::Keyring, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities, pii, reconcile};
+
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "ConfirmShadowUserRequest")]
+pub struct Request {
+    /// The user that the shadow user is merged into.
+    user_id: String,
+    /// Overrides the connect group resolved from `cg`.
+    connect_group_id: Option<String>,
+    /// Overrides the pastoral role resolved from `pastoral_status`.
+    pastoral_role_id: Option<String>,
+    /// Overrides the ministries resolved from `ministry`, an empty list joins none.
+    ministry_ids: Option<Vec<String>>,
+    /// Overrides the ministry role the ministries are joined with.
+    ministry_role_id: Option<String>,
+}
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<entities::User>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _confirm_shadow_user(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        keyring: web::Data<&Keyring>,
+        no: Path<i32>,
+        body: payload::Json<Request>,
+    ) -> Result<Response, Error> {
+        auth.require(&db, Permission::UsersAdmin).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        let shadow_user = sqlx::query_as::<_, entities::ShadowUser>(
+            r#"
+            SELECT * FROM shadow_user
+            WHERE no = $1 AND deleted IS NOT TRUE
+            FOR UPDATE
+            "#,
+        )
+        .bind(*no)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::not_found(
+                "SHADOW_USER_NOT_FOUND",
+                format!("Shadow user with no '{}' not found", *no),
+            ),
+            _ => Error::from(e),
+        })?;
+
+        if let Some(claimed_by) = &shadow_user.claimed_by {
+            return Err(Error::conflict(
+                "SHADOW_USER_ALREADY_CLAIMED",
+                format!(
+                    "Shadow user with no '{}' was already merged into user '{}'",
+                    *no, claimed_by
+                ),
+            ));
+        }
+
+        let resolution = reconcile::resolve(&mut tx, &shadow_user).await?;
+        let unresolved = |field: &str| {
+            resolution
+                .unresolved
+                .iter()
+                .filter(|value| value.field == field)
+                .map(|value| value.value.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let mut error = Error::bad_request(
+            "SHADOW_USER_UNRESOLVED",
+            format!(
+                "Shadow user with no '{}' refers to unknown connect groups, roles or ministries, \
+                 pass them explicitly",
+                *no
+            ),
+        );
+        let mut resolved = true;
+
+        let connect_group_id = body
+            .connect_group_id
+            .clone()
+            .or_else(|| resolution.connect_group.as_ref().map(|cg| cg.id.clone()));
+        if body.connect_group_id.is_none() {
+            for cg in unresolved("cg") {
+                resolved = false;
+                error = error.with_detail(
+                    "connect_group_id",
+                    "CONNECT_GROUP_UNRESOLVED",
+                    format!("Connect group '{}' matches no single connect group", cg),
+                );
+            }
+        }
+
+        let pastoral_role_id = body.pastoral_role_id.clone().or_else(|| {
+            resolution
+                .pastoral_role
+                .as_ref()
+                .map(|role| role.id.clone())
+        });
+        if connect_group_id.is_some() && pastoral_role_id.is_none() {
+            resolved = false;
+            error = error.with_detail(
+                "pastoral_role_id",
+                "PASTORAL_ROLE_UNRESOLVED",
+                format!(
+                    "Pastoral role '{}' matches no pastoral role",
+                    shadow_user.pastoral_status.as_deref().unwrap_or_default()
+                ),
+            );
+        }
+
+        let ministry_ids = match &body.ministry_ids {
+            Some(ministry_ids) => ministry_ids.clone(),
+            None => {
+                for ministry in unresolved("ministry") {
+                    resolved = false;
+                    error = error.with_detail(
+                        "ministry_ids",
+                        "MINISTRY_UNRESOLVED",
+                        format!("Ministry '{}' matches no single ministry", ministry),
+                    );
+                }
+                resolution
+                    .ministries
+                    .iter()
+                    .map(|ministry| ministry.id.clone())
+                    .collect()
+            }
+        };
+
+        let ministry_role_id = body.ministry_role_id.clone().or_else(|| {
+            resolution
+                .ministry_role
+                .as_ref()
+                .map(|role| role.id.clone())
+        });
+        if !ministry_ids.is_empty() && ministry_role_id.is_none() {
+            resolved = false;
+            error = error.with_detail(
+                "ministry_role_id",
+                "MINISTRY_ROLE_UNRESOLVED",
+                "There is no ministry role to join the ministries with",
+            );
+        }
+
+        if !resolved {
+            return Err(error);
+        }
+
+        let user = sqlx::query_as::<_, pii::UserRow>(
+            r#"SELECT * FROM "user" WHERE id = $1 AND deleted IS FALSE FOR UPDATE"#,
+        )
+        .bind(&body.user_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::not_found(
+                "USER_NOT_FOUND",
+                format!("User with id '{}' not found", &body.user_id),
+            ),
+            _ => Error::from(e),
+        })?
+        .open(&keyring)?;
+
+        // Fields already filled in by the user are more recent than the spreadsheets, only the
+        // missing ones are taken from the shadow user.
+        let sealed = pii::Details::of(&user)
+            .or(pii::Details::of_shadow_user(&shadow_user))
+            .seal(&keyring)?;
+
+        let user = sqlx::query_as::<_, pii::UserRow>(
+            r#"
+            UPDATE "user" u SET
+                username                = COALESCE(u.username, s.username),
+                given_name              = COALESCE(u.given_name, s.given_name),
+                family_name             = COALESCE(u.family_name, s.family_name),
+                gender                  = COALESCE(u.gender, s.gender),
+                ic_number_encrypted     = $3,
+                ic_number_index         = $4,
+                ic_number               = NULL,
+                phone_number_encrypted  = $5,
+                phone_number_index      = $6,
+                phone_number            = NULL,
+                nickname                = COALESCE(u.nickname, s.nickname),
+                avatar_url              = COALESCE(u.avatar_url, s.avatar_url),
+                address_encrypted       = $7,
+                address                 = NULL,
+                date_of_birth_encrypted = $8,
+                date_of_birth           = NULL,
+                year_of_birth           = $9,
+                updated_at              = NOW()
+            FROM shadow_user s
+            WHERE u.id = $1 AND s.no = $2
+            RETURNING u.*
+            "#,
+        )
+        .bind(&user.id)
+        .bind(*no)
+        .bind(&sealed.ic_number)
+        .bind(&sealed.ic_number_index)
+        .bind(&sealed.phone_number)
+        .bind(&sealed.phone_number_index)
+        .bind(&sealed.address)
+        .bind(&sealed.date_of_birth)
+        .bind(sealed.year_of_birth)
+        .fetch_one(&mut *tx)
+        .await?
+        .open(&keyring)?;
+
+        reconcile::claim(
+            &mut tx,
+            *no,
+            &user.id,
+            &reconcile::Memberships {
+                connect_group: connect_group_id.zip(pastoral_role_id),
+                ministry_ids,
+                ministry_role_id,
+            },
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Response::Ok(payload::Json(pii::mask_for(&auth, user))))
+    }
+}
@@ -0,0 +1,50 @@
+use poem::web;
+use poem_openapi::{param::Path, payload};
+
+use common::{
+    auth::BearerAuth,
+    database::Database,
+    error::Error,
+    projection::{Fields, Projected},
+};
+
+use crate::{authorization::Permission, entities};
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Projected<entities::ShadowUser>>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _get_shadow_user(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        no: Path<i32>,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::ShadowUser>(fields)?;
+
+        auth.require(&db, Permission::UsersAdmin).await?;
+
+        let shadow_user = sqlx::query_as::<_, entities::ShadowUser>(
+            r#"
+            SELECT * FROM shadow_user
+            WHERE no = $1 AND deleted IS NOT TRUE
+            "#,
+        )
+        .bind(*no)
+        .fetch_one(&db.db)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::not_found(
+                "SHADOW_USER_NOT_FOUND",
+                format!("Shadow user with no '{}' not found", *no),
+            ),
+            _ => Error::from(e),
+        })?;
+
+        Ok(Response::Ok(payload::Json(fields.project(shadow_user))))
+    }
+}
@@ -0,0 +1,54 @@
+use poem::web;
+use poem_openapi::{param::Path, payload};
+
+use common::{
+    auth::BearerAuth,
+    database::Database,
+    error::Error,
+    projection::{Fields, Projected},
+};
+
+use crate::{authorization::Permission, entities, reconcile};
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Projected<entities::ShadowUserResolution>>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _get_shadow_user_resolution(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        no: Path<i32>,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::ShadowUserResolution>(fields)?;
+
+        auth.require(&db, Permission::UsersAdmin).await?;
+
+        let mut conn = db.db.acquire().await?;
+
+        let shadow_user = sqlx::query_as::<_, entities::ShadowUser>(
+            r#"
+            SELECT * FROM shadow_user
+            WHERE no = $1 AND deleted IS NOT TRUE
+            "#,
+        )
+        .bind(*no)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::not_found(
+                "SHADOW_USER_NOT_FOUND",
+                format!("Shadow user with no '{}' not found", *no),
+            ),
+            _ => Error::from(e),
+        })?;
+
+        let resolution = reconcile::resolve(&mut conn, &shadow_user).await?;
+
+        Ok(Response::Ok(payload::Json(fields.project(resolution))))
+    }
+}
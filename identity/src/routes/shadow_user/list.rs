@@ -0,0 +1,88 @@
+use poem::web;
+use poem_openapi::payload;
+
+use common::{
+    auth::BearerAuth,
+    database::Database,
+    error::Error,
+    pagination::{self, Listing, Page, Sortable},
+    projection::{Fields, Projected},
+};
+
+use crate::{authorization::Permission, entities};
+
+const LISTING: Listing = Listing {
+    select: "*",
+    from: "shadow_user",
+    key: "lpad(no::TEXT, 10, '0')",
+    sortable: &[
+        Sortable {
+            name: "no",
+            expr: "no",
+            ty: "INTEGER",
+        },
+        Sortable {
+            name: "name",
+            expr: "name",
+            ty: "TEXT",
+        },
+        Sortable {
+            name: "created_at",
+            expr: "created_at",
+            ty: "TIMESTAMPTZ",
+        },
+    ],
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub search: Option<String>,
+    pub claimed: Option<bool>,
+}
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Page<Projected<entities::ShadowUser>>>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _list_shadow_users(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        params: pagination::Params,
+        filter: Filter,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::ShadowUser>(fields)?;
+
+        auth.require(&db, Permission::UsersAdmin).await?;
+
+        let shadow_users = LISTING
+            .fetch(&db.db, &params, |query| {
+                query.push(" AND deleted IS NOT TRUE");
+                if let Some(search) = &filter.search {
+                    let pattern = format!("%{}%", search.replace('%', "\\%").replace('_', "\\_"));
+                    query
+                        .push(" AND (name ILIKE ")
+                        .push_bind(pattern.clone())
+                        .push(" OR email ILIKE ")
+                        .push_bind(pattern)
+                        .push(" OR normalise_phone_number(phone_number) = normalise_phone_number(")
+                        .push_bind(search.clone())
+                        .push("))");
+                }
+                if let Some(claimed) = filter.claimed {
+                    query
+                        .push(" AND (claimed_by IS NOT NULL) = ")
+                        .push_bind(claimed);
+                }
+            })
+            .await?;
+
+        Ok(Response::Ok(payload::Json(
+            fields.project_page(shadow_users),
+        )))
+    }
+}
@@ -0,0 +1,70 @@
+use poem::web;
+use poem_openapi::{param::Path, payload};
+
+use common::{
+    auth::BearerAuth,
+    crypto::Keyring,
+    database::Database,
+    error::Error,
+    pagination,
+    projection::{Fields, Projected},
+};
+
+use crate::{authorization::Permission, entities, pii, reconcile};
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Vec<Projected<entities::ShadowUserMatch>>>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _list_shadow_user_matches(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        keyring: web::Data<&Keyring>,
+        no: Path<i32>,
+        limit: Option<i64>,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::ShadowUserMatch>(fields)?;
+
+        auth.require(&db, Permission::UsersAdmin).await?;
+
+        let limit = limit.unwrap_or(10);
+        if !(1..=pagination::MAX_LIMIT).contains(&limit) {
+            return Err(pagination::Error::InvalidLimit.into());
+        }
+
+        let mut conn = db.db.acquire().await?;
+
+        let shadow_user = sqlx::query_as::<_, entities::ShadowUser>(
+            r#"
+            SELECT * FROM shadow_user
+            WHERE no = $1 AND deleted IS NOT TRUE
+            "#,
+        )
+        .bind(*no)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::not_found(
+                "SHADOW_USER_NOT_FOUND",
+                format!("Shadow user with no '{}' not found", *no),
+            ),
+            _ => Error::from(e),
+        })?;
+
+        let matches = reconcile::find_matches(&mut conn, &keyring, &shadow_user, limit)
+            .await?
+            .into_iter()
+            .map(|mut candidate| {
+                candidate.user = pii::mask_for(&auth, candidate.user);
+                candidate
+            })
+            .collect();
+
+        Ok(Response::Ok(payload::Json(fields.project_all(matches))))
+    }
+}
@@ -0,0 +1,5 @@
+pub mod confirm;
+pub mod get;
+pub mod get_resolution;
+pub mod list;
+pub mod list_matches;
@@ -0,0 +1,36 @@
+use poem::web;
+use poem_openapi::{payload, Object};
+use serde::{Deserialize, Serialize};
+
+use common::{auth::BearerAuth, database::Database, error::Error};
+
+use crate::{authorization::Permission, duplicates};
+
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "DetectUserDuplicatesResponse")]
+pub struct Detection {
+    /// The number of pairs of users found, including the ones already reviewed.
+    found: u64,
+}
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Detection>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _detect_user_duplicates(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+    ) -> Result<Response, Error> {
+        auth.require(&db, Permission::UsersAdmin).await?;
+
+        let mut tx = db.db.begin().await?;
+        let found = duplicates::detect(&mut tx).await?;
+        tx.commit().await?;
+
+        Ok(Response::Ok(payload::Json(Detection { found })))
+    }
+}
@@ -0,0 +1,87 @@
+use poem::web;
+use poem_openapi::payload;
+
+use common::{
+    auth::BearerAuth,
+    database::Database,
+    error::Error,
+    pagination::{self, Listing, Page, Sortable},
+    projection::{Fields, Projected},
+};
+
+use crate::{authorization::Permission, entities};
+
+const LISTING: Listing = Listing {
+    select: "d.*, u.name AS user_name, du.name AS duplicate_user_name",
+    from: r#"
+        user_duplicate d
+        JOIN "user" u ON u.id = d.user_id
+        JOIN "user" du ON du.id = d.duplicate_user_id
+    "#,
+    key: "d.user_id || ' ' || d.duplicate_user_id",
+    sortable: &[
+        Sortable {
+            name: "detected_at",
+            expr: "d.detected_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "score",
+            expr: "d.score",
+            ty: "DOUBLE PRECISION",
+        },
+    ],
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub status: Option<entities::UserDuplicateStatus>,
+    pub user_id: Option<String>,
+    pub min_score: Option<f64>,
+}
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Page<Projected<entities::UserDuplicate>>>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _list_user_duplicates(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        params: pagination::Params,
+        filter: Filter,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::UserDuplicate>(fields)?;
+
+        auth.require(&db, Permission::UsersAdmin).await?;
+
+        let duplicates = LISTING
+            .fetch(&db.db, &params, |query| {
+                // A merged pair keeps the merged user, which is soft-deleted by the merge.
+                query.push(
+                    " AND ((u.deleted IS FALSE AND du.deleted IS FALSE) OR d.status = 'merged')",
+                );
+                if let Some(status) = filter.status {
+                    query.push(" AND d.status = ").push_bind(status);
+                }
+                if let Some(user_id) = &filter.user_id {
+                    query
+                        .push(" AND (d.user_id = ")
+                        .push_bind(user_id.clone())
+                        .push(" OR d.duplicate_user_id = ")
+                        .push_bind(user_id.clone())
+                        .push(")");
+                }
+                if let Some(min_score) = filter.min_score {
+                    query.push(" AND d.score >= ").push_bind(min_score);
+                }
+            })
+            .await?;
+
+        Ok(Response::Ok(payload::Json(fields.project_page(duplicates))))
+    }
+}
@@ -0,0 +1,3 @@
+pub mod detect;
+pub mod list;
+pub mod update;
@@ -0,0 +1,107 @@
+use poem::web;
+use poem_openapi::{param::Path, payload, Object};
+use serde::{Deserialize, Serialize};
+
+use common::{auth::BearerAuth, database::Database, error::Error};
+
+use crate::{
+    authorization::Permission,
+    entities::{self, UserDuplicateStatus},
+};
+
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "UpdateUserDuplicateRequest")]
+pub struct Request {
+    /// `dismissed` when the users are distinct people, or `pending` to review the pair again.
+    status: UserDuplicateStatus,
+}
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<entities::UserDuplicate>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _update_user_duplicate(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        user_id: Path<String>,
+        duplicate_user_id: Path<String>,
+        body: payload::Json<Request>,
+    ) -> Result<Response, Error> {
+        auth.require(&db, Permission::UsersAdmin).await?;
+
+        if body.status == UserDuplicateStatus::Merged {
+            return Err(Error::bad_request(
+                "USER_DUPLICATE_STATUS_INVALID",
+                format!(
+                    "Merge the users through `/users/{}/merge` instead",
+                    &*user_id
+                ),
+            ));
+        }
+
+        let mut tx = db.db.begin().await?;
+
+        let status = sqlx::query_scalar::<_, UserDuplicateStatus>(
+            r#"
+            SELECT status FROM user_duplicate
+            WHERE user_id = $1 AND duplicate_user_id = $2
+            FOR UPDATE
+            "#,
+        )
+        .bind(&*user_id)
+        .bind(&*duplicate_user_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::not_found(
+                "USER_DUPLICATE_NOT_FOUND",
+                format!(
+                    "Duplicate of user '{}' and '{}' not found",
+                    &*user_id, &*duplicate_user_id
+                ),
+            ),
+            _ => Error::from(e),
+        })?;
+
+        if status == UserDuplicateStatus::Merged {
+            return Err(Error::conflict(
+                "USER_DUPLICATE_ALREADY_MERGED",
+                format!(
+                    "Users '{}' and '{}' were already merged",
+                    &*user_id, &*duplicate_user_id
+                ),
+            ));
+        }
+
+        let duplicate = sqlx::query_as::<_, entities::UserDuplicate>(
+            r#"
+            WITH d AS (
+                UPDATE user_duplicate SET
+                    status      = $3,
+                    reviewed_by = $4,
+                    reviewed_at = NOW()
+                WHERE user_id = $1 AND duplicate_user_id = $2
+                RETURNING *
+            )
+            SELECT d.*, u.name AS user_name, du.name AS duplicate_user_name
+            FROM d
+            JOIN "user" u ON u.id = d.user_id
+            JOIN "user" du ON du.id = d.duplicate_user_id
+            "#,
+        )
+        .bind(&*user_id)
+        .bind(&*duplicate_user_id)
+        .bind(body.status)
+        .bind(&auth.0.id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Response::Ok(payload::Json(duplicate)))
+    }
+}
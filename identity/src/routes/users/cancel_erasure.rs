@@ -0,0 +1,46 @@
+use poem::web;
+use poem_openapi::{param::Path, payload};
+
+use common::{audit, auth::BearerAuth, database::Database, error::Error, outbox};
+
+use crate::{authorization::Permission, entities};
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<entities::UserErasure>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _cancel_user_erasure(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: Path<String>,
+    ) -> Result<Response, Error> {
+        auth.require(&db, Permission::UsersAdmin).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        // Carried out erasures are kept as a record, they cannot be undone anyway.
+        let erasure = sqlx::query_as::<_, entities::UserErasure>(
+            "DELETE FROM user_erasure WHERE user_id = $1 AND erased_at IS NULL RETURNING *",
+        )
+        .bind(&*id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::not_found(
+                "USER_ERASURE_NOT_FOUND",
+                format!("No pending erasure of user with id '{}'", &*id),
+            ),
+            _ => Error::from(e),
+        })?;
+
+        outbox::publish(&mut tx, "user.erasure_cancelled", &erasure).await?;
+
+        tx.commit().await?;
+
+        Ok(Response::Ok(payload::Json(erasure)))
+    }
+}
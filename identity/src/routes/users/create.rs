@@ -2,7 +2,12 @@ use poem::web;
 use poem_openapi::{payload, Object};
 use serde::{Deserialize, Serialize};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{
+    audit, auth::BearerAuth, authorization, crypto::Keyring, database::Database, error::Error,
+    outbox,
+};
+
+use crate::{auth0_sync, authorization::Permission, claim, entities, pii};
 
 #[derive(Debug, Clone, Deserialize, Serialize, Object)]
 #[oai(rename = "CreateUserRequest")]
@@ -23,6 +28,8 @@ pub struct Request {
     avatar_url: Option<String>,
     address: Option<entities::Address>,
     date_of_birth: Option<chrono::DateTime<chrono::Utc>>,
+    /// Create a blank user even though an existing profile matches the email or phone number.
+    skip_claim: Option<bool>,
 }
 
 #[derive(poem_openapi::ApiResponse)]
@@ -31,121 +38,141 @@ pub enum Response {
     Ok(payload::Json<entities::User>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
-}
-
 impl crate::routes::Routes {
     pub async fn _create_user(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
+        keyring: web::Data<&Keyring>,
         body: payload::Json<Request>,
     ) -> Result<Response, Error> {
-        let user = sqlx::query_as_unchecked!(
-            entities::User,
+        super::require_new_user_access(&auth, &body.id)?;
+
+        if !body.skip_claim.unwrap_or(false) {
+            // Users registering themselves are only matched on the email of their access token,
+            // see `_list_user_claim_candidates`.
+            let (email, phone_number) = if auth.is_granted_by_token(Permission::UsersWrite) {
+                (body.email.as_str(), body.phone_number.as_deref())
+            } else {
+                (auth.0.email.as_str(), None)
+            };
+
+            let mut conn = db.db.acquire().await?;
+            let candidates =
+                claim::find_candidates(&mut conn, &keyring, &body.id, Some(email), phone_number)
+                    .await?;
+
+            if !candidates.is_empty() {
+                return Err(Error::conflict(
+                    "USER_PROFILE_CLAIMABLE",
+                    format!(
+                        "An existing profile matches the email or phone number, list them through \
+                         `/users/{}/claims/candidates` to claim one, or pass `skip_claim`",
+                        &body.id
+                    ),
+                ));
+            }
+        }
+
+        // Only admins may vouch for an email or phone number, everyone else has to verify it.
+        let vouches = match auth.require(&db, Permission::UsersAdmin).await {
+            Ok(()) => true,
+            Err(authorization::Error::Forbidden(_)) => false,
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        let sealed = pii::Details {
+            ic_number: body.ic_number.clone(),
+            phone_number: body.phone_number.clone(),
+            address: body.address.clone(),
+            date_of_birth: body.date_of_birth,
+        }
+        .seal(&keyring)?;
+
+        let user = sqlx::query_as::<_, pii::UserRow>(
             r#"
             INSERT INTO "user" (
-                id, 
-                no, 
-                name, 
-                email, 
-                email_verified, 
-                username, 
-                given_name, 
-                family_name, 
-                gender, 
-                ic_number, 
-                phone_number, 
-                phone_number_verified, 
-                nickname, 
-                avatar_url, 
-                address,
-                date_of_birth
+                id,
+                no,
+                name,
+                email,
+                email_verified,
+                username,
+                given_name,
+                family_name,
+                gender,
+                ic_number_encrypted,
+                ic_number_index,
+                phone_number_encrypted,
+                phone_number_index,
+                phone_number_verified,
+                nickname,
+                avatar_url,
+                address_encrypted,
+                date_of_birth_encrypted,
+                year_of_birth
             ) VALUES (
-                $1, 
-                $2, 
-                $3, 
-                $4, 
-                $5, 
-                $6, 
-                $7, 
-                $8, 
-                $9, 
-                $10, 
-                $11, 
-                $12, 
-                $13, 
-                $14, 
+                $1,
+                COALESCE($2, nextval('user_no_seq')),
+                $3,
+                $4,
+                $5,
+                $6,
+                $7,
+                $8,
+                $9,
+                $10,
+                $11,
+                $12,
+                $13,
+                $14,
                 $15,
-                $16
-            ) 
+                $16,
+                $17,
+                $18,
+                $19
+            )
             RETURNING *
             "#,
-            &body.id,
-            &body.no,
-            &body.name,
-            &body.email,
-            &body.email_verified.unwrap_or(false),
-            &body.username,
-            &body.given_name,
-            &body.family_name,
-            &body.gender,
-            &body.ic_number,
-            &body.phone_number,
-            &body.phone_number_verified,
-            &body.nickname,
-            &body.avatar_url,
-            &body.address,
-            &body.date_of_birth,
         )
-        .fetch_one(&db.db)
-        .await
-        .map_err(|e| match e {
-            sqlx::Error::Database(e)
-                if e.is_unique_violation()
-                    && e.constraint()
-                        .is_some_and(|constraint| constraint == "user_pkey") =>
-            {
-                Error::BadRequest(payload::Json(ErrorResponse {
-                    message: format!("User with id '{}' already exists", body.id),
-                }))
-            }
-            sqlx::Error::Database(e)
-                if e.is_unique_violation()
-                    && e.constraint()
-                        .is_some_and(|constraint| constraint == "user_email_key") =>
-            {
-                Error::BadRequest(payload::Json(ErrorResponse {
-                    message: format!("User with email '{}' already exists", body.email),
-                }))
-            }
-            sqlx::Error::Database(e)
-                if e.is_unique_violation()
-                    && e.constraint()
-                        .is_some_and(|constraint| constraint == "user_no_key") =>
-            {
-                Error::BadRequest(payload::Json(ErrorResponse {
-                    message: format!("User with no '{:?}' already exists", body.no),
-                }))
-            }
-            sqlx::Error::Database(e)
-                if e.is_unique_violation()
-                    && e.constraint()
-                        .is_some_and(|constraint| constraint == "user_username_key") =>
-            {
-                Error::BadRequest(payload::Json(ErrorResponse {
-                    message: format!("User with username '{:?}' already exists", body.username),
-                }))
-            }
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
+        .bind(&body.id)
+        .bind(body.no)
+        .bind(&body.name)
+        .bind(&body.email)
+        .bind(body.email_verified.filter(|_| vouches).unwrap_or(false))
+        .bind(&body.username)
+        .bind(&body.given_name)
+        .bind(&body.family_name)
+        .bind(body.gender)
+        .bind(&sealed.ic_number)
+        .bind(&sealed.ic_number_index)
+        .bind(&sealed.phone_number)
+        .bind(&sealed.phone_number_index)
+        .bind(
+            body.phone_number_verified
+                .filter(|_| vouches)
+                .unwrap_or(false),
+        )
+        .bind(&body.nickname)
+        .bind(&body.avatar_url)
+        .bind(&sealed.address)
+        .bind(&sealed.date_of_birth)
+        .bind(sealed.year_of_birth)
+        .fetch_one(&mut *tx)
+        .await?
+        .open(&keyring)?;
+
+        outbox::publish(&mut tx, "user.created", &pii::mask(user.clone())).await?;
+
+        tx.commit().await?;
+
+        // Synced once committed, Auth0 failing must not fail the change. The reconciliation job
+        // fixes the profiles left out of sync.
+        if let Err(e) = auth0_sync::push(&self.management, &user, false).await {
+            tracing::error!("Failed to sync user '{}' to Auth0: {}", user.id, e);
+        }
 
         Ok(Response::Ok(payload::Json(user)))
     }
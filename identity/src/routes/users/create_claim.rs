@@ -0,0 +1,182 @@
+use poem::web;
+use poem_openapi::{param::Path, payload, Object};
+use serde::{Deserialize, Serialize};
+
+use common::{audit, auth::BearerAuth, crypto::Keyring, database::Database, error::Error};
+
+use crate::{
+    authorization::Permission,
+    claim,
+    entities::{self, ClaimChannel},
+    notifier::{Message, Recipient},
+};
+
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "CreateUserClaimRequest")]
+pub struct Request {
+    /// The email the user signed up with, which the claimed profile takes over. Users acting on
+    /// themselves always claim with the email of their access token.
+    email: String,
+    phone_number: Option<String>,
+    /// The shadow user to claim, exclusive with `user_id`.
+    shadow_user_no: Option<i32>,
+    /// The soft-deleted user to claim, exclusive with `shadow_user_no`.
+    user_id: Option<String>,
+    /// How to deliver the one-time code, to the email or phone number of the claimed profile.
+    channel: ClaimChannel,
+}
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<entities::UserClaim>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _create_user_claim(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        keyring: web::Data<&Keyring>,
+        id: Path<String>,
+        body: payload::Json<Request>,
+    ) -> Result<Response, Error> {
+        super::require_new_user_access(&auth, &id)?;
+
+        if body.shadow_user_no.is_some() == body.user_id.is_some() {
+            return Err(Error::bad_request(
+                "USER_CLAIM_TARGET_INVALID",
+                "Exactly one of shadow_user_no and user_id must be given",
+            ));
+        }
+
+        // Users claiming for themselves are only matched on the email of their access token, so
+        // that they cannot probe other emails or phone numbers, nor take over a profile under an
+        // email they do not own.
+        let (email, phone_number) = if auth.is_granted_by_token(Permission::UsersWrite) {
+            (body.email.clone(), body.phone_number.clone())
+        } else {
+            (auth.0.email.clone(), None)
+        };
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        let (registered, recent_claims) = sqlx::query_as::<_, (bool, i64)>(
+            r#"
+            SELECT
+                EXISTS (SELECT 1 FROM "user" WHERE id = $1),
+                (
+                    SELECT COUNT(*)
+                    FROM user_claim
+                    WHERE user_id = $1 AND created_at > NOW() - INTERVAL '1 hour'
+                )
+            "#,
+        )
+        .bind(&*id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if registered {
+            return Err(Error::conflict(
+                "USER_ALREADY_REGISTERED",
+                format!(
+                    "User with id '{}' already exists, only new users can claim a profile",
+                    &*id
+                ),
+            ));
+        }
+        if recent_claims >= claim::MAX_CLAIMS_PER_HOUR {
+            return Err(Error::conflict(
+                "USER_CLAIM_RATE_LIMITED",
+                "Too many claims were started recently, try again later",
+            ));
+        }
+
+        let candidate = claim::find_candidates(
+            &mut tx,
+            &keyring,
+            &id,
+            Some(&email),
+            phone_number.as_deref(),
+        )
+        .await?
+        .into_iter()
+        .find(|candidate| {
+            candidate.shadow_user_no == body.shadow_user_no && candidate.user_id == body.user_id
+        })
+        .ok_or_else(|| {
+            Error::not_found(
+                "USER_CLAIM_CANDIDATE_NOT_FOUND",
+                "No claimable profile matches the email or phone number",
+            )
+        })?;
+
+        let destination = candidate.destination(body.channel).ok_or_else(|| {
+            Error::bad_request(
+                "USER_CLAIM_CHANNEL_UNAVAILABLE",
+                "The profile has no email or phone number to send the code through this channel",
+            )
+        })?;
+
+        let claim_id = format!("user_claim_{}", ulid::Ulid::new());
+        let code = claim::generate_code();
+
+        let user_claim = sqlx::query_as::<_, entities::UserClaim>(
+            r#"
+            INSERT INTO user_claim (
+                id,
+                user_id,
+                email,
+                shadow_user_no,
+                claimed_user_id,
+                channel,
+                destination,
+                code_hash,
+                expires_at
+            ) VALUES (
+                $1,
+                $2,
+                $3,
+                $4,
+                $5,
+                $6,
+                $7,
+                $8,
+                NOW() + make_interval(mins => $9)
+            )
+            RETURNING *
+            "#,
+        )
+        .bind(&claim_id)
+        .bind(&*id)
+        .bind(&email)
+        .bind(body.shadow_user_no)
+        .bind(&body.user_id)
+        .bind(body.channel)
+        .bind(claim::mask(body.channel, destination))
+        .bind(claim::hash_code(&claim_id, &code))
+        .bind(claim::CODE_TTL_MINUTES as i32)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        self.notifier
+            .send(&Message {
+                recipient: match body.channel {
+                    ClaimChannel::Email => Recipient::Email(destination.to_string()),
+                    ClaimChannel::Phone => Recipient::Phone(destination.to_string()),
+                },
+                subject: "Your FGACYC verification code".to_string(),
+                body: format!(
+                    "Your code to claim your FGACYC profile is {}. It expires in {} minutes.",
+                    code,
+                    claim::CODE_TTL_MINUTES
+                ),
+            })
+            .await
+            .map_err(Error::internal)?;
+
+        tx.commit().await?;
+
+        Ok(Response::Ok(payload::Json(user_claim)))
+    }
+}
@@ -2,13 +2,19 @@ use poem::web;
 use poem_openapi::{param::Path, payload, Object};
 use serde::{Deserialize, Serialize};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{audit, auth::BearerAuth, database::Database, error::Error};
+
+use crate::entities;
 
 #[derive(Debug, Clone, Deserialize, Serialize, Object)]
 #[oai(rename = "CreateUserRelationshipRequest")]
 pub struct Request {
     destination_user_id: String,
+    /// One of the relationship types, e.g. `parent`.
     relationship: String,
+    /// Whether the user may pick up the destination user, only granted by users holding
+    /// the `users:write` permission over the destination user.
+    can_pick_up: Option<bool>,
 }
 
 #[derive(poem_openapi::ApiResponse)]
@@ -17,67 +23,49 @@ pub enum Response {
     Ok(payload::Json<entities::UserRelationship>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
-}
-
 impl crate::routes::Routes {
     pub async fn _create_user_relationship(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
         body: payload::Json<Request>,
     ) -> Result<Response, Error> {
-        let user_relationship = sqlx::query_as!(
-            entities::UserRelationship,
+        super::require_write_access(&auth, &db, &id).await?;
+        if body.can_pick_up.is_some() {
+            super::require_pick_up_grant(&auth, &db, &body.destination_user_id).await?;
+        }
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        super::require_active(&mut *tx, &[id.as_str(), body.destination_user_id.as_str()]).await?;
+
+        let user_relationship = sqlx::query_as::<_, entities::UserRelationship>(
             r#"
             INSERT INTO user_relationship (
                 source_user_id,
                 destination_user_id,
-                relationship
+                relationship,
+                can_pick_up
             ) VALUES (
-                $1, 
-                $2, 
-                $3
-            ) 
+                $1,
+                $2,
+                $3,
+                COALESCE($4, FALSE)
+            )
             RETURNING *
             "#,
-            &*id,
-            &body.destination_user_id,
-            &body.relationship,
         )
-        .fetch_one(&db.db)
-        .await
-        .map_err(|e| match e {
-            sqlx::Error::Database(e)
-                if e.is_unique_violation()
-                    && e.constraint()
-                        .is_some_and(|constraint| constraint == "user_relationship_pkey") =>
-            {
-                Error::BadRequest(payload::Json(ErrorResponse {
-                    message: format!(
-                        "source_user_id({}) and destination_user_id({}) already has a relationship",
-                        *id, body.destination_user_id
-                    ),
-                }))
-            }
-            sqlx::Error::Database(e)
-                if e.constraint()
-                    .is_some_and(|constraint| constraint == "source_destination_check") =>
-            {
-                Error::BadRequest(payload::Json(ErrorResponse {
-                    message: format!("source_user_id and destination_user_id must be different"),
-                }))
-            }
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
+        .bind(&*id)
+        .bind(&body.destination_user_id)
+        .bind(&body.relationship)
+        .bind(body.can_pick_up)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        super::put_inverse(&mut tx, &user_relationship).await?;
+
+        tx.commit().await?;
 
         Ok(Response::Ok(payload::Json(user_relationship)))
     }
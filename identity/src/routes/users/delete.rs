@@ -1,7 +1,9 @@
 use poem::web;
 use poem_openapi::{param::Path, payload};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{audit, auth::BearerAuth, crypto::Keyring, database::Database, error::Error, outbox};
+
+use crate::{auth0_sync, authorization::Permission, entities, pii};
 
 #[derive(poem_openapi::ApiResponse)]
 pub enum Response {
@@ -9,42 +11,47 @@ pub enum Response {
     Ok(payload::Json<entities::User>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
-}
-
 impl crate::routes::Routes {
     pub async fn _delete_user(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
+        keyring: web::Data<&Keyring>,
         id: Path<String>,
     ) -> Result<Response, Error> {
-        let user = sqlx::query_as_unchecked!(
-            entities::User,
+        auth.require(&db, Permission::UsersAdmin).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        let user = sqlx::query_as::<_, pii::UserRow>(
             r#"
-            UPDATE "user" SET deleted = true, updated_at = NOW() WHERE id = $1::TEXT RETURNING *
+            UPDATE "user" SET deleted = TRUE, updated_at = NOW()
+            WHERE id = $1 AND deleted IS FALSE
+            RETURNING *
             "#,
-            &*id
         )
-        .fetch_one(&db.db)
+        .bind(&*id)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| match e {
-            sqlx::error::Error::RowNotFound => Error::NotFound(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
-
-        Ok(Response::Ok(payload::Json(user)))
+            sqlx::Error::RowNotFound => Error::not_found(
+                "USER_NOT_FOUND",
+                format!("User with id '{}' not found", &*id),
+            ),
+            _ => Error::from(e),
+        })?
+        .open(&keyring)?;
+
+        outbox::publish(&mut tx, "user.deleted", &pii::mask(user.clone())).await?;
+
+        tx.commit().await?;
+
+        // Synced once committed, Auth0 failing must not fail the change. The reconciliation job
+        // fixes the profiles left out of sync.
+        if let Err(e) = auth0_sync::block(&self.management, &user.id, true).await {
+            tracing::error!("Failed to sync user '{}' to Auth0: {}", user.id, e);
+        }
+
+        Ok(Response::Ok(payload::Json(pii::mask_for(&auth, user))))
     }
 }
@@ -2,7 +2,9 @@ use poem::web;
 use poem_openapi::{param::Path, payload, Object};
 use serde::{Deserialize, Serialize};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{audit, auth::BearerAuth, database::Database, error::Error};
+
+use crate::entities;
 
 #[derive(Debug, Clone, Deserialize, Serialize, Object)]
 #[oai(rename = "DeleteUserRelationshipRequest")]
@@ -16,44 +18,51 @@ pub enum Response {
     Ok(payload::Json<entities::UserRelationship>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
-}
-
 impl crate::routes::Routes {
     pub async fn _delete_user_relationship(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
         body: payload::Json<Request>,
     ) -> Result<Response, Error> {
-        let user_relationship = sqlx::query_as_unchecked!(
-            entities::UserRelationship,
+        super::require_write_access(&auth, &db, &id).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        let user_relationship = sqlx::query_as::<_, entities::UserRelationship>(
             r#"
-            DELETE FROM user_relationship WHERE source_user_id = $1::TEXT AND destination_user_id = $2::TEXT RETURNING *
+            DELETE FROM user_relationship WHERE source_user_id = $1 AND destination_user_id = $2 RETURNING *
             "#,
-            &*id,
-            &body.destination_user_id
         )
-        .fetch_one(&db.db)
+        .bind(&*id)
+        .bind(&body.destination_user_id)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| match e {
-            sqlx::error::Error::RowNotFound => Error::NotFound(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
+            sqlx::Error::RowNotFound => Error::not_found(
+                "USER_RELATIONSHIP_NOT_FOUND",
+                format!(
+                    "User relationship between '{}' and '{}' not found",
+                    &*id, &body.destination_user_id
+                ),
+            ),
+            _ => Error::from(e),
         })?;
 
+        // A relationship goes along with its inverse.
+        sqlx::query(
+            r#"
+            DELETE FROM user_relationship WHERE source_user_id = $1 AND destination_user_id = $2
+            "#,
+        )
+        .bind(&body.destination_user_id)
+        .bind(&*id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
         Ok(Response::Ok(payload::Json(user_relationship)))
     }
 }
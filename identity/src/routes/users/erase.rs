@@ -0,0 +1,71 @@
+use poem::web;
+use poem_openapi::{param::Path, payload, Object};
+use serde::{Deserialize, Serialize};
+
+use common::{audit, auth::BearerAuth, database::Database, error::Error, outbox};
+
+use crate::{authorization::Permission, entities, privacy};
+
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "EraseUserRequest")]
+pub struct Request {
+    /// Why the personal data of the user is erased (e.g., requested by the user).
+    reason: Option<String>,
+    /// Days the erasure may be cancelled for, 30 by default. The user is erased right away when
+    /// `0`.
+    #[oai(validator(minimum(value = "0"), maximum(value = "365")))]
+    grace_period_days: Option<i64>,
+}
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<entities::UserErasure>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _erase_user(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: Path<String>,
+        body: payload::Json<Request>,
+    ) -> Result<Response, Error> {
+        auth.require(&db, Permission::UsersAdmin).await?;
+
+        let grace_period_days = body.grace_period_days.unwrap_or(privacy::GRACE_PERIOD_DAYS);
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        let erasure = sqlx::query_as::<_, entities::UserErasure>(
+            r#"
+            INSERT INTO user_erasure (user_id, requested_by, reason, scheduled_at)
+            VALUES ($1, $2, $3, NOW() + make_interval(days => $4::INTEGER))
+            RETURNING *
+            "#,
+        )
+        .bind(&*id)
+        .bind(&auth.0.id)
+        .bind(&body.reason)
+        .bind(grace_period_days)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let erasure = if grace_period_days == 0 {
+            privacy::erase(&mut tx, &id).await?;
+            sqlx::query_as::<_, entities::UserErasure>(
+                "SELECT * FROM user_erasure WHERE user_id = $1",
+            )
+            .bind(&*id)
+            .fetch_one(&mut *tx)
+            .await?
+        } else {
+            outbox::publish(&mut tx, "user.erasure_requested", &erasure).await?;
+            erasure
+        };
+
+        tx.commit().await?;
+
+        Ok(Response::Ok(payload::Json(erasure)))
+    }
+}
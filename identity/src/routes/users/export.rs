@@ -0,0 +1,88 @@
+use poem::web;
+use poem_openapi::{param::Path, payload, Enum, ResponseContent};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use common::{auth::BearerAuth, crypto::Keyring, database::Database, error::Error};
+
+use crate::{authorization::Permission, pii, privacy};
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Deserialize, Serialize, Enum)]
+#[oai(rename = "UserExportFormat", rename_all = "lowercase")]
+pub enum Format {
+    /// A single JSON document.
+    #[default]
+    Json,
+    /// A ZIP archive of one JSON file per section, along with a manifest.
+    Zip,
+}
+
+#[derive(ResponseContent)]
+pub enum Export {
+    Json(payload::Json<Value>),
+    Zip(payload::Binary<Vec<u8>>),
+}
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(Export, #[oai(header = "Content-Disposition")] String),
+}
+
+impl crate::routes::Routes {
+    pub async fn _export_user(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        keyring: web::Data<&Keyring>,
+        id: Path<String>,
+        format: Option<Format>,
+    ) -> Result<Response, Error> {
+        auth.require_self_or(&db, &id, Permission::UsersAdmin)
+            .await?;
+
+        // Read from a single snapshot so that the sections agree with each other.
+        let mut tx = db.db.begin().await?;
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ READ ONLY")
+            .execute(&mut *tx)
+            .await?;
+        let sections = privacy::export(&mut tx, &keyring, &id, !pii::can_read(&auth, &id))
+            .await?
+            .ok_or_else(|| {
+                Error::not_found(
+                    "USER_NOT_FOUND",
+                    format!("User with id '{}' not found", &*id),
+                )
+            })?;
+        tx.commit().await?;
+
+        let exported_at = chrono::Utc::now();
+        let file_name = format!("{}-{}", &*id, exported_at.format("%Y%m%d%H%M%S"));
+        match format.unwrap_or_default() {
+            Format::Json => {
+                let mut export = Map::new();
+                export.insert("user_id".to_string(), Value::from(id.as_str()));
+                export.insert(
+                    "exported_at".to_string(),
+                    Value::from(exported_at.to_rfc3339()),
+                );
+                for (name, rows) in sections {
+                    export.insert(name.to_string(), rows);
+                }
+
+                Ok(Response::Ok(
+                    Export::Json(payload::Json(Value::Object(export))),
+                    format!("attachment; filename=\"{}.json\"", file_name),
+                ))
+            }
+            Format::Zip => {
+                let archive = privacy::zip(&id, exported_at, &sections).map_err(Error::internal)?;
+
+                Ok(Response::Ok(
+                    Export::Zip(payload::Binary(archive)),
+                    format!("attachment; filename=\"{}.zip\"", file_name),
+                ))
+            }
+        }
+    }
+}
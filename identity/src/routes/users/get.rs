@@ -1,50 +1,57 @@
 use poem::web;
 use poem_openapi::{param::Path, payload};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{
+    auth::BearerAuth,
+    crypto::Keyring,
+    database::Database,
+    error::Error,
+    projection::{Fields, Projected},
+};
+
+use crate::{authorization::Permission, entities, pii, scope::Scope};
 
 #[derive(poem_openapi::ApiResponse)]
 pub enum Response {
     #[oai(status = 200)]
-    Ok(payload::Json<entities::User>),
-}
-
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
+    Ok(payload::Json<Projected<entities::User>>),
 }
 
 impl crate::routes::Routes {
     pub async fn _get_user(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
+        keyring: web::Data<&Keyring>,
         id: Path<String>,
+        fields: Option<String>,
     ) -> Result<Response, Error> {
-        let user = sqlx::query_as_unchecked!(
-            entities::User,
-            r#"
-            SELECT * from "user" WHERE id = $1::TEXT AND deleted IS FALSE
-            "#,
-            &*id
+        let fields = Fields::parse::<entities::User>(fields)?;
+
+        let accessible = Scope::can_access(&auth, &db, Permission::UsersRead, &id).await?;
+        if !accessible {
+            return Err(Error::not_found(
+                "USER_NOT_FOUND",
+                format!("User with id '{}' not found", &*id),
+            ));
+        }
+
+        let user = sqlx::query_as::<_, pii::UserRow>(
+            r#"SELECT * FROM "user" WHERE id = $1 AND deleted IS FALSE"#,
         )
+        .bind(&*id)
         .fetch_one(&db.db)
         .await
         .map_err(|e| match e {
-            sqlx::error::Error::RowNotFound => Error::NotFound(payload::Json(ErrorResponse {
-                message: format!("User with id '{}' not found", &*id),
-            })),
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
+            sqlx::Error::RowNotFound => Error::not_found(
+                "USER_NOT_FOUND",
+                format!("User with id '{}' not found", &*id),
+            ),
+            _ => Error::from(e),
         })?;
 
-        Ok(Response::Ok(payload::Json(user)))
+        Ok(Response::Ok(payload::Json(
+            fields.project(user.reveal(&keyring, &auth)?),
+        )))
     }
 }
@@ -1,7 +1,13 @@
 use poem::web;
 use poem_openapi::{param::Path, payload, ApiResponse, Object};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{
+    database::Database,
+    error::Error,
+    projection::{Fields, Projected},
+};
+
+use crate::entities;
 
 #[derive(Object)]
 #[oai(rename = "GetUserConnectGroupsRepsonse")]
@@ -13,19 +19,7 @@ pub struct ResponseBody {
 #[derive(ApiResponse)]
 pub enum Response {
     #[oai(status = 200)]
-    Ok(payload::Json<Vec<ResponseBody>>),
-}
-
-#[derive(ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
+    Ok(payload::Json<Vec<Projected<ResponseBody>>>),
 }
 
 impl crate::routes::Routes {
@@ -33,7 +27,12 @@ impl crate::routes::Routes {
         &self,
         db: web::Data<&Database>,
         id: Path<String>,
+        fields: Option<String>,
     ) -> Result<Response, Error> {
+        let fields = Fields::parse::<ResponseBody>(fields)?;
+
+        super::require_active(&db.db, &[id.as_str()]).await?;
+
         let results = sqlx::query!(
             r#"
             SELECT 
@@ -60,12 +59,7 @@ impl crate::routes::Routes {
             &*id
         )
         .fetch_all(&db.db)
-        .await
-        .map_err(|e| match e {
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
+        .await?;
 
         Ok(Response::Ok(payload::Json(
             results
@@ -90,6 +84,7 @@ impl crate::routes::Routes {
                         weight: result.pr_weight,
                     },
                 })
+                .map(|body| fields.project(body))
                 .collect(),
         )))
     }
@@ -0,0 +1,130 @@
+use poem::web;
+use poem_openapi::{param::Path, payload, Object};
+use serde::{Deserialize, Serialize};
+
+use common::{
+    auth::BearerAuth,
+    database::Database,
+    error::Error,
+    projection::{Fields, Projected},
+};
+
+use crate::{authorization::Permission, entities, scope::Scope};
+
+/// The farthest relationships are followed.
+const MAX_DEPTH: i32 = 5;
+
+/// The users reached by following relationships, along with the relationships between them and
+/// the households they belong to.
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "Family")]
+pub struct Family {
+    /// Nearest first, starting with the user the family is retrieved for.
+    pub members: Vec<entities::FamilyMember>,
+    /// Every relationship between two members, in both directions.
+    pub relationships: Vec<entities::UserRelationship>,
+    pub households: Vec<entities::Household>,
+}
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Projected<Family>>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _get_user_family(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: Path<String>,
+        depth: Option<i32>,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<Family>(fields)?;
+
+        let accessible = Scope::can_access(&auth, &db, Permission::UsersRead, &id).await?;
+        if !accessible {
+            return Err(Error::not_found(
+                "USER_NOT_FOUND",
+                format!("User with id '{}' not found", &*id),
+            ));
+        }
+
+        // Relationships are stored along with their inverse, following them from the source
+        // reaches every relative. Deleted users are not followed.
+        let members = sqlx::query_as::<_, entities::FamilyMember>(
+            r#"
+            WITH RECURSIVE family (user_id, depth) AS (
+                SELECT id, 0 FROM "user" WHERE id = $1 AND deleted IS FALSE
+                UNION
+                SELECT r.destination_user_id, f.depth + 1
+                FROM
+                    family f
+                        INNER JOIN user_relationship r ON r.source_user_id = f.user_id
+                        INNER JOIN "user" u ON u.id = r.destination_user_id
+                WHERE u.deleted IS FALSE AND f.depth < $2
+            )
+            SELECT
+                u.id AS user_id,
+                u.name,
+                f.depth,
+                ARRAY(
+                    SELECT household_id FROM user_household
+                    WHERE user_id = u.id
+                    ORDER BY household_id
+                ) AS household_ids
+            FROM
+                (SELECT user_id, MIN(depth) AS depth FROM family GROUP BY user_id) f
+                    INNER JOIN "user" u ON u.id = f.user_id
+            ORDER BY f.depth, u.name
+            "#,
+        )
+        .bind(&*id)
+        .bind(depth.unwrap_or(1).clamp(1, MAX_DEPTH))
+        .fetch_all(&db.db)
+        .await?;
+        if members.is_empty() {
+            return Err(Error::not_found(
+                "USER_NOT_FOUND",
+                format!("User with id '{}' not found", &*id),
+            ));
+        }
+
+        let user_ids = members
+            .iter()
+            .map(|m| m.user_id.clone())
+            .collect::<Vec<_>>();
+
+        let relationships = sqlx::query_as::<_, entities::UserRelationship>(
+            r#"
+            SELECT * FROM user_relationship
+            WHERE source_user_id = ANY($1) AND destination_user_id = ANY($1)
+            ORDER BY source_user_id, destination_user_id
+            "#,
+        )
+        .bind(&user_ids)
+        .fetch_all(&db.db)
+        .await?;
+
+        let households = sqlx::query_as::<_, entities::Household>(
+            r#"
+            SELECT * FROM household h
+            WHERE EXISTS (
+                SELECT 1 FROM user_household
+                WHERE household_id = h.id AND user_id = ANY($1)
+            )
+            ORDER BY h.name, h.id
+            "#,
+        )
+        .bind(&user_ids)
+        .fetch_all(&db.db)
+        .await?;
+
+        Ok(Response::Ok(payload::Json(fields.project(Family {
+            members,
+            relationships,
+            households,
+        }))))
+    }
+}
@@ -0,0 +1,53 @@
+use poem::web;
+use poem_openapi::{param::Path, payload};
+
+use common::{
+    auth::BearerAuth,
+    database::Database,
+    error::Error,
+    projection::{Fields, Projected},
+};
+
+use crate::{authorization::Permission, entities, scope::Scope};
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Vec<Projected<entities::Household>>>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _get_user_households(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: Path<String>,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::Household>(fields)?;
+
+        let accessible = Scope::can_access(&auth, &db, Permission::UsersRead, &id).await?;
+        if !accessible {
+            return Err(Error::not_found(
+                "USER_NOT_FOUND",
+                format!("User with id '{}' not found", &*id),
+            ));
+        }
+
+        let households = sqlx::query_as::<_, entities::Household>(
+            r#"
+            SELECT h.*
+            FROM
+                household h
+                    INNER JOIN user_household uh ON uh.household_id = h.id
+            WHERE uh.user_id = $1
+            ORDER BY h.name, h.id
+            "#,
+        )
+        .bind(&*id)
+        .fetch_all(&db.db)
+        .await?;
+
+        Ok(Response::Ok(payload::Json(fields.project_all(households))))
+    }
+}
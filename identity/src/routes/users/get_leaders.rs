@@ -0,0 +1,56 @@
+use poem::web;
+use poem_openapi::{param::Path, payload};
+
+use common::{
+    auth::BearerAuth,
+    database::Database,
+    error::Error,
+    projection::{Fields, Projected},
+};
+
+use crate::{authorization::Permission, entities, pastoral_care, scope::Scope};
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Vec<Projected<entities::PastoralLink>>>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _get_user_leaders(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: Path<String>,
+        max_depth: Option<i32>,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::PastoralLink>(fields)?;
+
+        let accessible = Scope::can_access(&auth, &db, Permission::UsersRead, &id).await?;
+        if !accessible {
+            return Err(Error::not_found(
+                "USER_NOT_FOUND",
+                format!("User with id '{}' not found", &*id),
+            ));
+        }
+
+        // Unlike their flock, the leaders of a user are not limited to the scope, so that
+        // everyone can find out who cares for them.
+        let mut query = sqlx::QueryBuilder::new("");
+        pastoral_care::push_chain(
+            &mut query,
+            &id,
+            max_depth.unwrap_or(pastoral_care::MAX_DEPTH),
+            true,
+        );
+        query.push(" ORDER BY r.depth, pr.weight NULLS LAST, u.name");
+
+        let leaders = query
+            .build_query_as::<entities::PastoralLink>()
+            .fetch_all(&db.db)
+            .await?;
+
+        Ok(Response::Ok(payload::Json(fields.project_all(leaders))))
+    }
+}
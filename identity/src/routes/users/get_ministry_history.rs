@@ -0,0 +1,55 @@
+use poem::web;
+use poem_openapi::{param::Path, payload};
+
+use common::{
+    auth::BearerAuth,
+    database::Database,
+    error::Error,
+    projection::{Fields, Projected},
+};
+
+use crate::{
+    authorization::Permission,
+    entities,
+    routes::ministry::list_history::{FROM, SELECT},
+    scope::Scope,
+};
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Vec<Projected<entities::MinistryMembership>>>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _get_user_ministry_history(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: Path<String>,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::MinistryMembership>(fields)?;
+
+        let accessible = Scope::can_access(&auth, &db, Permission::UsersRead, &id).await?;
+        if !accessible {
+            return Err(Error::not_found(
+                "USER_NOT_FOUND",
+                format!("User with id '{}' not found", &*id),
+            ));
+        }
+
+        let memberships = sqlx::query_as::<_, entities::MinistryMembership>(&format!(
+            r#"
+            SELECT {SELECT} FROM {FROM}
+            WHERE h.user_id = $1
+            ORDER BY h.joined_at NULLS FIRST, h.id
+            "#
+        ))
+        .bind(&*id)
+        .fetch_all(&db.db)
+        .await?;
+
+        Ok(Response::Ok(payload::Json(fields.project_all(memberships))))
+    }
+}
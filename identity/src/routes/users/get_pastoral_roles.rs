@@ -1,24 +1,18 @@
 use poem::web;
 use poem_openapi::{param::Path, payload};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{
+    database::Database,
+    error::Error,
+    projection::{Fields, Projected},
+};
+
+use crate::entities;
 
 #[derive(poem_openapi::ApiResponse)]
 pub enum Response {
     #[oai(status = 200)]
-    Ok(payload::Json<Vec<entities::PastoralRole>>),
-}
-
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
+    Ok(payload::Json<Vec<Projected<entities::PastoralRole>>>),
 }
 
 impl crate::routes::Routes {
@@ -26,7 +20,12 @@ impl crate::routes::Routes {
         &self,
         db: web::Data<&Database>,
         id: Path<String>,
+        fields: Option<String>,
     ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::PastoralRole>(fields)?;
+
+        super::require_active(&db.db, &[id.as_str()]).await?;
+
         let roles = sqlx::query_as!(
             entities::PastoralRole,
             r#"
@@ -40,13 +39,8 @@ impl crate::routes::Routes {
             &*id
         )
         .fetch_all(&db.db)
-        .await
-        .map_err(|e| match e {
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
+        .await?;
 
-        Ok(Response::Ok(payload::Json(roles)))
+        Ok(Response::Ok(payload::Json(fields.project_all(roles))))
     }
 }
@@ -0,0 +1,84 @@
+use poem::web;
+use poem_openapi::{param::Path, payload};
+
+use common::{
+    auth::BearerAuth,
+    crypto::Keyring,
+    database::Database,
+    error::Error,
+    projection::{Fields, Projected},
+};
+
+use crate::{authorization::Permission, claim, entities, pii, scope::Scope};
+
+#[derive(sqlx::FromRow)]
+struct PickUpRow {
+    #[sqlx(flatten)]
+    pick_up: entities::PickUp,
+    phone_number_encrypted: Option<Vec<u8>>,
+}
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Vec<Projected<entities::PickUp>>>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _get_user_pick_ups(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        keyring: web::Data<&Keyring>,
+        id: Path<String>,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::PickUp>(fields)?;
+
+        let accessible = Scope::can_access(&auth, &db, Permission::UsersRead, &id).await?;
+        if !accessible {
+            return Err(Error::not_found(
+                "USER_NOT_FOUND",
+                format!("User with id '{}' not found", &*id),
+            ));
+        }
+
+        let rows = sqlx::query_as::<_, PickUpRow>(
+            r#"
+            SELECT
+                u.id AS user_id,
+                u.name,
+                u.phone_number,
+                u.phone_number_encrypted,
+                r.relationship
+            FROM
+                user_relationship r
+                    INNER JOIN "user" u ON u.id = r.source_user_id
+            WHERE r.destination_user_id = $1 AND r.can_pick_up AND u.deleted IS FALSE
+            ORDER BY u.name
+            "#,
+        )
+        .bind(&*id)
+        .fetch_all(&db.db)
+        .await?;
+
+        let mut pick_ups = Vec::with_capacity(rows.len());
+        for row in rows {
+            let mut pick_up = row.pick_up;
+            pick_up.phone_number = pii::open_phone_number(
+                &keyring,
+                row.phone_number_encrypted.as_deref(),
+                pick_up.phone_number,
+            )?;
+            if !pii::can_read(&auth, &pick_up.user_id) {
+                pick_up.phone_number = pick_up
+                    .phone_number
+                    .as_deref()
+                    .map(claim::mask_phone_number);
+            }
+            pick_ups.push(pick_up);
+        }
+
+        Ok(Response::Ok(payload::Json(fields.project_all(pick_ups))))
+    }
+}
@@ -1,48 +1,259 @@
 use poem::web;
-use poem_openapi::{param, payload};
+use poem_openapi::payload;
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{
+    auth::BearerAuth,
+    crypto::{self, Keyring},
+    database::Database,
+    error::Error,
+    pagination::{self, Listing, Page, Rank, Sortable},
+    projection::{Fields, Projected},
+};
+use sqlx::{Postgres, QueryBuilder};
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Response {
-    #[oai(status = 200)]
-    Ok(payload::Json<Vec<entities::User>>),
+use crate::{authorization::Permission, entities, pii, scope::Scope};
+
+const LISTING: Listing = Listing {
+    select: "u.*",
+    from: r#""user" u"#,
+    key: "u.id",
+    sortable: &[
+        Sortable {
+            name: "created_at",
+            expr: "u.created_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "updated_at",
+            expr: "u.updated_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "name",
+            expr: "u.name",
+            ty: "TEXT",
+        },
+        Sortable {
+            name: "email",
+            expr: "u.email",
+            ty: "TEXT",
+        },
+        Sortable {
+            name: "no",
+            expr: "u.no",
+            ty: "INTEGER",
+        },
+    ],
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub search: Option<String>,
+    pub satellite_id: Option<String>,
+    pub connect_group_id: Option<String>,
+    pub ministry_id: Option<String>,
+    pub gender: Option<entities::Gender>,
+    pub min_age: Option<i32>,
+    pub max_age: Option<i32>,
+    pub created_after: Option<chrono::DateTime<chrono::Utc>>,
+    pub include_deleted: bool,
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
+/// A parsed `search` parameter.
+struct Search {
+    query: String,
+    /// Every word of the query as a prefix (e.g. `ali:* & ta:*`), to match partial names.
+    prefix: Option<String>,
+    /// The blind indexes of the query when it may be a phone or IC number.
+    phone_number_index: Option<Vec<u8>>,
+    ic_number_index: Option<Vec<u8>>,
+}
+
+impl Search {
+    fn parse(keyring: &Keyring, query: &str) -> Result<Option<Self>, crypto::Error> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Ok(None);
+        }
+
+        // Only letters and digits are kept, so that the words are valid `to_tsquery` operands.
+        let words = query
+            .split_whitespace()
+            .map(|word| {
+                word.chars()
+                    .filter(|c| c.is_alphanumeric())
+                    .collect::<String>()
+            })
+            .filter(|word| !word.is_empty())
+            .map(|word| format!("{}:*", word))
+            .collect::<Vec<_>>();
 
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
+        let numeric = query.chars().any(|c| c.is_ascii_digit());
+        Ok(Some(Self {
+            query: query.to_string(),
+            prefix: (!words.is_empty()).then(|| words.join(" & ")),
+            phone_number_index: match numeric {
+                true => pii::phone_number_index(keyring, query)?,
+                false => None,
+            },
+            ic_number_index: match numeric {
+                true => pii::ic_number_index(keyring, query)?,
+                false => None,
+            },
+        }))
+    }
+
+    fn push_filter(&self, query: &mut QueryBuilder<'static, Postgres>) {
+        query
+            .push(" AND (u.search_vector @@ websearch_to_tsquery('simple', ")
+            .push_bind(self.query.clone())
+            .push(") OR ")
+            .push_bind(self.query.clone())
+            .push(" <% u.name");
+        if let Some(prefix) = &self.prefix {
+            query
+                .push(" OR u.search_vector @@ to_tsquery('simple', ")
+                .push_bind(prefix.clone())
+                .push(")");
+        }
+        if let Some(phone_number_index) = &self.phone_number_index {
+            query
+                .push(" OR u.phone_number_index = ")
+                .push_bind(phone_number_index.clone());
+        }
+        if let Some(ic_number_index) = &self.ic_number_index {
+            query
+                .push(" OR u.ic_number_index = ")
+                .push_bind(ic_number_index.clone());
+        }
+        query.push(")");
+    }
+
+    /// How well a user matches, full words weigh more than partial ones.
+    fn push_rank(&self, query: &mut QueryBuilder<'static, Postgres>) {
+        query
+            .push("(ts_rank_cd(u.search_vector, websearch_to_tsquery('simple', ")
+            .push_bind(self.query.clone())
+            .push(")) + word_similarity(")
+            .push_bind(self.query.clone())
+            .push(", u.name)");
+        if let Some(prefix) = &self.prefix {
+            query
+                .push(" + ts_rank_cd(u.search_vector, to_tsquery('simple', ")
+                .push_bind(prefix.clone())
+                .push(")) / 2");
+        }
+        query.push(")");
+    }
+}
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Page<Projected<entities::User>>>),
 }
 
 impl crate::routes::Routes {
     pub async fn _list_users(
         &self,
-        search: param::Query<Option<String>>,
+        auth: BearerAuth,
         db: web::Data<&Database>,
+        keyring: web::Data<&Keyring>,
+        params: pagination::Params,
+        filter: Filter,
+        fields: Option<String>,
     ) -> Result<Response, Error> {
-        let users = sqlx::query_as_unchecked!(
-            entities::User,
-            r#"
-            SELECT * 
-            FROM "user"
-            WHERE 
-                deleted IS FALSE AND
-                to_tsvector(email || ' ' || name || ' ' || COALESCE(username, '') || ' ' || COALESCE(given_name, '') || ' ' || COALESCE(family_name, '')) @@ to_tsquery($1)
-            "#,
-            search.clone().unwrap_or("".to_string()),
-        )
-        .fetch_all(&db.db)
-        .await
-        .map_err(|e| match e {
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
-
-        Ok(Response::Ok(payload::Json(users)))
+        let fields = Fields::parse::<entities::User>(fields)?;
+
+        if filter.include_deleted {
+            auth.require(&db, Permission::UsersAdmin).await?;
+        }
+        let scope = Scope::resolve(&auth, &db, Permission::UsersRead).await?;
+
+        let search = match filter.search.as_deref() {
+            Some(query) => Search::parse(&keyring, query)?,
+            None => None,
+        };
+        let rank = search.as_ref().map(|search| Rank {
+            name: "relevance",
+            expr: move |query: &mut QueryBuilder<'static, Postgres>| search.push_rank(query),
+            ty: "REAL",
+        });
+
+        let users = LISTING
+            .fetch_ranked_map(
+                &db.db,
+                &params,
+                rank,
+                |query| {
+                    scope.push_filter(query);
+                    if !filter.include_deleted {
+                        query.push(" AND u.deleted IS FALSE");
+                    }
+                    if let Some(search) = &search {
+                        search.push_filter(query);
+                    }
+                    if let Some(satellite_id) = &filter.satellite_id {
+                        query
+                            .push(
+                                r#"
+                                AND EXISTS (
+                                    SELECT 1
+                                    FROM
+                                        user_connect_group ucg
+                                            INNER JOIN connect_group cg ON ucg.connect_group_id = cg.id
+                                    WHERE ucg.user_id = u.id AND cg.satellite_id = "#,
+                            )
+                            .push_bind(satellite_id.clone())
+                            .push(")");
+                    }
+                    if let Some(connect_group_id) = &filter.connect_group_id {
+                        query
+                            .push(
+                                r#"
+                                AND EXISTS (
+                                    SELECT 1
+                                    FROM user_connect_group ucg
+                                    WHERE ucg.user_id = u.id AND ucg.connect_group_id = "#,
+                            )
+                            .push_bind(connect_group_id.clone())
+                            .push(")");
+                    }
+                    if let Some(ministry_id) = &filter.ministry_id {
+                        query
+                            .push(
+                                r#"
+                                AND EXISTS (
+                                    SELECT 1
+                                    FROM user_ministry um
+                                    WHERE um.user_id = u.id AND um.ministry_id = "#,
+                            )
+                            .push_bind(ministry_id.clone())
+                            .push(")");
+                    }
+                    if let Some(gender) = filter.gender {
+                        query.push(" AND u.gender = ").push_bind(gender);
+                    }
+                    // Dates of birth are encrypted, so users are filtered by the age they turn
+                    // this year.
+                    if let Some(min_age) = filter.min_age {
+                        query
+                            .push(" AND u.year_of_birth <= EXTRACT(YEAR FROM NOW())::INTEGER - ")
+                            .push_bind(min_age);
+                    }
+                    if let Some(max_age) = filter.max_age {
+                        query
+                            .push(" AND u.year_of_birth >= EXTRACT(YEAR FROM NOW())::INTEGER - ")
+                            .push_bind(max_age);
+                    }
+                    if let Some(created_after) = filter.created_after {
+                        query.push(" AND u.created_at > ").push_bind(created_after);
+                    }
+                },
+                |row: pii::UserRow| row.reveal(&keyring, &auth),
+            )
+            .await?;
+
+        Ok(Response::Ok(payload::Json(fields.project_page(users))))
     }
 }
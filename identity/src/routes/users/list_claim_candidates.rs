@@ -0,0 +1,57 @@
+use poem::web;
+use poem_openapi::{param::Path, payload};
+
+use common::{
+    auth::BearerAuth,
+    crypto::Keyring,
+    database::Database,
+    error::Error,
+    projection::{Fields, Projected},
+};
+
+use crate::{authorization::Permission, claim, entities};
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Vec<Projected<entities::ClaimCandidate>>>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _list_user_claim_candidates(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        keyring: web::Data<&Keyring>,
+        id: Path<String>,
+        email: Option<String>,
+        phone_number: Option<String>,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::ClaimCandidate>(fields)?;
+
+        super::require_new_user_access(&auth, &id)?;
+
+        // Users looking for their own profiles are only matched on the email of their access
+        // token, so that they cannot look up whose profiles other emails or phone numbers match.
+        let (email, phone_number) = if auth.is_granted_by_token(Permission::UsersWrite) {
+            (email, phone_number)
+        } else {
+            (Some(auth.0.email.clone()), None)
+        };
+
+        let mut conn = db.db.acquire().await?;
+        let candidates = claim::find_candidates(
+            &mut conn,
+            &keyring,
+            &id,
+            email.as_deref(),
+            phone_number.as_deref(),
+        )
+        .await?;
+
+        Ok(Response::Ok(payload::Json(fields.project_all(
+            candidates.iter().map(claim::Candidate::to_masked).collect(),
+        ))))
+    }
+}
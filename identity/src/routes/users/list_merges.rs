@@ -0,0 +1,44 @@
+use poem::web;
+use poem_openapi::{param::Path, payload};
+
+use common::{
+    auth::BearerAuth,
+    database::Database,
+    error::Error,
+    projection::{Fields, Projected},
+};
+
+use crate::{authorization::Permission, entities};
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Vec<Projected<entities::UserMerge>>>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _list_user_merges(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: Path<String>,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::UserMerge>(fields)?;
+
+        auth.require(&db, Permission::UsersAdmin).await?;
+
+        let merges = sqlx::query_as::<_, entities::UserMerge>(
+            r#"
+            SELECT * FROM user_merge
+            WHERE user_id = $1 OR merged_user_id = $1
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(&*id)
+        .fetch_all(&db.db)
+        .await?;
+
+        Ok(Response::Ok(payload::Json(fields.project_all(merges))))
+    }
+}
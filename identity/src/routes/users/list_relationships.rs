@@ -1,21 +1,18 @@
 use poem::web;
 use poem_openapi::{param::Path, payload};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{
+    database::Database,
+    error::Error,
+    projection::{Fields, Projected},
+};
+
+use crate::entities;
 
 #[derive(poem_openapi::ApiResponse)]
 pub enum Response {
     #[oai(status = 200)]
-    Ok(payload::Json<Vec<entities::UserRelationship>>),
-}
-
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
+    Ok(payload::Json<Vec<Projected<entities::UserRelationship>>>),
 }
 
 impl crate::routes::Routes {
@@ -23,22 +20,24 @@ impl crate::routes::Routes {
         &self,
         db: web::Data<&Database>,
         id: Path<String>,
+        fields: Option<String>,
     ) -> Result<Response, Error> {
-        let users = sqlx::query_as_unchecked!(
-            entities::UserRelationship,
+        let fields = Fields::parse::<entities::UserRelationship>(fields)?;
+
+        let users = sqlx::query_as::<_, entities::UserRelationship>(
             r#"
-            SELECT * from user_relationship WHERE source_user_id = $1::TEXT
+            SELECT r.*
+            FROM
+                user_relationship r
+                    INNER JOIN "user" s ON s.id = r.source_user_id
+                    INNER JOIN "user" d ON d.id = r.destination_user_id
+            WHERE r.source_user_id = $1 AND s.deleted IS FALSE AND d.deleted IS FALSE
             "#,
-            &*id
         )
+        .bind(&*id)
         .fetch_all(&db.db)
-        .await
-        .map_err(|e| match e {
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
+        .await?;
 
-        Ok(Response::Ok(payload::Json(users)))
+        Ok(Response::Ok(payload::Json(fields.project_all(users))))
     }
 }
@@ -0,0 +1,452 @@
+use poem::web;
+use poem_openapi::{param::Path, payload, Object};
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+
+use common::{audit, auth::BearerAuth, database::Database, error::Error, outbox};
+
+use crate::{authorization::Permission, entities, membership};
+
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "MergeUserRequest")]
+pub struct Request {
+    /// The duplicate user to merge into this one, it is soft-deleted afterwards.
+    merged_user_id: String,
+    /// Report what the merge would change without changing anything.
+    dry_run: Option<bool>,
+}
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<entities::UserMerge>),
+}
+
+/// Run a statement binding the kept user as `$1` and the merged user as `$2`, returning the
+/// number of rows it changed.
+async fn execute(
+    conn: &mut PgConnection,
+    sql: &str,
+    user_id: &str,
+    merged_user_id: &str,
+) -> Result<i32, sqlx::Error> {
+    let result = sqlx::query(sql)
+        .bind(user_id)
+        .bind(merged_user_id)
+        .execute(conn)
+        .await?;
+    Ok(result.rows_affected() as i32)
+}
+
+impl crate::routes::Routes {
+    pub async fn _merge_user(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: Path<String>,
+        body: payload::Json<Request>,
+    ) -> Result<Response, Error> {
+        auth.require(&db, Permission::UsersAdmin).await?;
+
+        let merged_user_id = body.merged_user_id.as_str();
+        if *id == merged_user_id {
+            return Err(Error::bad_request(
+                "USER_MERGE_WITH_SELF",
+                "A user cannot be merged into itself",
+            ));
+        }
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        // Locked in a consistent order so that concurrent merges of the same users cannot
+        // deadlock.
+        let found = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT id FROM "user"
+            WHERE id = ANY($1) AND deleted IS FALSE
+            ORDER BY id
+            FOR UPDATE
+            "#,
+        )
+        .bind(vec![id.to_string(), merged_user_id.to_string()])
+        .fetch_all(&mut *tx)
+        .await?;
+        for user_id in [id.as_str(), merged_user_id] {
+            if !found.iter().any(|found| found == user_id) {
+                return Err(Error::not_found(
+                    "USER_NOT_FOUND",
+                    format!("User with id '{}' not found", user_id),
+                ));
+            }
+        }
+
+        membership::set_left_reason(&mut tx, Some(&format!("Merged into user '{}'", &*id))).await?;
+
+        // When both users are in the same connect group or ministry, the kept user takes the
+        // most senior of the two roles and the other membership is dropped.
+        execute(
+            &mut tx,
+            r#"
+            UPDATE user_connect_group k SET user_role = m.user_role
+            FROM user_connect_group m, pastoral_role kr, pastoral_role mr
+            WHERE
+                k.user_id = $1
+                AND m.user_id = $2
+                AND m.connect_group_id = k.connect_group_id
+                AND kr.id = k.user_role
+                AND mr.id = m.user_role
+                AND mr.weight < kr.weight
+            "#,
+            &id,
+            merged_user_id,
+        )
+        .await?;
+        let connect_groups_dropped = execute(
+            &mut tx,
+            r#"
+            DELETE FROM user_connect_group m USING user_connect_group k
+            WHERE m.user_id = $2 AND k.user_id = $1 AND k.connect_group_id = m.connect_group_id
+            "#,
+            &id,
+            merged_user_id,
+        )
+        .await?;
+        let connect_groups_moved = execute(
+            &mut tx,
+            "UPDATE user_connect_group SET user_id = $1 WHERE user_id = $2",
+            &id,
+            merged_user_id,
+        )
+        .await?;
+
+        execute(
+            &mut tx,
+            r#"
+            UPDATE user_ministry k SET user_role = m.user_role
+            FROM user_ministry m, ministry_role kr, ministry_role mr
+            WHERE
+                k.user_id = $1
+                AND m.user_id = $2
+                AND m.ministry_id = k.ministry_id
+                AND kr.id = k.user_role
+                AND mr.id = m.user_role
+                AND mr.weight < kr.weight
+            "#,
+            &id,
+            merged_user_id,
+        )
+        .await?;
+        let ministries_dropped = execute(
+            &mut tx,
+            r#"
+            DELETE FROM user_ministry m USING user_ministry k
+            WHERE m.user_id = $2 AND k.user_id = $1 AND k.ministry_id = m.ministry_id
+            "#,
+            &id,
+            merged_user_id,
+        )
+        .await?;
+        let ministries_moved = execute(
+            &mut tx,
+            "UPDATE user_ministry SET user_id = $1 WHERE user_id = $2",
+            &id,
+            merged_user_id,
+        )
+        .await?;
+
+        // The history of the merged user becomes the kept user's, including the memberships
+        // dropped above, since both are the same person.
+        execute(
+            &mut tx,
+            "UPDATE user_connect_group_history SET user_id = $1 WHERE user_id = $2",
+            &id,
+            merged_user_id,
+        )
+        .await?;
+        execute(
+            &mut tx,
+            "UPDATE user_ministry_history SET user_id = $1 WHERE user_id = $2",
+            &id,
+            merged_user_id,
+        )
+        .await?;
+
+        // The kept user keeps their own overseer and takes over the leaders the merged user
+        // oversaw, oversight between the two users is dropped.
+        execute(
+            &mut tx,
+            r#"
+            DELETE FROM user_oversight
+            WHERE
+                (user_id = $1 AND overseer_id = $2)
+                OR (user_id = $2 AND overseer_id = $1)
+                OR (user_id = $2 AND EXISTS (SELECT 1 FROM user_oversight WHERE user_id = $1))
+            "#,
+            &id,
+            merged_user_id,
+        )
+        .await?;
+        execute(
+            &mut tx,
+            "UPDATE user_oversight SET user_id = $1, updated_at = NOW() WHERE user_id = $2",
+            &id,
+            merged_user_id,
+        )
+        .await?;
+        execute(
+            &mut tx,
+            "UPDATE user_oversight SET overseer_id = $1, updated_at = NOW() WHERE overseer_id = $2",
+            &id,
+            merged_user_id,
+        )
+        .await?;
+
+        execute(
+            &mut tx,
+            r#"
+            DELETE FROM user_household m
+            WHERE m.user_id = $2 AND EXISTS (
+                SELECT 1 FROM user_household
+                WHERE user_id = $1 AND household_id = m.household_id
+            )
+            "#,
+            &id,
+            merged_user_id,
+        )
+        .await?;
+        execute(
+            &mut tx,
+            "UPDATE user_household SET user_id = $1 WHERE user_id = $2",
+            &id,
+            merged_user_id,
+        )
+        .await?;
+
+        // Relationships between the two users and ones the kept user already has are dropped,
+        // the kept user's own description of the relationship wins.
+        let mut relationships_dropped = execute(
+            &mut tx,
+            r#"
+            DELETE FROM user_relationship m
+            WHERE m.source_user_id = $2 AND (
+                m.destination_user_id = $1
+                OR EXISTS (
+                    SELECT 1 FROM user_relationship
+                    WHERE source_user_id = $1 AND destination_user_id = m.destination_user_id
+                )
+            )
+            "#,
+            &id,
+            merged_user_id,
+        )
+        .await?;
+        let mut relationships_moved = execute(
+            &mut tx,
+            r#"
+            UPDATE user_relationship SET source_user_id = $1, updated_at = NOW()
+            WHERE source_user_id = $2
+            "#,
+            &id,
+            merged_user_id,
+        )
+        .await?;
+        relationships_dropped += execute(
+            &mut tx,
+            r#"
+            DELETE FROM user_relationship m
+            WHERE m.destination_user_id = $2 AND (
+                m.source_user_id = $1
+                OR EXISTS (
+                    SELECT 1 FROM user_relationship
+                    WHERE source_user_id = m.source_user_id AND destination_user_id = $1
+                )
+            )
+            "#,
+            &id,
+            merged_user_id,
+        )
+        .await?;
+        relationships_moved += execute(
+            &mut tx,
+            r#"
+            UPDATE user_relationship SET destination_user_id = $1, updated_at = NOW()
+            WHERE destination_user_id = $2
+            "#,
+            &id,
+            merged_user_id,
+        )
+        .await?;
+
+        // The event tables reference users without foreign keys.
+        let attendance_dropped = execute(
+            &mut tx,
+            r#"
+            DELETE FROM attendance m USING attendance k
+            WHERE m.user_id = $2 AND k.user_id = $1 AND k.session_id = m.session_id
+            "#,
+            &id,
+            merged_user_id,
+        )
+        .await?;
+        let attendance_moved = execute(
+            &mut tx,
+            "UPDATE attendance SET user_id = $1, updated_at = NOW() WHERE user_id = $2",
+            &id,
+            merged_user_id,
+        )
+        .await?;
+
+        let form_data_dropped = execute(
+            &mut tx,
+            r#"
+            DELETE FROM registration_form_field_data m USING registration_form_field_data k
+            WHERE
+                m.user_id = $2
+                AND k.user_id = $1
+                AND k.registration_id = m.registration_id
+                AND k.name = m.name
+            "#,
+            &id,
+            merged_user_id,
+        )
+        .await?;
+        let form_data_moved = execute(
+            &mut tx,
+            r#"
+            UPDATE registration_form_field_data SET user_id = $1, updated_at = NOW()
+            WHERE user_id = $2
+            "#,
+            &id,
+            merged_user_id,
+        )
+        .await?;
+
+        execute(
+            &mut tx,
+            "UPDATE shadow_user SET claimed_by = $1 WHERE claimed_by = $2",
+            &id,
+            merged_user_id,
+        )
+        .await?;
+
+        // Only the missing fields are taken from the merged user. The username and email stay
+        // with it since they are unique, and it is soft-deleted rather than removed. Encrypted
+        // details are copied as they are, along with the plaintext they may still be stored in.
+        execute(
+            &mut tx,
+            r#"
+            UPDATE "user" u SET
+                given_name              = COALESCE(u.given_name, m.given_name),
+                family_name             = COALESCE(u.family_name, m.family_name),
+                gender                  = COALESCE(u.gender, m.gender),
+                ic_number_encrypted     = CASE WHEN k.ic_number
+                    THEN u.ic_number_encrypted ELSE m.ic_number_encrypted END,
+                ic_number_index         = CASE WHEN k.ic_number
+                    THEN u.ic_number_index ELSE m.ic_number_index END,
+                ic_number               = CASE WHEN k.ic_number
+                    THEN u.ic_number ELSE m.ic_number END,
+                phone_number_verified   = CASE WHEN k.phone_number
+                    THEN u.phone_number_verified ELSE m.phone_number_verified END,
+                phone_number_encrypted  = CASE WHEN k.phone_number
+                    THEN u.phone_number_encrypted ELSE m.phone_number_encrypted END,
+                phone_number_index      = CASE WHEN k.phone_number
+                    THEN u.phone_number_index ELSE m.phone_number_index END,
+                phone_number            = CASE WHEN k.phone_number
+                    THEN u.phone_number ELSE m.phone_number END,
+                nickname                = COALESCE(u.nickname, m.nickname),
+                avatar_url              = COALESCE(u.avatar_url, m.avatar_url),
+                address_encrypted       = CASE WHEN k.address
+                    THEN u.address_encrypted ELSE m.address_encrypted END,
+                address                 = CASE WHEN k.address
+                    THEN u.address ELSE m.address END,
+                date_of_birth_encrypted = CASE WHEN k.date_of_birth
+                    THEN u.date_of_birth_encrypted ELSE m.date_of_birth_encrypted END,
+                date_of_birth           = CASE WHEN k.date_of_birth
+                    THEN u.date_of_birth ELSE m.date_of_birth END,
+                year_of_birth           = CASE WHEN k.date_of_birth
+                    THEN u.year_of_birth ELSE m.year_of_birth END,
+                updated_at              = NOW()
+            FROM
+                "user" m,
+                (
+                    SELECT
+                        num_nonnulls(ic_number, ic_number_encrypted) > 0 AS ic_number,
+                        num_nonnulls(phone_number, phone_number_encrypted) > 0 AS phone_number,
+                        num_nonnulls(address, address_encrypted) > 0 AS address,
+                        num_nonnulls(date_of_birth, date_of_birth_encrypted) > 0 AS date_of_birth
+                    FROM "user" WHERE id = $1
+                ) k
+            WHERE u.id = $1 AND m.id = $2
+            "#,
+            &id,
+            merged_user_id,
+        )
+        .await?;
+        sqlx::query(r#"UPDATE "user" SET deleted = TRUE, updated_at = NOW() WHERE id = $1"#)
+            .bind(merged_user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE user_duplicate SET status = 'merged', reviewed_by = $3, reviewed_at = NOW()
+            WHERE (user_id, duplicate_user_id) IN (($1, $2), ($2, $1))
+            "#,
+        )
+        .bind(&*id)
+        .bind(merged_user_id)
+        .bind(&auth.0.id)
+        .execute(&mut *tx)
+        .await?;
+
+        let merge = sqlx::query_as::<_, entities::UserMerge>(
+            r#"
+            INSERT INTO user_merge (
+                id,
+                user_id,
+                merged_user_id,
+                merged_by,
+                connect_groups_moved,
+                connect_groups_dropped,
+                ministries_moved,
+                ministries_dropped,
+                relationships_moved,
+                relationships_dropped,
+                attendance_moved,
+                attendance_dropped,
+                form_data_moved,
+                form_data_dropped
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            RETURNING *
+            "#,
+        )
+        .bind(format!("user_merge_{}", ulid::Ulid::new()))
+        .bind(&*id)
+        .bind(merged_user_id)
+        .bind(&auth.0.id)
+        .bind(connect_groups_moved)
+        .bind(connect_groups_dropped)
+        .bind(ministries_moved)
+        .bind(ministries_dropped)
+        .bind(relationships_moved)
+        .bind(relationships_dropped)
+        .bind(attendance_moved)
+        .bind(attendance_dropped)
+        .bind(form_data_moved)
+        .bind(form_data_dropped)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        outbox::publish(&mut tx, "user.merged", &merge).await?;
+
+        // A dry run goes through the whole merge so that the counts are exact, and then throws
+        // it away, along with the event.
+        if body.dry_run.unwrap_or(false) {
+            tx.rollback().await?;
+        } else {
+            tx.commit().await?;
+        }
+
+        Ok(Response::Ok(payload::Json(merge)))
+    }
+}
@@ -1,13 +1,142 @@
+pub mod cancel_erasure;
 pub mod create;
+pub mod create_claim;
 pub mod create_relationship;
 pub mod delete;
 pub mod delete_relationship;
+pub mod erase;
+pub mod export;
 pub mod get;
+pub mod get_connect_group_history;
 pub mod get_connect_groups;
+pub mod get_family;
+pub mod get_flock;
+pub mod get_households;
+pub mod get_leaders;
 pub mod get_ministries;
+pub mod get_ministry_history;
 pub mod get_ministry_roles;
 pub mod get_pastoral_roles;
+pub mod get_pick_ups;
 pub mod list;
+pub mod list_claim_candidates;
+pub mod list_merges;
 pub mod list_relationships;
+pub mod merge;
+pub mod purge;
+pub mod remove_overseer;
+pub mod restore;
+pub mod set_overseer;
 pub mod update;
 pub mod update_relationship;
+pub mod verify_claim;
+
+use sqlx::{PgConnection, PgExecutor};
+
+use common::{auth::BearerAuth, authorization, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities, scope::Scope};
+
+/// Ensure the users identified by `user_ids` exist and are not deleted.
+pub(super) async fn require_active<'c>(
+    executor: impl PgExecutor<'c>,
+    user_ids: &[&str],
+) -> Result<(), Error> {
+    let found = sqlx::query_scalar::<_, String>(
+        r#"SELECT id FROM "user" WHERE id = ANY($1) AND deleted IS FALSE"#,
+    )
+    .bind(user_ids)
+    .fetch_all(executor)
+    .await?;
+
+    match user_ids
+        .iter()
+        .find(|id| !found.iter().any(|found| found == *id))
+    {
+        Some(id) => Err(Error::not_found(
+            "USER_NOT_FOUND",
+            format!("User with id '{}' not found", id),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Ensure the authenticated user either is the user identified by `user_id` or holds the
+/// `users:write` permission over them. Leaders granted it through their pastoral role may only
+/// act on the members they oversee, any other user is reported as not found.
+pub(super) async fn require_write_access(
+    auth: &BearerAuth,
+    db: &Database,
+    user_id: &str,
+) -> Result<(), Error> {
+    auth.require_self_or(db, user_id, Permission::UsersWrite)
+        .await?;
+    if auth.0.id == user_id {
+        return Ok(());
+    }
+
+    let accessible = Scope::can_access(auth, db, Permission::UsersWrite, user_id).await?;
+    if !accessible {
+        return Err(Error::not_found(
+            "USER_NOT_FOUND",
+            format!("User with id '{}' not found", user_id),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Ensure the authenticated user either is the user identified by `user_id`, who is not
+/// registered yet, or holds the `users:write` permission in their access token. Pastoral roles
+/// only grant it over the members a leader oversees, which new users are not.
+pub(super) fn require_new_user_access(auth: &BearerAuth, user_id: &str) -> Result<(), Error> {
+    if auth.0.id == user_id || auth.is_granted_by_token(Permission::UsersWrite) {
+        return Ok(());
+    }
+
+    Err(authorization::Error::Forbidden(Permission::UsersWrite).into())
+}
+
+/// Ensure the authenticated user may decide who picks up the user identified by `user_id`,
+/// which users cannot grant themselves.
+async fn require_pick_up_grant(
+    auth: &BearerAuth,
+    db: &Database,
+    user_id: &str,
+) -> Result<(), Error> {
+    auth.require(db, Permission::UsersWrite).await?;
+
+    let accessible = Scope::can_access(auth, db, Permission::UsersWrite, user_id).await?;
+    if !accessible {
+        return Err(Error::not_found(
+            "USER_NOT_FOUND",
+            format!("User with id '{}' not found", user_id),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Store the inverse of `relationship`, so that the destination user holds the relationship back
+/// to the source user. Whether the destination user may pick up the source user is left as is.
+async fn put_inverse(
+    conn: &mut PgConnection,
+    relationship: &entities::UserRelationship,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO user_relationship (source_user_id, destination_user_id, relationship)
+        SELECT $1, $2, inverse FROM relationship_type WHERE name = $3
+        ON CONFLICT (source_user_id, destination_user_id) DO UPDATE SET
+            relationship = EXCLUDED.relationship,
+            updated_at   = NOW()
+        WHERE user_relationship.relationship IS DISTINCT FROM EXCLUDED.relationship
+        "#,
+    )
+    .bind(&relationship.destination_user_id)
+    .bind(&relationship.source_user_id)
+    .bind(&relationship.relationship)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
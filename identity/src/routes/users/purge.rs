@@ -0,0 +1,104 @@
+use poem::web;
+use poem_openapi::{param::Path, payload};
+use serde_json::json;
+
+use common::{audit, auth::BearerAuth, crypto::Keyring, database::Database, error::Error, outbox};
+
+use crate::{auth0_sync, authorization::Permission, entities, pii, privacy};
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<entities::User>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _purge_user(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        keyring: web::Data<&Keyring>,
+        id: Path<String>,
+    ) -> Result<Response, Error> {
+        auth.require(&db, Permission::UsersAdmin).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        let deleted =
+            sqlx::query_scalar::<_, bool>(r#"SELECT deleted FROM "user" WHERE id = $1 FOR UPDATE"#)
+                .bind(&*id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| match e {
+                    sqlx::Error::RowNotFound => Error::not_found(
+                        "USER_NOT_FOUND",
+                        format!("User with id '{}' not found", &*id),
+                    ),
+                    _ => Error::from(e),
+                })?;
+        if !deleted {
+            return Err(Error::conflict(
+                "USER_NOT_DELETED",
+                format!("User '{}' must be deleted before it is purged", &*id),
+            ));
+        }
+
+        // The merges into the user are the audit trail of the users merged into it and cannot
+        // outlive it, such a user can only be erased.
+        let merged_into = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM user_merge WHERE user_id = $1)",
+        )
+        .bind(&*id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if merged_into {
+            return Err(Error::conflict(
+                "USER_HAS_MERGES",
+                format!(
+                    "User '{}' has users merged into it and can only be erased",
+                    &*id
+                ),
+            ));
+        }
+
+        // The rows referencing the user without cascading, along with the attendance and
+        // registration form data of the event service which only hold its id. The membership
+        // history, oversight, households, duplicates and claims of the user cascade.
+        for sql in [
+            "DELETE FROM user_connect_group WHERE user_id = $1",
+            "DELETE FROM user_ministry WHERE user_id = $1",
+            "DELETE FROM user_relationship WHERE source_user_id = $1 OR destination_user_id = $1",
+            "DELETE FROM user_merge WHERE merged_user_id = $1",
+            "DELETE FROM user_erasure WHERE user_id = $1",
+            "DELETE FROM user_claim WHERE user_id = $1",
+            // The shadow users claimed may be claimed again.
+            "UPDATE shadow_user SET claimed_by = NULL, claimed_at = NULL WHERE claimed_by = $1",
+            "DELETE FROM attendance WHERE user_id = $1",
+            "DELETE FROM registration_form_field_data WHERE user_id = $1",
+        ] {
+            sqlx::query(sql).bind(&*id).execute(&mut *tx).await?;
+        }
+
+        let user =
+            sqlx::query_as::<_, pii::UserRow>(r#"DELETE FROM "user" WHERE id = $1 RETURNING *"#)
+                .bind(&*id)
+                .fetch_one(&mut *tx)
+                .await?
+                .open(&keyring)?;
+
+        // Redacted last, so that the rows recorded by the purge itself are redacted as well.
+        privacy::redact_audit_log(&mut tx, &id, &[]).await?;
+
+        outbox::publish(&mut tx, "user.purged", json!({ "id": &*id })).await?;
+
+        tx.commit().await?;
+
+        // Synced once committed, Auth0 failing must not fail the purge. The reconciliation job
+        // reports the accounts left behind.
+        if let Err(e) = auth0_sync::remove(&self.management, &user.id).await {
+            tracing::error!("Failed to sync user '{}' to Auth0: {}", user.id, e);
+        }
+
+        Ok(Response::Ok(payload::Json(pii::mask_for(&auth, user))))
+    }
+}
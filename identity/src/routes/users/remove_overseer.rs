@@ -0,0 +1,45 @@
+use poem::web;
+use poem_openapi::{param::Path, payload};
+
+use common::{audit, auth::BearerAuth, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities};
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<entities::UserOversight>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _remove_user_overseer(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: Path<String>,
+    ) -> Result<Response, Error> {
+        auth.require(&db, Permission::UsersAdmin).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        let oversight = sqlx::query_as::<_, entities::UserOversight>(
+            r#"
+            DELETE FROM user_oversight WHERE user_id = $1 RETURNING *
+            "#,
+        )
+        .bind(&*id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::not_found(
+                "USER_OVERSIGHT_NOT_FOUND",
+                format!("User with id '{}' has no overseer", &*id),
+            ),
+            _ => Error::from(e),
+        })?;
+
+        tx.commit().await?;
+
+        Ok(Response::Ok(payload::Json(oversight)))
+    }
+}
@@ -0,0 +1,84 @@
+use poem::web;
+use poem_openapi::{param::Path, payload};
+
+use common::{audit, auth::BearerAuth, crypto::Keyring, database::Database, error::Error, outbox};
+
+use crate::{auth0_sync, authorization::Permission, entities, pii};
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<entities::User>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _restore_user(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        keyring: web::Data<&Keyring>,
+        id: Path<String>,
+    ) -> Result<Response, Error> {
+        auth.require(&db, Permission::UsersAdmin).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        let (erased, merged_into) = sqlx::query_as::<_, (bool, Option<String>)>(
+            r#"
+            SELECT
+                EXISTS (SELECT 1 FROM user_erasure WHERE user_id = u.id AND erased_at IS NOT NULL),
+                (SELECT user_id FROM user_merge WHERE merged_user_id = u.id LIMIT 1)
+            FROM "user" u
+            WHERE u.id = $1 AND u.deleted IS TRUE
+            FOR UPDATE
+            "#,
+        )
+        .bind(&*id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::not_found(
+                "USER_NOT_FOUND",
+                format!("Deleted user with id '{}' not found", &*id),
+            ),
+            _ => Error::from(e),
+        })?;
+        if erased {
+            return Err(Error::conflict(
+                "USER_ERASED",
+                format!("User '{}' has been erased and cannot be restored", &*id),
+            ));
+        }
+        if let Some(user_id) = merged_into {
+            return Err(Error::conflict(
+                "USER_MERGED",
+                format!(
+                    "User '{}' has been merged into user '{}' and cannot be restored",
+                    &*id, user_id
+                ),
+            ));
+        }
+
+        let user = sqlx::query_as::<_, pii::UserRow>(
+            r#"
+            UPDATE "user" SET deleted = FALSE, updated_at = NOW() WHERE id = $1 RETURNING *
+            "#,
+        )
+        .bind(&*id)
+        .fetch_one(&mut *tx)
+        .await?
+        .open(&keyring)?;
+
+        outbox::publish(&mut tx, "user.restored", &pii::mask(user.clone())).await?;
+
+        tx.commit().await?;
+
+        // Synced once committed, Auth0 failing must not fail the change. The reconciliation job
+        // fixes the profiles left out of sync.
+        if let Err(e) = auth0_sync::block(&self.management, &user.id, false).await {
+            tracing::error!("Failed to sync user '{}' to Auth0: {}", user.id, e);
+        }
+
+        Ok(Response::Ok(payload::Json(pii::mask_for(&auth, user))))
+    }
+}
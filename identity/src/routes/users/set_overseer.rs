@@ -0,0 +1,85 @@
+use poem::web;
+use poem_openapi::{param::Path, payload, Object};
+use serde::{Deserialize, Serialize};
+
+use common::{audit, auth::BearerAuth, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities};
+
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "SetUserOverseerRequest")]
+pub struct Request {
+    /// The more senior leader overseeing the user, e.g. the coach of a CGL.
+    overseer_id: String,
+}
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<entities::UserOversight>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _set_user_overseer(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: Path<String>,
+        body: payload::Json<Request>,
+    ) -> Result<Response, Error> {
+        auth.require(&db, Permission::UsersAdmin).await?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        // Serializes changes to the oversight, so that concurrent changes cannot form a cycle
+        // that neither of them sees.
+        sqlx::query("LOCK TABLE user_oversight IN SHARE ROW EXCLUSIVE MODE")
+            .execute(&mut *tx)
+            .await?;
+
+        super::require_active(&mut *tx, &[id.as_str(), body.overseer_id.as_str()]).await?;
+
+        let cycle = sqlx::query_scalar::<_, bool>(
+            r#"
+            WITH RECURSIVE overseer AS (
+                SELECT overseer_id FROM user_oversight WHERE user_id = $1
+                UNION
+                SELECT o.overseer_id
+                FROM user_oversight o INNER JOIN overseer ON o.user_id = overseer.overseer_id
+            )
+            SELECT EXISTS (SELECT 1 FROM overseer WHERE overseer_id = $2)
+            "#,
+        )
+        .bind(&body.overseer_id)
+        .bind(&*id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if cycle {
+            return Err(Error::bad_request(
+                "USER_OVERSIGHT_CYCLE",
+                format!(
+                    "User '{}' already oversees user '{}', directly or through others",
+                    &*id, &body.overseer_id
+                ),
+            ));
+        }
+
+        let oversight = sqlx::query_as::<_, entities::UserOversight>(
+            r#"
+            INSERT INTO user_oversight (user_id, overseer_id) VALUES ($1, $2)
+            ON CONFLICT (user_id) DO UPDATE SET
+                overseer_id = EXCLUDED.overseer_id,
+                updated_at  = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(&*id)
+        .bind(&body.overseer_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Response::Ok(payload::Json(oversight)))
+    }
+}
@@ -2,7 +2,12 @@ use poem::web;
 use poem_openapi::{param::Path, payload, Object};
 use serde::{Deserialize, Serialize};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{
+    audit, auth::BearerAuth, authorization, crypto::Keyring, database::Database, error::Error,
+    outbox,
+};
+
+use crate::{auth0_sync, authorization::Permission, entities, pii};
 
 #[derive(Debug, Clone, Deserialize, Serialize, Object)]
 #[oai(rename = "UpdateUserRequest")]
@@ -31,74 +36,116 @@ pub enum Response {
     Ok(payload::Json<entities::User>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
-}
-
 impl crate::routes::Routes {
     pub async fn _update_user(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
+        keyring: web::Data<&Keyring>,
         id: Path<String>,
         body: payload::Json<Request>,
     ) -> Result<Response, Error> {
-        let user = sqlx::query_as_unchecked!(
-            entities::User,
+        super::require_write_access(&auth, &db, &id).await?;
+
+        // Only admins may vouch for an email or phone number, everyone else has to verify it.
+        let vouches = match auth.require(&db, Permission::UsersAdmin).await {
+            Ok(()) => true,
+            Err(authorization::Error::Forbidden(_)) => false,
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        // A new email has to be verified again, whatever `email_verified` says.
+        let previous_email = sqlx::query_scalar::<_, String>(
+            r#"SELECT email FROM "user" WHERE id = $1 AND deleted IS FALSE FOR UPDATE"#,
+        )
+        .bind(&*id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let email_changed = body.email.is_some() && body.email != previous_email;
+
+        let sealed = pii::Details {
+            ic_number: body.ic_number.clone(),
+            phone_number: body.phone_number.clone(),
+            address: body.address.clone(),
+            date_of_birth: body.date_of_birth,
+        }
+        .seal(&keyring)?;
+
+        // Details given replace both their encrypted value and the plaintext one it may have had.
+        let user = sqlx::query_as::<_, pii::UserRow>(
             r#"
-            UPDATE "user" SET  
-                name                  = COALESCE($1, name),
-                email                 = COALESCE($2, email), 
-                email_verified        = COALESCE($3, email_verified),
-                username              = COALESCE($4, username),
-                given_name            = COALESCE($5, given_name),
-                family_name           = COALESCE($6, family_name),
-                gender                = COALESCE($7, gender), 
-                ic_number             = COALESCE($8, ic_number), 
-                phone_number          = COALESCE($9, phone_number), 
-                phone_number_verified = COALESCE($10, phone_number_verified), 
-                nickname              = COALESCE($11, nickname),
-                avatar_url            = COALESCE($12, avatar_url),
-                address               = COALESCE($13, address),
-                date_of_birth         = COALESCE($14, date_of_birth),
-                updated_at            = NOW()
-            WHERE id = $15 AND deleted IS FALSE
+            UPDATE "user" SET
+                name                    = COALESCE($1, name),
+                email                   = COALESCE($2, email),
+                email_verified          = COALESCE($3, email_verified),
+                username                = COALESCE($4, username),
+                given_name              = COALESCE($5, given_name),
+                family_name             = COALESCE($6, family_name),
+                gender                  = COALESCE($7, gender),
+                ic_number_encrypted     = COALESCE($8, ic_number_encrypted),
+                ic_number_index         = CASE WHEN $8::BYTEA IS NULL THEN ic_number_index ELSE $9 END,
+                ic_number               = CASE WHEN $8::BYTEA IS NULL THEN ic_number END,
+                phone_number_encrypted  = COALESCE($10, phone_number_encrypted),
+                phone_number_index      = CASE WHEN $10::BYTEA IS NULL THEN phone_number_index ELSE $11 END,
+                phone_number            = CASE WHEN $10::BYTEA IS NULL THEN phone_number END,
+                phone_number_verified   = COALESCE($12, phone_number_verified),
+                nickname                = COALESCE($13, nickname),
+                avatar_url              = COALESCE($14, avatar_url),
+                address_encrypted       = COALESCE($15, address_encrypted),
+                address                 = CASE WHEN $15::BYTEA IS NULL THEN address END,
+                date_of_birth_encrypted = COALESCE($16, date_of_birth_encrypted),
+                date_of_birth           = CASE WHEN $16::BYTEA IS NULL THEN date_of_birth END,
+                year_of_birth           = COALESCE($17, year_of_birth),
+                updated_at              = NOW()
+            WHERE id = $18 AND deleted IS FALSE
             RETURNING *
             "#,
-            &body.name,
-            &body.email,
-            &body.email_verified,
-            &body.username,
-            &body.given_name,
-            &body.family_name,
-            &body.gender,
-            &body.ic_number,
-            &body.phone_number,
-            &body.phone_number_verified,
-            &body.nickname,
-            &body.avatar_url,
-            &body.address,
-            &body.date_of_birth,
-            &*id,
         )
-        .fetch_one(&db.db)
+        .bind(&body.name)
+        .bind(&body.email)
+        .bind(if email_changed {
+            Some(false)
+        } else {
+            body.email_verified.filter(|_| vouches)
+        })
+        .bind(&body.username)
+        .bind(&body.given_name)
+        .bind(&body.family_name)
+        .bind(body.gender)
+        .bind(&sealed.ic_number)
+        .bind(&sealed.ic_number_index)
+        .bind(&sealed.phone_number)
+        .bind(&sealed.phone_number_index)
+        .bind(body.phone_number_verified.filter(|_| vouches))
+        .bind(&body.nickname)
+        .bind(&body.avatar_url)
+        .bind(&sealed.address)
+        .bind(&sealed.date_of_birth)
+        .bind(sealed.year_of_birth)
+        .bind(&*id)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| match e {
-            sqlx::error::Error::RowNotFound => Error::NotFound(payload::Json(ErrorResponse {
-                message: format!("User with id '{}' not found", &*id),
-            })),
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
-        })?;
+            sqlx::Error::RowNotFound => Error::not_found(
+                "USER_NOT_FOUND",
+                format!("User with id '{}' not found", &*id),
+            ),
+            _ => Error::from(e),
+        })?
+        .open(&keyring)?;
+
+        outbox::publish(&mut tx, "user.updated", &pii::mask(user.clone())).await?;
+
+        tx.commit().await?;
+
+        // Synced once committed, Auth0 failing must not fail the change. The reconciliation job
+        // fixes the profiles left out of sync.
+        if let Err(e) = auth0_sync::push(&self.management, &user, email_changed).await {
+            tracing::error!("Failed to sync user '{}' to Auth0: {}", user.id, e);
+        }
 
-        Ok(Response::Ok(payload::Json(user)))
+        Ok(Response::Ok(payload::Json(pii::mask_for(&auth, user))))
     }
 }
@@ -2,13 +2,19 @@ use poem::web;
 use poem_openapi::{param::Path, payload, Object};
 use serde::{Deserialize, Serialize};
 
-use crate::{database::Database, entities, error::ErrorResponse};
+use common::{audit, auth::BearerAuth, database::Database, error::Error};
+
+use crate::entities;
 
 #[derive(Debug, Clone, Deserialize, Serialize, Object)]
 #[oai(rename = "UpdateUserRelationshipRequest")]
 pub struct Request {
     destination_user_id: String,
+    /// One of the relationship types, the destination user's relationship back follows.
     relationship: Option<String>,
+    /// Whether the user may pick up the destination user, only changed by users holding
+    /// the `users:write` permission over the destination user.
+    can_pick_up: Option<bool>,
 }
 
 #[derive(poem_openapi::ApiResponse)]
@@ -17,52 +23,54 @@ pub enum Response {
     Ok(payload::Json<entities::UserRelationship>),
 }
 
-#[derive(poem_openapi::ApiResponse)]
-pub enum Error {
-    #[oai(status = 400)]
-    BadRequest(payload::Json<ErrorResponse>),
-
-    #[oai(status = 404)]
-    NotFound(payload::Json<ErrorResponse>),
-
-    #[oai(status = 500)]
-    InternalServer(payload::Json<ErrorResponse>),
-}
-
 impl crate::routes::Routes {
     pub async fn _update_user_relationship(
         &self,
+        auth: BearerAuth,
         db: web::Data<&Database>,
         id: Path<String>,
         body: payload::Json<Request>,
     ) -> Result<Response, Error> {
-        let user = sqlx::query_as_unchecked!(
-            entities::UserRelationship,
+        super::require_write_access(&auth, &db, &id).await?;
+        if body.can_pick_up.is_some() {
+            super::require_pick_up_grant(&auth, &db, &body.destination_user_id).await?;
+        }
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        super::require_active(&mut *tx, &[id.as_str(), body.destination_user_id.as_str()]).await?;
+
+        let user = sqlx::query_as::<_, entities::UserRelationship>(
             r#"
-            UPDATE user_relationship SET  
+            UPDATE user_relationship SET
                 relationship = COALESCE($1, relationship),
+                can_pick_up  = COALESCE($2, can_pick_up),
                 updated_at   = NOW()
-            WHERE source_user_id = $2 AND destination_user_id = $3
+            WHERE source_user_id = $3 AND destination_user_id = $4
             RETURNING *
             "#,
-            &body.relationship,
-            &*id,
-            &body.destination_user_id
         )
-        .fetch_one(&db.db)
+        .bind(&body.relationship)
+        .bind(body.can_pick_up)
+        .bind(&*id)
+        .bind(&body.destination_user_id)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| match e {
-            sqlx::error::Error::RowNotFound => Error::NotFound(payload::Json(ErrorResponse {
-                message: format!(
+            sqlx::Error::RowNotFound => Error::not_found(
+                "USER_RELATIONSHIP_NOT_FOUND",
+                format!(
                     "User relationship between source({}) and destination({}) not found",
                     &*id, &body.destination_user_id
                 ),
-            })),
-            _ => Error::InternalServer(payload::Json(ErrorResponse::from(
-                &e as &(dyn std::error::Error + Send + Sync),
-            ))),
+            ),
+            _ => Error::from(e),
         })?;
 
+        super::put_inverse(&mut tx, &user).await?;
+
+        tx.commit().await?;
+
         Ok(Response::Ok(payload::Json(user)))
     }
 }
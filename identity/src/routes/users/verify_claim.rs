@@ -0,0 +1,278 @@
+use poem::web;
+use poem_openapi::{param::Path, payload, Object};
+use serde::{Deserialize, Serialize};
+
+use common::{audit, auth::BearerAuth, crypto::Keyring, database::Database, error::Error};
+
+use crate::{
+    claim,
+    entities::{self, ClaimChannel},
+    pii, reconcile,
+};
+
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "VerifyUserClaimRequest")]
+pub struct Request {
+    /// The one-time code that was sent.
+    code: String,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct PendingClaim {
+    email: String,
+    shadow_user_no: Option<i32>,
+    claimed_user_id: Option<String>,
+    channel: ClaimChannel,
+    code_hash: String,
+    attempts: i32,
+    expired: bool,
+    verified: bool,
+}
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<entities::User>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _verify_user_claim(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        keyring: web::Data<&Keyring>,
+        id: Path<String>,
+        claim_id: Path<String>,
+        body: payload::Json<Request>,
+    ) -> Result<Response, Error> {
+        super::require_new_user_access(&auth, &id)?;
+
+        let mut tx = audit::begin(&db, &auth).await?;
+
+        let pending = sqlx::query_as::<_, PendingClaim>(
+            r#"
+            SELECT
+                email,
+                shadow_user_no,
+                claimed_user_id,
+                channel,
+                code_hash,
+                attempts,
+                expires_at < NOW() AS expired,
+                verified_at IS NOT NULL AS verified
+            FROM user_claim
+            WHERE id = $1 AND user_id = $2
+            FOR UPDATE
+            "#,
+        )
+        .bind(&*claim_id)
+        .bind(&*id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::not_found(
+                "USER_CLAIM_NOT_FOUND",
+                format!("Claim with id '{}' not found", &*claim_id),
+            ),
+            _ => Error::from(e),
+        })?;
+
+        if pending.verified {
+            return Err(Error::conflict(
+                "USER_CLAIM_ALREADY_VERIFIED",
+                format!("Claim with id '{}' was already verified", &*claim_id),
+            ));
+        }
+        if pending.expired || pending.attempts >= claim::MAX_ATTEMPTS {
+            return Err(Error::bad_request(
+                "USER_CLAIM_EXPIRED",
+                "The code expired or too many wrong codes were submitted, start a new claim",
+            ));
+        }
+        if claim::hash_code(&claim_id, &body.code) != pending.code_hash {
+            // The attempt must be counted even though the request fails.
+            sqlx::query("UPDATE user_claim SET attempts = attempts + 1 WHERE id = $1")
+                .bind(&*claim_id)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+
+            return Err(Error::bad_request(
+                "USER_CLAIM_CODE_INVALID",
+                "The code is invalid",
+            ));
+        }
+
+        let verified_email = pending.channel == ClaimChannel::Email;
+        let verified_phone_number = pending.channel == ClaimChannel::Phone;
+
+        let user = match (pending.shadow_user_no, &pending.claimed_user_id) {
+            (Some(shadow_user_no), _) => {
+                let shadow_user = sqlx::query_as::<_, entities::ShadowUser>(
+                    r#"
+                    SELECT * FROM shadow_user
+                    WHERE no = $1 AND claimed_by IS NULL AND deleted IS NOT TRUE
+                    FOR UPDATE
+                    "#,
+                )
+                .bind(shadow_user_no)
+                .fetch_optional(&mut *tx)
+                .await?
+                .ok_or_else(|| {
+                    Error::conflict(
+                        "USER_CLAIM_TARGET_UNAVAILABLE",
+                        "The profile was claimed or removed in the meantime",
+                    )
+                })?;
+
+                let sealed = pii::Details::of_shadow_user(&shadow_user).seal(&keyring)?;
+
+                let user = sqlx::query_as::<_, pii::UserRow>(
+                    r#"
+                    INSERT INTO "user" (
+                        id,
+                        name,
+                        email,
+                        email_verified,
+                        username,
+                        given_name,
+                        family_name,
+                        gender,
+                        ic_number_encrypted,
+                        ic_number_index,
+                        phone_number_encrypted,
+                        phone_number_index,
+                        phone_number_verified,
+                        nickname,
+                        avatar_url,
+                        address_encrypted,
+                        date_of_birth_encrypted,
+                        year_of_birth
+                    )
+                    SELECT
+                        $1,
+                        s.name,
+                        $2,
+                        COALESCE($3 AND lower(s.email) = lower($2), FALSE),
+                        s.username,
+                        s.given_name,
+                        s.family_name,
+                        s.gender,
+                        $6,
+                        $7,
+                        $8,
+                        $9,
+                        $4,
+                        s.nickname,
+                        s.avatar_url,
+                        $10,
+                        $11,
+                        $12
+                    FROM shadow_user s
+                    WHERE s.no = $5
+                    RETURNING *
+                    "#,
+                )
+                .bind(&*id)
+                .bind(&pending.email)
+                .bind(verified_email)
+                .bind(verified_phone_number)
+                .bind(shadow_user_no)
+                .bind(&sealed.ic_number)
+                .bind(&sealed.ic_number_index)
+                .bind(&sealed.phone_number)
+                .bind(&sealed.phone_number_index)
+                .bind(&sealed.address)
+                .bind(&sealed.date_of_birth)
+                .bind(sealed.year_of_birth)
+                .fetch_one(&mut *tx)
+                .await?
+                .open(&keyring)?;
+
+                // Unlike an admin confirming a shadow user, members cannot pick what their
+                // spreadsheet row should have resolved to, the unresolved values are left out.
+                let resolution = reconcile::resolve(&mut tx, &shadow_user).await?;
+                reconcile::claim(
+                    &mut tx,
+                    shadow_user_no,
+                    &user.id,
+                    &reconcile::Memberships::resolved(&resolution),
+                )
+                .await?;
+
+                user
+            }
+            (None, Some(claimed_user_id)) => {
+                // Changing the id carries the memberships and relationships over through their
+                // `ON UPDATE CASCADE` foreign keys.
+                let user = sqlx::query_as::<_, pii::UserRow>(
+                    r#"
+                    UPDATE "user" SET
+                        id                    = $1,
+                        email                 = $2,
+                        email_verified        = $3 AND lower(email) = lower($2),
+                        phone_number_verified = $4 OR COALESCE(phone_number_verified, FALSE),
+                        deleted               = FALSE,
+                        updated_at            = NOW()
+                    WHERE id = $5 AND deleted IS TRUE
+                    RETURNING *
+                    "#,
+                )
+                .bind(&*id)
+                .bind(&pending.email)
+                .bind(verified_email)
+                .bind(verified_phone_number)
+                .bind(claimed_user_id)
+                .fetch_optional(&mut *tx)
+                .await?
+                .ok_or_else(|| {
+                    Error::conflict(
+                        "USER_CLAIM_TARGET_UNAVAILABLE",
+                        "The profile was claimed or removed in the meantime",
+                    )
+                })?
+                .open(&keyring)?;
+
+                // The event tables reference users without foreign keys.
+                sqlx::query(
+                    r#"
+                    UPDATE attendance a SET user_id = $1
+                    WHERE a.user_id = $2 AND NOT EXISTS (
+                        SELECT 1 FROM attendance
+                        WHERE session_id = a.session_id AND user_id = $1
+                    )
+                    "#,
+                )
+                .bind(&*id)
+                .bind(claimed_user_id)
+                .execute(&mut *tx)
+                .await?;
+                sqlx::query(
+                    r#"
+                    UPDATE registration_form_field_data d SET user_id = $1
+                    WHERE d.user_id = $2 AND NOT EXISTS (
+                        SELECT 1 FROM registration_form_field_data
+                        WHERE registration_id = d.registration_id AND name = d.name AND user_id = $1
+                    )
+                    "#,
+                )
+                .bind(&*id)
+                .bind(claimed_user_id)
+                .execute(&mut *tx)
+                .await?;
+
+                user
+            }
+            (None, None) => unreachable!("user_claim_target_check ensures a claim has a target"),
+        };
+
+        sqlx::query("UPDATE user_claim SET verified_at = NOW() WHERE id = $1")
+            .bind(&*claim_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(Response::Ok(payload::Json(pii::mask_for(&auth, user))))
+    }
+}
@@ -0,0 +1,81 @@
+use poem::web;
+use poem_openapi::{payload, Object};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use common::{auth::BearerAuth, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities};
+
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "CreateWebhookRequest")]
+pub struct Request {
+    url: String,
+    /// Types of the events to deliver, every type when empty.
+    #[oai(default)]
+    event_types: Vec<String>,
+    description: Option<String>,
+}
+
+/// A newly created webhook along with the secret its deliveries are signed with, which is not
+/// returned again.
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+pub struct CreatedWebhook {
+    #[oai(flatten)]
+    #[serde(flatten)]
+    webhook: entities::Webhook,
+    secret: String,
+}
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<CreatedWebhook>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _create_webhook(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        body: payload::Json<Request>,
+    ) -> Result<Response, Error> {
+        auth.require(&db, Permission::WebhooksWrite).await?;
+
+        super::validate_url(&body.url)?;
+
+        let secret = generate_secret();
+        let webhook = sqlx::query_as::<_, entities::Webhook>(
+            r#"
+            INSERT INTO webhook (id, url, secret, event_types, description)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(format!("webhook_{}", ulid::Ulid::new()))
+        .bind(&body.url)
+        .bind(&secret)
+        .bind(&body.event_types)
+        .bind(&body.description)
+        .fetch_one(&db.db)
+        .await?;
+
+        Ok(Response::Ok(payload::Json(CreatedWebhook {
+            webhook,
+            secret,
+        })))
+    }
+}
+
+/// Generate a random 256-bit secret, hex encoded.
+fn generate_secret() -> String {
+    let mut secret = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut secret);
+    format!(
+        "whsec_{}",
+        secret
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>()
+    )
+}
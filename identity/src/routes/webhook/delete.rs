@@ -0,0 +1,40 @@
+use poem::web;
+use poem_openapi::{param::Path, payload};
+
+use common::{auth::BearerAuth, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities};
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<entities::Webhook>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _delete_webhook(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: Path<String>,
+    ) -> Result<Response, Error> {
+        auth.require(&db, Permission::WebhooksWrite).await?;
+
+        // Its deliveries are deleted along with it.
+        let webhook = sqlx::query_as::<_, entities::Webhook>(
+            r#"DELETE FROM webhook WHERE id = $1 RETURNING *"#,
+        )
+        .bind(&*id)
+        .fetch_one(&db.db)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::not_found(
+                "WEBHOOK_NOT_FOUND",
+                format!("Webhook with id '{}' not found", &*id),
+            ),
+            _ => Error::from(e),
+        })?;
+
+        Ok(Response::Ok(payload::Json(webhook)))
+    }
+}
@@ -0,0 +1,46 @@
+use poem::web;
+use poem_openapi::{param::Path, payload};
+
+use common::{
+    auth::BearerAuth,
+    database::Database,
+    error::Error,
+    projection::{Fields, Projected},
+};
+
+use crate::{authorization::Permission, entities};
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Projected<entities::Webhook>>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _get_webhook(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: Path<String>,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::Webhook>(fields)?;
+
+        auth.require(&db, Permission::WebhooksWrite).await?;
+
+        let webhook =
+            sqlx::query_as::<_, entities::Webhook>(r#"SELECT * FROM webhook WHERE id = $1"#)
+                .bind(&*id)
+                .fetch_one(&db.db)
+                .await
+                .map_err(|e| match e {
+                    sqlx::Error::RowNotFound => Error::not_found(
+                        "WEBHOOK_NOT_FOUND",
+                        format!("Webhook with id '{}' not found", &*id),
+                    ),
+                    _ => Error::from(e),
+                })?;
+
+        Ok(Response::Ok(payload::Json(fields.project(webhook))))
+    }
+}
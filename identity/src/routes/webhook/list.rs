@@ -0,0 +1,54 @@
+use poem::web;
+use poem_openapi::payload;
+
+use common::{
+    auth::BearerAuth,
+    database::Database,
+    error::Error,
+    pagination::{self, Listing, Page, Sortable},
+    projection::{Fields, Projected},
+};
+
+use crate::{authorization::Permission, entities};
+
+const LISTING: Listing = Listing {
+    select: "w.*",
+    from: "webhook w",
+    key: "w.id",
+    sortable: &[
+        Sortable {
+            name: "created_at",
+            expr: "w.created_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "url",
+            expr: "w.url",
+            ty: "TEXT",
+        },
+    ],
+};
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Page<Projected<entities::Webhook>>>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _list_webhooks(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        params: pagination::Params,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::Webhook>(fields)?;
+
+        auth.require(&db, Permission::WebhooksWrite).await?;
+
+        let webhooks = LISTING.fetch(&db.db, &params, |_| {}).await?;
+
+        Ok(Response::Ok(payload::Json(fields.project_page(webhooks))))
+    }
+}
@@ -0,0 +1,79 @@
+use poem::web;
+use poem_openapi::payload;
+
+use common::{
+    auth::BearerAuth,
+    database::Database,
+    error::Error,
+    pagination::{self, Listing, Page, Sortable},
+    projection::{Fields, Projected},
+};
+
+use crate::{authorization::Permission, entities};
+
+pub(super) const SELECT: &str = "d.*, e.type AS event_type, e.payload";
+
+const LISTING: Listing = Listing {
+    select: SELECT,
+    from: "webhook_delivery d JOIN outbox_event e ON e.id = d.event_id",
+    // Padded so that the ids, which are compared as text, keep their order.
+    key: "lpad(d.id::TEXT, 19, '0')",
+    sortable: &[
+        Sortable {
+            name: "created_at",
+            expr: "d.created_at",
+            ty: "TIMESTAMPTZ",
+        },
+        Sortable {
+            name: "next_attempt_at",
+            expr: "d.next_attempt_at",
+            ty: "TIMESTAMPTZ",
+        },
+    ],
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub webhook_id: Option<String>,
+    pub status: Option<entities::WebhookDeliveryStatus>,
+    pub event_type: Option<String>,
+}
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<Page<Projected<entities::WebhookDelivery>>>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _list_webhook_deliveries(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        params: pagination::Params,
+        filter: Filter,
+        fields: Option<String>,
+    ) -> Result<Response, Error> {
+        let fields = Fields::parse::<entities::WebhookDelivery>(fields)?;
+
+        auth.require(&db, Permission::WebhooksWrite).await?;
+
+        let deliveries = LISTING
+            .fetch(&db.db, &params, |query| {
+                if let Some(webhook_id) = &filter.webhook_id {
+                    query
+                        .push(" AND d.webhook_id = ")
+                        .push_bind(webhook_id.clone());
+                }
+                if let Some(status) = filter.status {
+                    query.push(" AND d.status = ").push_bind(status);
+                }
+                if let Some(event_type) = &filter.event_type {
+                    query.push(" AND e.type = ").push_bind(event_type.clone());
+                }
+            })
+            .await?;
+
+        Ok(Response::Ok(payload::Json(fields.project_page(deliveries))))
+    }
+}
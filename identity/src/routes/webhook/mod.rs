@@ -0,0 +1,20 @@
+pub mod create;
+pub mod delete;
+pub mod get;
+pub mod list;
+pub mod list_deliveries;
+pub mod retry_delivery;
+pub mod update;
+
+use common::error::Error;
+
+/// Refuse URLs that are not absolute HTTP(S) URLs, since the dispatcher could not post to them.
+fn validate_url(url: &str) -> Result<(), Error> {
+    match reqwest::Url::parse(url) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => Ok(()),
+        _ => Err(Error::bad_request(
+            "WEBHOOK_URL_INVALID",
+            format!("Webhook URL '{}' is not an absolute HTTP or HTTPS URL", url),
+        )),
+    }
+}
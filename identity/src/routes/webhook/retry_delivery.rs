@@ -0,0 +1,65 @@
+use poem::web;
+use poem_openapi::{param::Path, payload};
+
+use common::{auth::BearerAuth, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities};
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<entities::WebhookDelivery>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _retry_webhook_delivery(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: Path<i64>,
+    ) -> Result<Response, Error> {
+        auth.require(&db, Permission::WebhooksWrite).await?;
+
+        let delivery = sqlx::query_as::<_, entities::WebhookDelivery>(&format!(
+            r#"
+            WITH d AS (
+                UPDATE webhook_delivery SET
+                    status          = 'pending',
+                    attempts        = 0,
+                    next_attempt_at = NOW()
+                WHERE id = $1 AND status != 'delivered'
+                RETURNING *
+            )
+            SELECT {} FROM d JOIN outbox_event e ON e.id = d.event_id
+            "#,
+            super::list_deliveries::SELECT
+        ))
+        .bind(*id)
+        .fetch_optional(&db.db)
+        .await?;
+
+        match delivery {
+            Some(delivery) => Ok(Response::Ok(payload::Json(delivery))),
+            // Either it does not exist or it was delivered.
+            None => {
+                let exists = sqlx::query_scalar::<_, bool>(
+                    r#"SELECT EXISTS (SELECT 1 FROM webhook_delivery WHERE id = $1)"#,
+                )
+                .bind(*id)
+                .fetch_one(&db.db)
+                .await?;
+
+                Err(match exists {
+                    true => Error::conflict(
+                        "WEBHOOK_DELIVERY_ALREADY_DELIVERED",
+                        format!("Webhook delivery '{}' was already delivered", *id),
+                    ),
+                    false => Error::not_found(
+                        "WEBHOOK_DELIVERY_NOT_FOUND",
+                        format!("Webhook delivery with id '{}' not found", *id),
+                    ),
+                })
+            }
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use poem::web;
+use poem_openapi::{param::Path, payload, Object};
+use serde::{Deserialize, Serialize};
+
+use common::{auth::BearerAuth, database::Database, error::Error};
+
+use crate::{authorization::Permission, entities};
+
+#[derive(Debug, Clone, Deserialize, Serialize, Object)]
+#[oai(rename = "UpdateWebhookRequest")]
+pub struct Request {
+    url: Option<String>,
+    event_types: Option<Vec<String>>,
+    description: Option<String>,
+    active: Option<bool>,
+}
+
+#[derive(poem_openapi::ApiResponse)]
+pub enum Response {
+    #[oai(status = 200)]
+    Ok(payload::Json<entities::Webhook>),
+}
+
+impl crate::routes::Routes {
+    pub async fn _update_webhook(
+        &self,
+        auth: BearerAuth,
+        db: web::Data<&Database>,
+        id: Path<String>,
+        body: payload::Json<Request>,
+    ) -> Result<Response, Error> {
+        auth.require(&db, Permission::WebhooksWrite).await?;
+
+        if let Some(url) = &body.url {
+            super::validate_url(url)?;
+        }
+
+        let webhook = sqlx::query_as::<_, entities::Webhook>(
+            r#"
+            UPDATE webhook SET
+                url         = COALESCE($1, url),
+                event_types = COALESCE($2, event_types),
+                description = COALESCE($3, description),
+                active      = COALESCE($4, active),
+                updated_at  = NOW()
+            WHERE id = $5
+            RETURNING *
+            "#,
+        )
+        .bind(&body.url)
+        .bind(&body.event_types)
+        .bind(&body.description)
+        .bind(body.active)
+        .bind(&*id)
+        .fetch_one(&db.db)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::not_found(
+                "WEBHOOK_NOT_FOUND",
+                format!("Webhook with id '{}' not found", &*id),
+            ),
+            _ => Error::from(e),
+        })?;
+
+        Ok(Response::Ok(payload::Json(webhook)))
+    }
+}
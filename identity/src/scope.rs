@@ -0,0 +1,131 @@
+use sqlx::{Postgres, QueryBuilder};
+
+use common::{auth::BearerAuth, database::Database};
+
+use crate::authorization::Permission;
+
+/// Pastoral roles with a weight at or below this lead their connect groups, e.g. a CGL.
+pub(crate) const CONNECT_GROUP_LEADER_WEIGHT: i32 = 4;
+
+/// Pastoral roles with a weight at or below this oversee every connect group of their satellite,
+/// e.g. a coach or a satellite pastor.
+pub(crate) const SATELLITE_LEADER_WEIGHT: i32 = 3;
+
+/// The users that the authenticated user is allowed to see or act upon.
+#[derive(Debug, Clone)]
+pub enum Scope {
+    /// Every user, granted through the access token itself.
+    All,
+
+    /// The user themselves, along with the members of the connect groups they lead and of the
+    /// satellites they oversee.
+    Limited {
+        user_id: String,
+        connect_group_ids: Vec<String>,
+        satellite_ids: Vec<String>,
+    },
+}
+
+impl Scope {
+    /// Resolve the scope of users the authenticated user may access with `permission`.
+    ///
+    /// Holding `permission` in the access token grants access to every user, otherwise the scope
+    /// is derived from the pastoral roles the user holds in `user_connect_group`.
+    pub async fn resolve(
+        auth: &BearerAuth,
+        db: &Database,
+        permission: Permission,
+    ) -> Result<Self, sqlx::Error> {
+        if auth.is_granted_by_token(permission) {
+            return Ok(Scope::All);
+        }
+
+        let roles = sqlx::query_as::<_, (String, String, i32)>(
+            r#"
+            SELECT cg.id, cg.satellite_id, pr.weight
+            FROM
+                user_connect_group ucg
+                    INNER JOIN connect_group cg ON ucg.connect_group_id = cg.id
+                    INNER JOIN pastoral_role pr ON ucg.user_role = pr.id
+            WHERE ucg.user_id = $1 AND pr.weight <= $2
+            "#,
+        )
+        .bind(&auth.0.id)
+        .bind(CONNECT_GROUP_LEADER_WEIGHT)
+        .fetch_all(&db.db)
+        .await?;
+
+        let mut connect_group_ids = Vec::new();
+        let mut satellite_ids = Vec::new();
+        for (connect_group_id, satellite_id, weight) in roles {
+            if weight <= SATELLITE_LEADER_WEIGHT {
+                satellite_ids.push(satellite_id);
+            } else {
+                connect_group_ids.push(connect_group_id);
+            }
+        }
+
+        Ok(Scope::Limited {
+            user_id: auth.0.id.clone(),
+            connect_group_ids,
+            satellite_ids,
+        })
+    }
+
+    /// Whether the user identified by `user_id` is within the scope resolved for `permission`.
+    pub async fn can_access(
+        auth: &BearerAuth,
+        db: &Database,
+        permission: Permission,
+        user_id: &str,
+    ) -> Result<bool, sqlx::Error> {
+        Self::resolve(auth, db, permission)
+            .await?
+            .contains(db, user_id)
+            .await
+    }
+
+    /// Push a condition restricting the query to users within this scope.
+    ///
+    /// The query must alias the `"user"` table as `u` and be built as `WHERE TRUE` (or any other
+    /// condition), since the condition is pushed prefixed with `AND`.
+    pub fn push_filter(&self, query: &mut QueryBuilder<'static, Postgres>) {
+        let Scope::Limited {
+            user_id,
+            connect_group_ids,
+            satellite_ids,
+        } = self
+        else {
+            return;
+        };
+
+        query
+            .push(" AND (u.id = ")
+            .push_bind(user_id.clone())
+            .push(
+                r#"
+                OR EXISTS (
+                    SELECT 1
+                    FROM
+                        user_connect_group scope_ucg
+                            INNER JOIN connect_group scope_cg ON scope_ucg.connect_group_id = scope_cg.id
+                    WHERE scope_ucg.user_id = u.id AND (
+                        scope_ucg.connect_group_id = ANY("#,
+            )
+            .push_bind(connect_group_ids.clone())
+            .push(") OR scope_cg.satellite_id = ANY(")
+            .push_bind(satellite_ids.clone())
+            .push("))))");
+    }
+
+    /// Whether the user identified by `user_id` is within this scope, deleted users never are.
+    pub async fn contains(&self, db: &Database, user_id: &str) -> Result<bool, sqlx::Error> {
+        let mut query = QueryBuilder::new(r#"SELECT EXISTS (SELECT 1 FROM "user" u WHERE u.id = "#);
+        query.push_bind(user_id.to_string());
+        query.push(" AND u.deleted IS FALSE");
+        self.push_filter(&mut query);
+        query.push(")");
+
+        query.build_query_scalar::<bool>().fetch_one(&db.db).await
+    }
+}
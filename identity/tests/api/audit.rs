@@ -0,0 +1,86 @@
+use poem::http::StatusCode;
+use serde_json::json;
+
+use crate::{admin, app, create_connect_group, create_pastoral_role, create_user};
+
+#[tokio::test]
+async fn audit_mutations() {
+    let app = app().await;
+    create_user(&app, "alice").await;
+    let connect_group = create_connect_group(&app, 1).await;
+    let id = connect_group["id"].as_str().unwrap();
+    let member = create_pastoral_role(&app, "Member", 10).await;
+
+    app.post(format!("/connect-groups/{}/users", id))
+        .bearer(&admin(&app))
+        .json(json!({ "users": [{ "user_id": "alice", "role_id": member["id"] }] }))
+        .send()
+        .await
+        .ok();
+    let removal = app
+        .delete(format!("/connect-groups/{}/users", id))
+        .bearer(&admin(&app))
+        .json(json!({ "users": ["alice"] }))
+        .send()
+        .await;
+    removal.assert_status(StatusCode::OK);
+    let request_id = removal.headers["x-request-id"]
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    let page = app
+        .get(format!(
+            "/audit?resource=user_connect_group&resource_id=alice/{}&sort=created_at",
+            id
+        ))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    let logs = page["items"].as_array().unwrap();
+    assert_eq!(logs.len(), 2);
+    assert_eq!(logs[0]["action"], "insert");
+    assert_eq!(logs[0]["actor"], "admin");
+    assert_eq!(logs[0]["before"], json!(null));
+    assert_eq!(logs[0]["after"]["user_role"], member["id"]);
+    assert_eq!(logs[1]["action"], "delete");
+    assert_eq!(logs[1]["request_id"], request_id.as_str());
+    assert_eq!(logs[1]["before"]["user_role"], member["id"]);
+    assert_eq!(logs[1]["after"], json!(null));
+
+    app.patch("/users/alice")
+        .bearer(&app.token("alice"))
+        .json(json!({ "id": "alice", "nickname": "Ally" }))
+        .send()
+        .await
+        .ok();
+
+    let page = app
+        .get("/audit?actor=alice")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    let logs = page["items"].as_array().unwrap();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0]["resource"], "user");
+    assert_eq!(logs[0]["resource_id"], "alice");
+    assert_eq!(logs[0]["action"], "update");
+    assert_eq!(logs[0]["changes"], json!({ "nickname": "Ally" }));
+    assert!(logs[0]["after"].get("search_vector").is_none());
+
+    let page = app
+        .get("/audit?actor=admin&created_after=2999-01-01T00:00:00Z")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(page["items"], json!([]));
+
+    app.get("/audit")
+        .bearer(&app.token("alice").permissions(["users:admin"]))
+        .send()
+        .await
+        .assert_error(StatusCode::FORBIDDEN, "MISSING_PERMISSION");
+}
@@ -0,0 +1,295 @@
+use std::sync::{Arc, Mutex};
+
+use common::testing::TestApp;
+use identity::notifier::{Message, Notifier, Recipient};
+use poem::http::StatusCode;
+use serde_json::json;
+
+use crate::{
+    admin, create_connect_group, create_pastoral_role, create_user, insert_shadow_user, ShadowUser,
+};
+
+/// Keeps the messages instead of delivering them, so that tests can read the one-time codes.
+#[derive(Clone, Default)]
+struct Outbox(Arc<Mutex<Vec<Message>>>);
+
+impl Outbox {
+    fn last(&self) -> Message {
+        self.0
+            .lock()
+            .unwrap()
+            .last()
+            .cloned()
+            .expect("no message sent")
+    }
+
+    fn last_code(&self) -> String {
+        self.last()
+            .body
+            .split(|c: char| !c.is_ascii_digit())
+            .find(|word| word.len() == 6)
+            .expect("no code in the message")
+            .to_string()
+    }
+}
+
+#[async_trait::async_trait]
+impl Notifier for Outbox {
+    async fn send(&self, message: &Message) -> Result<(), anyhow::Error> {
+        self.0.lock().unwrap().push(message.clone());
+        Ok(())
+    }
+}
+
+async fn app(outbox: &Outbox) -> TestApp {
+    let outbox = outbox.clone();
+    TestApp::new(|management| identity::routes::Routes::new(management).with_notifier(outbox)).await
+}
+
+#[tokio::test]
+async fn claim_shadow_user() {
+    let outbox = Outbox::default();
+    let app = app(&outbox).await;
+    let connect_group = create_connect_group(&app, 1).await;
+    create_pastoral_role(&app, "CGL", 4).await;
+    let no = insert_shadow_user(
+        &app,
+        ShadowUser {
+            email: Some("alice.tan@example.com"),
+            cg: Some("1A"),
+            pastoral_status: Some("CGL"),
+            ..ShadowUser::new("Alice Tan", "012-345 6789")
+        },
+    )
+    .await;
+
+    app.post("/users")
+        .bearer(&app.token("alice").email("alice.tan@example.com"))
+        .json(json!({ "id": "alice", "name": "Alice", "email": "alice.tan@example.com" }))
+        .send()
+        .await
+        .assert_error(StatusCode::CONFLICT, "USER_PROFILE_CLAIMABLE");
+
+    // Users acting on themselves only find the profiles matching the email of their token.
+    let candidates = app
+        .get("/users/alice/claims/candidates?email=alice.tan@example.com")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(candidates, json!([]));
+
+    let candidates = app
+        .get("/users/alice/claims/candidates")
+        .bearer(&app.token("alice").email("alice.tan@example.com"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(candidates.as_array().unwrap().len(), 1);
+    assert_eq!(candidates[0]["shadow_user_no"], no);
+    assert_eq!(candidates[0]["name"], "A**** T**");
+    assert_eq!(
+        candidates[0]["destinations"],
+        json!([
+            { "channel": "email", "destination": "a********@example.com" },
+            { "channel": "phone", "destination": "*******789" },
+        ])
+    );
+
+    app.post("/users/alice/claims")
+        .bearer(&app.token("alice"))
+        .json(json!({ "email": "alice.tan@example.com", "channel": "phone" }))
+        .send()
+        .await
+        .assert_error(StatusCode::BAD_REQUEST, "USER_CLAIM_TARGET_INVALID");
+
+    // Nor can they claim a profile under an email other than the one of their token.
+    app.post("/users/alice/claims")
+        .bearer(&app.token("alice"))
+        .json(json!({
+            "email": "alice.tan@example.com",
+            "phone_number": "012-345 6789",
+            "shadow_user_no": no,
+            "channel": "phone",
+        }))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "USER_CLAIM_CANDIDATE_NOT_FOUND");
+
+    let claim = app
+        .post("/users/alice/claims")
+        .bearer(&app.token("alice").email("alice.tan@example.com"))
+        .json(json!({
+            "email": "alice.tan@example.com",
+            "shadow_user_no": no,
+            "channel": "phone",
+        }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(claim["destination"], "*******789");
+    assert_eq!(
+        outbox.last().recipient,
+        Recipient::Phone("012-345 6789".to_string())
+    );
+
+    let verify = format!(
+        "/users/alice/claims/{}/verify",
+        claim["id"].as_str().unwrap()
+    );
+    let code = outbox.last_code();
+    let wrong = if code == "000000" { "111111" } else { "000000" };
+    app.post(&verify)
+        .bearer(&app.token("alice"))
+        .json(json!({ "code": wrong }))
+        .send()
+        .await
+        .assert_error(StatusCode::BAD_REQUEST, "USER_CLAIM_CODE_INVALID");
+
+    app.post(format!(
+        "/users/bob/claims/{}/verify",
+        claim["id"].as_str().unwrap()
+    ))
+    .bearer(&app.token("bob"))
+    .json(json!({ "code": code }))
+    .send()
+    .await
+    .assert_error(StatusCode::NOT_FOUND, "USER_CLAIM_NOT_FOUND");
+
+    let user = app
+        .post(&verify)
+        .bearer(&app.token("alice"))
+        .json(json!({ "code": code }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(user["id"], "alice");
+    assert_eq!(user["name"], "Alice Tan");
+    assert_eq!(user["email"], "alice.tan@example.com");
+    assert_eq!(user["email_verified"], false);
+    assert_eq!(user["phone_number_verified"], true);
+
+    let connect_groups = app
+        .get("/users/alice/connect-groups")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(connect_groups[0]["cg"]["id"], connect_group["id"]);
+
+    app.post(&verify)
+        .bearer(&app.token("alice"))
+        .json(json!({ "code": code }))
+        .send()
+        .await
+        .assert_error(StatusCode::CONFLICT, "USER_CLAIM_ALREADY_VERIFIED");
+
+    let shadow_user = app
+        .get(format!("/shadow-users/{}", no))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(shadow_user["claimed_by"], "alice");
+}
+
+#[tokio::test]
+async fn claim_deleted_user() {
+    let outbox = Outbox::default();
+    let app = app(&outbox).await;
+    create_user(&app, "old").await;
+    create_user(&app, "bob").await;
+    app.post("/users/old/relationships")
+        .bearer(&admin(&app))
+        .json(json!({ "destination_user_id": "bob", "relationship": "sibling" }))
+        .send()
+        .await
+        .ok();
+    app.delete("/users/old")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+
+    app.post("/users")
+        .bearer(&app.token("new").email("old@example.com"))
+        .json(json!({ "id": "new", "name": "new", "email": "old@example.com" }))
+        .send()
+        .await
+        .assert_error(StatusCode::CONFLICT, "USER_PROFILE_CLAIMABLE");
+
+    let claim = app
+        .post("/users/new/claims")
+        .bearer(&app.token("new").email("old@example.com"))
+        .json(json!({ "email": "old@example.com", "user_id": "old", "channel": "email" }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(claim["destination"], "o**@example.com");
+    assert_eq!(
+        outbox.last().recipient,
+        Recipient::Email("old@example.com".to_string())
+    );
+
+    let verify = format!("/users/new/claims/{}/verify", claim["id"].as_str().unwrap());
+    let user = app
+        .post(&verify)
+        .bearer(&app.token("new"))
+        .json(json!({ "code": outbox.last_code() }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(user["id"], "new");
+    assert_eq!(user["email_verified"], true);
+
+    let relationships = app
+        .get("/users/new/relationships")
+        .bearer(&app.token("new"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(relationships[0]["destination_user_id"], "bob");
+}
+
+#[tokio::test]
+async fn skip_claim() {
+    let outbox = Outbox::default();
+    let app = app(&outbox).await;
+    insert_shadow_user(
+        &app,
+        ShadowUser {
+            email: Some("alice@example.com"),
+            ..ShadowUser::new("Alice Tan", "0123456789")
+        },
+    )
+    .await;
+
+    let body = json!({
+        "id": "alice",
+        "name": "Alice",
+        "email": "alice@example.com",
+        "phone_number": "+60 12-345 6789",
+    });
+    app.post("/users")
+        .bearer(&app.token("alice"))
+        .json(body.clone())
+        .send()
+        .await
+        .assert_error(StatusCode::CONFLICT, "USER_PROFILE_CLAIMABLE");
+
+    let mut body = body;
+    body["skip_claim"] = json!(true);
+    app.post("/users")
+        .bearer(&app.token("alice"))
+        .json(body)
+        .send()
+        .await
+        .ok();
+
+    app.post("/users/alice/claims")
+        .bearer(&app.token("alice"))
+        .json(json!({ "email": "alice@example.com", "user_id": "bob", "channel": "email" }))
+        .send()
+        .await
+        .assert_error(StatusCode::CONFLICT, "USER_ALREADY_REGISTERED");
+}
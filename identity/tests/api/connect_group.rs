@@ -0,0 +1,425 @@
+use poem::http::StatusCode;
+use serde_json::{json, Value};
+
+use crate::{
+    admin, app, create_connect_group, create_connect_group_category, create_pastoral_role,
+    create_satellite, create_user, ids,
+};
+
+#[tokio::test]
+async fn create_connect_group_with_missing_references() {
+    let app = app().await;
+    let category = create_connect_group_category(&app, "Youth").await;
+
+    app.post("/connect-groups")
+        .bearer(&admin(&app))
+        .json(json!({
+            "name": "Orphan",
+            "no": 1,
+            "variant": "A",
+            "satellite_id": "satellite_missing",
+            "category_id": category["id"],
+        }))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "SATELLITE_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn manage_connect_groups() {
+    let app = app().await;
+    let connect_group = create_connect_group(&app, 1).await;
+    let id = connect_group["id"].as_str().unwrap();
+
+    let page = app
+        .get("/connect-groups")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(ids(&page), [id]);
+
+    let fetched = app
+        .get(format!("/connect-groups/{}", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(fetched["no"], 1);
+
+    let satellite = create_satellite(&app, 2).await;
+    let updated = app
+        .patch(format!("/connect-groups/{}", id))
+        .bearer(&admin(&app))
+        .json(json!({ "satellite_id": satellite["id"], "variant": "B" }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(updated["satellite_id"], satellite["id"]);
+    // Variants are stored as `CHAR(2)`, padded with spaces.
+    assert_eq!(updated["variant"], "B ");
+
+    app.delete(format!("/connect-groups/{}", id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+
+    app.get(format!("/connect-groups/{}", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "CONNECT_GROUP_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn manage_connect_group_members() {
+    let app = app().await;
+    let connect_group = create_connect_group(&app, 1).await;
+    let id = connect_group["id"].as_str().unwrap();
+    let leader = create_pastoral_role(&app, "CGL", 4).await;
+    let member = create_pastoral_role(&app, "Member", 10).await;
+    create_user(&app, "alice").await;
+    create_user(&app, "bob").await;
+    create_user(&app, "carol").await;
+
+    app.post(format!("/connect-groups/{}/users", id))
+        .bearer(&admin(&app))
+        .json(json!({
+            "users": [
+                { "user_id": "alice", "role_id": leader["id"] },
+                { "user_id": "bob", "role_id": member["id"] },
+            ],
+        }))
+        .send()
+        .await
+        .ok();
+
+    // Associating a user again only updates their role.
+    app.post(format!("/connect-groups/{}/users", id))
+        .bearer(&admin(&app))
+        .json(json!({ "users": [{ "user_id": "bob", "role_id": member["id"] }] }))
+        .send()
+        .await
+        .ok();
+
+    let page = app
+        .get(format!("/connect-groups/{}/users?sort=name", id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(ids(&page), ["alice", "bob"]);
+
+    // The leader sees the members of their connect group, a member only sees themselves.
+    let page = app
+        .get(format!("/connect-groups/{}/users?sort=name", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(ids(&page), ["alice", "bob"]);
+
+    let page = app
+        .get(format!("/connect-groups/{}/users", id))
+        .bearer(&app.token("bob"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(ids(&page), ["bob"]);
+
+    app.get("/users/bob")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    app.get("/users/carol")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "USER_NOT_FOUND");
+
+    app.delete(format!("/connect-groups/{}/users", id))
+        .bearer(&admin(&app))
+        .json(json!({ "users": ["bob"] }))
+        .send()
+        .await
+        .ok();
+
+    let page = app
+        .get(format!("/connect-groups/{}/users", id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(ids(&page), ["alice"]);
+}
+
+#[tokio::test]
+async fn connect_group_membership_history() {
+    let app = app().await;
+    let connect_group = create_connect_group(&app, 1).await;
+    let id = connect_group["id"].as_str().unwrap();
+    let leader = create_pastoral_role(&app, "CGL", 4).await;
+    let member = create_pastoral_role(&app, "Member", 10).await;
+    create_user(&app, "alice").await;
+
+    for role in [&member, &leader] {
+        app.post(format!("/connect-groups/{}/users", id))
+            .bearer(&admin(&app))
+            .json(json!({ "users": [{ "user_id": "alice", "role_id": role["id"] }] }))
+            .send()
+            .await
+            .ok();
+    }
+
+    app.delete(format!("/connect-groups/{}/users", id))
+        .bearer(&admin(&app))
+        .json(json!({ "users": ["alice"], "reason": "Moved overseas" }))
+        .send()
+        .await
+        .ok();
+
+    // The role change ends the first period, leaving ends the second.
+    let history = app
+        .get("/users/alice/connect-group-history")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    let periods = history.as_array().unwrap();
+    assert_eq!(periods.len(), 2);
+    assert_eq!(periods[0]["user_role"], member["id"]);
+    assert_eq!(periods[0]["left_reason"], json!(null));
+    assert_eq!(periods[1]["user_role"], leader["id"]);
+    assert_eq!(periods[1]["left_reason"], "Moved overseas");
+    assert_eq!(periods[1]["connect_group_id"], id);
+
+    let at = |time: &Value| time.as_str().unwrap().replace('+', "%2B");
+    let page = app
+        .get(format!(
+            "/connect-groups/{}/history?at={}",
+            id,
+            at(&periods[1]["joined_at"])
+        ))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(page["items"].as_array().unwrap().len(), 1);
+    assert_eq!(page["items"][0]["user_id"], "alice");
+    assert_eq!(page["items"][0]["user_role"], leader["id"]);
+
+    let page = app
+        .get(format!(
+            "/connect-groups/{}/history?at={}",
+            id,
+            at(&periods[1]["left_at"])
+        ))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert!(page["items"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn multiply_connect_group() {
+    let app = app().await;
+    let parent = create_connect_group(&app, 1).await;
+    let id = parent["id"].as_str().unwrap();
+    let member = create_pastoral_role(&app, "Member", 10).await;
+    for user in ["alice", "bob", "carol"] {
+        create_user(&app, user).await;
+    }
+    app.post(format!("/connect-groups/{}/users", id))
+        .bearer(&admin(&app))
+        .json(json!({
+            "users": [
+                { "user_id": "alice", "role_id": member["id"] },
+                { "user_id": "bob", "role_id": member["id"] },
+            ],
+        }))
+        .send()
+        .await
+        .ok();
+
+    app.post(format!("/connect-groups/{}/multiply", id))
+        .bearer(&admin(&app))
+        .json(json!({ "children": [{ "variant": "B", "user_ids": ["carol"] }] }))
+        .send()
+        .await
+        .assert_error(StatusCode::BAD_REQUEST, "USER_NOT_IN_CONNECT_GROUP");
+
+    let multiplication = app
+        .post(format!("/connect-groups/{}/multiply", id))
+        .bearer(&admin(&app))
+        .json(json!({
+            "children": [
+                { "variant": "B", "user_ids": ["alice"] },
+                { "name": "Connect group 1C", "variant": "C", "user_ids": ["bob"] },
+            ],
+            "close_parent": true,
+        }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(multiplication["parent"]["active"], false);
+    let children = multiplication["children"].as_array().unwrap();
+    assert_eq!(children[0]["name"], parent["name"]);
+    assert_eq!(children[0]["no"], 1);
+    assert_eq!(children[0]["satellite_id"], parent["satellite_id"]);
+    assert_eq!(children[1]["name"], "Connect group 1C");
+    let child_id = children[0]["id"].as_str().unwrap();
+
+    let page = app
+        .get(format!("/connect-groups/{}/users", child_id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(ids(&page), ["alice"]);
+
+    app.post(format!("/connect-groups/{}/multiply", id))
+        .bearer(&admin(&app))
+        .json(json!({ "children": [{ "variant": "D" }] }))
+        .send()
+        .await
+        .assert_error(StatusCode::CONFLICT, "CONNECT_GROUP_CLOSED");
+
+    let lineage = app
+        .get(format!("/connect-groups/{}/lineage", id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert!(lineage["ancestors"].as_array().unwrap().is_empty());
+    assert_eq!(lineage["tree"]["id"], id);
+    assert_eq!(lineage["tree"]["children"].as_array().unwrap().len(), 2);
+
+    let lineage = app
+        .get(format!("/connect-groups/{}/lineage", child_id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(lineage["ancestors"][0]["id"], id);
+    assert!(lineage["tree"]["multiplied_at"].is_string());
+
+    let lineage = app
+        .get(format!(
+            "/connect-groups/{}/lineage?fields=ancestors",
+            child_id
+        ))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(lineage["ancestors"][0]["id"], id);
+    assert!(lineage.get("tree").is_none());
+}
+
+#[tokio::test]
+async fn close_and_reopen_connect_group() {
+    let app = app().await;
+    let connect_group = create_connect_group(&app, 1).await;
+    let id = connect_group["id"].as_str().unwrap();
+    let other = create_connect_group(&app, 2).await;
+    let other_id = other["id"].as_str().unwrap();
+    let member = create_pastoral_role(&app, "Member", 10).await;
+    create_user(&app, "alice").await;
+    app.post(format!("/connect-groups/{}/users", id))
+        .bearer(&admin(&app))
+        .json(json!({ "users": [{ "user_id": "alice", "role_id": member["id"] }] }))
+        .send()
+        .await
+        .ok();
+
+    app.post(format!("/connect-groups/{}/close", id))
+        .bearer(&admin(&app))
+        .json(json!({}))
+        .send()
+        .await
+        .assert_error(StatusCode::CONFLICT, "CONNECT_GROUP_HAS_MEMBERS");
+    app.delete(format!("/connect-groups/{}", id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .assert_error(StatusCode::CONFLICT, "CONNECT_GROUP_IN_USE");
+
+    let closed = app
+        .post(format!("/connect-groups/{}/close", id))
+        .bearer(&admin(&app))
+        .json(json!({ "reassign_to": other_id }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(closed["active"], false);
+    assert!(closed["closed_at"].is_string());
+
+    let page = app
+        .get(format!("/connect-groups/{}/users", other_id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(ids(&page), ["alice"]);
+
+    // Closed connect groups are hidden from the listing but keep their history.
+    let page = app
+        .get("/connect-groups")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(ids(&page), [other_id]);
+    let page = app
+        .get("/connect-groups?active=false")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(ids(&page), [id]);
+    let history = app
+        .get("/users/alice/connect-group-history")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(history[0]["connect_group_id"], id);
+    assert!(history[0]["left_at"].is_string());
+
+    app.post(format!("/connect-groups/{}/users", id))
+        .bearer(&admin(&app))
+        .json(json!({ "users": [{ "user_id": "alice", "role_id": member["id"] }] }))
+        .send()
+        .await
+        .assert_error(StatusCode::CONFLICT, "CONNECT_GROUP_CLOSED");
+
+    let reopened = app
+        .post(format!("/connect-groups/{}/reopen", id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(reopened["active"], true);
+    assert_eq!(reopened["closed_at"], json!(null));
+
+    app.post(format!("/connect-groups/{}/reopen", id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .assert_error(StatusCode::CONFLICT, "CONNECT_GROUP_NOT_CLOSED");
+
+    // The connect group that took the members is only deleted when forced by an admin.
+    app.delete(format!("/connect-groups/{}?force=true", other_id))
+        .bearer(&app.token("bob").permissions(["connect-groups:write"]))
+        .send()
+        .await
+        .assert_error(StatusCode::FORBIDDEN, "MISSING_PERMISSION");
+    app.delete(format!("/connect-groups/{}?force=true", other_id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+}
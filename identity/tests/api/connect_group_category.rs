@@ -0,0 +1,61 @@
+use poem::http::StatusCode;
+use serde_json::json;
+
+use crate::{admin, app, create_connect_group_category, ids};
+
+#[tokio::test]
+async fn manage_connect_group_categories() {
+    let app = app().await;
+    let category = create_connect_group_category(&app, "Youth").await;
+    let id = category["id"].as_str().unwrap();
+
+    app.post("/connect-group-categories")
+        .bearer(&admin(&app))
+        .json(json!({ "name": "Youth" }))
+        .send()
+        .await
+        .assert_error(StatusCode::CONFLICT, "CONNECT_GROUP_CATEGORY_NAME_TAKEN");
+
+    let page = app
+        .get("/connect-group-categories")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(ids(&page), [id]);
+
+    let fetched = app
+        .get(format!("/connect-group-categories/{}", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(fetched["name"], "Youth");
+
+    let updated = app
+        .patch(format!("/connect-group-categories/{}", id))
+        .bearer(&admin(&app))
+        .json(json!({ "name": "Young adults" }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(updated["name"], "Young adults");
+
+    app.delete(format!("/connect-group-categories/{}", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .assert_error(StatusCode::FORBIDDEN, "MISSING_PERMISSION");
+
+    app.delete(format!("/connect-group-categories/{}", id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+
+    app.get(format!("/connect-group-categories/{}", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "CONNECT_GROUP_CATEGORY_NOT_FOUND");
+}
@@ -0,0 +1,164 @@
+use poem::http::StatusCode;
+use serde_json::{json, Value};
+
+use crate::{address, admin, app, create_user};
+
+fn user_ids(members: &Value) -> Vec<&str> {
+    members
+        .as_array()
+        .expect("response is a list")
+        .iter()
+        .map(|member| member["user_id"].as_str().expect("member has a user id"))
+        .collect()
+}
+
+#[tokio::test]
+async fn manage_family_and_households() {
+    let app = app().await;
+    for user in ["mum", "kid", "grandma", "stranger"] {
+        create_user(&app, user).await;
+    }
+    let mum = app.token("mum");
+
+    // Nobody can allow themselves to pick up a child.
+    app.post("/users/mum/relationships")
+        .bearer(&mum)
+        .json(
+            json!({ "destination_user_id": "kid", "relationship": "parent", "can_pick_up": true }),
+        )
+        .send()
+        .await
+        .assert_error(StatusCode::FORBIDDEN, "MISSING_PERMISSION");
+
+    app.post("/users/mum/relationships")
+        .bearer(&mum)
+        .json(json!({ "destination_user_id": "kid", "relationship": "aunty" }))
+        .send()
+        .await
+        .assert_error(StatusCode::BAD_REQUEST, "RELATIONSHIP_TYPE_NOT_FOUND");
+
+    let relationship = app
+        .post("/users/mum/relationships")
+        .bearer(&mum)
+        .json(json!({ "destination_user_id": "kid", "relationship": "parent" }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(relationship["can_pick_up"], false);
+
+    let relationships = app
+        .get("/users/kid/relationships")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(relationships[0]["destination_user_id"], "mum");
+    assert_eq!(relationships[0]["relationship"], "child");
+
+    app.patch("/users/mum/relationships")
+        .bearer(&admin(&app))
+        .json(json!({ "destination_user_id": "kid", "can_pick_up": true }))
+        .send()
+        .await
+        .ok();
+    app.post("/users/grandma/relationships")
+        .bearer(&admin(&app))
+        .json(json!({ "destination_user_id": "mum", "relationship": "parent" }))
+        .send()
+        .await
+        .ok();
+
+    let pick_ups = app
+        .get("/users/kid/pick-ups")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(user_ids(&pick_ups), ["mum"]);
+
+    let household = app
+        .post("/households")
+        .bearer(&admin(&app))
+        .json(json!({ "name": "Home", "address": address(), "user_ids": ["mum", "kid"] }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(user_ids(&household["members"]), ["kid", "mum"]);
+    let household_url = format!("/households/{}", household["id"].as_str().unwrap());
+
+    app.post(format!("{}/users", household_url))
+        .bearer(&admin(&app))
+        .json(json!({ "user_ids": ["mum"] }))
+        .send()
+        .await
+        .assert_error(StatusCode::CONFLICT, "USER_ALREADY_IN_HOUSEHOLD");
+
+    // Households are only visible through their members.
+    app.get(&household_url)
+        .bearer(&app.token("kid"))
+        .send()
+        .await
+        .ok();
+    app.get(&household_url)
+        .bearer(&app.token("stranger"))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "HOUSEHOLD_NOT_FOUND");
+
+    let family = app
+        .get("/users/kid/family")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(user_ids(&family["members"]), ["kid", "mum"]);
+
+    let family = app
+        .get("/users/kid/family?depth=2")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(user_ids(&family["members"]), ["kid", "mum", "grandma"]);
+    assert_eq!(family["relationships"].as_array().unwrap().len(), 4);
+    assert_eq!(family["households"][0]["id"], household["id"]);
+    assert_eq!(family["members"][0]["household_ids"][0], household["id"]);
+
+    let family = app
+        .get("/users/kid/family?fields=households")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(family.as_object().unwrap().len(), 1);
+    assert_eq!(family["households"][0]["id"], household["id"]);
+
+    app.delete("/users/mum/relationships")
+        .bearer(&mum)
+        .json(json!({ "destination_user_id": "kid" }))
+        .send()
+        .await
+        .ok();
+
+    let relationships = app
+        .get("/users/kid/relationships")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(relationships.as_array().unwrap().len(), 0);
+
+    app.delete(&household_url)
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+
+    let households = app
+        .get("/users/kid/households")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(households.as_array().unwrap().len(), 0);
+}
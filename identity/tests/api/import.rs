@@ -0,0 +1,140 @@
+use common::testing::{Form, TestApp, Token};
+use poem::http::StatusCode;
+use serde_json::{json, Value};
+
+use crate::{admin, app, create_connect_group, create_pastoral_role, create_user};
+
+const FILE: &str = "Full Name\tD.O.B.\tPhone\tPastoral Status\tCG Name\tSatellite
+Mok Chee Hoong (Ray)\t11.6.1989\t012-345 6789\tCGL\t01A\tSeremban
+Lim Chuan Jie (Jack)\t17.1.1994\t\tOM\tConnect group 1\tSatellite 1
+\t\t\t\t\t
+";
+
+fn mapping() -> Value {
+    json!({
+        "name": "Full Name",
+        "date_of_birth": "D.O.B.",
+        "phone_number": "Phone",
+        "pastoral_role": "Pastoral Status",
+        "connect_group": "CG Name",
+        "satellite": "Satellite",
+        "satellite_aliases": { "Seremban": "Satellite 1" },
+        "pastoral_role_aliases": { "CGL": "CGL role", "OM": "Member" },
+    })
+}
+
+async fn import(app: &TestApp, token: &Token, file: &str, mapping: Value, dry_run: bool) -> Value {
+    app.post("/imports/users")
+        .bearer(token)
+        .multipart(
+            Form::new()
+                .file("file", "members.csv", "text/csv", file)
+                .text("mapping", mapping.to_string())
+                .text("dry_run", dry_run.to_string()),
+        )
+        .send()
+        .await
+        .ok()
+}
+
+#[tokio::test]
+async fn import_users() {
+    let app = app().await;
+    let connect_group = create_connect_group(&app, 1).await;
+    create_pastoral_role(&app, "CGL role", 4).await;
+    create_pastoral_role(&app, "Member", 10).await;
+
+    app.post("/imports/users")
+        .bearer(&app.token("alice").permissions(["users:write"]))
+        .multipart(
+            Form::new()
+                .file("file", "members.csv", "text/csv", FILE)
+                .text("mapping", mapping().to_string()),
+        )
+        .send()
+        .await
+        .assert_error(StatusCode::FORBIDDEN, "MISSING_PERMISSION");
+
+    app.post("/imports/users")
+        .bearer(&admin(&app))
+        .multipart(
+            Form::new()
+                .file("file", "members.csv", "text/csv", FILE)
+                .text("mapping", json!({ "name": "Name" }).to_string()),
+        )
+        .send()
+        .await
+        .assert_error(StatusCode::BAD_REQUEST, "IMPORT_COLUMN_NOT_FOUND");
+
+    let report = import(&app, &admin(&app), FILE, mapping(), true).await;
+    assert_eq!(report["committed"], false);
+    assert_eq!(report["rows"], 2);
+    assert_eq!(report["users_created"], 2);
+    assert_eq!(report["memberships_created"], 2);
+    assert_eq!(report["errors"], json!([]));
+
+    let users = app
+        .get(format!(
+            "/connect-groups/{}/users",
+            connect_group["id"].as_str().unwrap()
+        ))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(users["items"], json!([]));
+
+    let report = import(&app, &admin(&app), FILE, mapping(), false).await;
+    assert_eq!(report["committed"], true);
+    assert_eq!(report["users_created"], 2);
+    assert_eq!(report["memberships_created"], 2);
+
+    let user_id: String =
+        sqlx::query_scalar(r#"SELECT id FROM "user" WHERE name = 'Mok Chee Hoong (Ray)'"#)
+            .fetch_one(&app.database.db)
+            .await
+            .unwrap();
+    let user = app
+        .get(format!("/users/{}", user_id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(user["phone_number"], "+60123456789");
+
+    // Importing the same file again matches the users it created.
+    let report = import(&app, &admin(&app), FILE, mapping(), false).await;
+    assert_eq!(report["users_created"], 0);
+    assert_eq!(report["users_matched"], 2);
+    assert_eq!(report["memberships_created"], 0);
+
+    let file = "Full Name\tD.O.B.\tPhone\tPastoral Status\tCG Name\tSatellite
+Tan Ah Kow\t31.2.1990\t\tOM\t99Z\tSeremban
+";
+    let report = import(&app, &admin(&app), file, mapping(), false).await;
+    assert_eq!(report["committed"], false);
+    assert_eq!(report["errors"][0]["row"], 2);
+    assert_eq!(report["errors"][0]["code"], "INVALID_DATE");
+    assert_eq!(report["errors"][0]["column"], "D.O.B.");
+    assert_eq!(report["errors"][1]["code"], "CONNECT_GROUP_NOT_FOUND");
+
+    // Namesakes are not merged without a date of birth to tell them apart, nor are deleted users
+    // matched.
+    create_user(&app, "bob").await;
+    app.delete("/users/bob")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    let file = "Full Name\tEmail
+Lim Chuan Jie (Jack)\t
+Bob\tbob@example.com
+";
+    let mapping = json!({ "name": "Full Name", "email": "Email" });
+    let report = import(&app, &admin(&app), file, mapping, false).await;
+    assert_eq!(report["committed"], false);
+    assert_eq!(report["errors"][0]["row"], 2);
+    assert_eq!(report["errors"][0]["code"], "USER_AMBIGUOUS");
+    assert_eq!(report["errors"][1]["row"], 3);
+    assert_eq!(report["errors"][1]["code"], "USER_DELETED");
+}
@@ -0,0 +1,221 @@
+//! End-to-end tests of the identity endpoints, served by `common::testing::TestApp` against a
+//! throwaway Postgres and a mock of the Auth0 management API.
+
+use common::testing::{TestApp, Token};
+use serde_json::{json, Value};
+
+mod audit;
+mod claim;
+mod connect_group;
+mod connect_group_category;
+mod family;
+mod import;
+mod ministry;
+mod ministry_department;
+mod ministry_role;
+mod ministry_team;
+mod org_tree;
+mod pastoral_care;
+mod pastoral_role;
+mod privacy;
+mod satellite;
+mod shadow_user;
+mod user_duplicate;
+mod users;
+mod webhook;
+
+pub async fn app() -> TestApp {
+    TestApp::new(identity::routes::Routes::new).await
+}
+
+/// A token holding every permission of the service.
+pub fn admin(app: &TestApp) -> Token {
+    app.token("admin").permissions([
+        "users:admin",
+        "satellites:write",
+        "connect-groups:write",
+        "ministries:write",
+        "roles:write",
+        "audit:read",
+        "webhooks:write",
+        "pii:read",
+    ])
+}
+
+pub fn address() -> Value {
+    json!({
+        "line_one": "1 Jalan Kenari",
+        "city": "Puchong",
+        "state": "Selangor",
+        "country": "MY",
+        "postal_code": "47100",
+    })
+}
+
+pub async fn create_user(app: &TestApp, id: &str) -> Value {
+    app.post("/users")
+        .bearer(&admin(app))
+        .json(json!({
+            "id": id,
+            "name": id,
+            "email": format!("{}@example.com", id),
+        }))
+        .send()
+        .await
+        .ok()
+}
+
+pub async fn create_satellite(app: &TestApp, no: i32) -> Value {
+    app.post("/satellites")
+        .bearer(&admin(app))
+        .json(json!({ "no": no, "name": format!("Satellite {}", no), "address": address() }))
+        .send()
+        .await
+        .ok()
+}
+
+pub async fn create_connect_group_category(app: &TestApp, name: &str) -> Value {
+    app.post("/connect-group-categories")
+        .bearer(&admin(app))
+        .json(json!({ "name": name }))
+        .send()
+        .await
+        .ok()
+}
+
+/// Create a connect group along with its own satellite and category.
+pub async fn create_connect_group(app: &TestApp, no: i32) -> Value {
+    let satellite = create_satellite(app, no).await;
+    let category = create_connect_group_category(app, &format!("Category {}", no)).await;
+
+    app.post("/connect-groups")
+        .bearer(&admin(app))
+        .json(json!({
+            "name": format!("Connect group {}", no),
+            "no": no,
+            "variant": "A",
+            "satellite_id": satellite["id"],
+            "category_id": category["id"],
+        }))
+        .send()
+        .await
+        .ok()
+}
+
+pub async fn create_pastoral_role(app: &TestApp, name: &str, weight: i32) -> Value {
+    app.post("/pastoral-roles")
+        .bearer(&admin(app))
+        .json(json!({ "name": name, "description": name, "weight": weight }))
+        .send()
+        .await
+        .ok()
+}
+
+pub async fn create_ministry_role(app: &TestApp, name: &str, weight: i32) -> Value {
+    app.post("/ministry-roles")
+        .bearer(&admin(app))
+        .json(json!({ "name": name, "description": name, "weight": weight }))
+        .send()
+        .await
+        .ok()
+}
+
+pub async fn create_ministry_team(app: &TestApp, name: &str) -> Value {
+    app.post("/ministry-teams")
+        .bearer(&admin(app))
+        .json(json!({ "name": name, "description": name }))
+        .send()
+        .await
+        .ok()
+}
+
+pub async fn create_ministry_department(app: &TestApp, name: &str) -> Value {
+    app.post("/ministry-departments")
+        .bearer(&admin(app))
+        .json(json!({ "name": name, "description": name }))
+        .send()
+        .await
+        .ok()
+}
+
+/// Create a ministry along with its own team, department and satellite.
+pub async fn create_ministry(app: &TestApp, name: &str) -> Value {
+    let team = create_ministry_team(app, &format!("{} team", name)).await;
+    let department = create_ministry_department(app, &format!("{} department", name)).await;
+    let satellite = create_satellite(app, 1).await;
+
+    app.post("/ministries")
+        .bearer(&admin(app))
+        .json(json!({
+            "name": name,
+            "description": name,
+            "department_id": department["id"],
+            "team_id": team["id"],
+            "satellite_id": satellite["id"],
+        }))
+        .send()
+        .await
+        .ok()
+}
+
+/// The ids of the items of a page returned by a list endpoint.
+pub fn ids(page: &Value) -> Vec<&str> {
+    page["items"]
+        .as_array()
+        .expect("response is a page")
+        .iter()
+        .map(|item| item["id"].as_str().expect("item has an id"))
+        .collect()
+}
+
+/// The columns of a shadow user, which are only ever inserted by the spreadsheet imports.
+pub struct ShadowUser<'a> {
+    pub name: &'a str,
+    pub phone_number: &'a str,
+    pub email: Option<&'a str>,
+    pub ic_number: Option<&'a str>,
+    pub cg: Option<&'a str>,
+    pub pastoral_status: Option<&'a str>,
+    pub ministry: Option<&'a str>,
+}
+
+impl<'a> ShadowUser<'a> {
+    pub fn new(name: &'a str, phone_number: &'a str) -> Self {
+        Self {
+            name,
+            phone_number,
+            email: None,
+            ic_number: Some("900101-14-5678"),
+            cg: None,
+            pastoral_status: None,
+            ministry: None,
+        }
+    }
+}
+
+pub async fn insert_shadow_user(app: &TestApp, shadow_user: ShadowUser<'_>) -> i32 {
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO shadow_user (
+            name,
+            phone_number,
+            email,
+            ic_number,
+            cg,
+            pastoral_status,
+            ministry
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING no
+        "#,
+    )
+    .bind(shadow_user.name)
+    .bind(shadow_user.phone_number)
+    .bind(shadow_user.email)
+    .bind(shadow_user.ic_number)
+    .bind(shadow_user.cg)
+    .bind(shadow_user.pastoral_status)
+    .bind(shadow_user.ministry)
+    .fetch_one(&app.database.db)
+    .await
+    .expect("failed to insert a shadow user")
+}
@@ -0,0 +1,130 @@
+use poem::http::StatusCode;
+use serde_json::json;
+
+use crate::{
+    admin, app, create_ministry, create_ministry_department, create_ministry_role,
+    create_ministry_team, create_satellite, create_user, ids,
+};
+
+#[tokio::test]
+async fn create_ministry_with_missing_references() {
+    let app = app().await;
+    let team = create_ministry_team(&app, "Worship").await;
+    let satellite = create_satellite(&app, 1).await;
+
+    app.post("/ministries")
+        .bearer(&admin(&app))
+        .json(json!({
+            "name": "Worship",
+            "description": "Worship",
+            "department_id": "ministry_department_missing",
+            "team_id": team["id"],
+            "satellite_id": satellite["id"],
+        }))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "MINISTRY_DEPARTMENT_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn manage_ministries() {
+    let app = app().await;
+    let ministry = create_ministry(&app, "Worship").await;
+    let id = ministry["id"].as_str().unwrap();
+
+    let page = app
+        .get("/ministries")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(ids(&page), [id]);
+
+    let fetched = app
+        .get(format!("/ministries/{}", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(fetched["name"], "Worship");
+
+    let department = create_ministry_department(&app, "Creative").await;
+    let updated = app
+        .patch(format!("/ministries/{}", id))
+        .bearer(&admin(&app))
+        .json(json!({ "department_id": department["id"] }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(updated["department_id"], department["id"]);
+
+    app.delete(format!("/ministries/{}", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .assert_error(StatusCode::FORBIDDEN, "MISSING_PERMISSION");
+
+    app.delete(format!("/ministries/{}", id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+
+    app.get(format!("/ministries/{}", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "MINISTRY_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn manage_ministry_members() {
+    let app = app().await;
+    let ministry = create_ministry(&app, "Worship").await;
+    let id = ministry["id"].as_str().unwrap();
+    let role = create_ministry_role(&app, "Member", 10).await;
+    create_user(&app, "alice").await;
+    create_user(&app, "bob").await;
+
+    app.post(format!("/ministries/{}/users", id))
+        .bearer(&admin(&app))
+        .json(json!({
+            "users": [
+                { "user_id": "alice", "role_id": role["id"] },
+                { "user_id": "bob", "role_id": role["id"] },
+            ],
+        }))
+        .send()
+        .await
+        .ok();
+
+    app.post(format!("/ministries/{}/users", id))
+        .bearer(&admin(&app))
+        .json(json!({ "users": [{ "user_id": "mallory", "role_id": role["id"] }] }))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "USER_NOT_FOUND");
+
+    let page = app
+        .get(format!("/ministries/{}/users?sort=name", id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(ids(&page), ["alice", "bob"]);
+
+    app.delete(format!("/ministries/{}/users", id))
+        .bearer(&admin(&app))
+        .json(json!({ "users": ["alice"] }))
+        .send()
+        .await
+        .ok();
+
+    let page = app
+        .get(format!("/ministries/{}/users", id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(ids(&page), ["bob"]);
+}
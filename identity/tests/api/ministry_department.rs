@@ -0,0 +1,55 @@
+use poem::http::StatusCode;
+use serde_json::json;
+
+use crate::{admin, app, create_ministry_department, ids};
+
+#[tokio::test]
+async fn manage_ministry_departments() {
+    let app = app().await;
+    let created = create_ministry_department(&app, "Worship").await;
+    let id = created["id"].as_str().unwrap();
+
+    app.post("/ministry-departments")
+        .bearer(&app.token("alice"))
+        .json(json!({ "name": "Media", "description": "Media" }))
+        .send()
+        .await
+        .assert_error(StatusCode::FORBIDDEN, "MISSING_PERMISSION");
+
+    let page = app
+        .get("/ministry-departments")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(ids(&page), [id]);
+
+    let fetched = app
+        .get(format!("/ministry-departments/{}", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(fetched["name"], "Worship");
+
+    let updated = app
+        .patch(format!("/ministry-departments/{}", id))
+        .bearer(&admin(&app))
+        .json(json!({ "description": "Praise and worship" }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(updated["description"], "Praise and worship");
+
+    app.delete(format!("/ministry-departments/{}", id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+
+    app.get(format!("/ministry-departments/{}", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "MINISTRY_DEPARTMENT_NOT_FOUND");
+}
@@ -0,0 +1,76 @@
+use common::testing::Role;
+use poem::http::StatusCode;
+use serde_json::json;
+
+use crate::{admin, app, create_ministry_role, ids};
+
+#[tokio::test]
+async fn manage_ministry_roles() {
+    let app = app().await;
+
+    app.post("/ministry-roles")
+        .bearer(&app.token("alice"))
+        .json(json!({ "name": "Leader", "description": "Leader", "weight": 4 }))
+        .send()
+        .await
+        .assert_error(StatusCode::FORBIDDEN, "MISSING_PERMISSION");
+    assert!(app.management.roles().is_empty());
+
+    // Roles are created on Auth0 first, the id of the Auth0 role is used as the role id.
+    let role = create_ministry_role(&app, "Leader", 4).await;
+    let id = role["id"].as_str().unwrap();
+    assert_eq!(
+        app.management.role(id),
+        Some(Role {
+            id: id.to_string(),
+            name: "Leader".to_string(),
+            description: "Leader".to_string(),
+        })
+    );
+
+    let page = app
+        .get("/ministry-roles")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(ids(&page), [id]);
+
+    let fetched = app
+        .get(format!("/ministry-roles/{}", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(fetched["weight"], 4);
+
+    let updated = app
+        .patch(format!("/ministry-roles/{}", id))
+        .bearer(&admin(&app))
+        .json(json!({ "name": "Senior leader", "weight": 3 }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(updated["name"], "Senior leader");
+    assert_eq!(updated["weight"], 3);
+    assert_eq!(app.management.role(id).unwrap().name, "Senior leader");
+
+    app.delete(format!("/ministry-roles/{}", id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert!(app.management.roles().is_empty());
+
+    app.get(format!("/ministry-roles/{}", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "MINISTRY_ROLE_NOT_FOUND");
+
+    app.delete(format!("/ministry-roles/{}", id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "MINISTRY_ROLE_NOT_FOUND");
+}
@@ -0,0 +1,117 @@
+use poem::http::StatusCode;
+use serde_json::json;
+
+use crate::{
+    admin, app, create_connect_group, create_ministry, create_ministry_role, create_pastoral_role,
+    create_user,
+};
+
+#[tokio::test]
+async fn get_org_tree() {
+    let app = app().await;
+    let ministry = create_ministry(&app, "Worship").await;
+    let connect_group = create_connect_group(&app, 2).await;
+    let coach = create_pastoral_role(&app, "Coach", 3).await;
+    let leader = create_pastoral_role(&app, "CGL", 4).await;
+    let member = create_pastoral_role(&app, "Member", 10).await;
+    let head = create_ministry_role(&app, "Head", 1).await;
+    let volunteer = create_ministry_role(&app, "Volunteer", 10).await;
+    for user in ["alice", "bob", "carol"] {
+        create_user(&app, user).await;
+    }
+
+    app.post(format!(
+        "/connect-groups/{}/users",
+        connect_group["id"].as_str().unwrap()
+    ))
+    .bearer(&admin(&app))
+    .json(json!({
+        "users": [
+            { "user_id": "alice", "role_id": leader["id"] },
+            { "user_id": "bob", "role_id": member["id"] },
+            { "user_id": "carol", "role_id": coach["id"] },
+        ],
+    }))
+    .send()
+    .await
+    .ok();
+    app.post(format!(
+        "/ministries/{}/users",
+        ministry["id"].as_str().unwrap()
+    ))
+    .bearer(&admin(&app))
+    .json(json!({
+        "users": [
+            { "user_id": "alice", "role_id": volunteer["id"] },
+            { "user_id": "bob", "role_id": head["id"] },
+        ],
+    }))
+    .send()
+    .await
+    .ok();
+
+    let tree = app
+        .get("/org-tree")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(tree.as_array().unwrap().len(), 2);
+
+    let satellite = &tree[0];
+    assert_eq!(satellite["id"], ministry["satellite_id"]);
+    assert_eq!(satellite["member_count"], 2);
+    let department = &satellite["departments"][0];
+    assert_eq!(department["id"], ministry["department_id"]);
+    assert_eq!(department["teams"][0]["id"], ministry["team_id"]);
+    let node = &department["teams"][0]["ministries"][0];
+    assert_eq!(node["id"], ministry["id"]);
+    assert_eq!(node["member_count"], 2);
+    assert_eq!(
+        node["leaders"],
+        json!([{
+            "user_id": "bob",
+            "name": "bob",
+            "role_id": head["id"],
+            "role_name": "Head",
+        }])
+    );
+
+    let satellite = app
+        .get(format!(
+            "/satellites/{}/org-tree",
+            connect_group["satellite_id"].as_str().unwrap()
+        ))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(satellite, tree[1]);
+    assert_eq!(satellite["member_count"], 3);
+    assert_eq!(satellite["leaders"][0]["user_id"], "carol");
+    let category = &satellite["categories"][0];
+    assert_eq!(category["id"], connect_group["category_id"]);
+    let node = &category["connect_groups"][0];
+    assert_eq!(node["id"], connect_group["id"]);
+    assert_eq!(node["member_count"], 3);
+    let leaders = node["leaders"].as_array().unwrap();
+    let leaders = leaders.iter().map(|l| l["user_id"].as_str().unwrap());
+    assert_eq!(leaders.collect::<Vec<_>>(), ["carol", "alice"]);
+
+    app.get("/satellites/satellite_missing/org-tree")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "SATELLITE_NOT_FOUND");
+
+    let tree = app
+        .get("/org-tree?fields=id,member_count")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(
+        tree[1],
+        json!({ "id": connect_group["satellite_id"], "member_count": 3 })
+    );
+}
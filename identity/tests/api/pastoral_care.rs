@@ -0,0 +1,103 @@
+use poem::http::StatusCode;
+use serde_json::{json, Value};
+
+use crate::{admin, app, create_connect_group, create_pastoral_role, create_user};
+
+fn user_ids(links: &Value) -> Vec<&str> {
+    links
+        .as_array()
+        .expect("response is a list")
+        .iter()
+        .map(|link| link["user_id"].as_str().expect("link has a user id"))
+        .collect()
+}
+
+#[tokio::test]
+async fn resolve_leaders_and_flock() {
+    let app = app().await;
+    let connect_group = create_connect_group(&app, 1).await;
+    let leader = create_pastoral_role(&app, "CGL", 4).await;
+    let member = create_pastoral_role(&app, "Member", 10).await;
+    for user in ["alice", "bob", "carol", "dave"] {
+        create_user(&app, user).await;
+    }
+    app.post(format!(
+        "/connect-groups/{}/users",
+        connect_group["id"].as_str().unwrap()
+    ))
+    .bearer(&admin(&app))
+    .json(json!({
+        "users": [
+            { "user_id": "alice", "role_id": leader["id"] },
+            { "user_id": "bob", "role_id": member["id"] },
+        ],
+    }))
+    .send()
+    .await
+    .ok();
+
+    // Carol coaches alice, and is herself overseen by dave.
+    for (user, overseer) in [("alice", "carol"), ("carol", "dave")] {
+        app.put(format!("/users/{}/overseer", user))
+            .bearer(&admin(&app))
+            .json(json!({ "overseer_id": overseer }))
+            .send()
+            .await
+            .ok();
+    }
+    app.put("/users/dave/overseer")
+        .bearer(&admin(&app))
+        .json(json!({ "overseer_id": "alice" }))
+        .send()
+        .await
+        .assert_error(StatusCode::BAD_REQUEST, "USER_OVERSIGHT_CYCLE");
+
+    let leaders = app
+        .get("/users/bob/leaders")
+        .bearer(&app.token("bob"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(user_ids(&leaders), ["alice", "carol", "dave"]);
+    assert_eq!(leaders[0]["connect_group_id"], connect_group["id"]);
+    assert_eq!(leaders[0]["role_id"], leader["id"]);
+    assert_eq!(leaders[1]["connect_group_id"], json!(null));
+    assert_eq!(leaders[2]["depth"], 3);
+
+    let leaders = app
+        .get("/users/bob/leaders?max_depth=2")
+        .bearer(&app.token("bob"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(user_ids(&leaders), ["alice", "carol"]);
+
+    let flock = app
+        .get("/users/alice/flock")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(user_ids(&flock), ["bob"]);
+
+    let flock = app
+        .get("/users/dave/flock?max_depth=3")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(user_ids(&flock), ["carol", "alice", "bob"]);
+
+    app.delete("/users/alice/overseer")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    let leaders = app
+        .get("/users/bob/leaders")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(user_ids(&leaders), ["alice"]);
+}
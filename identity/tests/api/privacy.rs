@@ -0,0 +1,159 @@
+use poem::http::StatusCode;
+use serde_json::json;
+
+use crate::{admin, app, create_user};
+
+#[tokio::test]
+async fn export_and_erase_user() {
+    let app = app().await;
+    create_user(&app, "alice").await;
+    create_user(&app, "bob").await;
+    let alice = app.token("alice");
+
+    app.patch("/users/alice")
+        .bearer(&alice)
+        .json(
+            json!({ "id": "alice", "ic_number": "900101-14-5678", "phone_number": "+60123456789" }),
+        )
+        .send()
+        .await
+        .ok();
+    app.post("/users/alice/relationships")
+        .bearer(&alice)
+        .json(json!({ "destination_user_id": "bob", "relationship": "sibling" }))
+        .send()
+        .await
+        .ok();
+
+    // The event service writes the attendance to the same database.
+    for sql in [
+        "INSERT INTO event_type (name) VALUES ('camp')",
+        "INSERT INTO event (id, name, description, type) VALUES ('event_1', 'Camp', 'Camp', 'camp')",
+        r#"
+        INSERT INTO "session" (id, event_id, name, expected_attendees, start_at, end_at)
+        VALUES ('session_1', 'event_1', 'Day 1', 10, NOW(), NOW())
+        "#,
+        "INSERT INTO attendance (session_id, user_id) VALUES ('session_1', 'alice')",
+    ] {
+        sqlx::query(sql)
+            .execute(&app.database.db)
+            .await
+            .expect("failed to record an attendance");
+    }
+
+    app.get("/users/alice/export")
+        .bearer(&app.token("bob"))
+        .send()
+        .await
+        .assert_error(StatusCode::FORBIDDEN, "MISSING_PERMISSION");
+
+    let export = app
+        .get("/users/alice/export")
+        .bearer(&alice)
+        .send()
+        .await
+        .ok();
+    assert_eq!(export["user_id"], "alice");
+    assert_eq!(export["user"][0]["ic_number"], "900101-14-5678");
+    assert_eq!(export["relationships"].as_array().unwrap().len(), 2);
+    assert_eq!(export["attendance"][0]["event_name"], "Camp");
+    assert!(!export["audit_log"].as_array().unwrap().is_empty());
+
+    let archive = app
+        .get("/users/alice/export?format=zip")
+        .bearer(&alice)
+        .send()
+        .await;
+    archive.assert_status(StatusCode::OK);
+    assert!(archive.headers["content-disposition"]
+        .to_str()
+        .unwrap()
+        .ends_with(".zip\""));
+
+    // Only admins may erase a user, not even the user themselves.
+    app.post("/users/alice/erase")
+        .bearer(&alice)
+        .json(json!({}))
+        .send()
+        .await
+        .assert_error(StatusCode::FORBIDDEN, "MISSING_PERMISSION");
+
+    let erasure = app
+        .post("/users/alice/erase")
+        .bearer(&admin(&app))
+        .json(json!({ "reason": "Requested by the user" }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(erasure["requested_by"], "admin");
+    assert_eq!(erasure["erased_at"], json!(null));
+    app.post("/users/alice/erase")
+        .bearer(&admin(&app))
+        .json(json!({}))
+        .send()
+        .await
+        .assert_error(StatusCode::CONFLICT, "USER_ERASURE_EXISTS");
+
+    // Cancelled within the grace period.
+    app.delete("/users/alice/erase")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    app.delete("/users/alice/erase")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "USER_ERASURE_NOT_FOUND");
+
+    let erasure = app
+        .post("/users/alice/erase")
+        .bearer(&admin(&app))
+        .json(json!({ "grace_period_days": 0 }))
+        .send()
+        .await
+        .ok();
+    assert_ne!(erasure["erased_at"], json!(null));
+
+    // Carried out erasures cannot be cancelled.
+    app.delete("/users/alice/erase")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "USER_ERASURE_NOT_FOUND");
+
+    let export = app
+        .get("/users/alice/export")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(export["user"][0]["name"], "Erased user");
+    assert_eq!(export["user"][0]["email"], "alice@erased.invalid");
+    assert_eq!(export["user"][0]["ic_number"], json!(null));
+    assert_eq!(export["user"][0]["deleted"], true);
+    assert_eq!(export["relationships"], json!([]));
+    // The attendance is still counted.
+    assert_eq!(export["attendance"].as_array().unwrap().len(), 1);
+
+    let page = app
+        .get("/audit?resource=user&resource_id=alice")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    for log in page["items"].as_array().unwrap() {
+        for recorded in [&log["before"], &log["after"], &log["changes"]] {
+            assert_eq!(recorded["ic_number"], json!(null));
+            assert_eq!(recorded["email"], json!(null));
+        }
+    }
+
+    let relationships = app
+        .get("/users/bob/relationships")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(relationships, json!([]));
+}
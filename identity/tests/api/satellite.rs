@@ -0,0 +1,76 @@
+use poem::http::StatusCode;
+use serde_json::json;
+
+use crate::{address, admin, app, create_connect_group, create_satellite, ids};
+
+#[tokio::test]
+async fn create_satellite_requires_permission() {
+    let app = app().await;
+
+    app.post("/satellites")
+        .bearer(&app.token("alice"))
+        .json(json!({ "no": 1, "name": "Puchong", "address": address() }))
+        .send()
+        .await
+        .assert_error(StatusCode::FORBIDDEN, "MISSING_PERMISSION");
+}
+
+#[tokio::test]
+async fn manage_satellites() {
+    let app = app().await;
+    let satellite = create_satellite(&app, 1).await;
+    let id = satellite["id"].as_str().unwrap();
+    assert_eq!(satellite["address"]["city"], "Puchong");
+
+    let page = app
+        .get("/satellites")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(ids(&page), [id]);
+
+    let fetched = app
+        .get(format!("/satellites/{}", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(fetched["name"], "Satellite 1");
+
+    let updated = app
+        .patch(format!("/satellites/{}", id))
+        .bearer(&admin(&app))
+        .json(json!({ "name": "Puchong" }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(updated["name"], "Puchong");
+
+    app.delete(format!("/satellites/{}", id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+
+    app.get(format!("/satellites/{}", id))
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "SATELLITE_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn delete_satellite_in_use() {
+    let app = app().await;
+    let connect_group = create_connect_group(&app, 1).await;
+
+    app.delete(format!(
+        "/satellites/{}",
+        connect_group["satellite_id"].as_str().unwrap()
+    ))
+    .bearer(&admin(&app))
+    .send()
+    .await
+    .assert_error(StatusCode::CONFLICT, "RESOURCE_IN_USE");
+}
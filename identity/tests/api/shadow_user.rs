@@ -0,0 +1,175 @@
+use poem::http::StatusCode;
+use serde_json::json;
+
+use crate::{
+    admin, app, create_connect_group, create_ministry, create_ministry_role, create_pastoral_role,
+    create_user, insert_shadow_user, ShadowUser,
+};
+
+#[tokio::test]
+async fn list_shadow_users() {
+    let app = app().await;
+    let no = insert_shadow_user(&app, ShadowUser::new("Alice Tan", "0123456789")).await;
+    insert_shadow_user(&app, ShadowUser::new("Bob Lim", "0198765432")).await;
+
+    app.get("/shadow-users")
+        .bearer(&app.token("alice").permissions(["users:write"]))
+        .send()
+        .await
+        .assert_error(StatusCode::FORBIDDEN, "MISSING_PERMISSION");
+
+    let page = app
+        .get("/shadow-users?search=alice")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(page["items"][0]["no"], no);
+    assert_eq!(page["total_estimate"], 1);
+
+    let page = app
+        .get("/shadow-users?limit=1&sort=no")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(page["items"][0]["name"], "Alice Tan");
+    let cursor = page["next_cursor"].as_str().expect("there is a next page");
+
+    let page = app
+        .get(format!("/shadow-users?limit=1&sort=no&cursor={}", cursor))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(page["items"][0]["name"], "Bob Lim");
+    assert!(page["next_cursor"].is_null());
+
+    let shadow_user = app
+        .get(format!("/shadow-users/{}", no))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(shadow_user["name"], "Alice Tan");
+    assert!(shadow_user["claimed_by"].is_null());
+
+    app.get("/shadow-users/0")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "SHADOW_USER_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn match_shadow_users() {
+    let app = app().await;
+    create_user(&app, "alice").await;
+    create_user(&app, "bob").await;
+    app.patch("/users/alice")
+        .bearer(&admin(&app))
+        .json(json!({ "id": "alice", "phone_number": "+60 12-345 6789" }))
+        .send()
+        .await
+        .ok();
+    let no = insert_shadow_user(&app, ShadowUser::new("Alice Tan", "0123456789")).await;
+
+    let matches = app
+        .get(format!("/shadow-users/{}/matches", no))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    let matches = matches.as_array().unwrap();
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0]["user"]["id"], "alice");
+    assert_eq!(matches[0]["matched_on"], json!(["phone_number", "name"]));
+    assert!(matches[0]["score"].as_f64().unwrap() >= 0.8);
+}
+
+#[tokio::test]
+async fn confirm_shadow_user() {
+    let app = app().await;
+    create_user(&app, "alice").await;
+    let connect_group = create_connect_group(&app, 1).await;
+    let pastoral_role = create_pastoral_role(&app, "CGL", 4).await;
+    let ministry = create_ministry(&app, "Worship").await;
+    create_ministry_role(&app, "Member", 5).await;
+    let no = insert_shadow_user(
+        &app,
+        ShadowUser {
+            cg: Some("1 A"),
+            pastoral_status: Some("cgl"),
+            ministry: Some("Worship, Choir"),
+            ..ShadowUser::new("Alice Tan", "0123456789")
+        },
+    )
+    .await;
+
+    let resolution = app
+        .get(format!("/shadow-users/{}/resolution", no))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(resolution["connect_group"]["id"], connect_group["id"]);
+    assert_eq!(resolution["pastoral_role"]["id"], pastoral_role["id"]);
+    assert_eq!(resolution["ministries"][0]["id"], ministry["id"]);
+    assert_eq!(
+        resolution["unresolved"],
+        json!([{ "field": "ministry", "value": "Choir" }])
+    );
+
+    let response = app
+        .post(format!("/shadow-users/{}/confirm", no))
+        .bearer(&admin(&app))
+        .json(json!({ "user_id": "alice" }))
+        .send()
+        .await;
+    response.assert_error(StatusCode::BAD_REQUEST, "SHADOW_USER_UNRESOLVED");
+    assert_eq!(response.body["details"][0]["field"], "ministry_ids");
+
+    let user = app
+        .post(format!("/shadow-users/{}/confirm", no))
+        .bearer(&admin(&app))
+        .json(json!({ "user_id": "alice", "ministry_ids": [ministry["id"]] }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(user["phone_number"], "0123456789");
+    assert_eq!(user["ic_number"], "900101-14-5678");
+    // Fields the user filled in themselves are kept.
+    assert_eq!(user["name"], "alice");
+
+    let connect_groups = app
+        .get("/users/alice/connect-groups")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(connect_groups[0]["cg"]["id"], connect_group["id"]);
+    assert_eq!(connect_groups[0]["role"]["id"], pastoral_role["id"]);
+
+    let ministries = app
+        .get("/users/alice/ministries")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(ministries.as_array().unwrap().len(), 1);
+
+    let shadow_user = app
+        .get(format!("/shadow-users/{}", no))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(shadow_user["claimed_by"], "alice");
+
+    app.post(format!("/shadow-users/{}/confirm", no))
+        .bearer(&admin(&app))
+        .json(json!({ "user_id": "alice" }))
+        .send()
+        .await
+        .assert_error(StatusCode::CONFLICT, "SHADOW_USER_ALREADY_CLAIMED");
+}
@@ -0,0 +1,110 @@
+use poem::http::StatusCode;
+use serde_json::json;
+
+use common::testing::TestApp;
+
+use crate::{admin, app, create_user};
+
+async fn create_named_user(app: &TestApp, id: &str, name: &str, email: &str) {
+    app.post("/users")
+        .bearer(&admin(app))
+        .json(json!({ "id": id, "name": name, "email": email }))
+        .send()
+        .await
+        .ok();
+}
+
+#[tokio::test]
+async fn detect_and_review_user_duplicates() {
+    let app = app().await;
+    create_named_user(&app, "alice", "Alice Tan", "Alice.Tan+church@example.com").await;
+    create_named_user(&app, "alice2", "Alice Tan", "alicetan@example.com").await;
+    create_user(&app, "bob").await;
+
+    app.post("/user-duplicates/detect")
+        .bearer(&app.token("alice").permissions(["users:write"]))
+        .send()
+        .await
+        .assert_error(StatusCode::FORBIDDEN, "MISSING_PERMISSION");
+
+    let detection = app
+        .post("/user-duplicates/detect")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(detection["found"], 1);
+
+    let page = app
+        .get("/user-duplicates?status=pending&sort=-score")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(page["items"].as_array().unwrap().len(), 1);
+    let duplicate = &page["items"][0];
+    assert_eq!(duplicate["user_id"], "alice");
+    assert_eq!(duplicate["duplicate_user_id"], "alice2");
+    assert_eq!(duplicate["matched_on"], json!(["email", "name"]));
+    assert!(duplicate["score"].as_f64().unwrap() > 0.9);
+
+    let duplicate = app
+        .patch("/user-duplicates/alice/alice2")
+        .bearer(&admin(&app))
+        .json(json!({ "status": "dismissed" }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(duplicate["status"], "dismissed");
+    assert_eq!(duplicate["reviewed_by"], "admin");
+
+    // Detecting again keeps the review.
+    app.post("/user-duplicates/detect")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    let page = app
+        .get("/user-duplicates?user_id=alice2")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(page["items"][0]["status"], "dismissed");
+
+    app.patch("/user-duplicates/alice/alice2")
+        .bearer(&admin(&app))
+        .json(json!({ "status": "merged" }))
+        .send()
+        .await
+        .assert_error(StatusCode::BAD_REQUEST, "USER_DUPLICATE_STATUS_INVALID");
+
+    app.post("/users/alice/merge")
+        .bearer(&admin(&app))
+        .json(json!({ "merged_user_id": "alice2" }))
+        .send()
+        .await
+        .ok();
+
+    let page = app
+        .get("/user-duplicates?status=merged")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(page["items"].as_array().unwrap().len(), 1);
+
+    app.patch("/user-duplicates/alice/alice2")
+        .bearer(&admin(&app))
+        .json(json!({ "status": "pending" }))
+        .send()
+        .await
+        .assert_error(StatusCode::CONFLICT, "USER_DUPLICATE_ALREADY_MERGED");
+
+    app.patch("/user-duplicates/alice/bob")
+        .bearer(&admin(&app))
+        .json(json!({ "status": "dismissed" }))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "USER_DUPLICATE_NOT_FOUND");
+}
@@ -0,0 +1,1054 @@
+use common::{crypto::Keyring, testing};
+use identity::{auth0_sync, pii};
+use poem::http::StatusCode;
+use serde_json::json;
+
+use crate::{
+    address, admin, app, create_connect_group, create_ministry, create_ministry_role,
+    create_pastoral_role, create_user, ids,
+};
+
+#[tokio::test]
+async fn rejects_missing_and_invalid_tokens() {
+    let app = app().await;
+
+    app.get("/users")
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    app.get("/users")
+        .bearer(&admin(&app).issuer("https://attacker.example.com/"))
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    app.get("/users")
+        .bearer(&admin(&app).expires_at(1))
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    app.get("/users")
+        .bearer(&admin(&app).scope("openid"))
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn returns_request_id_on_errors() {
+    let app = app().await;
+
+    let response = app.get("/users/missing").bearer(&admin(&app)).send().await;
+
+    response.assert_error(StatusCode::NOT_FOUND, "USER_NOT_FOUND");
+    let request_id = response.headers["x-request-id"].to_str().unwrap();
+    assert_eq!(response.body["request_id"], request_id);
+}
+
+#[tokio::test]
+async fn create_users() {
+    let app = app().await;
+
+    // Users may register themselves, but not vouch for their own email or phone number.
+    let user = app
+        .post("/users")
+        .bearer(&app.token("alice"))
+        .json(json!({
+            "id": "alice",
+            "name": "Alice",
+            "email": "alice@example.com",
+            "email_verified": true,
+            "phone_number": "+60123456789",
+            "phone_number_verified": true,
+        }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(user["id"], "alice");
+    assert_eq!(user["deleted"], false);
+    assert_eq!(user["email_verified"], false);
+    assert_eq!(user["phone_number_verified"], false);
+
+    let user = app
+        .post("/users")
+        .bearer(&admin(&app))
+        .json(json!({
+            "id": "carol",
+            "name": "Carol",
+            "email": "carol@example.com",
+            "email_verified": true,
+        }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(user["email_verified"], true);
+
+    app.post("/users")
+        .bearer(&app.token("alice"))
+        .json(json!({ "id": "bob", "name": "Bob", "email": "bob@example.com" }))
+        .send()
+        .await
+        .assert_error(StatusCode::FORBIDDEN, "MISSING_PERMISSION");
+
+    let response = app
+        .post("/users")
+        .bearer(&admin(&app))
+        .json(json!({ "id": "bob", "name": "Bob", "email": "alice@example.com" }))
+        .send()
+        .await;
+    response.assert_error(StatusCode::CONFLICT, "USER_EMAIL_TAKEN");
+    assert_eq!(response.body["details"][0]["field"], "email");
+}
+
+#[tokio::test]
+async fn list_users() {
+    let app = app().await;
+    create_user(&app, "alice").await;
+    create_user(&app, "bob").await;
+
+    let page = app
+        .get("/users?sort=name")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(ids(&page), ["alice", "bob"]);
+
+    let page = app
+        .get("/users?limit=1&sort=name")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(ids(&page), ["alice"]);
+    assert_eq!(page["total_estimate"], 2);
+    let cursor = page["next_cursor"].as_str().expect("there is a next page");
+
+    let page = app
+        .get(format!("/users?limit=1&sort=name&cursor={}", cursor))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(ids(&page), ["bob"]);
+    // Only the first page is counted.
+    assert!(page["total_estimate"].is_null());
+
+    // Without `users:read` only the user themselves is visible.
+    let page = app
+        .get("/users")
+        .bearer(&app.token("bob"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(ids(&page), ["bob"]);
+
+    app.get("/users?sort=password")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .assert_error(StatusCode::BAD_REQUEST, "INVALID_SORT");
+}
+
+#[tokio::test]
+async fn search_users() {
+    let app = app().await;
+    for user in [
+        json!({
+            "id": "alice",
+            "name": "Alice Tan",
+            "email": "alice@example.com",
+            "gender": "female",
+            "ic_number": "900101-14-5678",
+            "phone_number": "012-345 6789",
+            "date_of_birth": "1990-01-01T00:00:00Z",
+        }),
+        json!({
+            "id": "alicia",
+            "name": "Alicia Wong",
+            "email": "alicia@example.com",
+            "gender": "female",
+            "date_of_birth": "2020-06-01T00:00:00Z",
+        }),
+        json!({
+            "id": "bob",
+            "name": "Bob Lim",
+            "email": "bob@example.com",
+            "gender": "male",
+        }),
+    ] {
+        app.post("/users")
+            .bearer(&admin(&app))
+            .json(user)
+            .send()
+            .await
+            .ok();
+    }
+
+    let search = |query: &'static str| {
+        let app = &app;
+        async move {
+            let page = app
+                .get(format!("/users?{}", query))
+                .bearer(&admin(app))
+                .send()
+                .await
+                .ok();
+            ids(&page).into_iter().map(String::from).collect::<Vec<_>>()
+        }
+    };
+
+    // Exact matches rank above partial and misspelt ones.
+    assert_eq!(search("search=alice").await[0], "alice");
+    assert_eq!(search("search=alica&sort=name").await, ["alice", "alicia"]);
+    assert_eq!(search("search=ali&sort=name").await, ["alice", "alicia"]);
+    assert_eq!(search("search=%22alice%20tan%22").await, ["alice"]);
+    assert_eq!(search("search=lim").await, ["bob"]);
+    assert_eq!(search("search=%2B60%2012-3456789").await, ["alice"]);
+    assert_eq!(search("search=900101145678").await, ["alice"]);
+    assert_eq!(search("search=%20&sort=name").await.len(), 3);
+    assert!(search("search=%26%21(").await.is_empty());
+
+    assert_eq!(search("gender=male").await, ["bob"]);
+    assert_eq!(search("min_age=18").await, ["alice"]);
+    assert_eq!(search("max_age=17").await, ["alicia"]);
+
+    app.get("/users?sort=relevance")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .assert_error(StatusCode::BAD_REQUEST, "INVALID_SORT");
+}
+
+#[tokio::test]
+async fn get_user() {
+    let app = app().await;
+    create_user(&app, "alice").await;
+    create_user(&app, "bob").await;
+
+    let user = app
+        .get("/users/alice")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(user["name"], "alice");
+
+    app.get("/users/alice")
+        .bearer(&app.token("bob"))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "USER_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn update_user() {
+    let app = app().await;
+    create_user(&app, "alice").await;
+
+    let user = app
+        .patch("/users/alice")
+        .bearer(&app.token("alice"))
+        .json(json!({ "id": "alice", "nickname": "Ali" }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(user["nickname"], "Ali");
+
+    // Only admins may mark an email or phone number verified.
+    let user = app
+        .patch("/users/alice")
+        .bearer(&app.token("alice"))
+        .json(json!({ "id": "alice", "email_verified": true, "phone_number_verified": true }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(user["email_verified"], false);
+    assert_ne!(user["phone_number_verified"], true);
+    let user = app
+        .patch("/users/alice")
+        .bearer(&admin(&app))
+        .json(json!({ "id": "alice", "email_verified": true }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(user["email_verified"], true);
+
+    app.patch("/users/alice")
+        .bearer(&app.token("bob"))
+        .json(json!({ "id": "alice", "nickname": "Bob" }))
+        .send()
+        .await
+        .assert_error(StatusCode::FORBIDDEN, "MISSING_PERMISSION");
+}
+
+#[tokio::test]
+async fn delete_user() {
+    let app = app().await;
+    create_user(&app, "alice").await;
+
+    app.delete("/users/alice")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .assert_error(StatusCode::FORBIDDEN, "MISSING_PERMISSION");
+
+    let user = app
+        .delete("/users/alice")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(user["deleted"], true);
+
+    app.delete("/users/missing")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "USER_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn restore_and_purge_users() {
+    let app = app().await;
+    let connect_group = create_connect_group(&app, 1).await;
+    let id = connect_group["id"].as_str().unwrap();
+    let member = create_pastoral_role(&app, "Member", 10).await;
+    for user in ["alice", "bob", "carol"] {
+        create_user(&app, user).await;
+    }
+    app.post(format!("/connect-groups/{}/users", id))
+        .bearer(&admin(&app))
+        .json(json!({ "users": [{ "user_id": "alice", "role_id": member["id"] }] }))
+        .send()
+        .await
+        .ok();
+    app.delete("/users/alice")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+
+    // Deleted users are left out everywhere.
+    let page = app
+        .get("/users?sort=name")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(ids(&page), ["bob", "carol"]);
+    let page = app
+        .get(format!("/connect-groups/{}/users", id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(ids(&page), Vec::<&str>::new());
+    app.get("/users/alice/connect-groups")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "USER_NOT_FOUND");
+    app.post("/users/bob/relationships")
+        .bearer(&admin(&app))
+        .json(json!({ "destination_user_id": "alice", "relationship": "sibling" }))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "USER_NOT_FOUND");
+    app.delete("/users/alice")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "USER_NOT_FOUND");
+
+    app.get("/users?include_deleted=true")
+        .bearer(&app.token("bob").permissions(["users:read"]))
+        .send()
+        .await
+        .assert_error(StatusCode::FORBIDDEN, "MISSING_PERMISSION");
+    let page = app
+        .get("/users?include_deleted=true&sort=name")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(ids(&page), ["alice", "bob", "carol"]);
+
+    app.post("/users/bob/restore")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "USER_NOT_FOUND");
+    let user = app
+        .post("/users/alice/restore")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(user["deleted"], false);
+    let page = app
+        .get(format!("/connect-groups/{}/users", id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(ids(&page), ["alice"]);
+
+    app.post("/users/bob/merge")
+        .bearer(&admin(&app))
+        .json(json!({ "merged_user_id": "carol" }))
+        .send()
+        .await
+        .ok();
+    app.post("/users/carol/restore")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .assert_error(StatusCode::CONFLICT, "USER_MERGED");
+
+    app.post("/users/alice/purge")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .assert_error(StatusCode::CONFLICT, "USER_NOT_DELETED");
+    app.delete("/users/alice")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    app.post("/users/alice/purge")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .assert_error(StatusCode::FORBIDDEN, "MISSING_PERMISSION");
+    app.post("/users/alice/purge")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+
+    let page = app
+        .get("/users?include_deleted=true&sort=name")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(ids(&page), ["bob", "carol"]);
+    let history = app
+        .get(format!("/connect-groups/{}/history", id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(history["items"], json!([]));
+
+    // Only the audit log of the purge is kept, without personal data.
+    let page = app
+        .get("/audit?resource=user&resource_id=alice")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert!(!page["items"].as_array().unwrap().is_empty());
+    for log in page["items"].as_array().unwrap() {
+        for recorded in [&log["before"], &log["after"], &log["changes"]] {
+            assert_eq!(recorded["name"], json!(null));
+            assert_eq!(recorded["email"], json!(null));
+        }
+    }
+
+    // The merges into a user are kept, only the merge of a purged user is removed.
+    app.delete("/users/bob")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    app.post("/users/bob/purge")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .assert_error(StatusCode::CONFLICT, "USER_HAS_MERGES");
+    let merges: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM user_merge WHERE user_id = 'bob'")
+        .fetch_one(&app.database.db)
+        .await
+        .unwrap();
+    assert_eq!(merges, 1);
+    app.post("/users/carol/purge")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    let merges: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM user_merge")
+        .fetch_one(&app.database.db)
+        .await
+        .unwrap();
+    assert_eq!(merges, 0);
+}
+
+#[tokio::test]
+async fn encrypts_and_masks_sensitive_details() {
+    let app = app().await;
+    app.post("/users")
+        .bearer(&admin(&app))
+        .json(json!({
+            "id": "alice",
+            "name": "Alice Tan",
+            "email": "alice@example.com",
+            "ic_number": "900101-14-5678",
+            "phone_number": "012-345 6789",
+            "address": address(),
+            "date_of_birth": "1990-01-01T00:00:00Z",
+        }))
+        .send()
+        .await
+        .ok();
+
+    let (plaintext, encrypted): (bool, bool) = sqlx::query_as(
+        r#"
+        SELECT
+            num_nonnulls(ic_number, phone_number, address, date_of_birth) > 0,
+            num_nulls(ic_number_encrypted, phone_number_encrypted, address_encrypted,
+                      date_of_birth_encrypted) = 0
+        FROM "user" WHERE id = 'alice'
+        "#,
+    )
+    .fetch_one(&app.database.db)
+    .await
+    .unwrap();
+    assert!(!plaintext);
+    assert!(encrypted);
+
+    // Users read their own details, others only with `pii:read` granted by their token.
+    for token in [app.token("alice"), admin(&app)] {
+        let user = app.get("/users/alice").bearer(&token).send().await.ok();
+        assert_eq!(user["ic_number"], "900101-14-5678");
+        assert_eq!(user["phone_number"], "012-345 6789");
+        assert_eq!(user["address"]["line_one"], "1 Jalan Kenari");
+        assert!(user["date_of_birth"].is_string());
+    }
+    let user = app
+        .get("/users/alice")
+        .bearer(&app.token("bob").permissions(["users:read"]))
+        .send()
+        .await
+        .ok();
+    assert_eq!(user["ic_number"], "********5678");
+    assert_eq!(user["phone_number"], "*******789");
+    assert_eq!(user["address"]["line_one"], "1 J**** K*****");
+    assert_eq!(user["address"]["city"], "Puchong");
+    assert_eq!(user["date_of_birth"], json!(null));
+
+    // Details stored before they were encrypted are encrypted by the rotation.
+    sqlx::query(
+        r#"
+        INSERT INTO "user" (id, name, email, phone_number)
+        VALUES ('legacy', 'Legacy', 'legacy@example.com', '+60 12-999 8888')
+        "#,
+    )
+    .execute(&app.database.db)
+    .await
+    .unwrap();
+
+    let keyring = Keyring::new(
+        &format!(
+            "rotated:C8XQ1doWxclEXacQZlSffUgcrUQeSHwInzEUtOxYOQc=,{}",
+            testing::PII_KEYS
+        ),
+        testing::PII_INDEX_KEY,
+    )
+    .unwrap();
+    assert_eq!(
+        pii::rotate(&app.database, &keyring, false).await.unwrap(),
+        2
+    );
+    assert_eq!(
+        pii::rotate(&app.database, &keyring, false).await.unwrap(),
+        0
+    );
+
+    let users = sqlx::query_as::<_, pii::UserRow>(r#"SELECT * FROM "user" ORDER BY id"#)
+        .fetch_all(&app.database.db)
+        .await
+        .unwrap();
+    let previous = Keyring::new(testing::PII_KEYS, testing::PII_INDEX_KEY).unwrap();
+    assert!(users[0].clone().open(&previous).is_err());
+    let [alice, legacy] = users
+        .into_iter()
+        .map(|user| user.open(&keyring).unwrap())
+        .collect::<Vec<_>>()
+        .try_into()
+        .unwrap();
+    assert_eq!(alice.ic_number.as_deref(), Some("900101-14-5678"));
+    assert_eq!(legacy.phone_number.as_deref(), Some("+60 12-999 8888"));
+
+    let plaintext: Option<String> =
+        sqlx::query_scalar(r#"SELECT phone_number FROM "user" WHERE id = 'legacy'"#)
+            .fetch_one(&app.database.db)
+            .await
+            .unwrap();
+    assert_eq!(plaintext, None);
+}
+
+#[tokio::test]
+async fn get_user_connect_groups_and_pastoral_roles() {
+    let app = app().await;
+    create_user(&app, "alice").await;
+    let connect_group = create_connect_group(&app, 1).await;
+    let role = create_pastoral_role(&app, "CGL", 4).await;
+
+    app.post(format!(
+        "/connect-groups/{}/users",
+        connect_group["id"].as_str().unwrap()
+    ))
+    .bearer(&admin(&app))
+    .json(json!({ "users": [{ "user_id": "alice", "role_id": role["id"] }] }))
+    .send()
+    .await
+    .ok();
+
+    let connect_groups = app
+        .get("/users/alice/connect-groups")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(connect_groups[0]["cg"]["id"], connect_group["id"]);
+    assert_eq!(connect_groups[0]["role"]["id"], role["id"]);
+
+    let roles = app
+        .get("/users/alice/pastoral-roles")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(roles[0]["id"], role["id"]);
+}
+
+#[tokio::test]
+async fn get_user_ministries_and_ministry_roles() {
+    let app = app().await;
+    create_user(&app, "alice").await;
+    let ministry = create_ministry(&app, "Worship").await;
+    let role = create_ministry_role(&app, "HOD", 2).await;
+
+    app.post(format!(
+        "/ministries/{}/users",
+        ministry["id"].as_str().unwrap()
+    ))
+    .bearer(&admin(&app))
+    .json(json!({ "users": [{ "user_id": "alice", "role_id": role["id"] }] }))
+    .send()
+    .await
+    .ok();
+
+    let ministries = app
+        .get("/users/alice/ministries")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(ministries.as_array().unwrap().len(), 1);
+
+    let roles = app
+        .get("/users/alice/ministry-roles")
+        .bearer(&app.token("alice"))
+        .send()
+        .await
+        .ok();
+    assert_eq!(roles[0]["id"], role["id"]);
+}
+
+#[tokio::test]
+async fn manage_user_relationships() {
+    let app = app().await;
+    create_user(&app, "alice").await;
+    create_user(&app, "bob").await;
+    let alice = app.token("alice");
+
+    let relationship = app
+        .post("/users/alice/relationships")
+        .bearer(&alice)
+        .json(json!({ "destination_user_id": "bob", "relationship": "sibling" }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(relationship["relationship"], "sibling");
+
+    app.post("/users/alice/relationships")
+        .bearer(&alice)
+        .json(json!({ "destination_user_id": "alice", "relationship": "self" }))
+        .send()
+        .await
+        .assert_error(StatusCode::BAD_REQUEST, "USER_RELATIONSHIP_WITH_SELF");
+
+    let relationships = app
+        .get("/users/alice/relationships")
+        .bearer(&alice)
+        .send()
+        .await
+        .ok();
+    assert_eq!(relationships[0]["destination_user_id"], "bob");
+
+    let relationship = app
+        .patch("/users/alice/relationships")
+        .bearer(&alice)
+        .json(json!({ "destination_user_id": "bob", "relationship": "spouse" }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(relationship["relationship"], "spouse");
+
+    app.delete("/users/alice/relationships")
+        .bearer(&alice)
+        .json(json!({ "destination_user_id": "bob" }))
+        .send()
+        .await
+        .ok();
+
+    app.delete("/users/alice/relationships")
+        .bearer(&alice)
+        .json(json!({ "destination_user_id": "bob" }))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "USER_RELATIONSHIP_NOT_FOUND");
+
+    // Leaders only act on the members they oversee, and cannot create other users.
+    create_user(&app, "carol").await;
+    let connect_group = create_connect_group(&app, 1).await;
+    let leader = create_pastoral_role(&app, "CGL", 4).await;
+    let member = create_pastoral_role(&app, "Member", 6).await;
+    app.post(format!(
+        "/connect-groups/{}/users",
+        connect_group["id"].as_str().unwrap()
+    ))
+    .bearer(&admin(&app))
+    .json(json!({ "users": [
+        { "user_id": "carol", "role_id": leader["id"] },
+        { "user_id": "alice", "role_id": member["id"] },
+    ] }))
+    .send()
+    .await
+    .ok();
+    let carol = app.token("carol");
+
+    app.post("/users/alice/relationships")
+        .bearer(&carol)
+        .json(json!({ "destination_user_id": "bob", "relationship": "sibling" }))
+        .send()
+        .await
+        .ok();
+    app.post("/users/bob/relationships")
+        .bearer(&carol)
+        .json(json!({ "destination_user_id": "alice", "relationship": "sibling" }))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "USER_NOT_FOUND");
+    app.post("/users")
+        .bearer(&carol)
+        .json(json!({ "id": "dave", "name": "Dave", "email": "dave@example.com" }))
+        .send()
+        .await
+        .assert_error(StatusCode::FORBIDDEN, "MISSING_PERMISSION");
+}
+
+#[tokio::test]
+async fn merge_users() {
+    let app = app().await;
+    create_user(&app, "alice").await;
+    create_user(&app, "alice2").await;
+    create_user(&app, "bob").await;
+    let connect_group = create_connect_group(&app, 1).await;
+    let leader = create_pastoral_role(&app, "CGL", 4).await;
+    let member = create_pastoral_role(&app, "Member", 5).await;
+
+    app.post(format!(
+        "/connect-groups/{}/users",
+        connect_group["id"].as_str().unwrap()
+    ))
+    .bearer(&admin(&app))
+    .json(json!({
+        "users": [
+            { "user_id": "alice", "role_id": member["id"] },
+            { "user_id": "alice2", "role_id": leader["id"] },
+        ],
+    }))
+    .send()
+    .await
+    .ok();
+    for (source, destination) in [("alice2", "bob"), ("alice2", "alice")] {
+        app.post(format!("/users/{}/relationships", source))
+            .bearer(&admin(&app))
+            .json(json!({ "destination_user_id": destination, "relationship": "sibling" }))
+            .send()
+            .await
+            .ok();
+    }
+
+    app.post("/users/alice/merge")
+        .bearer(&app.token("alice").permissions(["users:write"]))
+        .json(json!({ "merged_user_id": "alice2" }))
+        .send()
+        .await
+        .assert_error(StatusCode::FORBIDDEN, "MISSING_PERMISSION");
+
+    app.post("/users/alice/merge")
+        .bearer(&admin(&app))
+        .json(json!({ "merged_user_id": "alice" }))
+        .send()
+        .await
+        .assert_error(StatusCode::BAD_REQUEST, "USER_MERGE_WITH_SELF");
+
+    let merge = app
+        .post("/users/alice/merge")
+        .bearer(&admin(&app))
+        .json(json!({ "merged_user_id": "alice2", "dry_run": true }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(merge["connect_groups_dropped"], 1);
+    // Relationships are counted along with their inverse.
+    assert_eq!(merge["relationships_moved"], 2);
+    assert_eq!(merge["relationships_dropped"], 2);
+
+    // Nothing changed during the dry run.
+    app.get("/users/alice2")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+
+    let merge = app
+        .post("/users/alice/merge")
+        .bearer(&admin(&app))
+        .json(json!({ "merged_user_id": "alice2" }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(merge["merged_by"], "admin");
+
+    app.get("/users/alice2")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "USER_NOT_FOUND");
+
+    let connect_groups = app
+        .get("/users/alice/connect-groups")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(connect_groups.as_array().unwrap().len(), 1);
+    assert_eq!(connect_groups[0]["role"]["id"], leader["id"]);
+
+    let relationships = app
+        .get("/users/alice/relationships")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(relationships.as_array().unwrap().len(), 1);
+    assert_eq!(relationships[0]["destination_user_id"], "bob");
+
+    let merges = app
+        .get("/users/alice2/merges")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(merges[0]["id"], merge["id"]);
+
+    app.post("/users/alice/merge")
+        .bearer(&admin(&app))
+        .json(json!({ "merged_user_id": "alice2" }))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "USER_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn projects_requested_fields() {
+    let app = app().await;
+    app.post("/users")
+        .bearer(&admin(&app))
+        .json(json!({
+            "id": "alice",
+            "name": "Alice Tan",
+            "email": "alice@example.com",
+            "ic_number": "900101-14-5678",
+            "avatar_url": "https://example.com/alice.png",
+        }))
+        .send()
+        .await
+        .ok();
+
+    let user = app
+        .get("/users/alice?fields=name,avatar_url")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(
+        user,
+        json!({ "name": "Alice Tan", "avatar_url": "https://example.com/alice.png" })
+    );
+
+    // Projected fields are still masked.
+    let users = app
+        .get("/users?fields=id,ic_number")
+        .bearer(&app.token("bob").permissions(["users:read"]))
+        .send()
+        .await
+        .ok();
+    assert_eq!(
+        users["items"],
+        json!([{ "id": "alice", "ic_number": "********5678" }])
+    );
+
+    app.get("/users/alice?fields=name,password")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .assert_error(StatusCode::BAD_REQUEST, "UNKNOWN_FIELD");
+
+    // Every field of a projected user is optional.
+    let specs = app.get("/specs").send().await.ok();
+    let schema = &specs["components"]["schemas"]["PartialUser"];
+    assert!(schema["properties"]["ic_number"].is_object());
+    assert!(schema["required"].as_array().into_iter().all(Vec::is_empty));
+}
+
+#[tokio::test]
+async fn syncs_users_with_auth0() {
+    let app = app().await;
+    app.management.insert_user(json!({
+        "user_id": "alice",
+        "email": "alice@example.com",
+        "email_verified": true,
+        "name": "alice@example.com",
+    }));
+
+    app.post("/users")
+        .bearer(&admin(&app))
+        .json(json!({
+            "id": "alice",
+            "name": "Alice Tan",
+            "email": "alice@example.com",
+            "email_verified": true,
+            "phone_number": "+60 12-345 6789",
+        }))
+        .send()
+        .await
+        .ok();
+    let profile = app.management.user("alice").unwrap();
+    assert_eq!(profile["name"], "Alice Tan");
+    // The phone number is only kept encrypted here.
+    assert!(profile["user_metadata"].is_null());
+
+    // A new email has to be verified again.
+    let user = app
+        .patch("/users/alice")
+        .bearer(&admin(&app))
+        .json(json!({
+            "id": "alice",
+            "email": "alice.tan@example.com",
+            "email_verified": true,
+        }))
+        .send()
+        .await
+        .ok();
+    assert_eq!(user["email_verified"], false);
+    let profile = app.management.user("alice").unwrap();
+    assert_eq!(profile["email"], "alice.tan@example.com");
+    assert_eq!(profile["email_verified"], false);
+    assert_eq!(app.management.verification_emails(), ["alice"]);
+
+    // Users without an Auth0 account are left alone.
+    create_user(&app, "bob").await;
+    assert!(app.management.user("bob").is_none());
+
+    app.delete("/users/alice")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(app.management.user("alice").unwrap()["blocked"], true);
+    app.post("/users/alice/restore")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(app.management.user("alice").unwrap()["blocked"], false);
+
+    // Profiles changed on Auth0 are reported then fixed, users who signed up without creating
+    // their user are only reported.
+    let mut profile = app.management.user("alice").unwrap();
+    profile["name"] = json!("Alicia");
+    profile["email_verified"] = json!(true);
+    app.management.insert_user(profile);
+    app.management.insert_user(json!({
+        "user_id": "carol",
+        "email": "carol@example.com",
+        "email_verified": true,
+        "name": "Carol Lim",
+    }));
+
+    let keyring = Keyring::new(testing::PII_KEYS, testing::PII_INDEX_KEY).unwrap();
+    let management = app.management.client().await;
+    for fix in [false, true] {
+        let report = auth0_sync::reconcile(&app.database, &keyring, &management, fix)
+            .await
+            .unwrap();
+        assert_eq!(report.checked, 2);
+        assert_eq!(
+            report.mismatches,
+            [auth0_sync::Mismatch {
+                user_id: "alice".to_string(),
+                fields: vec!["email_verified", "name"],
+            }]
+        );
+        assert_eq!(report.missing, ["carol"]);
+    }
+
+    let report = auth0_sync::reconcile(&app.database, &keyring, &management, false)
+        .await
+        .unwrap();
+    assert!(report.mismatches.is_empty());
+    assert_eq!(report.missing, ["carol"]);
+    assert_eq!(app.management.user("alice").unwrap()["name"], "Alice Tan");
+    let user = app
+        .get("/users/alice")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(user["email_verified"], true);
+    app.get("/users/carol")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "USER_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn reconciles_every_auth0_user() {
+    let app = app().await;
+    // Several pages of users, some signing up at the same time.
+    for i in 0..250 {
+        app.management.insert_user(json!({
+            "user_id": format!("user{:03}", i),
+            "email": format!("user{:03}@example.com", i),
+            "name": format!("User {}", i),
+            "created_at": format!("2023-01-01T00:{:02}:00.000Z", i / 5),
+        }));
+    }
+
+    let keyring = Keyring::new(testing::PII_KEYS, testing::PII_INDEX_KEY).unwrap();
+    let management = app.management.client().await;
+    let report = auth0_sync::reconcile(&app.database, &keyring, &management, false)
+        .await
+        .unwrap();
+    assert_eq!(report.checked, 250);
+    assert_eq!(report.missing.len(), 250);
+    assert_eq!(report.missing[0], "user000");
+    assert_eq!(report.missing[249], "user249");
+}
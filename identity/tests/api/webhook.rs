@@ -0,0 +1,203 @@
+use std::sync::{Arc, Mutex};
+
+use common::{outbox, testing::TestApp};
+use poem::{
+    http::{HeaderMap, StatusCode},
+    listener::{Acceptor, Listener, TcpListener},
+};
+use serde_json::{json, Value};
+
+use crate::{admin, app, create_connect_group, create_pastoral_role, create_user};
+
+/// The headers and body of each request a [`Receiver`] got.
+type Requests = Vec<(HeaderMap, Vec<u8>)>;
+
+/// A local endpoint keeping the requests posted to it and responding with `status`.
+#[derive(Clone)]
+struct Receiver {
+    url: String,
+    received: Arc<Mutex<Requests>>,
+}
+
+impl Receiver {
+    async fn start(status: StatusCode) -> Self {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let endpoint = poem::endpoint::make({
+            let received = received.clone();
+            move |mut req: poem::Request| {
+                let received = received.clone();
+                async move {
+                    let body = req.take_body().into_vec().await.unwrap();
+                    received.lock().unwrap().push((req.headers().clone(), body));
+                    status
+                }
+            }
+        });
+
+        let acceptor = TcpListener::bind("127.0.0.1:0")
+            .into_acceptor()
+            .await
+            .expect("failed to bind the webhook receiver");
+        let url = format!(
+            "http://{}/",
+            acceptor.local_addr()[0]
+                .as_socket_addr()
+                .expect("webhook receiver listens on TCP")
+        );
+        tokio::spawn(poem::Server::new_with_acceptor(acceptor).run(endpoint));
+
+        Self { url, received }
+    }
+
+    fn received(&self) -> Requests {
+        self.received.lock().unwrap().clone()
+    }
+}
+
+async fn dispatch(app: &TestApp) -> usize {
+    outbox::dispatch(&app.database, &reqwest::Client::new())
+        .await
+        .expect("failed to dispatch the deliveries")
+}
+
+async fn join_connect_group(app: &TestApp, id: &str, user_id: &str, role: &Value) {
+    app.post(format!("/connect-groups/{}/users", id))
+        .bearer(&admin(app))
+        .json(json!({ "users": [{ "user_id": user_id, "role_id": role["id"] }] }))
+        .send()
+        .await
+        .ok();
+}
+
+#[tokio::test]
+async fn deliver_signed_events() {
+    let app = app().await;
+    let receiver = Receiver::start(StatusCode::OK).await;
+    create_user(&app, "alice").await;
+    let connect_group = create_connect_group(&app, 1).await;
+    let id = connect_group["id"].as_str().unwrap();
+    let member = create_pastoral_role(&app, "Member", 10).await;
+
+    app.post("/webhooks")
+        .bearer(&admin(&app))
+        .json(json!({ "url": "ftp://example.com" }))
+        .send()
+        .await
+        .assert_error(StatusCode::BAD_REQUEST, "WEBHOOK_URL_INVALID");
+
+    let webhook = app
+        .post("/webhooks")
+        .bearer(&admin(&app))
+        .json(json!({
+            "url": receiver.url,
+            "event_types": ["connect_group.users_joined"],
+        }))
+        .send()
+        .await
+        .ok();
+    let secret = webhook["secret"].as_str().unwrap();
+    let webhook_id = webhook["id"].as_str().unwrap();
+
+    let fetched = app
+        .get(format!("/webhooks/{}", webhook_id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert!(fetched.get("secret").is_none());
+
+    // Only the subscribed event types are delivered.
+    create_user(&app, "bob").await;
+    join_connect_group(&app, id, "alice", &member).await;
+    assert_eq!(dispatch(&app).await, 1);
+    assert_eq!(dispatch(&app).await, 0);
+
+    let received = receiver.received();
+    assert_eq!(received.len(), 1);
+    let (headers, body) = &received[0];
+    let timestamp = headers[outbox::TIMESTAMP_HEADER]
+        .to_str()
+        .unwrap()
+        .parse::<i64>()
+        .unwrap();
+    assert_eq!(
+        headers[outbox::SIGNATURE_HEADER].to_str().unwrap(),
+        outbox::sign(secret, timestamp, body)
+    );
+    let event: Value = serde_json::from_slice(body).unwrap();
+    assert_eq!(event["type"], "connect_group.users_joined");
+    assert_eq!(event["actor"], "admin");
+    assert_eq!(event["payload"]["connect_group_id"], id);
+    assert_eq!(event["payload"]["users"][0]["user_id"], "alice");
+
+    let page = app
+        .get(format!("/webhook-deliveries?webhook_id={}", webhook_id))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(page["items"][0]["status"], "delivered");
+    assert_eq!(page["items"][0]["attempts"], 1);
+    assert_eq!(page["items"][0]["last_response_status"], 200);
+}
+
+#[tokio::test]
+async fn dead_letter_failed_deliveries() {
+    let app = app().await;
+    let receiver = Receiver::start(StatusCode::INTERNAL_SERVER_ERROR).await;
+    create_user(&app, "alice").await;
+    let connect_group = create_connect_group(&app, 1).await;
+    let member = create_pastoral_role(&app, "Member", 10).await;
+
+    app.post("/webhooks")
+        .bearer(&admin(&app))
+        .json(json!({ "url": receiver.url }))
+        .send()
+        .await
+        .ok();
+    join_connect_group(
+        &app,
+        connect_group["id"].as_str().unwrap(),
+        "alice",
+        &member,
+    )
+    .await;
+
+    for attempt in 1..=outbox::MAX_ATTEMPTS {
+        assert_eq!(dispatch(&app).await, 1, "attempt {}", attempt);
+        // Skip the backoff.
+        sqlx::query("UPDATE webhook_delivery SET next_attempt_at = NOW()")
+            .execute(&app.database.db)
+            .await
+            .unwrap();
+    }
+    assert_eq!(dispatch(&app).await, 0);
+    assert_eq!(receiver.received().len(), outbox::MAX_ATTEMPTS as usize);
+
+    let page = app
+        .get("/webhook-deliveries?status=dead")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    let dead = &page["items"][0];
+    assert_eq!(dead["event_type"], "connect_group.users_joined");
+    assert_eq!(dead["attempts"], outbox::MAX_ATTEMPTS);
+    assert_eq!(dead["last_response_status"], 500);
+
+    let delivery = app
+        .post(format!("/webhook-deliveries/{}/retry", dead["id"]))
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .ok();
+    assert_eq!(delivery["status"], "pending");
+    assert_eq!(delivery["attempts"], 0);
+    assert_eq!(dispatch(&app).await, 1);
+
+    app.post("/webhook-deliveries/0/retry")
+        .bearer(&admin(&app))
+        .send()
+        .await
+        .assert_error(StatusCode::NOT_FOUND, "WEBHOOK_DELIVERY_NOT_FOUND");
+}